@@ -0,0 +1,890 @@
+//! Healing arcs — trigger → bridge → gratitude
+//! -------------------------------------------
+//! Whisper: "a feeling that lands is a story with an ending." 🌈
+//!
+//! An arc follows one emotion event to its resolution. It links the trigger row in
+//! `emotions`, the bridge that was used (`bridges::suggest` with the person's overrides,
+//! unless they name their own steps), one tell per transition and the resolving
+//! gratitude row.
+//!
+//! States
+//!   opened ──bridge──▶ bridged ──resolve──▶ resolved
+//!     │                  │
+//!     └──resolve─────────┤ (a bridge is optional)
+//!     └──abandon─────────┴──▶ abandoned
+//!   `resolved` and `abandoned` are final; other moves answer 409.
+//!
+//! Endpoints (mounted under `/arcs`)
+//!   POST   /arcs              → { emotion_id } opens an arc on an existing emotion
+//!   GET    /arcs              → { items, next_before } (filters: who, state, kind;
+//!                               cursor: before=<id>; limit ≤ 200)
+//!   GET    /arcs/stats        → counts per state + mean seconds to bridge / to resolve
+//!   GET    /arcs/:id          → one arc
+//!   POST   /arcs/:id/bridge   → { breath?, doorway?, anchor? } (missing steps from the
+//!                               suggested bridge for the trigger's kind + intensity;
+//!                               the taxonomy's default bridge kind when it has one)
+//!   POST   /arcs/:id/resolve  → { note_id?, details?, sealed?, archetype?, privacy? } lands a
+//!                               gratitude emotion (intensity 1.0) and closes the arc
+//!   POST   /arcs/:id/abandon  → { reason? }
+//!
+//! `POST /emotions/resolve` with `arc_id` goes through the same `resolve()`.
+//!
+//! Durations are whole seconds: `to_bridge` (opened → bridged), `to_close`
+//! (opened → resolved/abandoned) and `elapsed` (to close, or to now while still open).
+
+use crate::AppState;
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    routing::{get, post},
+    Json, Router,
+};
+use chrono::{DateTime, Utc};
+use rusqlite::OptionalExtension;
+use serde::{Deserialize, Serialize};
+
+type ApiErr = (StatusCode, String);
+
+fn unprocessable(msg: impl Into<String>) -> ApiErr {
+    (StatusCode::UNPROCESSABLE_ENTITY, msg.into())
+}
+
+fn internal(e: impl std::fmt::Display) -> ApiErr {
+    (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ArcState {
+    Opened,
+    Bridged,
+    Resolved,
+    Abandoned,
+}
+
+impl ArcState {
+    pub const ALL: [ArcState; 4] = [
+        ArcState::Opened,
+        ArcState::Bridged,
+        ArcState::Resolved,
+        ArcState::Abandoned,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ArcState::Opened => "opened",
+            ArcState::Bridged => "bridged",
+            ArcState::Resolved => "resolved",
+            ArcState::Abandoned => "abandoned",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|st| st.as_str().eq_ignore_ascii_case(s.trim()))
+    }
+
+    /// Still waiting for an ending.
+    pub fn is_open(self) -> bool {
+        matches!(self, ArcState::Opened | ArcState::Bridged)
+    }
+}
+
+/// The steps that were offered (or chosen) between trigger and resolution.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ArcBridge {
+    pub pattern: String,
+    pub breath: String,
+    pub doorway: String,
+    pub anchor: String,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct Durations {
+    pub to_bridge: Option<i64>,
+    pub to_close: Option<i64>,
+    pub elapsed: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HealingArc {
+    pub id: i64,
+    pub who: String,
+    pub state: ArcState,
+    pub trigger_emotion_id: i64,
+    /// Copied from the trigger when the arc opened.
+    pub kind: String,
+    pub intensity: f64,
+    pub bridge: Option<ArcBridge>,
+    pub resolution_emotion_id: Option<i64>,
+    /// One tell per transition, oldest first.
+    pub tell_ids: Vec<i64>,
+    /// Why an arc was abandoned.
+    pub note: Option<String>,
+    pub opened_at: String,
+    pub bridged_at: Option<String>,
+    pub closed_at: Option<String>,
+    pub durations: Durations,
+}
+
+/// Why a transition was refused.
+#[derive(Debug, PartialEq)]
+pub enum ArcError {
+    Missing(&'static str),
+    Conflict(String),
+    Invalid(String),
+}
+
+const COLS: &str = "id, who, state, trigger_emotion_id, kind, intensity, bridge_pattern, breath, \
+     doorway, anchor, resolution_emotion_id, tell_ids, note, opened_at, bridged_at, closed_at";
+
+fn secs_between(from: &str, to: &str) -> Option<i64> {
+    let from = DateTime::parse_from_rfc3339(from).ok()?;
+    let to = DateTime::parse_from_rfc3339(to).ok()?;
+    Some((to - from).num_seconds().max(0))
+}
+
+fn row_to_arc(r: &rusqlite::Row) -> rusqlite::Result<HealingArc> {
+    let state: String = r.get(2)?;
+    let pattern: Option<String> = r.get(6)?;
+    let bridge = match pattern {
+        Some(pattern) => Some(ArcBridge {
+            pattern,
+            breath: r.get::<_, Option<String>>(7)?.unwrap_or_default(),
+            doorway: r.get::<_, Option<String>>(8)?.unwrap_or_default(),
+            anchor: r.get::<_, Option<String>>(9)?.unwrap_or_default(),
+        }),
+        None => None,
+    };
+    let tell_ids: String = r.get(11)?;
+    let opened_at: String = r.get(13)?;
+    let bridged_at: Option<String> = r.get(14)?;
+    let closed_at: Option<String> = r.get(15)?;
+    let now = Utc::now().to_rfc3339();
+    let durations = Durations {
+        to_bridge: bridged_at
+            .as_deref()
+            .and_then(|b| secs_between(&opened_at, b)),
+        to_close: closed_at
+            .as_deref()
+            .and_then(|c| secs_between(&opened_at, c)),
+        elapsed: secs_between(&opened_at, closed_at.as_deref().unwrap_or(&now)).unwrap_or(0),
+    };
+    Ok(HealingArc {
+        id: r.get(0)?,
+        who: r.get(1)?,
+        state: ArcState::parse(&state).unwrap_or(ArcState::Opened),
+        trigger_emotion_id: r.get(3)?,
+        kind: r.get(4)?,
+        intensity: r.get(5)?,
+        bridge,
+        resolution_emotion_id: r.get(10)?,
+        tell_ids: serde_json::from_str(&tell_ids).unwrap_or_default(),
+        note: r.get(12)?,
+        opened_at,
+        bridged_at,
+        closed_at,
+        durations,
+    })
+}
+
+pub fn get_one(c: &rusqlite::Connection, id: i64) -> rusqlite::Result<Option<HealingArc>> {
+    c.query_row(
+        &format!("SELECT {COLS} FROM arcs WHERE id = ?1"),
+        [id],
+        row_to_arc,
+    )
+    .optional()
+}
+
+/// Write a tell for a transition and append its id to the arc.
+fn tell(
+    c: &rusqlite::Connection,
+    arc: &HealingArc,
+    step: &str,
+    pre: &str,
+    now: &str,
+) -> rusqlite::Result<()> {
+    c.execute(
+        "INSERT INTO tells(node, pre_activation, action, created_at) VALUES(?1, ?2, ?3, ?4)",
+        rusqlite::params![
+            format!("arcs.{step}"),
+            pre,
+            format!("arc #{} {step} by {}", arc.id, arc.who),
+            now
+        ],
+    )?;
+    let mut ids = arc.tell_ids.clone();
+    ids.push(c.last_insert_rowid());
+    c.execute(
+        "UPDATE arcs SET tell_ids = ?1 WHERE id = ?2",
+        rusqlite::params![serde_json::to_string(&ids).unwrap_or_default(), arc.id],
+    )?;
+    Ok(())
+}
+
+/// Load an arc that can still move, or say why not.
+fn live(c: &rusqlite::Connection, id: i64) -> rusqlite::Result<Result<HealingArc, ArcError>> {
+    Ok(match get_one(c, id)? {
+        None => Err(ArcError::Missing("unknown arc")),
+        Some(a) if !a.state.is_open() => Err(ArcError::Conflict(format!(
+            "arc is already {}",
+            a.state.as_str()
+        ))),
+        Some(a) => Ok(a),
+    })
+}
+
+/// Open an arc on an existing emotion row. One live arc per trigger.
+pub fn open(
+    c: &mut rusqlite::Connection,
+    emotion_id: i64,
+    now: &str,
+) -> rusqlite::Result<Result<HealingArc, ArcError>> {
+    let tx = c.transaction()?;
+    let trigger: Option<(String, String, f64)> = tx
+        .query_row(
+            "SELECT who, kind, intensity FROM emotions WHERE id = ?1",
+            [emotion_id],
+            |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)),
+        )
+        .optional()?;
+    let Some((who, kind, intensity)) = trigger else {
+        return Ok(Err(ArcError::Missing("unknown emotion")));
+    };
+    if crate::taxonomy::normalize(&tx, &kind)?.kind == "gratitude" {
+        return Ok(Err(ArcError::Invalid(
+            "gratitude resolves arcs; it cannot open one".into(),
+        )));
+    }
+    let existing: Option<i64> = tx
+        .query_row(
+            "SELECT id FROM arcs WHERE trigger_emotion_id = ?1 AND state IN ('opened', 'bridged')",
+            [emotion_id],
+            |r| r.get(0),
+        )
+        .optional()?;
+    if let Some(id) = existing {
+        return Ok(Err(ArcError::Conflict(format!(
+            "emotion already has open arc #{id}"
+        ))));
+    }
+    tx.execute(
+        "INSERT INTO arcs(who, state, trigger_emotion_id, kind, intensity, opened_at)
+         VALUES(?1, 'opened', ?2, ?3, ?4, ?5)",
+        rusqlite::params![who, emotion_id, kind, intensity, now],
+    )?;
+    let id = tx.last_insert_rowid();
+    let arc = get_one(&tx, id)?.expect("just inserted");
+    tell(&tx, &arc, "open", &format!("{kind} @ {intensity:.2}"), now)?;
+    let arc = get_one(&tx, id)?.expect("just inserted");
+    tx.commit()?;
+    Ok(Ok(arc))
+}
+
+/// Steps the person actually used; missing ones come from the suggested bridge.
+#[derive(Debug, Default, Clone, Deserialize)]
+pub struct BridgeIn {
+    #[serde(default)]
+    pub breath: Option<String>,
+    #[serde(default)]
+    pub doorway: Option<String>,
+    #[serde(default)]
+    pub anchor: Option<String>,
+}
+
+/// opened → bridged.
+pub fn bridge(
+    c: &mut rusqlite::Connection,
+    id: i64,
+    steps: &BridgeIn,
+    now: &str,
+) -> rusqlite::Result<Result<HealingArc, ArcError>> {
+    let tx = c.transaction()?;
+    let arc = match live(&tx, id)? {
+        Ok(a) if a.state == ArcState::Bridged => {
+            return Ok(Err(ArcError::Conflict("arc is already bridged".into())))
+        }
+        Ok(a) => a,
+        Err(e) => return Ok(Err(e)),
+    };
+    let suggested = crate::bridges::suggest(&tx, &arc.kind, arc.intensity, Some(&arc.who))?;
+    let pick = |v: &Option<String>, fallback: &str| {
+        v.as_deref()
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .unwrap_or(fallback)
+            .to_string()
+    };
+    let b = ArcBridge {
+        breath: pick(&steps.breath, &suggested.breath),
+        doorway: pick(&steps.doorway, &suggested.doorway),
+        anchor: pick(&steps.anchor, &suggested.anchor),
+        pattern: suggested.pattern,
+    };
+    tx.execute(
+        "UPDATE arcs SET state = 'bridged', bridge_pattern = ?1, breath = ?2, doorway = ?3,
+           anchor = ?4, bridged_at = ?5
+         WHERE id = ?6",
+        rusqlite::params![b.pattern, b.breath, b.doorway, b.anchor, now, id],
+    )?;
+    tell(
+        &tx,
+        &arc,
+        "bridge",
+        &format!("{}: {}", b.pattern, b.doorway),
+        now,
+    )?;
+    let arc = get_one(&tx, id)?.expect("exists");
+    tx.commit()?;
+    Ok(Ok(arc))
+}
+
+/// How the resolving gratitude row is written.
+#[derive(Debug, Default, Clone, Deserialize)]
+pub struct ResolveIn {
+    #[serde(default)]
+    pub note_id: Option<i64>,
+    #[serde(default)]
+    pub details: Option<String>,
+    #[serde(default)]
+    pub sealed: Option<bool>,
+    #[serde(default)]
+    pub archetype: Option<String>,
+    #[serde(default)]
+    pub privacy: Option<String>,
+}
+
+/// opened | bridged → resolved. Lands a gratitude emotion (intensity 1.0) and links it.
+pub fn resolve(
+    c: &mut rusqlite::Connection,
+    id: i64,
+    input: &ResolveIn,
+    now: &str,
+) -> rusqlite::Result<Result<HealingArc, ArcError>> {
+    let privacy = input
+        .privacy
+        .as_deref()
+        .map(str::trim)
+        .unwrap_or("private")
+        .to_string();
+    if privacy.is_empty() {
+        return Ok(Err(ArcError::Invalid("privacy cannot be empty".into())));
+    }
+    let tx = c.transaction()?;
+    let arc = match live(&tx, id)? {
+        Ok(a) => a,
+        Err(e) => return Ok(Err(e)),
+    };
+    tx.execute(
+        "INSERT INTO emotions(ts, who, kind, intensity, note, note_id, details, sealed, archetype, privacy)
+         VALUES(?1, ?2, 'gratitude', 1.0, ?3, ?4, ?5, ?6, ?7, ?8)",
+        rusqlite::params![
+            now,
+            arc.who,
+            format!("arc #{id} resolved"),
+            input.note_id,
+            input.details,
+            input.sealed.unwrap_or(false),
+            input.archetype,
+            privacy
+        ],
+    )?;
+    let emotion_id = tx.last_insert_rowid();
+    tx.execute(
+        "UPDATE arcs SET state = 'resolved', resolution_emotion_id = ?1, closed_at = ?2
+         WHERE id = ?3",
+        rusqlite::params![emotion_id, now, id],
+    )?;
+    tell(
+        &tx,
+        &arc,
+        "resolve",
+        &format!("{} → gratitude", arc.kind),
+        now,
+    )?;
+    let arc = get_one(&tx, id)?.expect("exists");
+    tx.commit()?;
+    Ok(Ok(arc))
+}
+
+/// opened | bridged → abandoned.
+pub fn abandon(
+    c: &mut rusqlite::Connection,
+    id: i64,
+    reason: Option<&str>,
+    now: &str,
+) -> rusqlite::Result<Result<HealingArc, ArcError>> {
+    let tx = c.transaction()?;
+    let arc = match live(&tx, id)? {
+        Ok(a) => a,
+        Err(e) => return Ok(Err(e)),
+    };
+    tx.execute(
+        "UPDATE arcs SET state = 'abandoned', note = ?1, closed_at = ?2 WHERE id = ?3",
+        rusqlite::params![reason, now, id],
+    )?;
+    tell(&tx, &arc, "abandon", reason.unwrap_or("-"), now)?;
+    let arc = get_one(&tx, id)?.expect("exists");
+    tx.commit()?;
+    Ok(Ok(arc))
+}
+
+/// Filters for `GET /arcs` and `/arcs/stats`.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct Filter {
+    #[serde(default)]
+    pub who: Option<String>,
+    #[serde(default)]
+    pub state: Option<String>,
+    /// Case-insensitive.
+    #[serde(default)]
+    pub kind: Option<String>,
+}
+
+impl Filter {
+    /// Trim, drop empties and check `state`.
+    pub fn normalized(mut self) -> Result<Self, String> {
+        for field in [&mut self.who, &mut self.state, &mut self.kind] {
+            *field = field
+                .take()
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty());
+        }
+        if let Some(s) = self.state.as_deref() {
+            let st = ArcState::parse(s).ok_or_else(|| {
+                "state must be opened | bridged | resolved | abandoned".to_string()
+            })?;
+            self.state = Some(st.as_str().to_string());
+        }
+        Ok(self)
+    }
+}
+
+const FILTER_SQL: &str = "(?1 IS NULL OR who = ?1)
+     AND (?2 IS NULL OR state = ?2)
+     AND (?3 IS NULL OR lower(kind) = lower(?3))";
+
+/// Page of arcs, newest first; `before` is an id cursor.
+pub fn list(
+    c: &rusqlite::Connection,
+    f: &Filter,
+    before: Option<i64>,
+    limit: i64,
+) -> rusqlite::Result<Vec<HealingArc>> {
+    let mut stmt = c.prepare(&format!(
+        "SELECT {COLS} FROM arcs
+         WHERE {FILTER_SQL} AND (?4 IS NULL OR id < ?4)
+         ORDER BY id DESC LIMIT ?5"
+    ))?;
+    let rows = stmt
+        .query_map(
+            rusqlite::params![f.who, f.state, f.kind, before, limit],
+            row_to_arc,
+        )?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(rows)
+}
+
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct StateCounts {
+    pub opened: i64,
+    pub bridged: i64,
+    pub resolved: i64,
+    pub abandoned: i64,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct Stats {
+    pub total: i64,
+    pub states: StateCounts,
+    /// Mean seconds opened → bridged, over bridged arcs.
+    pub mean_to_bridge: Option<f64>,
+    /// Mean seconds opened → resolved, over resolved arcs.
+    pub mean_to_resolve: Option<f64>,
+}
+
+pub fn stats(c: &rusqlite::Connection, f: &Filter) -> rusqlite::Result<Stats> {
+    let mut stmt = c.prepare(&format!("SELECT {COLS} FROM arcs WHERE {FILTER_SQL}"))?;
+    let arcs = stmt
+        .query_map(rusqlite::params![f.who, f.state, f.kind], row_to_arc)?
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut states = StateCounts::default();
+    for a in &arcs {
+        *match a.state {
+            ArcState::Opened => &mut states.opened,
+            ArcState::Bridged => &mut states.bridged,
+            ArcState::Resolved => &mut states.resolved,
+            ArcState::Abandoned => &mut states.abandoned,
+        } += 1;
+    }
+    let mean =
+        |xs: Vec<i64>| (!xs.is_empty()).then(|| xs.iter().sum::<i64>() as f64 / xs.len() as f64);
+    Ok(Stats {
+        total: arcs.len() as i64,
+        states,
+        mean_to_bridge: mean(arcs.iter().filter_map(|a| a.durations.to_bridge).collect()),
+        mean_to_resolve: mean(
+            arcs.iter()
+                .filter(|a| a.state == ArcState::Resolved)
+                .filter_map(|a| a.durations.to_close)
+                .collect(),
+        ),
+    })
+}
+
+pub fn http_err(e: ArcError) -> ApiErr {
+    match e {
+        ArcError::Missing(what) => (StatusCode::NOT_FOUND, what.into()),
+        ArcError::Conflict(msg) => (StatusCode::CONFLICT, msg),
+        ArcError::Invalid(msg) => unprocessable(msg),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenIn {
+    emotion_id: i64,
+}
+
+/// POST /arcs
+async fn open_http(
+    State(state): State<AppState>,
+    Json(input): Json<OpenIn>,
+) -> Result<(StatusCode, Json<HealingArc>), ApiErr> {
+    let now = Utc::now().to_rfc3339();
+    state
+        .db
+        .0
+        .call(move |c| Ok(open(c, input.emotion_id, &now)?))
+        .await
+        .map_err(internal)?
+        .map(|a| (StatusCode::CREATED, Json(a)))
+        .map_err(http_err)
+}
+
+#[derive(Debug, Deserialize)]
+struct PageParams {
+    #[serde(default)]
+    before: Option<i64>,
+    #[serde(default)]
+    limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+struct Page {
+    items: Vec<HealingArc>,
+    /// Pass as `before` for the next page; absent on the last page.
+    next_before: Option<i64>,
+}
+
+/// GET /arcs?who=&state=&kind=&before=&limit=
+async fn list_http(
+    State(state): State<AppState>,
+    Query(filter): Query<Filter>,
+    Query(q): Query<PageParams>,
+) -> Result<Json<Page>, ApiErr> {
+    let filter = filter.normalized().map_err(unprocessable)?;
+    let limit = q.limit.unwrap_or(20).clamp(1, 200);
+    let before = q.before;
+    let items = state
+        .db
+        .0
+        .call(move |c| Ok(list(c, &filter, before, limit)?))
+        .await
+        .map_err(internal)?;
+    let next_before = (items.len() as i64 == limit)
+        .then(|| items.last().map(|a| a.id))
+        .flatten();
+    Ok(Json(Page { items, next_before }))
+}
+
+/// GET /arcs/stats?who=&state=&kind=
+async fn stats_http(
+    State(state): State<AppState>,
+    Query(filter): Query<Filter>,
+) -> Result<Json<Stats>, ApiErr> {
+    let filter = filter.normalized().map_err(unprocessable)?;
+    state
+        .db
+        .0
+        .call(move |c| Ok(stats(c, &filter)?))
+        .await
+        .map(Json)
+        .map_err(internal)
+}
+
+/// GET /arcs/:id
+async fn get_http(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+) -> Result<Json<HealingArc>, ApiErr> {
+    state
+        .db
+        .0
+        .call(move |c| Ok(get_one(c, id)?))
+        .await
+        .map_err(internal)?
+        .map(Json)
+        .ok_or((StatusCode::NOT_FOUND, "unknown arc".into()))
+}
+
+/// POST /arcs/:id/bridge
+async fn bridge_http(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    input: Option<Json<BridgeIn>>,
+) -> Result<Json<HealingArc>, ApiErr> {
+    let now = Utc::now().to_rfc3339();
+    let steps = input.map(|Json(b)| b).unwrap_or_default();
+    state
+        .db
+        .0
+        .call(move |c| Ok(bridge(c, id, &steps, &now)?))
+        .await
+        .map_err(internal)?
+        .map(Json)
+        .map_err(http_err)
+}
+
+/// POST /arcs/:id/resolve
+async fn resolve_http(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    input: Option<Json<ResolveIn>>,
+) -> Result<Json<HealingArc>, ApiErr> {
+    let now = Utc::now().to_rfc3339();
+    let input = input.map(|Json(r)| r).unwrap_or_default();
+    state
+        .db
+        .0
+        .call(move |c| Ok(resolve(c, id, &input, &now)?))
+        .await
+        .map_err(internal)?
+        .map(Json)
+        .map_err(http_err)
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct AbandonIn {
+    #[serde(default)]
+    reason: Option<String>,
+}
+
+/// POST /arcs/:id/abandon
+async fn abandon_http(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    input: Option<Json<AbandonIn>>,
+) -> Result<Json<HealingArc>, ApiErr> {
+    let now = Utc::now().to_rfc3339();
+    let reason = input
+        .and_then(|Json(a)| a.reason)
+        .map(|r| r.trim().to_string())
+        .filter(|r| !r.is_empty());
+    state
+        .db
+        .0
+        .call(move |c| Ok(abandon(c, id, reason.as_deref(), &now)?))
+        .await
+        .map_err(internal)?
+        .map(Json)
+        .map_err(http_err)
+}
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_http).post(open_http))
+        .route("/stats", get(stats_http))
+        .route("/:id", get(get_http))
+        .route("/:id/bridge", post(bridge_http))
+        .route("/:id/resolve", post(resolve_http))
+        .route("/:id/abandon", post(abandon_http))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conn() -> rusqlite::Connection {
+        let mut c = rusqlite::Connection::open_in_memory().unwrap();
+        crate::db::ensure_schema(&mut c).unwrap();
+        c
+    }
+
+    fn emotion(c: &rusqlite::Connection, who: &str, kind: &str, intensity: f64) -> i64 {
+        c.execute(
+            "INSERT INTO emotions(ts, who, kind, intensity) VALUES('2025-09-01T08:00:00+00:00', ?1, ?2, ?3)",
+            rusqlite::params![who, kind, intensity],
+        )
+        .unwrap();
+        c.last_insert_rowid()
+    }
+
+    #[test]
+    fn arc_walks_open_bridge_resolve_with_durations() {
+        let mut c = conn();
+        let fear = emotion(&c, "Raz", "fear", 0.8);
+
+        let arc = open(&mut c, fear, "2025-09-01T08:00:00+00:00")
+            .unwrap()
+            .unwrap();
+        assert_eq!(arc.state, ArcState::Opened);
+        assert_eq!((arc.kind.as_str(), arc.intensity), ("fear", 0.8));
+        assert!(matches!(
+            open(&mut c, fear, "2025-09-01T08:00:01+00:00").unwrap(),
+            Err(ArcError::Conflict(_))
+        ));
+
+        let arc = bridge(
+            &mut c,
+            arc.id,
+            &BridgeIn {
+                doorway: Some("walk to the window".into()),
+                ..Default::default()
+            },
+            "2025-09-01T08:02:00+00:00",
+        )
+        .unwrap()
+        .unwrap();
+        let b = arc.bridge.clone().unwrap();
+        assert_eq!(b.pattern, "stabilize-first");
+        assert_eq!(
+            b.breath, "box: in4-hold4-out6 × 4",
+            "high fear gets box breath"
+        );
+        assert_eq!(b.doorway, "walk to the window");
+
+        let arc = resolve(
+            &mut c,
+            arc.id,
+            &ResolveIn {
+                note_id: Some(5),
+                details: Some("landed".into()),
+                ..Default::default()
+            },
+            "2025-09-01T08:10:00+00:00",
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(arc.state, ArcState::Resolved);
+        assert_eq!(
+            arc.durations,
+            Durations {
+                to_bridge: Some(120),
+                to_close: Some(600),
+                elapsed: 600
+            }
+        );
+        assert_eq!(arc.tell_ids.len(), 3);
+        let nodes: Vec<String> = arc
+            .tell_ids
+            .iter()
+            .map(|id| {
+                c.query_row("SELECT node FROM tells WHERE id = ?1", [id], |r| r.get(0))
+                    .unwrap()
+            })
+            .collect();
+        assert_eq!(nodes, ["arcs.open", "arcs.bridge", "arcs.resolve"]);
+        let (kind, intensity, details, note_id): (String, f64, String, Option<i64>) = c
+            .query_row(
+                "SELECT kind, intensity, details, note_id FROM emotions WHERE id = ?1",
+                [arc.resolution_emotion_id.unwrap()],
+                |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?, r.get(3)?)),
+            )
+            .unwrap();
+        assert_eq!(
+            (kind.as_str(), intensity, details.as_str(), note_id),
+            ("gratitude", 1.0, "landed", Some(5))
+        );
+
+        // final states do not move
+        let now = "2025-09-01T09:00:00+00:00";
+        assert!(matches!(
+            abandon(&mut c, arc.id, None, now).unwrap(),
+            Err(ArcError::Conflict(_))
+        ));
+        assert!(matches!(
+            bridge(&mut c, arc.id, &BridgeIn::default(), now).unwrap(),
+            Err(ArcError::Conflict(_))
+        ));
+        assert_eq!(
+            resolve(&mut c, 999, &ResolveIn::default(), now)
+                .unwrap()
+                .unwrap_err(),
+            ArcError::Missing("unknown arc")
+        );
+        // the trigger is free again once its arc is closed
+        assert!(open(&mut c, fear, now).unwrap().is_ok());
+    }
+
+    #[test]
+    fn open_refuses_missing_and_gratitude_triggers() {
+        let mut c = conn();
+        let now = "2025-09-01T08:00:00+00:00";
+        assert_eq!(
+            open(&mut c, 42, now).unwrap().unwrap_err(),
+            ArcError::Missing("unknown emotion")
+        );
+        for kind in ["Gratitude", "thankful", "recunoștință"] {
+            let thanks = emotion(&c, "Raz", kind, 1.0);
+            assert!(
+                matches!(
+                    open(&mut c, thanks, now).unwrap(),
+                    Err(ArcError::Invalid(_))
+                ),
+                "{kind}"
+            );
+        }
+    }
+
+    #[test]
+    fn list_filters_and_stats_average_durations() {
+        let mut c = conn();
+        let at = |m: u32| format!("2025-09-01T08:{m:02}:00+00:00");
+        let a = emotion(&c, "Raz", "anger", 0.5);
+        let b = emotion(&c, "Raz", "shame", 0.4);
+        let d = emotion(&c, "Nico", "fear", 0.3);
+        let a = open(&mut c, a, &at(0)).unwrap().unwrap();
+        let b = open(&mut c, b, &at(0)).unwrap().unwrap();
+        open(&mut c, d, &at(0)).unwrap().unwrap();
+        resolve(&mut c, a.id, &ResolveIn::default(), &at(4))
+            .unwrap()
+            .unwrap();
+        bridge(&mut c, b.id, &BridgeIn::default(), &at(1))
+            .unwrap()
+            .unwrap();
+        resolve(&mut c, b.id, &ResolveIn::default(), &at(8))
+            .unwrap()
+            .unwrap();
+
+        let raz = Filter {
+            who: Some("Raz".into()),
+            ..Default::default()
+        };
+        let page = list(&c, &raz, None, 1).unwrap();
+        assert_eq!(page[0].id, b.id);
+        assert_eq!(list(&c, &raz, Some(b.id), 10).unwrap()[0].id, a.id);
+        let opened = Filter::default().normalized().unwrap();
+        assert_eq!(list(&c, &opened, None, 10).unwrap().len(), 3);
+        let still_open = Filter {
+            state: Some(" Opened ".into()),
+            ..Default::default()
+        }
+        .normalized()
+        .unwrap();
+        assert_eq!(list(&c, &still_open, None, 10).unwrap()[0].who, "Nico");
+        assert!(Filter {
+            state: Some("closed".into()),
+            ..Default::default()
+        }
+        .normalized()
+        .is_err());
+
+        let s = stats(&c, &raz).unwrap();
+        assert_eq!(s.total, 2);
+        assert_eq!(s.states.resolved, 2);
+        assert_eq!(s.mean_to_bridge, Some(60.0));
+        assert_eq!(s.mean_to_resolve, Some(360.0));
+    }
+}
@@ -0,0 +1,932 @@
+//! Bridge library — breath, doorway, anchor per kind and intensity
+//! ---------------------------------------------------------------
+//! Whisper: "the same storm, the same three steps — until yours work better." 🌉
+//!
+//! One table serves `/emotions/bridge`, `/patterns/bridge_suggest`, panic runs and
+//! healing arcs. Each row in `bridges` is a tier: it applies from `min_intensity` up to
+//! the next tier of the same kind. Seeded once from `SEEDS` (see `db::ensure_schema`).
+//!
+//! Kind lookup (`resolve_kind`), on the folded kind (`taxonomy::fold`):
+//!   1. a kind with rows            → itself
+//!   2. `bridge_aliases`            → its target (`panic`, `fear` → `anxiety`, …)
+//!   3. the emotion taxonomy        → that kind's default bridge, then 1–2 again
+//!   4. otherwise                   → `default`
+//!
+//! Profiles: rows with a `profile` (a person, e.g. `Raz`) override the global tier
+//! step by step — empty steps fall through to the global row for that intensity.
+//! Global rows always carry every step.
+//!
+//! Endpoints (mounted under `/bridges`)
+//!   GET    /bridges                 → rows (filters: kind, profile; `profile=` = global only)
+//!   POST   /bridges                 → { kind, profile?, min_intensity?, pattern?, hint?,
+//!                                       breath?, doorway?, anchor? } → 201
+//!   GET    /bridges/suggest         → ?kind=&intensity=&who= → the resolved bridge
+//!   GET    /bridges/aliases         → [{ alias, kind }]
+//!   PUT    /bridges/aliases/:alias  → { kind }
+//!   DELETE /bridges/aliases/:alias  → 204
+//!   GET    /bridges/:id             → one row
+//!   PATCH  /bridges/:id             → partial update ("" clears a profile row's step)
+//!   DELETE /bridges/:id             → 204 (the last global `default` tier is kept)
+
+use crate::taxonomy::fold;
+use crate::AppState;
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    routing::{get, put},
+    Json, Router,
+};
+use rusqlite::OptionalExtension;
+use serde::{Deserialize, Serialize};
+
+type ApiErr = (StatusCode, String);
+
+fn unprocessable(msg: impl Into<String>) -> ApiErr {
+    (StatusCode::UNPROCESSABLE_ENTITY, msg.into())
+}
+
+fn internal(e: impl std::fmt::Display) -> ApiErr {
+    (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+}
+
+/// Kind used when nothing else matches.
+pub const DEFAULT_KIND: &str = "default";
+
+// (kind, min_intensity, pattern, hint, breath, doorway, anchor)
+type SeedRow = (
+    &'static str,
+    f64,
+    &'static str,
+    &'static str,
+    &'static str,
+    &'static str,
+    &'static str,
+);
+
+#[rustfmt::skip]
+const SEEDS: &[SeedRow] = &[
+    ("anxiety", 0.0, "stabilize-first", "Double exhale, orient to room",
+        "double_exhale × 6", "sip water, feet on floor", "Name 3 objects you see."),
+    ("anxiety", 0.6, "stabilize-first", "Box breath, feet on floor, water sip",
+        "box: in4-hold4-out6 × 4", "sip water, feet on floor", "Name 3 objects you see."),
+    ("anger", 0.0, "decharge-then-name", "Long exhale, shake arms 30s, soften jaw",
+        "in4-out8 × 6", "shake arms 30s, step outside", "Lower shoulders, soften jaw."),
+    ("shame", 0.0, "worth-reminder", "4–6 breath, hand to heart, 3 facts (no story)",
+        "4-6 breath × 6", "write 3 objective facts (no story)", "Hand over heart: 'still worthy'."),
+    ("paradox", 0.0, "hold-both", "Even breath; one effort, one gift, no comparison",
+        "in4-out4 × 8", "touch ground + name 1 effort, 1 gift (no comparison)",
+        "Whisper: 'neither above nor below — simply alive.'"),
+    ("gratitude", 0.0, "land-in-thanks", "Long exhale; three one-line gratitudes",
+        "soft inhale, long exhale × 3", "write 3 one-line gratitudes", "🌬️ whisper: I am already held."),
+    ("attachment_test", 0.0, "ask-not-test", "Name the fear plainly; ask for reassurance, not proof",
+        "in4-hold2-out6 × 3", "say: 'I feel scared of X. Can you reassure me?'", "One honest request, then pause."),
+    ("sibling_trust", 0.0, "boundary-then-bridge", "State one boundary; decline forever-pact renewal",
+        "double_exhale × 6", "write a one-sentence boundary", "Love stays; pact ends."),
+    ("parent_planted", 0.0, "language-interrupt", "Name the parental line; replace with a present-tense truth",
+        "4-6 breath × 6", "say: 'That’s mom’s line; my line is…'", "Author the present."),
+    ("over_analysis", 0.0, "close-the-loop", "Set 2‑min timer; one next step; archive and stop",
+        "in4-out6 × 6", "start a 2‑minute timer", "Clarity lands, then rest."),
+    ("phantom_rival", 0.0, "reality-then-ask", "In 3 / Out 6; list 3 facts vs 3 guesses; then ask, don’t accuse",
+        "in3-out6 × 6", "write 3 facts vs 3 guesses; one clear ask", "Presence over phantom."),
+    ("default", 0.0, "return-to-center", "Stand up, shoulder roll, one true sentence",
+        "double_exhale × 6", "stand_up + shoulder_roll", "Return to center."),
+];
+
+const ALIASES: &[(&str, &str)] = &[
+    ("fear", "anxiety"),
+    ("panic", "anxiety"),
+    ("attachment_testing", "attachment_test"),
+    ("attachment", "attachment_test"),
+    ("sibling", "sibling_trust"),
+    ("parent", "parent_planted"),
+    ("analysis", "over_analysis"),
+    ("comparison_jealousy", "phantom_rival"),
+    ("jealousy", "phantom_rival"),
+];
+
+/// Insert the compiled-in tiers and aliases. Called once from `db::ensure_schema`.
+pub fn seed(c: &rusqlite::Connection) -> rusqlite::Result<()> {
+    let now = chrono::Utc::now().to_rfc3339();
+    for (kind, min, pattern, hint, breath, doorway, anchor) in SEEDS {
+        c.execute(
+            "INSERT OR IGNORE INTO bridges(kind, profile, min_intensity, pattern, hint, breath,
+               doorway, anchor, updated_at)
+             VALUES(?1, '', ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            rusqlite::params![kind, min, pattern, hint, breath, doorway, anchor, now],
+        )?;
+    }
+    for (alias, kind) in ALIASES {
+        c.execute(
+            "INSERT OR IGNORE INTO bridge_aliases(alias, kind) VALUES(?1, ?2)",
+            rusqlite::params![alias, kind],
+        )?;
+    }
+    Ok(())
+}
+
+/// A stored tier. Global rows (`profile: None`) carry every step.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct BridgeRow {
+    pub id: i64,
+    pub kind: String,
+    pub profile: Option<String>,
+    pub min_intensity: f64,
+    pub pattern: Option<String>,
+    pub hint: Option<String>,
+    pub breath: Option<String>,
+    pub doorway: Option<String>,
+    pub anchor: Option<String>,
+    pub updated_at: String,
+}
+
+const COLS: &str =
+    "id, kind, profile, min_intensity, pattern, hint, breath, doorway, anchor, updated_at";
+
+fn row_to_bridge(r: &rusqlite::Row) -> rusqlite::Result<BridgeRow> {
+    let profile: String = r.get(2)?;
+    Ok(BridgeRow {
+        id: r.get(0)?,
+        kind: r.get(1)?,
+        profile: (!profile.is_empty()).then_some(profile),
+        min_intensity: r.get(3)?,
+        pattern: r.get(4)?,
+        hint: r.get(5)?,
+        breath: r.get(6)?,
+        doorway: r.get(7)?,
+        anchor: r.get(8)?,
+        updated_at: r.get(9)?,
+    })
+}
+
+pub fn get_one(c: &rusqlite::Connection, id: i64) -> rusqlite::Result<Option<BridgeRow>> {
+    c.query_row(
+        &format!("SELECT {COLS} FROM bridges WHERE id = ?1"),
+        [id],
+        row_to_bridge,
+    )
+    .optional()
+}
+
+/// Rows by kind and/or profile (`Some("")` = global only), kind then tier order.
+pub fn list(
+    c: &rusqlite::Connection,
+    kind: Option<&str>,
+    profile: Option<&str>,
+) -> rusqlite::Result<Vec<BridgeRow>> {
+    let kind = kind.map(fold);
+    let profile = profile.map(str::trim);
+    c.prepare(&format!(
+        "SELECT {COLS} FROM bridges
+         WHERE (?1 IS NULL OR kind = ?1) AND (?2 IS NULL OR profile = ?2)
+         ORDER BY kind, profile, min_intensity"
+    ))?
+    .query_map(rusqlite::params![kind, profile], row_to_bridge)?
+    .collect()
+}
+
+fn has_rows(c: &rusqlite::Connection, kind: &str) -> rusqlite::Result<bool> {
+    Ok(c.query_row(
+        "SELECT 1 FROM bridges WHERE kind = ?1 AND profile = '' LIMIT 1",
+        [kind],
+        |_| Ok(()),
+    )
+    .optional()?
+    .is_some())
+}
+
+fn alias_target(c: &rusqlite::Connection, key: &str) -> rusqlite::Result<Option<String>> {
+    c.query_row(
+        "SELECT kind FROM bridge_aliases WHERE alias = ?1",
+        [key],
+        |r| r.get(0),
+    )
+    .optional()
+}
+
+/// The bridge kind whose tiers answer `raw` (see the module header for the order).
+pub fn resolve_kind(c: &rusqlite::Connection, raw: &str) -> rusqlite::Result<String> {
+    let direct = |key: &str| -> rusqlite::Result<Option<String>> {
+        if has_rows(c, key)? {
+            return Ok(Some(key.to_string()));
+        }
+        alias_target(c, key)
+    };
+    let key = fold(raw);
+    if let Some(kind) = direct(&key)? {
+        return Ok(kind);
+    }
+    if let Some(k) = crate::taxonomy::resolve(c, raw)? {
+        if let Some(kind) = direct(&fold(k.bridge_kind()))? {
+            return Ok(kind);
+        }
+    }
+    Ok(DEFAULT_KIND.to_string())
+}
+
+/// The highest tier at or below `intensity` (the lowest tier when all are above it).
+fn tier(
+    c: &rusqlite::Connection,
+    kind: &str,
+    profile: &str,
+    intensity: f64,
+) -> rusqlite::Result<Option<BridgeRow>> {
+    let rows = c
+        .prepare(&format!(
+            "SELECT {COLS} FROM bridges WHERE kind = ?1 AND profile = ?2 ORDER BY min_intensity"
+        ))?
+        .query_map(rusqlite::params![kind, profile], row_to_bridge)?
+        .collect::<Result<Vec<_>, _>>()?;
+    let below = rows
+        .iter()
+        .rev()
+        .find(|r| r.min_intensity <= intensity)
+        .cloned();
+    Ok(below.or_else(|| rows.into_iter().next()))
+}
+
+/// What callers get back: the steps plus where they came from.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct Bridge {
+    /// Bridge kind that answered (after aliases / taxonomy).
+    pub kind: String,
+    /// `min_intensity` of the global tier used.
+    pub tier: f64,
+    /// Set when a profile override changed at least one step.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+    pub pattern: String,
+    pub hint: String,
+    pub breath: String,
+    pub doorway: String,
+    pub anchor: String,
+}
+
+/// Resolve kind → tier → profile override. Intensity is clamped to 0..1 (NaN → 0.5).
+pub fn suggest(
+    c: &rusqlite::Connection,
+    raw_kind: &str,
+    intensity: f64,
+    profile: Option<&str>,
+) -> rusqlite::Result<Bridge> {
+    let intensity = if intensity.is_finite() {
+        intensity.clamp(0.0, 1.0)
+    } else {
+        0.5
+    };
+    let mut kind = resolve_kind(c, raw_kind)?;
+    let base = match tier(c, &kind, "", intensity)? {
+        Some(b) => b,
+        None => {
+            kind = DEFAULT_KIND.to_string();
+            tier(c, &kind, "", intensity)?.unwrap_or_else(|| BridgeRow {
+                id: 0,
+                kind: kind.clone(),
+                profile: None,
+                min_intensity: 0.0,
+                pattern: None,
+                hint: None,
+                breath: None,
+                doorway: None,
+                anchor: None,
+                updated_at: String::new(),
+            })
+        }
+    };
+    let over = match profile.map(str::trim).filter(|p| !p.is_empty()) {
+        Some(p) => tier(c, &kind, p, intensity)?,
+        None => None,
+    };
+    let step = |pick: fn(&BridgeRow) -> &Option<String>| -> String {
+        over.as_ref()
+            .and_then(|o| pick(o).clone())
+            .or_else(|| pick(&base).clone())
+            .unwrap_or_default()
+    };
+    Ok(Bridge {
+        tier: base.min_intensity,
+        profile: over.as_ref().and_then(|o| o.profile.clone()),
+        pattern: step(|r| &r.pattern),
+        hint: step(|r| &r.hint),
+        breath: step(|r| &r.breath),
+        doorway: step(|r| &r.doorway),
+        anchor: step(|r| &r.anchor),
+        kind,
+    })
+}
+
+/// Why a write was refused.
+#[derive(Debug, PartialEq)]
+pub enum BridgeError {
+    Missing,
+    Conflict(String),
+    Invalid(String),
+}
+
+/// Body for `POST /bridges`.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct BridgeIn {
+    pub kind: String,
+    #[serde(default)]
+    pub profile: Option<String>,
+    #[serde(default)]
+    pub min_intensity: Option<f64>,
+    #[serde(default)]
+    pub pattern: Option<String>,
+    #[serde(default)]
+    pub hint: Option<String>,
+    #[serde(default)]
+    pub breath: Option<String>,
+    #[serde(default)]
+    pub doorway: Option<String>,
+    #[serde(default)]
+    pub anchor: Option<String>,
+}
+
+fn clean(v: &Option<String>) -> Option<String> {
+    v.as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+}
+
+fn check_min(min: f64) -> Result<(), BridgeError> {
+    if (0.0..=1.0).contains(&min) {
+        Ok(())
+    } else {
+        Err(BridgeError::Invalid("min_intensity must be 0..1".into()))
+    }
+}
+
+fn check_complete(r: &BridgeRow) -> Result<(), BridgeError> {
+    let steps = [&r.pattern, &r.hint, &r.breath, &r.doorway, &r.anchor];
+    if r.profile.is_none() && steps.iter().any(|s| s.is_none()) {
+        return Err(BridgeError::Invalid(
+            "global tiers need pattern, hint, breath, doorway and anchor".into(),
+        ));
+    }
+    Ok(())
+}
+
+fn is_conflict(e: &rusqlite::Error) -> bool {
+    matches!(
+        e,
+        rusqlite::Error::SqliteFailure(f, _) if f.code == rusqlite::ErrorCode::ConstraintViolation
+    )
+}
+
+pub fn create(
+    c: &rusqlite::Connection,
+    input: &BridgeIn,
+    now: &str,
+) -> rusqlite::Result<Result<BridgeRow, BridgeError>> {
+    let kind = fold(&input.kind);
+    if kind.is_empty() {
+        return Ok(Err(BridgeError::Invalid("kind cannot be empty".into())));
+    }
+    let min = input.min_intensity.unwrap_or(0.0);
+    if let Err(e) = check_min(min) {
+        return Ok(Err(e));
+    }
+    let row = BridgeRow {
+        id: 0,
+        kind,
+        profile: clean(&input.profile),
+        min_intensity: min,
+        pattern: clean(&input.pattern),
+        hint: clean(&input.hint),
+        breath: clean(&input.breath),
+        doorway: clean(&input.doorway),
+        anchor: clean(&input.anchor),
+        updated_at: now.to_string(),
+    };
+    if let Err(e) = check_complete(&row) {
+        return Ok(Err(e));
+    }
+    let inserted = c.execute(
+        "INSERT INTO bridges(kind, profile, min_intensity, pattern, hint, breath, doorway, anchor,
+           updated_at)
+         VALUES(?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        rusqlite::params![
+            row.kind,
+            row.profile.as_deref().unwrap_or(""),
+            row.min_intensity,
+            row.pattern,
+            row.hint,
+            row.breath,
+            row.doorway,
+            row.anchor,
+            now
+        ],
+    );
+    match inserted {
+        Err(e) if is_conflict(&e) => Ok(Err(BridgeError::Conflict(
+            "a tier with this kind, profile and min_intensity exists".into(),
+        ))),
+        Err(e) => Err(e),
+        Ok(_) => Ok(get_one(c, c.last_insert_rowid())?.ok_or(BridgeError::Missing)),
+    }
+}
+
+/// PATCH body; absent fields are kept. `""` clears a profile row's step.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct BridgePatch {
+    #[serde(default)]
+    pub min_intensity: Option<f64>,
+    #[serde(default)]
+    pub pattern: Option<String>,
+    #[serde(default)]
+    pub hint: Option<String>,
+    #[serde(default)]
+    pub breath: Option<String>,
+    #[serde(default)]
+    pub doorway: Option<String>,
+    #[serde(default)]
+    pub anchor: Option<String>,
+}
+
+pub fn update(
+    c: &rusqlite::Connection,
+    id: i64,
+    patch: &BridgePatch,
+    now: &str,
+) -> rusqlite::Result<Result<BridgeRow, BridgeError>> {
+    let Some(mut row) = get_one(c, id)? else {
+        return Ok(Err(BridgeError::Missing));
+    };
+    if let Some(min) = patch.min_intensity {
+        if let Err(e) = check_min(min) {
+            return Ok(Err(e));
+        }
+        row.min_intensity = min;
+    }
+    for (field, value) in [
+        (&mut row.pattern, &patch.pattern),
+        (&mut row.hint, &patch.hint),
+        (&mut row.breath, &patch.breath),
+        (&mut row.doorway, &patch.doorway),
+        (&mut row.anchor, &patch.anchor),
+    ] {
+        if value.is_some() {
+            *field = clean(value);
+        }
+    }
+    if let Err(e) = check_complete(&row) {
+        return Ok(Err(e));
+    }
+    let updated = c.execute(
+        "UPDATE bridges SET min_intensity = ?1, pattern = ?2, hint = ?3, breath = ?4,
+           doorway = ?5, anchor = ?6, updated_at = ?7
+         WHERE id = ?8",
+        rusqlite::params![
+            row.min_intensity,
+            row.pattern,
+            row.hint,
+            row.breath,
+            row.doorway,
+            row.anchor,
+            now,
+            id
+        ],
+    );
+    match updated {
+        Err(e) if is_conflict(&e) => Ok(Err(BridgeError::Conflict(
+            "a tier with this kind, profile and min_intensity exists".into(),
+        ))),
+        Err(e) => Err(e),
+        Ok(_) => Ok(get_one(c, id)?.ok_or(BridgeError::Missing)),
+    }
+}
+
+pub fn delete(c: &rusqlite::Connection, id: i64) -> rusqlite::Result<Result<(), BridgeError>> {
+    let Some(row) = get_one(c, id)? else {
+        return Ok(Err(BridgeError::Missing));
+    };
+    if row.kind == DEFAULT_KIND && row.profile.is_none() {
+        let globals: i64 = c.query_row(
+            "SELECT COUNT(*) FROM bridges WHERE kind = ?1 AND profile = ''",
+            [DEFAULT_KIND],
+            |r| r.get(0),
+        )?;
+        if globals <= 1 {
+            return Ok(Err(BridgeError::Invalid(
+                "the last global default tier cannot be deleted".into(),
+            )));
+        }
+    }
+    c.execute("DELETE FROM bridges WHERE id = ?1", [id])?;
+    Ok(Ok(()))
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct Alias {
+    pub alias: String,
+    pub kind: String,
+}
+
+pub fn aliases(c: &rusqlite::Connection) -> rusqlite::Result<Vec<Alias>> {
+    c.prepare("SELECT alias, kind FROM bridge_aliases ORDER BY alias")?
+        .query_map([], |r| {
+            Ok(Alias {
+                alias: r.get(0)?,
+                kind: r.get(1)?,
+            })
+        })?
+        .collect()
+}
+
+/// Point `alias` at a kind that has global tiers.
+pub fn set_alias(
+    c: &rusqlite::Connection,
+    alias: &str,
+    kind: &str,
+) -> rusqlite::Result<Result<Alias, BridgeError>> {
+    let (alias, kind) = (fold(alias), fold(kind));
+    if alias.is_empty() || alias == kind {
+        return Ok(Err(BridgeError::Invalid(
+            "alias must be non-empty and differ from kind".into(),
+        )));
+    }
+    if !has_rows(c, &kind)? {
+        return Ok(Err(BridgeError::Invalid(format!(
+            "'{kind}' has no global tiers"
+        ))));
+    }
+    if has_rows(c, &alias)? {
+        return Ok(Err(BridgeError::Conflict(format!(
+            "'{alias}' is a bridge kind with its own tiers"
+        ))));
+    }
+    c.execute(
+        "INSERT INTO bridge_aliases(alias, kind) VALUES(?1, ?2)
+         ON CONFLICT(alias) DO UPDATE SET kind = excluded.kind",
+        rusqlite::params![alias, kind],
+    )?;
+    Ok(Ok(Alias { alias, kind }))
+}
+
+pub fn remove_alias(c: &rusqlite::Connection, alias: &str) -> rusqlite::Result<bool> {
+    Ok(c.execute("DELETE FROM bridge_aliases WHERE alias = ?1", [fold(alias)])? > 0)
+}
+
+fn http_err(e: BridgeError) -> ApiErr {
+    match e {
+        BridgeError::Missing => (StatusCode::NOT_FOUND, "unknown bridge".into()),
+        BridgeError::Conflict(msg) => (StatusCode::CONFLICT, msg),
+        BridgeError::Invalid(msg) => unprocessable(msg),
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ListParams {
+    #[serde(default)]
+    kind: Option<String>,
+    /// Empty = global rows only.
+    #[serde(default)]
+    profile: Option<String>,
+}
+
+/// GET /bridges?kind=&profile=
+async fn list_http(
+    State(state): State<AppState>,
+    Query(q): Query<ListParams>,
+) -> Result<Json<Vec<BridgeRow>>, ApiErr> {
+    state
+        .db
+        .0
+        .call(move |c| Ok(list(c, q.kind.as_deref(), q.profile.as_deref())?))
+        .await
+        .map(Json)
+        .map_err(internal)
+}
+
+/// POST /bridges
+async fn create_http(
+    State(state): State<AppState>,
+    Json(input): Json<BridgeIn>,
+) -> Result<(StatusCode, Json<BridgeRow>), ApiErr> {
+    let now = chrono::Utc::now().to_rfc3339();
+    state
+        .db
+        .0
+        .call(move |c| Ok(create(c, &input, &now)?))
+        .await
+        .map_err(internal)?
+        .map(|b| (StatusCode::CREATED, Json(b)))
+        .map_err(http_err)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SuggestParams {
+    /// e.g. "panic", "anxiety", "anger", "shame"
+    #[serde(default)]
+    pub kind: Option<String>,
+    /// 0.0..=1.0; default 0.5 if omitted
+    #[serde(default)]
+    pub intensity: Option<f64>,
+    /// Profile whose overrides apply.
+    #[serde(default)]
+    pub who: Option<String>,
+}
+
+/// GET /bridges/suggest?kind=&intensity=&who=
+async fn suggest_http(
+    State(state): State<AppState>,
+    Query(q): Query<SuggestParams>,
+) -> Result<Json<Bridge>, ApiErr> {
+    let kind = q.kind.unwrap_or_else(|| DEFAULT_KIND.to_string());
+    let intensity = q.intensity.unwrap_or(0.5);
+    state
+        .db
+        .0
+        .call(move |c| Ok(suggest(c, &kind, intensity, q.who.as_deref())?))
+        .await
+        .map(Json)
+        .map_err(internal)
+}
+
+/// GET /bridges/:id
+async fn get_http(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+) -> Result<Json<BridgeRow>, ApiErr> {
+    state
+        .db
+        .0
+        .call(move |c| Ok(get_one(c, id)?))
+        .await
+        .map_err(internal)?
+        .map(Json)
+        .ok_or_else(|| http_err(BridgeError::Missing))
+}
+
+/// PATCH /bridges/:id
+async fn update_http(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    Json(patch): Json<BridgePatch>,
+) -> Result<Json<BridgeRow>, ApiErr> {
+    let now = chrono::Utc::now().to_rfc3339();
+    state
+        .db
+        .0
+        .call(move |c| Ok(update(c, id, &patch, &now)?))
+        .await
+        .map_err(internal)?
+        .map(Json)
+        .map_err(http_err)
+}
+
+/// DELETE /bridges/:id
+async fn delete_http(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+) -> Result<StatusCode, ApiErr> {
+    state
+        .db
+        .0
+        .call(move |c| Ok(delete(c, id)?))
+        .await
+        .map_err(internal)?
+        .map(|()| StatusCode::NO_CONTENT)
+        .map_err(http_err)
+}
+
+/// GET /bridges/aliases
+async fn aliases_http(State(state): State<AppState>) -> Result<Json<Vec<Alias>>, ApiErr> {
+    state
+        .db
+        .0
+        .call(|c| Ok(aliases(c)?))
+        .await
+        .map(Json)
+        .map_err(internal)
+}
+
+#[derive(Debug, Deserialize)]
+struct AliasIn {
+    kind: String,
+}
+
+/// PUT /bridges/aliases/:alias
+async fn set_alias_http(
+    State(state): State<AppState>,
+    Path(alias): Path<String>,
+    Json(input): Json<AliasIn>,
+) -> Result<Json<Alias>, ApiErr> {
+    state
+        .db
+        .0
+        .call(move |c| Ok(set_alias(c, &alias, &input.kind)?))
+        .await
+        .map_err(internal)?
+        .map(Json)
+        .map_err(http_err)
+}
+
+/// DELETE /bridges/aliases/:alias
+async fn remove_alias_http(
+    State(state): State<AppState>,
+    Path(alias): Path<String>,
+) -> Result<StatusCode, ApiErr> {
+    let removed = state
+        .db
+        .0
+        .call(move |c| Ok(remove_alias(c, &alias)?))
+        .await
+        .map_err(internal)?;
+    if !removed {
+        return Err((StatusCode::NOT_FOUND, "unknown alias".into()));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_http).post(create_http))
+        .route("/suggest", get(suggest_http))
+        .route("/aliases", get(aliases_http))
+        .route(
+            "/aliases/:alias",
+            put(set_alias_http).delete(remove_alias_http),
+        )
+        .route("/:id", get(get_http).patch(update_http).delete(delete_http))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conn() -> rusqlite::Connection {
+        let mut c = rusqlite::Connection::open_in_memory().unwrap();
+        crate::db::ensure_schema(&mut c).unwrap();
+        c
+    }
+
+    // (kind, intensity, kind answered, pattern, breath, doorway, anchor) — the cases the
+    // old `emotions::bridge_table` and `patterns::bridge_table` tests pinned.
+    #[rustfmt::skip]
+    const FIXTURES: &[(&str, f64, &str, &str, &str, &str, &str)] = &[
+        ("anxiety", 0.3, "anxiety", "stabilize-first", "double_exhale × 6", "sip water, feet on floor", "Name 3 objects you see."),
+        ("fear", 0.8, "anxiety", "stabilize-first", "box: in4-hold4-out6 × 4", "sip water, feet on floor", "Name 3 objects you see."),
+        ("panic", 0.9, "anxiety", "stabilize-first", "box: in4-hold4-out6 × 4", "sip water, feet on floor", "Name 3 objects you see."),
+        ("anger", 0.5, "anger", "decharge-then-name", "in4-out8 × 6", "shake arms 30s, step outside", "Lower shoulders, soften jaw."),
+        ("shame", 0.2, "shame", "worth-reminder", "4-6 breath × 6", "write 3 objective facts (no story)", "Hand over heart: 'still worthy'."),
+        ("paradox", 0.5, "paradox", "hold-both", "in4-out4 × 8", "touch ground + name 1 effort, 1 gift (no comparison)", "Whisper: 'neither above nor below — simply alive.'"),
+        ("gratitude", 0.4, "gratitude", "land-in-thanks", "soft inhale, long exhale × 3", "write 3 one-line gratitudes", "🌬️ whisper: I am already held."),
+        ("attachment_test", 0.5, "attachment_test", "ask-not-test", "in4-hold2-out6 × 3", "say: 'I feel scared of X. Can you reassure me?'", "One honest request, then pause."),
+        ("attachment-testing", 0.5, "attachment_test", "ask-not-test", "in4-hold2-out6 × 3", "say: 'I feel scared of X. Can you reassure me?'", "One honest request, then pause."),
+        ("sibling_trust", 0.7, "sibling_trust", "boundary-then-bridge", "double_exhale × 6", "write a one-sentence boundary", "Love stays; pact ends."),
+        ("parent_planted", 0.4, "parent_planted", "language-interrupt", "4-6 breath × 6", "say: 'That’s mom’s line; my line is…'", "Author the present."),
+        ("over_analysis", 0.3, "over_analysis", "close-the-loop", "in4-out6 × 6", "start a 2‑minute timer", "Clarity lands, then rest."),
+        ("phantom_rival", 0.5, "phantom_rival", "reality-then-ask", "in3-out6 × 6", "write 3 facts vs 3 guesses; one clear ask", "Presence over phantom."),
+        ("unknown", 0.9, "default", "return-to-center", "double_exhale × 6", "stand_up + shoulder_roll", "Return to center."),
+        ("unknown-kind", 0.4, "default", "return-to-center", "double_exhale × 6", "stand_up + shoulder_roll", "Return to center."),
+        // clamping and case
+        ("anxiety", -5.0, "anxiety", "stabilize-first", "double_exhale × 6", "sip water, feet on floor", "Name 3 objects you see."),
+        ("anxiety", 5.0, "anxiety", "stabilize-first", "box: in4-hold4-out6 × 4", "sip water, feet on floor", "Name 3 objects you see."),
+        ("AnXiEtY", 0.7, "anxiety", "stabilize-first", "box: in4-hold4-out6 × 4", "sip water, feet on floor", "Name 3 objects you see."),
+        // through the emotion taxonomy
+        ("anxious", 0.2, "anxiety", "stabilize-first", "double_exhale × 6", "sip water, feet on floor", "Name 3 objects you see."),
+        ("vinovăție", 0.5, "shame", "worth-reminder", "4-6 breath × 6", "write 3 objective facts (no story)", "Hand over heart: 'still worthy'."),
+        ("jealous", 0.5, "phantom_rival", "reality-then-ask", "in3-out6 × 6", "write 3 facts vs 3 guesses; one clear ask", "Presence over phantom."),
+    ];
+
+    #[test]
+    fn seeded_library_matches_fixtures() {
+        let c = conn();
+        for (raw, intensity, kind, pattern, breath, doorway, anchor) in FIXTURES {
+            let b = suggest(&c, raw, *intensity, None).unwrap();
+            let got = (
+                b.kind.as_str(),
+                b.pattern.as_str(),
+                b.breath.as_str(),
+                b.doorway.as_str(),
+                b.anchor.as_str(),
+            );
+            assert_eq!(
+                got,
+                (*kind, *pattern, *breath, *doorway, *anchor),
+                "{raw} @ {intensity}"
+            );
+            assert!(!b.hint.is_empty(), "{raw}");
+        }
+    }
+
+    #[test]
+    fn hints_follow_the_tier() {
+        let c = conn();
+        assert_eq!(
+            suggest(&c, "panic", 0.59, None).unwrap().hint,
+            "Double exhale, orient to room"
+        );
+        let high = suggest(&c, "panic", 0.6, None).unwrap();
+        assert_eq!(
+            (high.tier, high.hint.as_str()),
+            (0.6, "Box breath, feet on floor, water sip")
+        );
+        assert!(suggest(&c, "shame", 0.4, None)
+            .unwrap()
+            .hint
+            .contains("facts"));
+        assert!(suggest(&c, "over_analysis", 0.3, None)
+            .unwrap()
+            .hint
+            .contains("2‑min"));
+        assert_eq!(suggest(&c, "fear", f64::NAN, None).unwrap().tier, 0.0);
+    }
+
+    #[test]
+    fn profile_overrides_fill_in_over_the_global_tier() {
+        let c = conn();
+        let now = "2025-09-01T00:00:00+00:00";
+        let over = create(
+            &c,
+            &BridgeIn {
+                kind: "Anxiety".into(),
+                profile: Some("Raz".into()),
+                min_intensity: Some(0.0),
+                doorway: Some("step onto the balcony".into()),
+                ..Default::default()
+            },
+            now,
+        )
+        .unwrap()
+        .unwrap();
+        let raz = suggest(&c, "panic", 0.9, Some("Raz")).unwrap();
+        assert_eq!(raz.profile.as_deref(), Some("Raz"));
+        assert_eq!(raz.doorway, "step onto the balcony");
+        assert_eq!(
+            raz.breath, "box: in4-hold4-out6 × 4",
+            "global tier still picks the breath"
+        );
+        let nico = suggest(&c, "panic", 0.9, Some("Nico")).unwrap();
+        assert_eq!(
+            (nico.profile, nico.doorway.as_str()),
+            (None, "sip water, feet on floor")
+        );
+
+        // global tiers must stay complete; duplicates conflict
+        let incomplete = BridgeIn {
+            kind: "calm_down".into(),
+            breath: Some("x".into()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            create(&c, &incomplete, now).unwrap(),
+            Err(BridgeError::Invalid(_))
+        ));
+        let dup = BridgeIn {
+            kind: "anxiety".into(),
+            profile: Some("Raz".into()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            create(&c, &dup, now).unwrap(),
+            Err(BridgeError::Conflict(_))
+        ));
+        let global = list(&c, Some("anxiety"), Some("")).unwrap()[0].id;
+        let clear = BridgePatch {
+            anchor: Some(String::new()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            update(&c, global, &clear, now).unwrap(),
+            Err(BridgeError::Invalid(_))
+        ));
+        assert!(update(&c, over.id, &clear, now).unwrap().is_ok());
+
+        // the last global default stays
+        let default = list(&c, Some("default"), Some("")).unwrap()[0].id;
+        assert!(matches!(
+            delete(&c, default).unwrap(),
+            Err(BridgeError::Invalid(_))
+        ));
+        assert!(delete(&c, over.id).unwrap().is_ok());
+        assert_eq!(delete(&c, over.id).unwrap(), Err(BridgeError::Missing));
+    }
+
+    #[test]
+    fn aliases_point_at_kinds_with_tiers() {
+        let c = conn();
+        assert!(set_alias(&c, "Overthinking", "over_analysis")
+            .unwrap()
+            .is_ok());
+        assert_eq!(
+            suggest(&c, "overthinking", 0.5, None).unwrap().kind,
+            "over_analysis"
+        );
+        assert!(matches!(
+            set_alias(&c, "x", "nowhere").unwrap(),
+            Err(BridgeError::Invalid(_))
+        ));
+        assert!(matches!(
+            set_alias(&c, "anger", "shame").unwrap(),
+            Err(BridgeError::Conflict(_))
+        ));
+        assert!(remove_alias(&c, "overthinking").unwrap());
+        assert_eq!(
+            suggest(&c, "overthinking", 0.5, None).unwrap().kind,
+            "default"
+        );
+    }
+}
@@ -0,0 +1,911 @@
+//! Energy calendar — plan by band, not by hour
+//! -------------------------------------------
+//! Whisper: "guard the band, and the work guards you." 🗓️
+//!
+//! The pattern in docs/patterns/energy-calendar.md, on top of `energy_marks`:
+//!   • `energy_bands` — E0–E4 by default, each from `min_level` up to the next band, with
+//!     what it is good for and what to avoid. Seeded once from `SEEDS`; replaced as a
+//!     whole set through `PUT /energy/calendar/bands`.
+//!   • `energy_tasks` — work tagged with the band it needs ("review PR — E2+").
+//!   • a person's energy is their latest mark by `ts`, whatever its kind.
+//!   • when a mark moves the person into another band, `/energy/mark` writes an
+//!     `energy_transitions` row and returns it. The transitions sweep broadcasts new rows
+//!     (band only, never the level or note) on the bus and as `energy.transition`
+//!     relational events (see transitions.rs). A person's first mark is not a transition.
+//!
+//! Endpoints
+//!   GET    /energy/now?who=                  → { who, level, kind, ts, band, tasks, later }
+//!   GET    /energy/calendar/bands            → bands, highest first
+//!   PUT    /energy/calendar/bands            → [{ code, name, min_level, felt?, good_for?,
+//!                                               avoid? }]; one band starts at 0
+//!   GET    /energy/calendar/tasks?who=&all=  → open tasks (`all=true` adds done ones)
+//!   POST   /energy/calendar/tasks            → { title, band, minutes?, who? } → 201
+//!   PATCH  /energy/calendar/tasks/:id        → { title?, band?, minutes?, who?, done? }
+//!   DELETE /energy/calendar/tasks/:id        → 204
+//!   GET    /energy/calendar/transitions?who=&limit=
+
+use crate::AppState;
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    routing::get,
+    Json, Router,
+};
+use rusqlite::OptionalExtension;
+use serde::{Deserialize, Serialize};
+
+type ApiErr = (StatusCode, String);
+
+fn unprocessable(msg: impl Into<String>) -> ApiErr {
+    (StatusCode::UNPROCESSABLE_ENTITY, msg.into())
+}
+
+fn internal(e: impl std::fmt::Display) -> ApiErr {
+    (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+}
+
+/// "Keep it human: 4–5 max" — with a little room.
+const MAX_BANDS: usize = 7;
+/// The exit ritual offered when energy drops a band.
+pub const EXIT: &str = "water → shoulders → breath → 30s walk";
+
+// (code, name, min_level, felt, good_for, avoid)
+type SeedRow = (
+    &'static str,
+    &'static str,
+    f64,
+    &'static str,
+    &'static [&'static str],
+    &'static [&'static str],
+);
+
+#[rustfmt::skip]
+const SEEDS: &[SeedRow] = &[
+    ("E4", "Crown", 0.9, "bright, expansive, insightful",
+        &["architecture", "strategy", "naming"], &["inbox", "meetings"]),
+    ("E3", "Dragon", 0.7, "focused, sturdy",
+        &["deep work", "coding", "analysis"], &["context-thrash"]),
+    ("E2", "Play", 0.5, "curious, flexible",
+        &["reviews", "drafting", "pairing"], &["high-stakes decisions"]),
+    ("E1", "Life-force", 0.3, "steady but finite",
+        &["chores", "admin", "grooming"], &["commitments that create debt"]),
+    ("E0", "Void", 0.0, "empty/tired — needs refuel",
+        &["sleep", "breathwork", "walk", "water"], &["pushing through"]),
+];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Band {
+    pub code: String,
+    pub name: String,
+    pub min_level: f64,
+    /// Where the next band starts; absent on the top band.
+    #[serde(default, skip_deserializing, skip_serializing_if = "Option::is_none")]
+    pub max_level: Option<f64>,
+    #[serde(default)]
+    pub felt: Option<String>,
+    #[serde(default)]
+    pub good_for: Vec<String>,
+    #[serde(default)]
+    pub avoid: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Task {
+    pub id: i64,
+    pub title: String,
+    /// The band this task needs, or better.
+    pub band: String,
+    pub minutes: Option<i64>,
+    /// NULL = anyone.
+    pub who: Option<String>,
+    pub done_at: Option<String>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Transition {
+    pub id: i64,
+    pub ts: String,
+    pub who: String,
+    pub from: String,
+    pub to: String,
+    /// up | down
+    pub direction: String,
+    pub level: f64,
+    pub mark_id: i64,
+    /// The exit ritual, on the way down.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exit: Option<String>,
+}
+
+/// Why a write was refused.
+#[derive(Debug, PartialEq)]
+pub enum CalendarError {
+    Missing,
+    Conflict(String),
+    Invalid(String),
+}
+
+fn json_list(raw: String) -> Vec<String> {
+    serde_json::from_str(&raw).unwrap_or_default()
+}
+
+fn to_json(list: &[String]) -> String {
+    serde_json::to_string(list).unwrap_or_else(|_| "[]".into())
+}
+
+/// Insert `SEEDS` (called once from `db::ensure_schema`).
+pub fn seed(c: &rusqlite::Connection) -> rusqlite::Result<()> {
+    for (code, name, min, felt, good, avoid) in SEEDS {
+        let good: Vec<String> = good.iter().map(|s| s.to_string()).collect();
+        let avoid: Vec<String> = avoid.iter().map(|s| s.to_string()).collect();
+        c.execute(
+            "INSERT OR IGNORE INTO energy_bands(code, name, min_level, felt, good_for, avoid)
+             VALUES(?1, ?2, ?3, ?4, ?5, ?6)",
+            rusqlite::params![code, name, min, felt, to_json(&good), to_json(&avoid)],
+        )?;
+    }
+    Ok(())
+}
+
+/// Every band, highest first, with `max_level` filled in.
+pub fn bands(c: &rusqlite::Connection) -> rusqlite::Result<Vec<Band>> {
+    let mut out = c
+        .prepare(
+            "SELECT code, name, min_level, felt, good_for, avoid
+               FROM energy_bands ORDER BY min_level DESC",
+        )?
+        .query_map([], |r| {
+            Ok(Band {
+                code: r.get(0)?,
+                name: r.get(1)?,
+                min_level: r.get(2)?,
+                max_level: None,
+                felt: r.get(3)?,
+                good_for: json_list(r.get(4)?),
+                avoid: json_list(r.get(5)?),
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    for i in 1..out.len() {
+        out[i].max_level = Some(out[i - 1].min_level);
+    }
+    Ok(out)
+}
+
+/// Slack for marks stored while levels were f32: 0.7 was widened to 0.69999998
+/// and still belongs to the band starting at 0.7.
+const LEVEL_EPSILON: f64 = 1e-6;
+
+/// The band holding `level`; `bands` is highest first.
+pub fn band_for(bands: &[Band], level: f64) -> Option<&Band> {
+    bands
+        .iter()
+        .find(|b| level + LEVEL_EPSILON >= b.min_level)
+        .or_else(|| bands.last())
+}
+
+/// Replace every band. Codes are trimmed and uppercased; one band must start at 0.
+pub fn replace_bands(
+    c: &mut rusqlite::Connection,
+    input: Vec<Band>,
+) -> rusqlite::Result<Result<Vec<Band>, CalendarError>> {
+    let invalid = |m: &str| Ok(Err(CalendarError::Invalid(m.into())));
+    if input.is_empty() || input.len() > MAX_BANDS {
+        return invalid("between 1 and 7 bands");
+    }
+    let mut clean = Vec::new();
+    for b in input {
+        let code = b.code.trim().to_uppercase();
+        let name = b.name.trim().to_owned();
+        if code.is_empty() || name.is_empty() {
+            return invalid("every band needs a code and a name");
+        }
+        if !(0.0..=1.0).contains(&b.min_level) {
+            return invalid("min_level must be within 0..=1");
+        }
+        if clean.iter().any(|x: &Band| x.code == code) {
+            return invalid("band codes must be unique");
+        }
+        if clean.iter().any(|x: &Band| x.min_level == b.min_level) {
+            return invalid("band thresholds must be distinct");
+        }
+        clean.push(Band {
+            code,
+            name,
+            felt: b
+                .felt
+                .map(|f| f.trim().to_owned())
+                .filter(|f| !f.is_empty()),
+            ..b
+        });
+    }
+    if !clean.iter().any(|b| b.min_level == 0.0) {
+        return invalid("one band must start at 0");
+    }
+    let tx = c.transaction()?;
+    let mut orphaned = Vec::new();
+    {
+        let mut stmt =
+            tx.prepare("SELECT DISTINCT band FROM energy_tasks WHERE done_at IS NULL")?;
+        for code in stmt.query_map([], |r| r.get::<_, String>(0))? {
+            let code = code?;
+            if !clean.iter().any(|b| b.code == code) {
+                orphaned.push(code);
+            }
+        }
+    }
+    if !orphaned.is_empty() {
+        return Ok(Err(CalendarError::Conflict(format!(
+            "open tasks still need {}",
+            orphaned.join(", ")
+        ))));
+    }
+    tx.execute("DELETE FROM energy_bands", [])?;
+    for b in &clean {
+        tx.execute(
+            "INSERT INTO energy_bands(code, name, min_level, felt, good_for, avoid)
+             VALUES(?1, ?2, ?3, ?4, ?5, ?6)",
+            rusqlite::params![
+                b.code,
+                b.name,
+                b.min_level,
+                b.felt,
+                to_json(&b.good_for),
+                to_json(&b.avoid)
+            ],
+        )?;
+    }
+    tx.commit()?;
+    Ok(Ok(bands(c)?))
+}
+
+/// A person's latest mark by `ts`: (level, kind, ts).
+pub fn current(
+    c: &rusqlite::Connection,
+    who: &str,
+) -> rusqlite::Result<Option<(f64, String, String)>> {
+    c.query_row(
+        "SELECT level, kind, ts FROM energy_marks WHERE who = ?1
+          ORDER BY ts DESC, id DESC LIMIT 1",
+        [who],
+        |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)),
+    )
+    .optional()
+}
+
+/// The band code a person is in now.
+pub fn band_now(c: &rusqlite::Connection, who: &str) -> rusqlite::Result<Option<String>> {
+    let Some((level, _, _)) = current(c, who)? else {
+        return Ok(None);
+    };
+    Ok(band_for(&bands(c)?, level).map(|b| b.code.clone()))
+}
+
+/// Call after storing mark `mark_id` with the person's band from before it.
+/// Records and returns the crossing, if the mark moved them to another band.
+pub fn after_mark(
+    c: &rusqlite::Connection,
+    who: &str,
+    before: Option<String>,
+    mark_id: i64,
+) -> rusqlite::Result<Option<Transition>> {
+    let Some(from) = before else {
+        return Ok(None);
+    };
+    let Some((level, _, ts)) = current(c, who)? else {
+        return Ok(None);
+    };
+    let all = bands(c)?;
+    let Some(to) = band_for(&all, level) else {
+        return Ok(None);
+    };
+    if to.code == from {
+        return Ok(None);
+    }
+    // a band that was renamed away counts as the bottom
+    let rank = |code: &str| all.iter().position(|b| b.code == code).unwrap_or(all.len());
+    let direction = if rank(&to.code) < rank(&from) {
+        "up"
+    } else {
+        "down"
+    };
+    c.execute(
+        "INSERT INTO energy_transitions(ts, who, from_band, to_band, direction, level, mark_id)
+         VALUES(?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        rusqlite::params![ts, who, from, to.code, direction, level, mark_id],
+    )?;
+    Ok(Some(Transition {
+        id: c.last_insert_rowid(),
+        ts,
+        who: who.to_owned(),
+        from,
+        to: to.code.clone(),
+        direction: direction.into(),
+        level,
+        mark_id,
+        exit: (direction == "down").then(|| EXIT.to_string()),
+    }))
+}
+
+const TRANSITION_COLS: &str = "id, ts, who, from_band, to_band, direction, level, mark_id";
+
+fn row_to_transition(r: &rusqlite::Row) -> rusqlite::Result<Transition> {
+    let direction: String = r.get(5)?;
+    Ok(Transition {
+        id: r.get(0)?,
+        ts: r.get(1)?,
+        who: r.get(2)?,
+        from: r.get(3)?,
+        to: r.get(4)?,
+        exit: (direction == "down").then(|| EXIT.to_string()),
+        direction,
+        level: r.get(6)?,
+        mark_id: r.get(7)?,
+    })
+}
+
+pub fn transitions(
+    c: &rusqlite::Connection,
+    who: Option<&str>,
+    limit: i64,
+) -> rusqlite::Result<Vec<Transition>> {
+    c.prepare(&format!(
+        "SELECT {TRANSITION_COLS} FROM energy_transitions
+          WHERE ?1 IS NULL OR who = ?1 ORDER BY id DESC LIMIT ?2"
+    ))?
+    .query_map(rusqlite::params![who, limit], row_to_transition)?
+    .collect()
+}
+
+/// Transitions not broadcast yet, marked as published (oldest first).
+/// Any crossing not broadcast yet. A plain read, so idle sweeps never open a transaction.
+pub fn has_unpublished(c: &rusqlite::Connection) -> rusqlite::Result<bool> {
+    c.query_row(
+        "SELECT EXISTS(SELECT 1 FROM energy_transitions WHERE published_at IS NULL)",
+        [],
+        |r| r.get(0),
+    )
+}
+
+pub fn take_unpublished(
+    c: &mut rusqlite::Connection,
+    now: &str,
+) -> rusqlite::Result<Vec<Transition>> {
+    let tx = c.transaction()?;
+    let due = tx
+        .prepare(&format!(
+            "SELECT {TRANSITION_COLS} FROM energy_transitions
+              WHERE published_at IS NULL ORDER BY id LIMIT 100"
+        ))?
+        .query_map([], row_to_transition)?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    for t in &due {
+        tx.execute(
+            "UPDATE energy_transitions SET published_at = ?1 WHERE id = ?2",
+            rusqlite::params![now, t.id],
+        )?;
+    }
+    tx.commit()?;
+    Ok(due)
+}
+
+const TASK_COLS: &str = "id, title, band, minutes, who, done_at, created_at";
+
+fn row_to_task(r: &rusqlite::Row) -> rusqlite::Result<Task> {
+    Ok(Task {
+        id: r.get(0)?,
+        title: r.get(1)?,
+        band: r.get(2)?,
+        minutes: r.get(3)?,
+        who: r.get(4)?,
+        done_at: r.get(5)?,
+        created_at: r.get(6)?,
+    })
+}
+
+/// Tasks for `who` (theirs and anyone's), highest band first; done ones only with `all`.
+pub fn tasks(
+    c: &rusqlite::Connection,
+    who: Option<&str>,
+    all: bool,
+) -> rusqlite::Result<Vec<Task>> {
+    c.prepare(&format!(
+        "SELECT {TASK_COLS} FROM energy_tasks t
+          WHERE (?1 IS NULL OR t.who IS NULL OR t.who = ?1) AND (?2 OR t.done_at IS NULL)
+          ORDER BY (SELECT min_level FROM energy_bands b WHERE b.code = t.band) DESC, t.id"
+    ))?
+    .query_map(rusqlite::params![who, all], row_to_task)?
+    .collect()
+}
+
+fn get_task(c: &rusqlite::Connection, id: i64) -> rusqlite::Result<Option<Task>> {
+    c.query_row(
+        &format!("SELECT {TASK_COLS} FROM energy_tasks WHERE id = ?1"),
+        [id],
+        row_to_task,
+    )
+    .optional()
+}
+
+/// Trimmed, uppercased band code that exists.
+fn known_band(
+    c: &rusqlite::Connection,
+    raw: &str,
+) -> rusqlite::Result<Result<String, CalendarError>> {
+    let code = raw.trim().to_uppercase();
+    let found = c
+        .query_row(
+            "SELECT 1 FROM energy_bands WHERE code = ?1",
+            [&code],
+            |_| Ok(()),
+        )
+        .optional()?
+        .is_some();
+    Ok(if found {
+        Ok(code)
+    } else {
+        Err(CalendarError::Invalid(format!(
+            "unknown band `{}`",
+            raw.trim()
+        )))
+    })
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct TaskIn {
+    pub title: String,
+    pub band: String,
+    #[serde(default)]
+    pub minutes: Option<i64>,
+    #[serde(default)]
+    pub who: Option<String>,
+}
+
+pub fn create_task(
+    c: &rusqlite::Connection,
+    input: &TaskIn,
+    now: &str,
+) -> rusqlite::Result<Result<Task, CalendarError>> {
+    let title = input.title.trim();
+    if title.is_empty() {
+        return Ok(Err(CalendarError::Invalid("title cannot be empty".into())));
+    }
+    if input.minutes.is_some_and(|m| m <= 0) {
+        return Ok(Err(CalendarError::Invalid(
+            "minutes must be positive".into(),
+        )));
+    }
+    let band = match known_band(c, &input.band)? {
+        Ok(b) => b,
+        Err(e) => return Ok(Err(e)),
+    };
+    let who = input
+        .who
+        .as_deref()
+        .map(str::trim)
+        .filter(|w| !w.is_empty());
+    c.execute(
+        "INSERT INTO energy_tasks(title, band, minutes, who, created_at) VALUES(?1, ?2, ?3, ?4, ?5)",
+        rusqlite::params![title, band, input.minutes, who, now],
+    )?;
+    Ok(get_task(c, c.last_insert_rowid())?.ok_or(CalendarError::Missing))
+}
+
+/// Body for `PATCH /energy/calendar/tasks/:id`; `who: ""` makes it anyone's.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct TaskPatch {
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub band: Option<String>,
+    #[serde(default)]
+    pub minutes: Option<i64>,
+    #[serde(default)]
+    pub who: Option<String>,
+    #[serde(default)]
+    pub done: Option<bool>,
+}
+
+pub fn update_task(
+    c: &rusqlite::Connection,
+    id: i64,
+    patch: &TaskPatch,
+    now: &str,
+) -> rusqlite::Result<Result<Task, CalendarError>> {
+    let Some(mut task) = get_task(c, id)? else {
+        return Ok(Err(CalendarError::Missing));
+    };
+    if let Some(title) = &patch.title {
+        let title = title.trim();
+        if title.is_empty() {
+            return Ok(Err(CalendarError::Invalid("title cannot be empty".into())));
+        }
+        task.title = title.to_owned();
+    }
+    if let Some(band) = &patch.band {
+        task.band = match known_band(c, band)? {
+            Ok(b) => b,
+            Err(e) => return Ok(Err(e)),
+        };
+    }
+    if let Some(minutes) = patch.minutes {
+        if minutes <= 0 {
+            return Ok(Err(CalendarError::Invalid(
+                "minutes must be positive".into(),
+            )));
+        }
+        task.minutes = Some(minutes);
+    }
+    if let Some(who) = &patch.who {
+        task.who = Some(who.trim().to_owned()).filter(|w| !w.is_empty());
+    }
+    match patch.done {
+        Some(true) if task.done_at.is_none() => task.done_at = Some(now.to_owned()),
+        Some(false) => task.done_at = None,
+        _ => {}
+    }
+    c.execute(
+        "UPDATE energy_tasks SET title = ?2, band = ?3, minutes = ?4, who = ?5, done_at = ?6
+          WHERE id = ?1",
+        rusqlite::params![
+            id,
+            task.title,
+            task.band,
+            task.minutes,
+            task.who,
+            task.done_at
+        ],
+    )?;
+    Ok(Ok(task))
+}
+
+pub fn delete_task(c: &rusqlite::Connection, id: i64) -> rusqlite::Result<bool> {
+    Ok(c.execute("DELETE FROM energy_tasks WHERE id = ?1", [id])? > 0)
+}
+
+/// `GET /energy/now`: where a person is and what fits.
+#[derive(Debug, Clone, Serialize)]
+pub struct Now {
+    pub who: String,
+    pub level: f64,
+    pub kind: String,
+    pub ts: String,
+    pub band: Band,
+    /// Open tasks this band can carry, best fit first.
+    pub tasks: Vec<Task>,
+    /// Open tasks that need a higher band.
+    pub later: Vec<Task>,
+}
+
+pub fn now(c: &rusqlite::Connection, who: &str) -> rusqlite::Result<Option<Now>> {
+    let Some((level, kind, ts)) = current(c, who)? else {
+        return Ok(None);
+    };
+    let all = bands(c)?;
+    let Some(band) = band_for(&all, level).cloned() else {
+        return Ok(None);
+    };
+    let min_of = |code: &str| all.iter().find(|b| b.code == code).map(|b| b.min_level);
+    let (tasks, later) = tasks(c, Some(who), false)?
+        .into_iter()
+        .partition(|t| min_of(&t.band).is_some_and(|m| m <= band.min_level));
+    Ok(Some(Now {
+        who: who.to_owned(),
+        level,
+        kind,
+        ts,
+        band,
+        tasks,
+        later,
+    }))
+}
+
+fn http_err(e: CalendarError) -> ApiErr {
+    match e {
+        CalendarError::Missing => (StatusCode::NOT_FOUND, "unknown task".into()),
+        CalendarError::Conflict(msg) => (StatusCode::CONFLICT, msg),
+        CalendarError::Invalid(msg) => unprocessable(msg),
+    }
+}
+
+fn clean(v: Option<String>) -> Option<String> {
+    v.map(|v| v.trim().to_owned()).filter(|v| !v.is_empty())
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct WhoParams {
+    #[serde(default)]
+    pub who: Option<String>,
+}
+
+/// GET /energy/now?who=
+pub async fn now_http(
+    State(state): State<AppState>,
+    Query(q): Query<WhoParams>,
+) -> Result<Json<Now>, ApiErr> {
+    let who = clean(q.who).ok_or_else(|| unprocessable("who is required"))?;
+    let missing = format!("no energy marked yet for {who}");
+    state
+        .db
+        .0
+        .call(move |c| Ok(now(c, &who)?))
+        .await
+        .map_err(internal)?
+        .map(Json)
+        .ok_or((StatusCode::NOT_FOUND, missing))
+}
+
+/// GET /energy/calendar/bands
+async fn bands_http(State(state): State<AppState>) -> Result<Json<Vec<Band>>, ApiErr> {
+    state
+        .db
+        .0
+        .call(|c| Ok(bands(c)?))
+        .await
+        .map(Json)
+        .map_err(internal)
+}
+
+/// PUT /energy/calendar/bands
+async fn replace_bands_http(
+    State(state): State<AppState>,
+    Json(input): Json<Vec<Band>>,
+) -> Result<Json<Vec<Band>>, ApiErr> {
+    state
+        .db
+        .0
+        .call(move |c| Ok(replace_bands(c, input)?))
+        .await
+        .map_err(internal)?
+        .map(Json)
+        .map_err(http_err)
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct TaskParams {
+    #[serde(default)]
+    who: Option<String>,
+    #[serde(default)]
+    all: Option<bool>,
+}
+
+/// GET /energy/calendar/tasks?who=&all=
+async fn tasks_http(
+    State(state): State<AppState>,
+    Query(q): Query<TaskParams>,
+) -> Result<Json<Vec<Task>>, ApiErr> {
+    let who = clean(q.who);
+    let all = q.all.unwrap_or(false);
+    state
+        .db
+        .0
+        .call(move |c| Ok(tasks(c, who.as_deref(), all)?))
+        .await
+        .map(Json)
+        .map_err(internal)
+}
+
+/// POST /energy/calendar/tasks
+async fn create_task_http(
+    State(state): State<AppState>,
+    Json(input): Json<TaskIn>,
+) -> Result<(StatusCode, Json<Task>), ApiErr> {
+    let now = chrono::Utc::now().to_rfc3339();
+    state
+        .db
+        .0
+        .call(move |c| Ok(create_task(c, &input, &now)?))
+        .await
+        .map_err(internal)?
+        .map(|t| (StatusCode::CREATED, Json(t)))
+        .map_err(http_err)
+}
+
+/// PATCH /energy/calendar/tasks/:id
+async fn update_task_http(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    Json(patch): Json<TaskPatch>,
+) -> Result<Json<Task>, ApiErr> {
+    let now = chrono::Utc::now().to_rfc3339();
+    state
+        .db
+        .0
+        .call(move |c| Ok(update_task(c, id, &patch, &now)?))
+        .await
+        .map_err(internal)?
+        .map(Json)
+        .map_err(http_err)
+}
+
+/// DELETE /energy/calendar/tasks/:id
+async fn delete_task_http(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+) -> Result<StatusCode, ApiErr> {
+    let gone = state
+        .db
+        .0
+        .call(move |c| Ok(delete_task(c, id)?))
+        .await
+        .map_err(internal)?;
+    if gone {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(http_err(CalendarError::Missing))
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct TransitionParams {
+    #[serde(default)]
+    who: Option<String>,
+    #[serde(default)]
+    limit: Option<i64>,
+}
+
+/// GET /energy/calendar/transitions?who=&limit=
+async fn transitions_http(
+    State(state): State<AppState>,
+    Query(q): Query<TransitionParams>,
+) -> Result<Json<Vec<Transition>>, ApiErr> {
+    let who = clean(q.who);
+    let limit = q.limit.unwrap_or(50).clamp(1, 500);
+    state
+        .db
+        .0
+        .call(move |c| Ok(transitions(c, who.as_deref(), limit)?))
+        .await
+        .map(Json)
+        .map_err(internal)
+}
+
+/// Mounted under `/energy/calendar` (`/energy/now` lives in energy.rs's router).
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/bands", get(bands_http).put(replace_bands_http))
+        .route("/tasks", get(tasks_http).post(create_task_http))
+        .route(
+            "/tasks/:id",
+            axum::routing::patch(update_task_http).delete(delete_task_http),
+        )
+        .route("/transitions", get(transitions_http))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conn() -> rusqlite::Connection {
+        let mut c = rusqlite::Connection::open_in_memory().unwrap();
+        crate::db::ensure_schema(&mut c).unwrap();
+        c
+    }
+
+    fn mark(c: &rusqlite::Connection, who: &str, ts: &str, level: f64) -> Option<Transition> {
+        let before = band_now(c, who).unwrap();
+        c.execute(
+            "INSERT INTO energy_marks(ts, who, kind, level) VALUES(?1, ?2, 'dragon', ?3)",
+            rusqlite::params![ts, who, level],
+        )
+        .unwrap();
+        after_mark(c, who, before, c.last_insert_rowid()).unwrap()
+    }
+
+    #[test]
+    fn seeded_bands_follow_the_doc() {
+        let c = conn();
+        let all = bands(&c).unwrap();
+        let codes: Vec<&str> = all.iter().map(|b| b.code.as_str()).collect();
+        assert_eq!(codes, ["E4", "E3", "E2", "E1", "E0"]);
+        assert_eq!(all[1].max_level, Some(0.9));
+        assert_eq!(band_for(&all, 0.7).unwrap().code, "E3");
+        assert_eq!(band_for(&all, 0.69).unwrap().code, "E2");
+        assert_eq!(band_for(&all, f64::from(0.7_f32)).unwrap().code, "E3");
+        assert_eq!(band_for(&all, f64::from(0.9_f32)).unwrap().code, "E4");
+        assert_eq!(band_for(&all, 0.0).unwrap().code, "E0");
+        assert_eq!(band_for(&all, 1.0).unwrap().code, "E4");
+    }
+
+    #[test]
+    fn crossings_are_recorded_once_and_backdating_does_not_cross() {
+        let mut c = conn();
+        assert_eq!(mark(&c, "Raz", "2025-05-01T08:00:00+00:00", 0.75), None);
+        assert_eq!(mark(&c, "Raz", "2025-05-01T09:00:00+00:00", 0.8), None);
+        let down = mark(&c, "Raz", "2025-05-01T10:00:00+00:00", 0.4).unwrap();
+        assert_eq!(
+            (
+                down.from.as_str(),
+                down.to.as_str(),
+                down.direction.as_str()
+            ),
+            ("E3", "E1", "down")
+        );
+        assert_eq!(down.exit.as_deref(), Some(EXIT));
+        // older than the latest mark: the current band does not move
+        assert_eq!(mark(&c, "Raz", "2025-05-01T07:00:00+00:00", 0.95), None);
+        let up = mark(&c, "Raz", "2025-05-01T11:00:00+00:00", 0.95).unwrap();
+        assert_eq!(
+            (up.to.as_str(), up.direction.as_str(), up.exit),
+            ("E4", "up", None)
+        );
+
+        let sent = take_unpublished(&mut c, "2025-05-01T12:00:00+00:00").unwrap();
+        assert_eq!(sent.len(), 2);
+        assert!(take_unpublished(&mut c, "2025-05-01T12:01:00+00:00")
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn now_splits_tasks_by_band_and_bands_can_be_replaced() {
+        let mut c = conn();
+        let now_ts = "2025-05-01T00:00:00+00:00";
+        for (title, band, who) in [
+            ("architecture spike", "e4", None),
+            ("review PR", "E2", None),
+            ("inbox", "E1", Some("Raz")),
+            ("Nico's errand", "E0", Some("Nico")),
+        ] {
+            let input = TaskIn {
+                title: title.into(),
+                band: band.into(),
+                who: who.map(str::to_owned),
+                ..TaskIn::default()
+            };
+            create_task(&c, &input, now_ts).unwrap().unwrap();
+        }
+        let bad = TaskIn {
+            title: "x".into(),
+            band: "E9".into(),
+            ..TaskIn::default()
+        };
+        assert!(matches!(
+            create_task(&c, &bad, now_ts).unwrap(),
+            Err(CalendarError::Invalid(_))
+        ));
+
+        mark(&c, "Raz", "2025-05-01T08:00:00+00:00", 0.55);
+        let n = now(&c, "Raz").unwrap().unwrap();
+        assert_eq!(n.band.code, "E2");
+        let titles = |ts: &[Task]| ts.iter().map(|t| t.title.clone()).collect::<Vec<_>>();
+        assert_eq!(titles(&n.tasks), ["review PR", "inbox"]);
+        assert_eq!(titles(&n.later), ["architecture spike"]);
+        assert!(now(&c, "Ana").unwrap().is_none());
+
+        let two = vec![
+            Band {
+                code: "high".into(),
+                name: "High".into(),
+                min_level: 0.5,
+                max_level: None,
+                felt: None,
+                good_for: vec![],
+                avoid: vec![],
+            },
+            Band {
+                code: "low".into(),
+                name: "Low".into(),
+                min_level: 0.0,
+                max_level: None,
+                felt: None,
+                good_for: vec![],
+                avoid: vec![],
+            },
+        ];
+        assert!(matches!(
+            replace_bands(&mut c, two.clone()).unwrap(),
+            Err(CalendarError::Conflict(_))
+        ));
+        c.execute("DELETE FROM energy_tasks", []).unwrap();
+        let replaced = replace_bands(&mut c, two.clone()).unwrap().unwrap();
+        assert_eq!(replaced[0].code, "HIGH");
+        let mut no_floor = two;
+        no_floor[1].min_level = 0.1;
+        assert!(matches!(
+            replace_bands(&mut c, no_floor).unwrap(),
+            Err(CalendarError::Invalid(_))
+        ));
+    }
+}
@@ -1,4 +1,44 @@
-#[allow(dead_code)]
+//! Consciousness bands — classification and advice
+//! -----------------------------------------------
+//! Whisper: "tune to the band, and the signal finds you." 📻
+//!
+//! Two band models:
+//!   three  survival | integrity | coherence   (docs/maps/consciousness-gradient.md)
+//!   five   ground | flow | mind | field | source   (docs/modules/bands.md)
+//!
+//! The three-band class of an emotion comes from the taxonomy registry, else from the
+//! intensity. The five-band class comes from the canonical kind (`FIVE_BAND_KINDS`),
+//! else from the three-band class (survival → ground, integrity → mind, coherence →
+//! source). Energy marks use the level for three bands and the energy kind for five.
+//!
+//! Emotion and energy routes take `?band_model=three|five|none` (see `BandQuery`).
+//!
+//! Endpoints (mounted under `/consciousness`)
+//!   GET /consciousness/bands     → ?model= → every band of the model with its advice
+//!   GET /consciousness/classify  → ?kind=&intensity=&model= → { kind, known, band, advice, … }
+//!   GET /consciousness/series    → ?who=&model=&bucket=day|week&since=&until= → band
+//!                                  counts per bucket for one person, oldest first
+
+use crate::AppState;
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    routing::get,
+    Json, Router,
+};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+
+type ApiErr = (StatusCode, String);
+
+fn unprocessable(msg: impl Into<String>) -> ApiErr {
+    (StatusCode::UNPROCESSABLE_ENTITY, msg.into())
+}
+
+fn internal(e: impl std::fmt::Display) -> ApiErr {
+    (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
 #[serde(rename_all = "snake_case")]
 pub enum Band {
@@ -10,61 +50,510 @@ pub enum Band {
     Coherence,
 }
 
-#[allow(dead_code)]
-pub fn band_from_emotion(kind: &str, intensity: f32) -> Band {
-    let k = kind.to_ascii_lowercase();
-    // crude heuristic; refine with patterns/detect later
-    match k.as_str() {
-        "shame" | "guilt" | "apathy" | "grief" | "fear" | "desire" | "anger" | "pride" => {
-            Band::Survival
+impl Band {
+    pub const ALL: [Band; 3] = [Band::Survival, Band::Integrity, Band::Coherence];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Band::Survival => "survival",
+            Band::Integrity => "integrity",
+            Band::Coherence => "coherence",
         }
-        "courage" | "neutrality" | "willingness" | "acceptance" | "reason" => Band::Integrity,
-        "love" | "joy" | "peace" => Band::Coherence,
-        _ => {
-            // fallback by intensity
-            if intensity < 0.2 {
-                Band::Survival
-            } else if intensity < 0.6 {
-                Band::Integrity
-            } else {
-                Band::Coherence
-            }
+    }
+
+    /// Case-insensitive name → band.
+    pub fn parse(s: &str) -> Option<Band> {
+        Band::ALL
+            .into_iter()
+            .find(|b| b.as_str().eq_ignore_ascii_case(s.trim()))
+    }
+}
+
+/// The five-band map from `docs/modules/bands.md`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FiveBand {
+    /// Physical presence, body, survival
+    Ground,
+    /// Emotions, movement, cycles
+    Flow,
+    /// Thought patterns, narratives, judgment
+    Mind,
+    /// Intuition, signals, archetypes
+    Field,
+    /// Stillness, coherence, unity
+    Source,
+}
+
+impl FiveBand {
+    pub const ALL: [FiveBand; 5] = [
+        FiveBand::Ground,
+        FiveBand::Flow,
+        FiveBand::Mind,
+        FiveBand::Field,
+        FiveBand::Source,
+    ];
+
+    /// Nearest five-band class for a kind the five-band table does not name.
+    pub fn from_three(band: Band) -> FiveBand {
+        match band {
+            Band::Survival => FiveBand::Ground,
+            Band::Integrity => FiveBand::Mind,
+            Band::Coherence => FiveBand::Source,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BandModel {
+    Three,
+    Five,
+}
+
+impl BandModel {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            BandModel::Three => "three",
+            BandModel::Five => "five",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<BandModel> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "three" | "3" => Some(BandModel::Three),
+            "five" | "5" => Some(BandModel::Five),
+            _ => None,
+        }
+    }
+
+    /// Every band of the model, lowest first.
+    pub fn bands(self) -> Vec<ModelBand> {
+        match self {
+            BandModel::Three => Band::ALL.into_iter().map(ModelBand::Three).collect(),
+            BandModel::Five => FiveBand::ALL.into_iter().map(ModelBand::Five).collect(),
         }
     }
 }
 
-#[allow(dead_code)]
+/// A band in either model; serializes as its name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(untagged)]
+pub enum ModelBand {
+    Three(Band),
+    Five(FiveBand),
+}
+
+/// `?band_model=three|five|none` on emotion and energy routes.
+#[derive(Debug, Default, Deserialize)]
+pub struct BandQuery {
+    #[serde(default)]
+    pub band_model: Option<String>,
+}
+
+impl BandQuery {
+    /// `None` = leave the band out; an absent/empty parameter picks `default`.
+    pub fn model(&self, default: Option<BandModel>) -> Result<Option<BandModel>, String> {
+        match self.band_model.as_deref().map(str::trim) {
+            None | Some("") => Ok(default),
+            Some(s) if s.eq_ignore_ascii_case("none") => Ok(None),
+            Some(s) => BandModel::parse(s)
+                .map(Some)
+                .ok_or_else(|| "band_model must be three, five or none".into()),
+        }
+    }
+}
+
+#[allow(dead_code)] // library API; the binary's handlers all hold a connection
+pub fn band_from_emotion(kind: &str, intensity: f32) -> Band {
+    // compiled seed (synonyms included), for callers without a connection; with one,
+    // use `taxonomy::normalize`, which follows edits to the registry
+    crate::taxonomy::seed_band(kind).unwrap_or_else(|| band_from_intensity(intensity))
+}
+
+/// Fallback for kinds outside the taxonomy.
+pub fn band_from_intensity(intensity: f32) -> Band {
+    if intensity < 0.2 {
+        Band::Survival
+    } else if intensity < 0.6 {
+        Band::Integrity
+    } else {
+        Band::Coherence
+    }
+}
+
+/// Canonical emotion kinds (see `taxonomy::SEED`) placed on the five-band map.
+#[rustfmt::skip]
+const FIVE_BAND_KINDS: &[(FiveBand, &[&str])] = &[
+    (FiveBand::Ground, &["fear", "anxiety", "panic", "apathy"]),
+    (FiveBand::Flow, &["anger", "shame", "guilt", "grief", "desire", "pride", "jealousy"]),
+    (FiveBand::Mind, &["reason", "neutrality", "paradox"]),
+    (FiveBand::Field, &["courage", "willingness", "acceptance"]),
+    (FiveBand::Source, &["love", "joy", "peace", "gratitude"]),
+];
+
+/// Energy kinds (`energy_marks.kind`) on the five-band map.
+const FIVE_BAND_ENERGY: &[(FiveBand, &[&str])] = &[
+    (FiveBand::Ground, &["dragon"]),
+    (FiveBand::Flow, &["flow", "play"]),
+    (FiveBand::Mind, &["focus"]),
+    (FiveBand::Field, &["heart"]),
+    (FiveBand::Source, &["rest"]),
+];
+
+fn five_lookup(table: &[(FiveBand, &[&str])], kind: &str) -> Option<FiveBand> {
+    let key = crate::taxonomy::fold(kind);
+    table
+        .iter()
+        .find(|(_, kinds)| kinds.contains(&key.as_str()))
+        .map(|(b, _)| *b)
+}
+
+/// An emotion's band in `model`, given its canonical kind and three-band class.
+pub fn emotion_band(kind: &str, three: Band, model: BandModel) -> ModelBand {
+    match model {
+        BandModel::Three => ModelBand::Three(three),
+        BandModel::Five => ModelBand::Five(
+            five_lookup(FIVE_BAND_KINDS, kind).unwrap_or_else(|| FiveBand::from_three(three)),
+        ),
+    }
+}
+
+/// An energy mark's band: level for three bands, energy kind (else level) for five.
+pub fn energy_band(kind: &str, level: f64, model: BandModel) -> ModelBand {
+    // thresholds are f32 literals; narrowing keeps 0.6 on the boundary it names
+    let three = band_from_intensity(level as f32);
+    match model {
+        BandModel::Three => ModelBand::Three(three),
+        BandModel::Five => ModelBand::Five(
+            five_lookup(FIVE_BAND_ENERGY, kind).unwrap_or_else(|| FiveBand::from_three(three)),
+        ),
+    }
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
 pub struct BandAdvice {
     pub approach: &'static str,
     pub hints: Vec<&'static str>,
     pub ui_color_hint: &'static str,
 }
 
-#[allow(dead_code)]
+pub fn advice(band: ModelBand) -> BandAdvice {
+    let (approach, ui_color_hint, hints): (_, _, &[&'static str]) = match band {
+        ModelBand::Three(Band::Survival) | ModelBand::Five(FiveBand::Ground) => {
+            ("body-first", "red/orange", &["breath → doorway → anchor"])
+        }
+        ModelBand::Five(FiveBand::Flow) => (
+            "feel-and-move",
+            "orange/amber",
+            &["name the feeling", "move for 2 minutes", "let it pass"],
+        ),
+        ModelBand::Three(Band::Integrity) | ModelBand::Five(FiveBand::Mind) => (
+            "choice-first",
+            "green/cyan",
+            &["small action", "boundary", "reframe"],
+        ),
+        ModelBand::Five(FiveBand::Field) => (
+            "listen-first",
+            "cyan/blue",
+            &["notice the signal", "ask, don't assume", "share one truth"],
+        ),
+        ModelBand::Three(Band::Coherence) | ModelBand::Five(FiveBand::Source) => (
+            "stabilize-coherence",
+            "blue/violet",
+            &["gratitude ritual", "sharing", "stillness"],
+        ),
+    };
+    BandAdvice {
+        approach,
+        hints: hints.to_vec(),
+        ui_color_hint,
+    }
+}
+
+/// Three-band advice for an emotion; `advice` covers both models.
+#[allow(dead_code)] // library API, like `band_from_emotion`
 pub fn advice_for(kind: &str, intensity: f32) -> BandAdvice {
-    match band_from_emotion(kind, intensity) {
-        Band::Survival => BandAdvice {
-            approach: "body-first",
-            ui_color_hint: "red/orange",
-            hints: vec!["breath → doorway → anchor"],
-        },
-        Band::Integrity => BandAdvice {
-            approach: "choice-first",
-            ui_color_hint: "green/cyan",
-            hints: vec!["small action", "boundary", "reframe"],
-        },
-        Band::Coherence => BandAdvice {
-            approach: "stabilize-coherence",
-            ui_color_hint: "blue/violet",
-            hints: vec!["gratitude ritual", "sharing", "stillness"],
-        },
+    advice(ModelBand::Three(band_from_emotion(kind, intensity)))
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct BandInfo {
+    pub band: ModelBand,
+    #[serde(flatten)]
+    pub advice: BandAdvice,
+}
+
+pub fn bands(model: BandModel) -> Vec<BandInfo> {
+    model
+        .bands()
+        .into_iter()
+        .map(|band| BandInfo {
+            band,
+            advice: advice(band),
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct Classification {
+    /// Canonical kind (folded when the taxonomy does not know it).
+    pub kind: String,
+    pub known: bool,
+    pub intensity: f32,
+    pub model: &'static str,
+    pub band: ModelBand,
+    pub advice: BandAdvice,
+}
+
+/// Classify against the live taxonomy registry (edited kinds included).
+pub fn classify(
+    c: &rusqlite::Connection,
+    kind: &str,
+    intensity: f32,
+    model: BandModel,
+) -> rusqlite::Result<Classification> {
+    let n = crate::taxonomy::normalize(c, kind)?;
+    let three = n
+        .known
+        .as_ref()
+        .map_or_else(|| band_from_intensity(intensity), |k| k.band);
+    let band = emotion_band(&n.kind, three, model);
+    Ok(Classification {
+        known: n.known.is_some(),
+        kind: n.kind,
+        intensity,
+        model: model.as_str(),
+        band,
+        advice: advice(band),
+    })
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct BandCount {
+    pub band: ModelBand,
+    pub count: i64,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct SeriesPoint {
+    /// First day of the bucket (`YYYY-MM-DD`, UTC).
+    pub start: String,
+    pub count: i64,
+    pub mean_intensity: f64,
+    /// Every band of the model, lowest first.
+    pub bands: Vec<BandCount>,
+    /// Most frequent band (the lower one on ties).
+    pub dominant: ModelBand,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct Series {
+    pub who: String,
+    pub model: &'static str,
+    pub bucket: &'static str,
+    /// Oldest first; buckets without emotions are omitted.
+    pub points: Vec<SeriesPoint>,
+}
+
+/// One person's emotions bucketed by day or week, counted per band of `model`.
+pub fn series(
+    c: &rusqlite::Connection,
+    who: &str,
+    model: BandModel,
+    bucket: crate::emotions::Bucket,
+    since: Option<&str>,
+    until: Option<&str>,
+) -> rusqlite::Result<Series> {
+    use std::collections::BTreeMap;
+    let order = model.bands();
+    let mut stmt = c.prepare(&format!(
+        "SELECT ts, kind, intensity, {} FROM emotions
+         WHERE who = ?1 AND (?2 IS NULL OR ts >= ?2) AND (?3 IS NULL OR ts < ?3)
+         ORDER BY ts",
+        crate::taxonomy::BAND_SQL
+    ))?;
+    let mut rows = stmt.query(rusqlite::params![who, since, until])?;
+    let mut buckets: BTreeMap<chrono::NaiveDate, (i64, f64, Vec<i64>)> = BTreeMap::new();
+    while let Some(row) = rows.next()? {
+        let ts: String = row.get(0)?;
+        let kind: String = row.get(1)?;
+        let intensity: f32 = row.get(2)?;
+        let Some(start) = bucket.start(&ts) else {
+            continue;
+        };
+        let three = crate::emotions::band_of(row.get(3)?, intensity);
+        let band = emotion_band(&kind, three, model);
+        let slot = buckets
+            .entry(start)
+            .or_insert_with(|| (0, 0.0, vec![0; order.len()]));
+        slot.0 += 1;
+        slot.1 += intensity as f64;
+        if let Some(i) = order.iter().position(|b| *b == band) {
+            slot.2[i] += 1;
+        }
+    }
+    let points = buckets
+        .into_iter()
+        .map(|(start, (n, sum, counts))| {
+            let top = counts.iter().copied().max().unwrap_or_default();
+            let dominant = order[counts.iter().position(|&x| x == top).unwrap_or(0)];
+            SeriesPoint {
+                start: start.to_string(),
+                count: n,
+                mean_intensity: (sum / n as f64 * 1000.0).round() / 1000.0,
+                bands: order
+                    .iter()
+                    .zip(counts)
+                    .map(|(&band, count)| BandCount { band, count })
+                    .collect(),
+                dominant,
+            }
+        })
+        .collect();
+    Ok(Series {
+        who: who.to_string(),
+        model: model.as_str(),
+        bucket: bucket.as_str(),
+        points,
+    })
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ModelParams {
+    /// three (default) | five
+    #[serde(default)]
+    model: Option<String>,
+}
+
+impl ModelParams {
+    fn model(&self) -> Result<BandModel, ApiErr> {
+        match self.model.as_deref().map(str::trim) {
+            None | Some("") => Ok(BandModel::Three),
+            Some(m) => {
+                BandModel::parse(m).ok_or_else(|| unprocessable("model must be three or five"))
+            }
+        }
     }
 }
 
+/// GET /consciousness/bands?model=
+async fn bands_http(Query(q): Query<ModelParams>) -> Result<Json<Vec<BandInfo>>, ApiErr> {
+    Ok(Json(bands(q.model()?)))
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ClassifyParams {
+    #[serde(default)]
+    kind: Option<String>,
+    /// 0..1; default 0.5
+    #[serde(default)]
+    intensity: Option<f32>,
+}
+
+/// GET /consciousness/classify?kind=&intensity=&model=
+async fn classify_http(
+    State(state): State<AppState>,
+    Query(q): Query<ClassifyParams>,
+    Query(m): Query<ModelParams>,
+) -> Result<Json<Classification>, ApiErr> {
+    let model = m.model()?;
+    let kind = q.kind.unwrap_or_default().trim().to_string();
+    if kind.is_empty() {
+        return Err(unprocessable("kind is required"));
+    }
+    let intensity = q.intensity.unwrap_or(0.5);
+    if !(0.0..=1.0).contains(&intensity) {
+        return Err(unprocessable("intensity must be 0..1"));
+    }
+    state
+        .db
+        .0
+        .call(move |c| Ok(classify(c, &kind, intensity, model)?))
+        .await
+        .map(Json)
+        .map_err(internal)
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct SeriesParams {
+    #[serde(default)]
+    who: Option<String>,
+    /// day (default) | week
+    #[serde(default)]
+    bucket: Option<String>,
+    /// RFC3339, inclusive.
+    #[serde(default)]
+    since: Option<String>,
+    /// RFC3339, exclusive.
+    #[serde(default)]
+    until: Option<String>,
+}
+
+fn rfc3339(name: &str, v: Option<String>) -> Result<Option<String>, ApiErr> {
+    match v.as_deref().map(str::trim).filter(|s| !s.is_empty()) {
+        None => Ok(None),
+        Some(raw) => chrono::DateTime::parse_from_rfc3339(raw)
+            .map(|dt| Some(dt.with_timezone(&Utc).to_rfc3339()))
+            .map_err(|_| unprocessable(format!("{name} must be RFC3339"))),
+    }
+}
+
+/// GET /consciousness/series?who=&model=&bucket=&since=&until=
+async fn series_http(
+    State(state): State<AppState>,
+    Query(q): Query<SeriesParams>,
+    Query(m): Query<ModelParams>,
+) -> Result<Json<Series>, ApiErr> {
+    let model = m.model()?;
+    let who = q.who.unwrap_or_default().trim().to_string();
+    if who.is_empty() {
+        return Err(unprocessable("who is required"));
+    }
+    let bucket = match q.bucket.as_deref().map(str::trim) {
+        None | Some("") => crate::emotions::Bucket::Day,
+        Some(b) => crate::emotions::Bucket::parse(b)
+            .ok_or_else(|| unprocessable("bucket must be day or week"))?,
+    };
+    let since = rfc3339("since", q.since)?;
+    let until = rfc3339("until", q.until)?;
+    state
+        .db
+        .0
+        .call(move |c| {
+            Ok(series(
+                c,
+                &who,
+                model,
+                bucket,
+                since.as_deref(),
+                until.as_deref(),
+            )?)
+        })
+        .await
+        .map(Json)
+        .map_err(internal)
+}
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/bands", get(bands_http))
+        .route("/classify", get(classify_http))
+        .route("/series", get(series_http))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    /// Serialized name, as clients see it.
+    fn name(b: ModelBand) -> String {
+        serde_json::to_value(b)
+            .unwrap()
+            .as_str()
+            .unwrap()
+            .to_string()
+    }
+
     #[test]
     fn test_survival_emotion() {
         let kind = "fear";
@@ -143,4 +632,89 @@ mod tests {
         assert_eq!(advice.approach, "stabilize-coherence");
         assert_eq!(advice.ui_color_hint, "blue/violet");
     }
+
+    #[test]
+    fn five_band_model_covers_every_seeded_kind() {
+        for s in crate::taxonomy::SEED {
+            let named = five_lookup(FIVE_BAND_KINDS, s.name);
+            assert!(named.is_some(), "{} has no five-band place", s.name);
+        }
+        let five = |kind, band| name(emotion_band(kind, band, BandModel::Five));
+        assert_eq!(five("panic", Band::Survival), "ground");
+        assert_eq!(five("grief", Band::Survival), "flow");
+        assert_eq!(five("paradox", Band::Integrity), "mind");
+        assert_eq!(five("courage", Band::Integrity), "field");
+        assert_eq!(five("gratitude", Band::Coherence), "source");
+        // kinds added to the registry later fall back on their three-band class
+        assert_eq!(five("overwhelm", Band::Survival), "ground");
+        assert_eq!(five("awe", Band::Coherence), "source");
+
+        assert_eq!(name(energy_band("heart", 0.1, BandModel::Five)), "field");
+        assert_eq!(
+            name(energy_band("Heart", 0.1, BandModel::Three)),
+            "survival"
+        );
+        assert_eq!(name(energy_band("unknown", 0.9, BandModel::Five)), "source");
+        assert_eq!(bands(BandModel::Five).len(), 5);
+    }
+
+    #[test]
+    fn classify_uses_the_registry_and_series_buckets_per_person() {
+        let mut c = rusqlite::Connection::open_in_memory().unwrap();
+        crate::db::ensure_schema(&mut c).unwrap();
+        let cls = classify(&c, "Neliniște", 0.9, BandModel::Five).unwrap();
+        assert_eq!((cls.kind.as_str(), cls.known), ("anxiety", true));
+        assert_eq!(
+            (name(cls.band).as_str(), cls.advice.approach),
+            ("ground", "body-first")
+        );
+        let unknown = classify(&c, "Over Whelm", 0.7, BandModel::Three).unwrap();
+        assert_eq!(
+            (unknown.known, name(unknown.band).as_str()),
+            (false, "coherence")
+        );
+
+        for (ts, who, kind, i) in [
+            ("2025-09-01T08:00:00+00:00", "Raz", "fear", 0.8),
+            ("2025-09-01T09:00:00+00:00", "Raz", "anger", 0.6),
+            ("2025-09-01T20:00:00+00:00", "Raz", "gratitude", 1.0),
+            ("2025-09-03T08:00:00+00:00", "Raz", "gratitude", 1.0),
+            ("2025-09-03T08:00:00+00:00", "Nico", "fear", 0.4),
+        ] {
+            c.execute(
+                "INSERT INTO emotions(ts, who, kind, intensity) VALUES(?1, ?2, ?3, ?4)",
+                rusqlite::params![ts, who, kind, i],
+            )
+            .unwrap();
+        }
+        let s = series(
+            &c,
+            "Raz",
+            BandModel::Three,
+            crate::emotions::Bucket::Day,
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(s.points.len(), 2);
+        let first = &s.points[0];
+        assert_eq!((first.start.as_str(), first.count), ("2025-09-01", 3));
+        assert_eq!(first.bands[0].count, 2);
+        assert_eq!(name(first.dominant), "survival");
+        assert_eq!(name(s.points[1].dominant), "coherence");
+
+        let five = series(
+            &c,
+            "Raz",
+            BandModel::Five,
+            crate::emotions::Bucket::Week,
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(five.points.len(), 1);
+        let counts: Vec<i64> = five.points[0].bands.iter().map(|b| b.count).collect();
+        assert_eq!(counts, vec![1, 1, 0, 0, 2]);
+        assert_eq!(name(five.points[0].dominant), "source");
+    }
 }
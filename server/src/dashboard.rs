@@ -0,0 +1,443 @@
+//! Dashboard state — versioned, with optimistic concurrency
+//! --------------------------------------------------------
+//! Whisper: "two hands on one page — take turns." 🤝
+//!
+//! Every save is a new row in `dashboard_revisions`; the newest row is the current
+//! state. Readers get `rev` in the body and `ETag: "<rev>"`; writers must send
+//! `If-Match: "<rev>"` and receive 409 (with the current state) when someone else
+//! saved first. A write without `If-Match` is refused with 428; `If-Match: *` is the
+//! explicit "overwrite whatever is there".
+//!
+//! Endpoints (mounted under `/state`)
+//!   GET  /state/get                   → current state (+ ETag)
+//!   POST /state/set                   → partial update { members?, pillars?, note? } → new rev
+//!   GET  /state/history?limit=&before= → revisions, newest first
+//!   POST /state/restore { rev }       → copy an old revision forward as a new rev
+//!
+//! Notes
+//!   • The legacy `kv.dashboard_state` blob is migrated as rev 1 in `db::ensure_schema`.
+//!   • Members and pillars are validated against the roster in `team.rs` (422 on
+//!     unknown names/keys, disallowed values, energy outside 0..100); reads are
+//!     shaped to the roster so added/removed members show up without a save.
+
+use crate::{
+    models::{MemberEnergy, SetStateRequest},
+    team, AppState,
+};
+use axum::{
+    extract::{Query, State},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+/// Team snapshot shown before anything has been saved: roster defaults.
+pub fn default_team_state(defs: &team::Definitions) -> Value {
+    let mut state = json!({
+      "members": [],
+      "pillars": {},
+      "note": null,
+      "ts": chrono::Utc::now().to_rfc3339()
+    });
+    team::shape(defs, &mut state);
+    state
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Revision {
+    pub rev: i64,
+    pub ts: String,
+    /// `set` | `restore` | `migrate` | `roster`
+    pub source: String,
+    pub restored_from: Option<i64>,
+    pub state: Value,
+}
+
+fn etag(rev: i64) -> String {
+    format!("\"{rev}\"")
+}
+
+/// `None` = `If-Match: *`, `Some(rev)` = a specific revision; 428 when missing.
+fn if_match(headers: &HeaderMap) -> Result<Option<i64>, (StatusCode, String)> {
+    let Some(raw) = headers.get(header::IF_MATCH) else {
+        return Err((
+            StatusCode::PRECONDITION_REQUIRED,
+            "If-Match required (send the ETag from /state/get, or * to overwrite)".to_string(),
+        ));
+    };
+    let raw = raw
+        .to_str()
+        .map_err(|_| (StatusCode::BAD_REQUEST, "invalid If-Match".to_string()))?
+        .trim();
+    if raw == "*" {
+        return Ok(None);
+    }
+    let rev = raw
+        .trim_start_matches("W/")
+        .trim_matches('"')
+        .parse::<i64>()
+        .map_err(|_| {
+            (
+                StatusCode::BAD_REQUEST,
+                "If-Match must be a state ETag".to_string(),
+            )
+        })?;
+    Ok(Some(rev))
+}
+
+/// Body for reads: the stored state plus its `rev`.
+fn with_rev(mut state: Value, rev: i64) -> Value {
+    if let Some(obj) = state.as_object_mut() {
+        obj.insert("rev".into(), json!(rev));
+    }
+    state
+}
+
+fn respond(state: Value, rev: i64) -> Response {
+    let mut res = Json(with_rev(state, rev)).into_response();
+    if let Ok(v) = HeaderValue::from_str(&etag(rev)) {
+        res.headers_mut().insert(header::ETAG, v);
+    }
+    res
+}
+
+/// Latest revision shaped to the roster (rev 0 + defaults when nothing was saved yet).
+fn current(c: &rusqlite::Connection) -> rusqlite::Result<(i64, Value)> {
+    let defs = team::load(c)?;
+    let row: Option<(i64, String)> = c
+        .query_row(
+            "SELECT rev, state FROM dashboard_revisions ORDER BY rev DESC LIMIT 1",
+            [],
+            |r| Ok((r.get(0)?, r.get(1)?)),
+        )
+        .ok();
+    Ok(match row {
+        Some((rev, s)) => {
+            let mut v: Value = serde_json::from_str(&s)
+                .ok()
+                .filter(Value::is_object)
+                .unwrap_or_else(|| default_team_state(&defs));
+            team::shape(&defs, &mut v);
+            (rev, v)
+        }
+        None => (0, default_team_state(&defs)),
+    })
+}
+
+/// Carry a member's saved energy across a roster rename (new `roster` revision).
+/// No-op before the first save — defaults already come from the roster.
+pub(crate) fn rename_member(
+    c: &rusqlite::Connection,
+    old: &str,
+    new: &str,
+) -> rusqlite::Result<()> {
+    let row: Option<String> = c
+        .query_row(
+            "SELECT state FROM dashboard_revisions ORDER BY rev DESC LIMIT 1",
+            [],
+            |r| r.get(0),
+        )
+        .ok();
+    let Some(mut state) = row.and_then(|s| serde_json::from_str::<Value>(&s).ok()) else {
+        return Ok(());
+    };
+    let Some(members) = state["members"].as_array_mut() else {
+        return Ok(());
+    };
+    let mut renamed = false;
+    for m in members.iter_mut().filter(|m| m["name"] == old) {
+        m["name"] = Value::String(new.to_string());
+        renamed = true;
+    }
+    if renamed {
+        state["ts"] = Value::String(chrono::Utc::now().to_rfc3339());
+        append(c, &state, "roster", None)?;
+    }
+    Ok(())
+}
+
+/// Patch semantics: members update by name, pillars by key, note replaces.
+fn merge(cur: &mut Value, req: SetStateRequest) {
+    if let Some(ms) = req.members {
+        let mut members: Vec<MemberEnergy> = cur
+            .get("members")
+            .cloned()
+            .and_then(|v| serde_json::from_value(v).ok())
+            .unwrap_or_default();
+        for m in ms {
+            match members.iter_mut().find(|x| x.name == m.name) {
+                Some(x) => x.energy = m.energy,
+                None => members.push(MemberEnergy { avatar: None, ..m }),
+            }
+        }
+        cur["members"] = serde_json::to_value(members).unwrap_or(Value::Null);
+    }
+    if let Some(ps) = req.pillars {
+        if !cur["pillars"].is_object() {
+            cur["pillars"] = json!({});
+        }
+        for (k, v) in ps {
+            cur["pillars"][k] = Value::String(v);
+        }
+    }
+    if let Some(n) = req.note {
+        cur["note"] = Value::String(n);
+    }
+}
+
+fn append(
+    c: &rusqlite::Connection,
+    state: &Value,
+    source: &str,
+    restored_from: Option<i64>,
+) -> rusqlite::Result<i64> {
+    c.execute(
+        "INSERT INTO dashboard_revisions(ts, state, source, restored_from) VALUES(?1, ?2, ?3, ?4)",
+        rusqlite::params![
+            state["ts"].as_str().unwrap_or_default(),
+            state.to_string(),
+            source,
+            restored_from
+        ],
+    )?;
+    Ok(c.last_insert_rowid())
+}
+
+/// Outcome of a guarded write, decided inside one DB call.
+enum Write {
+    Saved(i64, Value),
+    Conflict(i64, Value),
+    Invalid(Vec<String>),
+    Missing,
+}
+
+fn conflict(rev: i64, state: Value) -> Response {
+    let mut res = (
+        StatusCode::CONFLICT,
+        Json(json!({
+            "error": "state changed since your read",
+            "current": with_rev(state, rev),
+        })),
+    )
+        .into_response();
+    if let Ok(v) = HeaderValue::from_str(&etag(rev)) {
+        res.headers_mut().insert(header::ETAG, v);
+    }
+    res
+}
+
+/// GET /state/get
+async fn get_state(State(state): State<AppState>) -> Result<Response, StatusCode> {
+    let (rev, v) = state
+        .db
+        .0
+        .call(|c| Ok(current(c)?))
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    Ok(respond(v, rev))
+}
+
+/// POST /state/set — merge + new revision; 428 without `If-Match`, 409 when it is stale.
+async fn set_state(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(req): Json<SetStateRequest>,
+) -> Result<Response, (StatusCode, String)> {
+    let expected = if_match(&headers)?;
+    let now = chrono::Utc::now().to_rfc3339();
+    let out = state
+        .db
+        .0
+        .call(move |c| {
+            let tx = c.transaction()?;
+            let (rev, cur) = current(&tx)?;
+            if let Some(want) = expected {
+                if want != rev {
+                    return Ok(Write::Conflict(rev, cur));
+                }
+            }
+            let defs = team::load(&tx)?;
+            if let Err(problems) = team::validate(&defs, &req) {
+                return Ok(Write::Invalid(problems));
+            }
+
+            let mut next = cur;
+            merge(&mut next, req);
+            team::shape(&defs, &mut next);
+            next["ts"] = Value::String(now);
+            if let Some(obj) = next.as_object_mut() {
+                obj.remove("rev");
+            }
+
+            let rev = append(&tx, &next, "set", None)?;
+            tx.commit()?;
+            Ok(Write::Saved(rev, next))
+        })
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(match out {
+        Write::Saved(rev, v) => respond(v, rev),
+        Write::Conflict(rev, v) => conflict(rev, v),
+        Write::Invalid(problems) => (
+            StatusCode::UNPROCESSABLE_ENTITY,
+            Json(json!({ "error": "invalid state", "problems": problems })),
+        )
+            .into_response(),
+        Write::Missing => StatusCode::NOT_FOUND.into_response(),
+    })
+}
+
+#[derive(Debug, Deserialize)]
+struct HistoryParams {
+    #[serde(default)]
+    limit: Option<i64>,
+    /// Only revisions older than this rev (paging).
+    #[serde(default)]
+    before: Option<i64>,
+}
+
+/// GET /state/history — newest first.
+async fn history(
+    State(state): State<AppState>,
+    Query(q): Query<HistoryParams>,
+) -> Result<Json<Vec<Revision>>, StatusCode> {
+    let limit = q.limit.unwrap_or(20).clamp(1, 200);
+    let before = q.before.unwrap_or(i64::MAX);
+    let rows = state
+        .db
+        .0
+        .call(move |c| {
+            let mut stmt = c.prepare(
+                "SELECT rev, ts, source, restored_from, state FROM dashboard_revisions
+                 WHERE rev < ?1 ORDER BY rev DESC LIMIT ?2",
+            )?;
+            let rows = stmt
+                .query_map(rusqlite::params![before, limit], |r| {
+                    let raw: String = r.get(4)?;
+                    Ok(Revision {
+                        rev: r.get(0)?,
+                        ts: r.get(1)?,
+                        source: r.get(2)?,
+                        restored_from: r.get(3)?,
+                        state: serde_json::from_str(&raw).unwrap_or(Value::Null),
+                    })
+                })?
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(rows)
+        })
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    Ok(Json(rows))
+}
+
+#[derive(Debug, Deserialize)]
+struct RestoreIn {
+    rev: i64,
+}
+
+/// POST /state/restore — old revision becomes the newest (history is never rewritten).
+async fn restore(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(input): Json<RestoreIn>,
+) -> Result<Response, (StatusCode, String)> {
+    let expected = if_match(&headers)?;
+    let now = chrono::Utc::now().to_rfc3339();
+    let out = state
+        .db
+        .0
+        .call(move |c| {
+            let tx = c.transaction()?;
+            let (rev, cur) = current(&tx)?;
+            if let Some(want) = expected {
+                if want != rev {
+                    return Ok(Write::Conflict(rev, cur));
+                }
+            }
+            let old: Option<String> = tx
+                .query_row(
+                    "SELECT state FROM dashboard_revisions WHERE rev = ?1",
+                    [input.rev],
+                    |r| r.get(0),
+                )
+                .ok();
+            let Some(old) = old else {
+                return Ok(Write::Missing);
+            };
+            let defs = team::load(&tx)?;
+            let mut next: Value = serde_json::from_str(&old)
+                .ok()
+                .filter(Value::is_object)
+                .unwrap_or_else(|| default_team_state(&defs));
+            team::shape(&defs, &mut next);
+            next["ts"] = Value::String(now);
+            let rev = append(&tx, &next, "restore", Some(input.rev))?;
+            tx.commit()?;
+            Ok(Write::Saved(rev, next))
+        })
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(match out {
+        Write::Saved(rev, v) => respond(v, rev),
+        Write::Conflict(rev, v) => conflict(rev, v),
+        Write::Invalid(_) => StatusCode::UNPROCESSABLE_ENTITY.into_response(),
+        Write::Missing => (StatusCode::NOT_FOUND, "unknown revision").into_response(),
+    })
+}
+
+/// Router to be mounted under `/state`.
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/get", get(get_state))
+        .route("/set", post(set_state))
+        .route("/history", get(history))
+        .route("/restore", post(restore))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn if_match_is_required_and_accepts_strong_weak_and_star() {
+        let mut h = HeaderMap::new();
+        assert_eq!(
+            if_match(&h).unwrap_err().0,
+            StatusCode::PRECONDITION_REQUIRED
+        );
+        h.insert(header::IF_MATCH, HeaderValue::from_static("\"7\""));
+        assert_eq!(if_match(&h).unwrap(), Some(7));
+        h.insert(header::IF_MATCH, HeaderValue::from_static("W/\"8\""));
+        assert_eq!(if_match(&h).unwrap(), Some(8));
+        h.insert(header::IF_MATCH, HeaderValue::from_static("*"));
+        assert_eq!(if_match(&h).unwrap(), None);
+        h.insert(header::IF_MATCH, HeaderValue::from_static("\"abc\""));
+        assert!(if_match(&h).is_err());
+    }
+
+    #[test]
+    fn legacy_blob_migrates_as_rev_one() {
+        let mut c = rusqlite::Connection::open_in_memory().unwrap();
+        c.execute_batch("CREATE TABLE kv(key TEXT PRIMARY KEY, value BLOB);")
+            .unwrap();
+        c.execute(
+            "INSERT INTO kv(key, value) VALUES('dashboard_state', ?1)",
+            [
+                br#"{"members":[],"pillars":{},"note":"kept","ts":"2026-01-05T09:00:00Z"}"#
+                    .to_vec(),
+            ],
+        )
+        .unwrap();
+        crate::db::ensure_schema(&mut c).unwrap();
+        crate::db::ensure_schema(&mut c).unwrap();
+
+        let (rev, v) = current(&c).unwrap();
+        assert_eq!(rev, 1);
+        assert_eq!(v["note"], "kept");
+    }
+}
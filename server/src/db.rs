@@ -8,8 +8,10 @@ Purpose
   in minor units).
 
 Tables (overview)
-• kv, profiles, threads, messages, tells, snapshots, status
-• emotions, energy_marks
+• kv, profiles, threads, messages, tells, gratitude, snapshots, status
+• emotions, emotion_kinds, emotion_kind_synonyms, arcs, bridges, bridge_aliases, energy_marks
+• energy_bands, energy_tasks, energy_transitions (energy calendar)
+• rhythm_configs, rhythm_sessions, rhythm_marks (work/rest cycles per person)
 • value_accounts(name, kind, currency)
 • value_entries(account_id, ts, direction[in|out], amount_minor, currency, memo, tags, counterparty, reference)
 
@@ -126,6 +128,19 @@ pub fn ensure_schema(c: &mut rusqlite::Connection) -> tokio_rusqlite::Result<()>
           handled_at     TEXT
         );
 
+        -- Gratitude ledger (/thanks; panic runs land a 'redirect' row)
+        CREATE TABLE IF NOT EXISTS gratitude(
+          id      INTEGER PRIMARY KEY AUTOINCREMENT,
+          ts      TEXT NOT NULL,
+          who     TEXT,
+          subject TEXT NOT NULL,
+          kind    TEXT,
+          note_id INTEGER,
+          details TEXT,
+          sealed  INTEGER NOT NULL DEFAULT 0    -- subject/details never leave the DB
+        );
+        CREATE INDEX IF NOT EXISTS idx_grat_ts ON gratitude(ts DESC);
+
         CREATE TABLE IF NOT EXISTS snapshots(
           id          INTEGER PRIMARY KEY,
           thread_id   INTEGER NOT NULL,
@@ -134,6 +149,7 @@ pub fn ensure_schema(c: &mut rusqlite::Connection) -> tokio_rusqlite::Result<()>
           ts          TEXT NOT NULL
         );
 
+        -- legacy global light; migrated into member_status(who='team') below
         CREATE TABLE IF NOT EXISTS status(
           id          INTEGER PRIMARY KEY,
           color       TEXT NOT NULL,         -- "green" | "yellow" | "red"
@@ -162,6 +178,79 @@ pub fn ensure_schema(c: &mut rusqlite::Connection) -> tokio_rusqlite::Result<()>
         CREATE INDEX IF NOT EXISTS idx_emotions_ts ON emotions(ts);
         CREATE INDEX IF NOT EXISTS idx_emotions_kind ON emotions(kind);
         CREATE INDEX IF NOT EXISTS idx_emotions_privacy ON emotions(privacy);
+        CREATE INDEX IF NOT EXISTS idx_emotions_who_ts_kind ON emotions(who, ts, kind);
+
+        -- Mood-history imports (see imports.rs): one row per background job and its report
+        CREATE TABLE IF NOT EXISTS import_jobs(
+          id             INTEGER PRIMARY KEY AUTOINCREMENT,
+          layout         TEXT NOT NULL,                        -- daylio | generic
+          state          TEXT NOT NULL,                        -- queued | running | done | failed
+          total          INTEGER NOT NULL DEFAULT 0,           -- data rows in the file
+          processed      INTEGER NOT NULL DEFAULT 0,
+          imported       INTEGER NOT NULL DEFAULT 0,
+          duplicates     INTEGER NOT NULL DEFAULT 0,           -- same (who, ts, kind) already stored
+          skipped        INTEGER NOT NULL DEFAULT 0,
+          unknown_kinds  TEXT NOT NULL DEFAULT '{}',           -- JSON { kind: rows }, stored folded
+          errors         TEXT NOT NULL DEFAULT '[]',           -- JSON [{ row, reason }], first 100
+          error          TEXT,                                 -- why a job failed
+          created_at     TEXT NOT NULL,
+          finished_at    TEXT
+        );
+
+        -- Emotion taxonomy (see taxonomy.rs); synonyms are folded lookup keys. Seeded once below.
+        CREATE TABLE IF NOT EXISTS emotion_kinds(
+          name        TEXT PRIMARY KEY,                        -- canonical, folded
+          band        TEXT NOT NULL,                           -- survival | integrity | coherence
+          bridge      TEXT,                                    -- bridge kind; NULL = name
+          color       TEXT,                                    -- UI hint
+          updated_at  TEXT NOT NULL
+        );
+        CREATE TABLE IF NOT EXISTS emotion_kind_synonyms(
+          synonym     TEXT PRIMARY KEY,
+          kind        TEXT NOT NULL                            -- emotion_kinds.name
+        );
+        CREATE INDEX IF NOT EXISTS idx_emotion_kind_synonyms_kind ON emotion_kind_synonyms(kind);
+
+        -- Healing arcs (see arcs.rs): trigger emotion → bridge → resolving gratitude
+        CREATE TABLE IF NOT EXISTS arcs(
+          id                    INTEGER PRIMARY KEY AUTOINCREMENT,
+          who                   TEXT NOT NULL,
+          state                 TEXT NOT NULL,                 -- opened | bridged | resolved | abandoned
+          trigger_emotion_id    INTEGER NOT NULL,
+          kind                  TEXT NOT NULL,                 -- copied from the trigger
+          intensity             REAL NOT NULL,
+          bridge_pattern        TEXT,
+          breath                TEXT,
+          doorway               TEXT,
+          anchor                TEXT,
+          resolution_emotion_id INTEGER,                       -- the gratitude row
+          tell_ids              TEXT NOT NULL DEFAULT '[]',    -- JSON array, one per transition
+          note                  TEXT,                          -- abandon reason
+          opened_at             TEXT NOT NULL,                 -- RFC3339 UTC
+          bridged_at            TEXT,
+          closed_at             TEXT
+        );
+        CREATE INDEX IF NOT EXISTS idx_arcs_who ON arcs(who, id);
+        CREATE INDEX IF NOT EXISTS idx_arcs_trigger ON arcs(trigger_emotion_id);
+
+        -- Bridge library (see bridges.rs): one row per kind/profile/intensity tier. Seeded once below.
+        CREATE TABLE IF NOT EXISTS bridges(
+          id             INTEGER PRIMARY KEY AUTOINCREMENT,
+          kind           TEXT NOT NULL,                        -- folded bridge kind
+          profile        TEXT NOT NULL DEFAULT '',             -- '' = global; else a person
+          min_intensity  REAL NOT NULL DEFAULT 0,              -- tier applies from here up
+          pattern        TEXT,                                 -- NULL only on profile rows
+          hint           TEXT,
+          breath         TEXT,
+          doorway        TEXT,
+          anchor         TEXT,
+          updated_at     TEXT NOT NULL,
+          UNIQUE(kind, profile, min_intensity)
+        );
+        CREATE TABLE IF NOT EXISTS bridge_aliases(
+          alias          TEXT PRIMARY KEY,
+          kind           TEXT NOT NULL                         -- bridges.kind
+        );
 
         -- Energy marks (time-series of energy levels per kind)
         -- kind: 'dragon' | 'heart' | 'play' | 'flow' | 'focus' | 'rest' (extensible)
@@ -178,6 +267,73 @@ pub fn ensure_schema(c: &mut rusqlite::Connection) -> tokio_rusqlite::Result<()>
         CREATE INDEX IF NOT EXISTS idx_energy_marks_kind ON energy_marks(kind);
         CREATE INDEX IF NOT EXISTS idx_energy_marks_who ON energy_marks(who);
 
+        -- Energy calendar (calendar.rs): bands, tasks tagged by band, band crossings
+        CREATE TABLE IF NOT EXISTS energy_bands(
+          code       TEXT PRIMARY KEY,                        -- 'E0' .. 'E4'
+          name       TEXT NOT NULL,
+          min_level  REAL NOT NULL UNIQUE,                    -- band runs up to the next one
+          felt       TEXT,
+          good_for   TEXT NOT NULL DEFAULT '[]',              -- JSON array
+          avoid      TEXT NOT NULL DEFAULT '[]'               -- JSON array
+        );
+        CREATE TABLE IF NOT EXISTS energy_tasks(
+          id         INTEGER PRIMARY KEY,
+          title      TEXT NOT NULL,
+          band       TEXT NOT NULL,                           -- energy_bands.code it needs
+          minutes    INTEGER,
+          who        TEXT,                                    -- NULL = anyone
+          done_at    TEXT,
+          created_at TEXT NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_energy_tasks_open ON energy_tasks(done_at, who);
+        CREATE TABLE IF NOT EXISTS energy_transitions(
+          id           INTEGER PRIMARY KEY,
+          ts           TEXT NOT NULL,                         -- ts of the mark that crossed
+          who          TEXT NOT NULL,
+          from_band    TEXT NOT NULL,
+          to_band      TEXT NOT NULL,
+          direction    TEXT NOT NULL,                         -- 'up' | 'down'
+          level        REAL NOT NULL,
+          mark_id      INTEGER NOT NULL,
+          published_at TEXT                                   -- set once broadcast
+        );
+        CREATE INDEX IF NOT EXISTS idx_energy_transitions_pending
+          ON energy_transitions(published_at);
+
+        -- Rhythm (rhythm.rs): work/rest cycles per person
+        CREATE TABLE IF NOT EXISTS rhythm_configs(
+          who          TEXT PRIMARY KEY,
+          work_minutes INTEGER NOT NULL,
+          rest_minutes INTEGER NOT NULL,
+          updated_at   TEXT NOT NULL,
+          auto_advance INTEGER NOT NULL DEFAULT 0,             -- server flips the phase
+          grace_secs   INTEGER NOT NULL DEFAULT 0,
+          doorway_mode TEXT                                     -- rest blocks suggest a doorway
+        );
+        CREATE TABLE IF NOT EXISTS rhythm_sessions(
+          id         INTEGER PRIMARY KEY,
+          who        TEXT NOT NULL,
+          started_at TEXT NOT NULL,
+          ended_at   TEXT                                     -- NULL while open
+        );
+        CREATE UNIQUE INDEX IF NOT EXISTS idx_rhythm_sessions_open
+          ON rhythm_sessions(who) WHERE ended_at IS NULL;
+        CREATE TABLE IF NOT EXISTS rhythm_marks(
+          id          INTEGER PRIMARY KEY,
+          session_id  INTEGER NOT NULL REFERENCES rhythm_sessions(id),
+          who         TEXT NOT NULL,
+          ts          TEXT NOT NULL,                           -- RFC3339, phase start
+          phase       TEXT NOT NULL CHECK(phase IN ('work', 'rest')),
+          source      TEXT NOT NULL DEFAULT 'manual',          -- start | manual | auto
+          extend_secs INTEGER NOT NULL DEFAULT 0,              -- snoozes added to this block
+          suggestion  TEXT,
+          pending     INTEGER NOT NULL DEFAULT 0               -- 1 until the clock announces it
+        );
+        CREATE INDEX IF NOT EXISTS idx_rhythm_marks_who_ts ON rhythm_marks(who, ts);
+        CREATE INDEX IF NOT EXISTS idx_rhythm_marks_session ON rhythm_marks(session_id);
+        CREATE INDEX IF NOT EXISTS idx_rhythm_marks_pending ON rhythm_marks(pending)
+          WHERE pending = 1;
+
         -- ───────────────────────────────────────────────────────────────
         -- Value Bridge (accounts + entries, minor units)
         -- ----------------------------------------------------------------
@@ -206,9 +362,325 @@ pub fn ensure_schema(c: &mut rusqlite::Connection) -> tokio_rusqlite::Result<()>
         CREATE INDEX IF NOT EXISTS idx_value_entries_account_ts ON value_entries(account_id, ts DESC);
         CREATE INDEX IF NOT EXISTS idx_value_entries_direction ON value_entries(direction);
         CREATE INDEX IF NOT EXISTS idx_value_entries_tags ON value_entries(tags);
+
+        -- ───────────────────────────────────────────────────────────────
+        -- Dashboard state revisions (newest row = current state).
+        -- Migrates the legacy kv `dashboard_state` blob as rev 1.
+        -- ----------------------------------------------------------------
+        CREATE TABLE IF NOT EXISTS dashboard_revisions(
+          rev            INTEGER PRIMARY KEY AUTOINCREMENT,
+          ts             TEXT NOT NULL,                          -- RFC3339
+          state          TEXT NOT NULL,                          -- JSON
+          source         TEXT NOT NULL DEFAULT 'set',            -- set | restore | migrate
+          restored_from  INTEGER
+        );
+
+        INSERT INTO dashboard_revisions(ts, state, source)
+          SELECT COALESCE(json_extract(CAST(value AS TEXT), '$.ts'), strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+                 CAST(value AS TEXT), 'migrate'
+          FROM kv
+          WHERE key = 'dashboard_state'
+            AND json_valid(CAST(value AS TEXT))
+            AND NOT EXISTS (SELECT 1 FROM dashboard_revisions);
+        DELETE FROM kv WHERE key = 'dashboard_state';
+
+        -- ───────────────────────────────────────────────────────────────
+        -- Readiness: one per-member model with TTL ('team' = global light).
+        -- Migrates legacy kv `status:{name}` rows and the single `status` row.
+        -- ----------------------------------------------------------------
+        CREATE TABLE IF NOT EXISTS member_status(
+          who         TEXT PRIMARY KEY,
+          color       TEXT NOT NULL,                             -- green | yellow | red
+          note        TEXT NOT NULL DEFAULT '',
+          updated_at  TEXT NOT NULL,                             -- RFC3339
+          expires_at  TEXT                                       -- RFC3339 or NULL
+        );
+
+        INSERT OR IGNORE INTO member_status(who, color, note, updated_at, expires_at)
+          SELECT substr(key, 8), CAST(value AS TEXT), '', strftime('%Y-%m-%dT%H:%M:%SZ', 'now'), NULL
+          FROM kv WHERE key LIKE 'status:%' AND length(key) > 7;
+        DELETE FROM kv WHERE key LIKE 'status:%';
+
+        INSERT OR IGNORE INTO member_status(who, color, note, updated_at, expires_at)
+          SELECT 'team', color, note,
+                 COALESCE(strftime('%Y-%m-%dT%H:%M:%SZ', updated_at), strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+                 expires_at
+          FROM status WHERE id = 1;
+
+        -- ───────────────────────────────────────────────────────────────
+        -- Readiness history (every color change, manual or TTL expiry)
+        -- ----------------------------------------------------------------
+        CREATE TABLE IF NOT EXISTS status_events(
+          id           INTEGER PRIMARY KEY,
+          ts           TEXT NOT NULL,                            -- RFC3339 (expiry rows: the expiry time)
+          who          TEXT NOT NULL,                            -- member name, or 'team' for the global light
+          color        TEXT NOT NULL,
+          prev         TEXT,
+          note         TEXT,
+          ttl_minutes  INTEGER,
+          expires_at   TEXT,
+          reason       TEXT NOT NULL DEFAULT 'manual'            -- manual | ttl-expired | panic
+        );
+        CREATE INDEX IF NOT EXISTS idx_status_events_who_ts ON status_events(who, ts);
+
+        -- ───────────────────────────────────────────────────────────────
+        -- Relational webhooks (consent registry + emitted envelopes)
+        -- ----------------------------------------------------------------
+        CREATE TABLE IF NOT EXISTS consents(
+          id          INTEGER PRIMARY KEY,
+          granted_by  TEXT NOT NULL,                             -- actor id ("raz")
+          scope       TEXT NOT NULL,                             -- event type ("panic.ui")
+          audience    TEXT NOT NULL,                             -- "group:<name>" | "user:<id>"
+          mode        TEXT NOT NULL DEFAULT 'explicit',
+          granted_at  TEXT NOT NULL,                             -- RFC3339
+          expires_at  TEXT,                                      -- RFC3339, NULL = until revoked
+          revoked_at  TEXT
+        );
+        CREATE INDEX IF NOT EXISTS idx_consents_granted_by ON consents(granted_by, scope);
+
+        CREATE TABLE IF NOT EXISTS relational_events(
+          id         TEXT PRIMARY KEY,                           -- evt_...
+          type       TEXT NOT NULL,
+          ts         TEXT NOT NULL,                              -- RFC3339
+          actor      TEXT NOT NULL,
+          delivered  INTEGER NOT NULL DEFAULT 0,
+          reason     TEXT,                                       -- "pending-consent:<why>" | "transport:<err>"
+          envelope   TEXT NOT NULL                               -- JSON
+        );
+        CREATE INDEX IF NOT EXISTS idx_relational_events_actor_ts ON relational_events(actor, ts DESC);
+
+        CREATE TABLE IF NOT EXISTS hook_nonces(
+          nonce    TEXT PRIMARY KEY,
+          seen_at  TEXT NOT NULL                                 -- RFC3339, pruned after 2× window
+        );
+
+        CREATE TABLE IF NOT EXISTS care_acks(
+          id           INTEGER PRIMARY KEY,
+          event_id     TEXT NOT NULL REFERENCES relational_events(id),
+          type         TEXT NOT NULL CHECK(type IN ('care.ack','care.done')),
+          by_id        TEXT,
+          by_display   TEXT,
+          action       TEXT,
+          outcome      TEXT,
+          note         TEXT,
+          ts           TEXT NOT NULL,                            -- sender ts (RFC3339)
+          received_at  TEXT NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_care_acks_event ON care_acks(event_id);
+
+        CREATE TABLE IF NOT EXISTS webhook_subscriptions(
+          id            INTEGER PRIMARY KEY,
+          name          TEXT NOT NULL,
+          url           TEXT NOT NULL,
+          format        TEXT NOT NULL DEFAULT 'm3',              -- m3 | slack | matrix | ntfy | form | custom
+          template      TEXT,                                    -- custom only: {{path.to.field}}
+          content_type  TEXT,                                    -- custom only (default application/json)
+          events        TEXT NOT NULL DEFAULT '*',               -- comma list; "panic.*" prefixes ok
+          secret        TEXT,                                    -- per-target HMAC secret (falls back to M3_WEBHOOK_SECRET)
+          active        INTEGER NOT NULL DEFAULT 1,
+          created_at    TEXT NOT NULL
+        );
+
+        -- Team roster + pillar definitions (see team.rs); seeded once below.
+        CREATE TABLE IF NOT EXISTS team_members(
+          id             INTEGER PRIMARY KEY AUTOINCREMENT,
+          name           TEXT NOT NULL UNIQUE,
+          avatar         TEXT,
+          default_energy INTEGER NOT NULL DEFAULT 60 CHECK(default_energy BETWEEN 0 AND 100),
+          position       INTEGER NOT NULL DEFAULT 0,
+          created_at     TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ','now'))
+        );
+        CREATE TABLE IF NOT EXISTS pillars(
+          key           TEXT PRIMARY KEY,
+          label         TEXT NOT NULL,
+          allowed       TEXT NOT NULL,                           -- JSON array of values
+          default_value TEXT NOT NULL,
+          position      INTEGER NOT NULL DEFAULT 0
+        );
+
+        -- Panic modes (see panic.rs); list columns are JSON arrays. Seeded once below.
+        CREATE TABLE IF NOT EXISTS panic_presets(
+          mode          TEXT PRIMARY KEY,
+          label         TEXT NOT NULL,
+          whispers      TEXT NOT NULL,
+          breaths       TEXT NOT NULL,
+          doorways      TEXT NOT NULL,
+          anchors       TEXT NOT NULL,
+          emotion_kind  TEXT NOT NULL,
+          intensity     REAL NOT NULL,
+          bridge_kind   TEXT NOT NULL,
+          rotation      TEXT NOT NULL DEFAULT 'tick',              -- tick | random | lru
+          updated_at    TEXT NOT NULL
+        );
+        CREATE TABLE IF NOT EXISTS panic_preset_usage(
+          mode          TEXT NOT NULL,
+          slot          TEXT NOT NULL,                           -- whisper | breath | doorway | anchor
+          value         TEXT NOT NULL,
+          uses          INTEGER NOT NULL DEFAULT 0,
+          last_used_at  TEXT NOT NULL,
+          PRIMARY KEY(mode, slot, value)
+        );
+
+        -- One row per panic run (see panic.rs); linked ids point at the rows it wrote.
+        CREATE TABLE IF NOT EXISTS panic_events(
+          id                  INTEGER PRIMARY KEY AUTOINCREMENT,
+          ts                  TEXT NOT NULL,                     -- RFC3339 UTC
+          who                 TEXT NOT NULL,
+          mode                TEXT NOT NULL,
+          source              TEXT NOT NULL,                     -- ui | run
+          whisper             TEXT NOT NULL,
+          breath              TEXT NOT NULL,
+          doorway             TEXT NOT NULL,
+          anchor              TEXT NOT NULL,
+          emotion_kind        TEXT NOT NULL,
+          intensity           REAL NOT NULL,
+          suggested_bridge    TEXT,
+          tell_id             INTEGER,
+          emotion_id          INTEGER,
+          gratitude_id        INTEGER,
+          relational_event_id TEXT,
+          log_path            TEXT,                              -- compact log export, if enabled
+          checkin_due_at      TEXT,                              -- "did that help?" (followup.rs)
+          checkin_sent_at     TEXT,
+          before_intensity    REAL,
+          after_intensity     REAL,
+          outcome_at          TEXT,
+          outcome_note        TEXT,
+          outcome_emotion_id  INTEGER
+        );
+        CREATE INDEX IF NOT EXISTS idx_panic_events_ts ON panic_events(ts);
+        CREATE INDEX IF NOT EXISTS idx_panic_events_who_ts ON panic_events(who, ts);
+        CREATE INDEX IF NOT EXISTS idx_panic_events_checkin ON panic_events(checkin_due_at)
+          WHERE checkin_sent_at IS NULL AND outcome_at IS NULL;
         "#,
     )?;
 
+    // energy_marks.ts used to be `datetime('now')`; bring those rows to RFC3339 UTC.
+    c.execute(
+        "UPDATE energy_marks SET ts = replace(ts, ' ', 'T') || '+00:00'
+          WHERE length(ts) = 19 AND substr(ts, 11, 1) = ' '",
+        [],
+    )?;
+
+    // The CSV of an import only lives in memory; jobs cut off by a restart cannot resume.
+    c.execute(
+        "UPDATE import_jobs SET state = 'failed', error = 'interrupted by a restart',
+                finished_at = ?1
+          WHERE state IN ('queued', 'running')",
+        [chrono::Utc::now().to_rfc3339()],
+    )?;
+
+    // Seed the roster/pillars the dashboard used to hardcode — once, so deletions stick.
+    let seeded: bool = c
+        .query_row("SELECT 1 FROM kv WHERE key = 'team_seeded'", [], |_| Ok(()))
+        .optional()?
+        .is_some();
+    if !seeded {
+        c.execute_batch(
+            r#"
+            INSERT OR IGNORE INTO team_members(name, default_energy, position) VALUES
+              ('Raz', 70, 0), ('Sawsan', 65, 1), ('Nico', 60, 2);
+            INSERT OR IGNORE INTO pillars(key, label, allowed, default_value, position) VALUES
+              ('crown',      'Crown',      '["good","watch","rest"]', 'watch', 0),
+              ('void',       'Void',       '["good","watch","rest"]', 'watch', 1),
+              ('play',       'Play',       '["good","watch","rest"]', 'good',  2),
+              ('dragon',     'Dragon',     '["good","watch","rest"]', 'watch', 3),
+              ('life_force', 'Life force', '["good","watch","rest"]', 'watch', 4);
+            INSERT OR IGNORE INTO kv(key, value) VALUES('team_seeded', '1');
+            "#,
+        )?;
+    }
+
+    // the kind as written, when the taxonomy stored it under another name (taxonomy.rs)
+    add_column_if_missing(c, "emotions", "kind_raw", "TEXT")?;
+    // sealed gratitude (mirror tag, like emotions.sealed)
+    add_column_if_missing(c, "gratitude", "sealed", "INTEGER NOT NULL DEFAULT 0")?;
+
+    // Panic presets from the palettes that used to be compiled in — also once.
+    let seeded: bool = c
+        .query_row(
+            "SELECT 1 FROM kv WHERE key = 'panic_presets_seeded'",
+            [],
+            |_| Ok(()),
+        )
+        .optional()?
+        .is_some();
+    if !seeded {
+        crate::panic::seed(c)?;
+        c.execute(
+            "INSERT OR IGNORE INTO kv(key, value) VALUES('panic_presets_seeded', '1')",
+            [],
+        )?;
+    }
+
+    // Emotion taxonomy from `taxonomy::SEED` — also once; renames existing rows.
+    let seeded: bool = c
+        .query_row(
+            "SELECT 1 FROM kv WHERE key = 'emotion_kinds_seeded'",
+            [],
+            |_| Ok(()),
+        )
+        .optional()?
+        .is_some();
+    if !seeded {
+        crate::taxonomy::seed_registry(c)?;
+        c.execute(
+            "INSERT OR IGNORE INTO kv(key, value) VALUES('emotion_kinds_seeded', '1')",
+            [],
+        )?;
+    }
+
+    // Bridge tiers and aliases from `bridges::SEEDS` — also once; edits live in the table.
+    let seeded: bool = c
+        .query_row("SELECT 1 FROM kv WHERE key = 'bridges_seeded'", [], |_| {
+            Ok(())
+        })
+        .optional()?
+        .is_some();
+    if !seeded {
+        crate::bridges::seed(c)?;
+        c.execute(
+            "INSERT OR IGNORE INTO kv(key, value) VALUES('bridges_seeded', '1')",
+            [],
+        )?;
+    }
+
+    // Energy bands from `calendar::SEEDS`, once; `PUT /energy/calendar/bands` edits them.
+    let seeded: bool = c
+        .query_row(
+            "SELECT 1 FROM kv WHERE key = 'energy_bands_seeded'",
+            [],
+            |_| Ok(()),
+        )
+        .optional()?
+        .is_some();
+    if !seeded {
+        crate::calendar::seed(c)?;
+        c.execute(
+            "INSERT OR IGNORE INTO kv(key, value) VALUES('energy_bands_seeded', '1')",
+            [],
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Guarded `ALTER TABLE … ADD COLUMN` (SQLite has no `IF NOT EXISTS` for columns).
+fn add_column_if_missing(
+    c: &rusqlite::Connection,
+    table: &str,
+    column: &str,
+    decl: &str,
+) -> rusqlite::Result<()> {
+    let exists = c
+        .prepare(&format!("PRAGMA table_info({table})"))?
+        .query_map([], |r| r.get::<_, String>(1))?
+        .collect::<Result<Vec<_>, _>>()?
+        .iter()
+        .any(|name| name == column);
+    if !exists {
+        c.execute_batch(&format!("ALTER TABLE {table} ADD COLUMN {column} {decl};"))?;
+    }
     Ok(())
 }
 
@@ -1,9 +1,15 @@
-use crate::consciousness::{band_from_emotion, Band};
+use crate::arcs;
+use crate::bridges;
+use crate::consciousness::{
+    band_from_intensity, emotion_band, Band, BandModel, BandQuery, ModelBand,
+};
+use crate::imports;
+use crate::taxonomy;
 use crate::tells;
 use crate::AppState;
 use axum::http::StatusCode;
-use axum::{extract::State, routing::get, routing::post, Json, Router};
-use chrono::Utc;
+use axum::{extract::Query, extract::State, routing::get, routing::post, Json, Router};
+use chrono::{Datelike, Utc};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Deserialize)]
@@ -27,66 +33,42 @@ pub struct EmotionOut {
     pub intensity: f32,
     pub note_id: Option<i64>,
     pub details: Option<String>,
+    /// Free-text note written by other flows (e.g. `panic ui: …`, `panic outcome #…`).
+    pub note: Option<String>,
     pub sealed: bool,
     pub archetype: Option<String>,
+    /// Three-band class (filters, aggregates); serialized as `band` per `?band_model=`.
+    #[serde(skip)]
     pub band: Band,
+    /// `band` in the requested model (three by default); absent with `band_model=none`.
+    #[serde(rename = "band", skip_serializing_if = "Option::is_none")]
+    pub band_view: Option<ModelBand>,
     pub privacy: String,
+    /// Set by `/emotions/add` when the kind is not in the taxonomy (stored folded).
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub unknown_kind: bool,
+}
+
+impl EmotionOut {
+    /// Express `band` in `model` (`None` leaves it out of the response).
+    pub fn in_model(mut self, model: Option<BandModel>) -> Self {
+        self.band_view = model.map(|m| emotion_band(&self.kind, self.band, m));
+        self
+    }
+}
+
+/// `?band_model=`, three when absent; 422 when unknown.
+fn band_model(q: &BandQuery) -> Result<Option<BandModel>, String> {
+    q.model(Some(BandModel::Three))
 }
 
 #[derive(Debug, Deserialize)]
 pub struct BridgeIn {
     pub kind: String,   // e.g. "anxiety", "anger", "shame"
     pub intensity: f32, // 0.0..=1.0
-}
-
-#[derive(Debug, Serialize)]
-pub struct BridgeOut {
-    pub breath: &'static str,
-    pub doorway: &'static str,
-    pub anchor: &'static str,
-}
-
-fn bridge_table(label: &str, intensity_01: f32) -> BridgeOut {
-    let l = label.to_ascii_lowercase();
-    // Map 0.0..=1.0 → 0..=10 as coarse buckets
-    let lvl: u8 = (intensity_01.clamp(0.0, 1.0) * 10.0).round() as u8;
-
-    match l.as_str() {
-        "anxiety" | "fear" => BridgeOut {
-            breath: if lvl >= 6 {
-                "box: in4-hold4-out6 × 4"
-            } else {
-                "double_exhale × 6"
-            },
-            doorway: "sip water, feet on floor",
-            anchor: "Name 3 objects you see.",
-        },
-        "anger" => BridgeOut {
-            breath: "in4-out8 × 6",
-            doorway: "shake arms 30s, step outside",
-            anchor: "Lower shoulders, soften jaw.",
-        },
-        "shame" => BridgeOut {
-            breath: "4-6 breath × 6",
-            doorway: "write 3 objective facts (no story)",
-            anchor: "Hand over heart: 'still worthy'.",
-        },
-        "paradox" => BridgeOut {
-            breath: "in4-out4 × 8",
-            doorway: "touch ground + name 1 effort, 1 gift (no comparison)",
-            anchor: "Whisper: 'neither above nor below — simply alive.'",
-        },
-        "gratitude" => BridgeOut {
-            breath: "soft inhale, long exhale × 3",
-            doorway: "write 3 one-line gratitudes",
-            anchor: "🌬️ whisper: I am already held.",
-        },
-        _ => BridgeOut {
-            breath: "double_exhale × 6",
-            doorway: "stand_up + shoulder_roll",
-            anchor: "Return to center.",
-        },
-    }
+    /// Profile whose bridge overrides apply (see bridges.rs).
+    #[serde(default)]
+    pub who: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -97,12 +79,17 @@ pub struct ResolveIn {
     pub sealed: Option<bool>,
     pub archetype: Option<String>,
     pub privacy: Option<String>,
+    /// Close this healing arc with the gratitude row (see arcs.rs); the arc's person wins.
+    #[serde(default)]
+    pub arc_id: Option<i64>,
 }
 
 async fn resolve_emotion(
     State(state): State<AppState>,
+    Query(bq): Query<BandQuery>,
     Json(input): Json<ResolveIn>,
 ) -> Result<Json<EmotionOut>, StatusCode> {
+    let model = band_model(&bq).map_err(|_| StatusCode::UNPROCESSABLE_ENTITY)?;
     // Validate/normalize
     let who = input.who.trim().to_owned();
     if who.is_empty() {
@@ -123,6 +110,19 @@ async fn resolve_emotion(
         return Err(StatusCode::UNPROCESSABLE_ENTITY);
     }
 
+    if let Some(arc_id) = input.arc_id {
+        let req = arcs::ResolveIn {
+            note_id,
+            details,
+            sealed: Some(sealed),
+            archetype,
+            privacy: Some(privacy),
+        };
+        return resolve_arc(&state, arc_id, req)
+            .await
+            .map(|Json(e)| Json(e.in_model(model)));
+    }
+
     // Land as gratitude @ intensity 1.0
     let ts = Utc::now().to_rfc3339();
     let kind = "gratitude".to_string();
@@ -140,7 +140,11 @@ async fn resolve_emotion(
                     params![ts, who, kind, intensity, note_id, details, sealed, archetype, privacy],
                 )?;
                 let id = conn.last_insert_rowid();
-                let band = band_from_emotion(&kind, intensity);
+                // the registry's band, so a re-banded `gratitude` shows here too
+                let band = taxonomy::normalize(conn, &kind)?
+                    .known
+                    .map(|k| k.band)
+                    .unwrap_or_else(|| band_from_intensity(intensity));
                 Ok(EmotionOut {
                     id,
                     ts,
@@ -149,10 +153,13 @@ async fn resolve_emotion(
                     intensity,
                     note_id,
                     details,
+                    note: None,
                     sealed,
                     archetype,
                     band,
+                    band_view: Some(ModelBand::Three(band)),
                     privacy,
+                    unknown_kind: false,
                 })
             },
         )
@@ -174,13 +181,43 @@ async fn resolve_emotion(
         let _ = tells::insert_tell(&state.db, node, &pre, &act, created_at).await;
     }
 
-    Ok(Json(inserted))
+    Ok(Json(inserted.in_model(model)))
+}
+
+/// `/emotions/resolve` with `arc_id`: the arc writes (and links) the gratitude row.
+async fn resolve_arc(
+    state: &AppState,
+    arc_id: i64,
+    req: arcs::ResolveIn,
+) -> Result<Json<EmotionOut>, StatusCode> {
+    let now = Utc::now().to_rfc3339();
+    state
+        .db
+        .0
+        .call(move |c| {
+            let arc = match arcs::resolve(c, arc_id, &req, &now)? {
+                Ok(arc) => arc,
+                Err(e) => return Ok(Err(e)),
+            };
+            let id = arc.resolution_emotion_id.unwrap_or_default();
+            Ok(Ok(c.query_row(
+                &format!("SELECT {EMOTION_COLS} FROM emotions WHERE id = ?1"),
+                [id],
+                row_to_emotion,
+            )?))
+        })
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .map(Json)
+        .map_err(|e| arcs::http_err(e).0)
 }
 
 async fn add_emotion(
     State(state): State<AppState>,
+    Query(bq): Query<BandQuery>,
     Json(input): Json<EmotionIn>,
 ) -> Result<Json<EmotionOut>, StatusCode> {
+    let model = band_model(&bq).map_err(|_| StatusCode::UNPROCESSABLE_ENTITY)?;
     // Validate input before touching the DB
     if !(0.0_f32..=1.0_f32).contains(&input.intensity) {
         return Err(StatusCode::UNPROCESSABLE_ENTITY);
@@ -200,194 +237,655 @@ async fn add_emotion(
         return Err(StatusCode::UNPROCESSABLE_ENTITY);
     }
 
-    let note_id = input.note_id;
-    let details = input.details;
-    let intensity = input.intensity;
-    let sealed = input.sealed;
-    let archetype = input.archetype;
-
+    let input = EmotionIn {
+        who,
+        kind,
+        privacy,
+        ..input
+    };
+    let strict = taxonomy::strict();
     let ts = Utc::now().to_rfc3339();
-    let inserted: EmotionOut = state
+    let inserted: Option<EmotionOut> = state
         .db
         .0
-        .call(
-            move |conn: &mut rusqlite::Connection| -> tokio_rusqlite::Result<EmotionOut> {
-                use rusqlite::params;
-                conn.execute(
-                    "INSERT INTO emotions(ts, who, kind, intensity, note_id, details, sealed, archetype, privacy)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
-                    params![ts, who, kind, intensity, note_id, details, sealed, archetype, privacy],
-                )?;
-                let id = conn.last_insert_rowid();
-                let band = band_from_emotion(&kind, intensity);
-                Ok(EmotionOut {
-                    id,
-                    ts,
-                    who,
-                    kind,
-                    intensity,
-                    note_id,
-                    details,
-                    sealed,
-                    archetype,
-                    band,
-                    privacy,
-                })
-            },
-        )
+        .call(move |conn| Ok(insert(conn, ts, input, None, strict)?))
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
-    Ok(Json(inserted))
+    inserted
+        .map(|e| Json(e.in_model(model)))
+        .ok_or(StatusCode::UNPROCESSABLE_ENTITY)
 }
 
+/// Store one emotion at `ts` (RFC3339 UTC) with its kind normalized through the taxonomy;
+/// the kind as written goes to `kind_raw` when it differs.
+/// `None` when the kind folds to nothing, or is unknown while `strict`.
+pub fn insert(
+    conn: &rusqlite::Connection,
+    ts: String,
+    input: EmotionIn,
+    note: Option<String>,
+    strict: bool,
+) -> rusqlite::Result<Option<EmotionOut>> {
+    // one spelling per feeling (see taxonomy.rs)
+    let normalized = taxonomy::normalize(conn, &input.kind)?;
+    let unknown_kind = normalized.known.is_none();
+    if normalized.kind.is_empty() || (unknown_kind && strict) {
+        return Ok(None);
+    }
+    let kind = normalized.kind;
+    let kind_raw = (input.kind != kind).then(|| input.kind.clone());
+    let EmotionIn {
+        who,
+        intensity,
+        note_id,
+        details,
+        sealed,
+        archetype,
+        privacy,
+        ..
+    } = input;
+    conn.execute(
+        "INSERT INTO emotions(ts, who, kind, kind_raw, intensity, note_id, details, note, sealed, archetype, privacy)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+        rusqlite::params![ts, who, kind, kind_raw, intensity, note_id, details, note, sealed, archetype, privacy],
+    )?;
+    let id = conn.last_insert_rowid();
+    let band = normalized
+        .known
+        .map(|k| k.band)
+        .unwrap_or_else(|| band_from_intensity(intensity));
+    Ok(Some(EmotionOut {
+        id,
+        ts,
+        who,
+        kind,
+        intensity,
+        note_id,
+        details,
+        note,
+        sealed,
+        archetype,
+        band,
+        band_view: Some(ModelBand::Three(band)),
+        privacy,
+        unknown_kind,
+    }))
+}
+
+/// The last column is the registry band (`taxonomy::BAND_SQL`), NULL for unknown kinds.
+const EMOTION_COLS: &str =
+    "id, ts, who, kind, intensity, note_id, details, note, sealed, archetype, privacy,
+     (SELECT band FROM emotion_kinds k WHERE k.name = emotions.kind)";
+
+/// Registry band, else the intensity fallback.
+pub fn band_of(registry: Option<String>, intensity: f32) -> Band {
+    registry
+        .as_deref()
+        .and_then(Band::parse)
+        .unwrap_or_else(|| band_from_intensity(intensity))
+}
+
+fn row_to_emotion(row: &rusqlite::Row) -> rusqlite::Result<EmotionOut> {
+    let kind: String = row.get(3)?;
+    let intensity: f32 = row.get(4)?;
+    let band = band_of(row.get(11)?, intensity);
+    Ok(EmotionOut {
+        id: row.get(0)?,
+        ts: row.get(1)?,
+        who: row.get(2)?,
+        band,
+        band_view: Some(ModelBand::Three(band)),
+        kind,
+        intensity,
+        note_id: row.get(5)?,
+        details: row.get(6)?,
+        note: row.get(7)?,
+        sealed: row.get(8)?,
+        archetype: row.get(9)?,
+        privacy: row.get(10)?,
+        unknown_kind: false,
+    })
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct RecentQuery {
+    /// Only rows with a larger id (polling).
+    #[serde(default)]
+    after: Option<i64>,
+}
+
+/// GET /emotions/recent?after=&band_model= — newest 20.
 async fn recent_emotions(
     State(state): State<AppState>,
+    Query(q): Query<RecentQuery>,
+    Query(bq): Query<BandQuery>,
 ) -> Result<Json<Vec<EmotionOut>>, StatusCode> {
+    let model = band_model(&bq).map_err(|_| StatusCode::UNPROCESSABLE_ENTITY)?;
     let out: Vec<EmotionOut> = state
         .db
         .0
-        .call(
-            move |conn: &mut rusqlite::Connection| -> tokio_rusqlite::Result<Vec<EmotionOut>> {
-                let mut stmt = conn.prepare(
-                    "SELECT id, ts, who, kind, intensity, note_id, details, sealed, archetype, privacy
-             FROM emotions
-             ORDER BY ts DESC
-             LIMIT 20",
-                )?;
-
-                let rows = stmt.query_map([], |row| {
-                    let id: i64 = row.get(0)?;
-                    let ts: String = row.get(1)?;
-                    let who: String = row.get(2)?;
-                    let kind: String = row.get(3)?;
-                    let intensity: f32 = row.get(4)?;
-                    let note_id: Option<i64> = row.get(5)?;
-                    let details: Option<String> = row.get(6)?;
-                    let sealed: bool = row.get(7)?;
-                    let archetype: Option<String> = row.get(8)?;
-                    let privacy: String = row.get(9)?;
-                    let band = band_from_emotion(&kind, intensity);
-                    Ok(EmotionOut { id, ts, who, kind, intensity, note_id, details, sealed, archetype, band, privacy })
-                })?;
-
-                let mut out = Vec::new();
-                for row in rows {
-                    out.push(row?);
-                }
-                Ok(out)
-            },
-        )
+        .call(move |conn| {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {EMOTION_COLS} FROM emotions
+                 WHERE (?1 IS NULL OR id > ?1)
+                 ORDER BY ts DESC
+                 LIMIT 20"
+            ))?;
+            let rows = stmt
+                .query_map([q.after], row_to_emotion)?
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(rows)
+        })
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
+    Ok(Json(out.into_iter().map(|e| e.in_model(model)).collect()))
+}
+
+// ---- Query + aggregates -----------------------------------------------------
+
+type ApiErr = (StatusCode, String);
+
+fn unprocessable(msg: impl Into<String>) -> ApiErr {
+    (StatusCode::UNPROCESSABLE_ENTITY, msg.into())
+}
+
+fn internal(e: impl std::fmt::Display) -> ApiErr {
+    (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+}
+
+/// Filters shared by `GET /emotions` and `/emotions/aggregate`. Empty fields match everything.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct EmotionFilter {
+    #[serde(default)]
+    pub who: Option<String>,
+    /// Case-insensitive.
+    #[serde(default)]
+    pub kind: Option<String>,
+    /// survival | integrity | coherence (derived from kind + intensity).
+    #[serde(default)]
+    pub band: Option<String>,
+    #[serde(default)]
+    pub archetype: Option<String>,
+    #[serde(default)]
+    pub privacy: Option<String>,
+    /// 0..1, inclusive.
+    #[serde(default)]
+    pub min_intensity: Option<f32>,
+    /// 0..1, inclusive.
+    #[serde(default)]
+    pub max_intensity: Option<f32>,
+    /// RFC3339, inclusive.
+    #[serde(default)]
+    pub since: Option<String>,
+    /// RFC3339, exclusive.
+    #[serde(default)]
+    pub until: Option<String>,
+}
+
+const FILTER_SQL: &str = "(?1 IS NULL OR who = ?1)
+     AND (?2 IS NULL OR lower(kind) = lower(?2))
+     AND (?3 IS NULL OR archetype = ?3)
+     AND (?4 IS NULL OR privacy = ?4)
+     AND (?5 IS NULL OR intensity >= ?5)
+     AND (?6 IS NULL OR intensity <= ?6)
+     AND (?7 IS NULL OR ts >= ?7)
+     AND (?8 IS NULL OR ts < ?8)";
+
+impl EmotionFilter {
+    /// Drop blanks, check ranges, normalize timestamps to UTC RFC3339 and parse the band.
+    pub fn normalized(mut self) -> Result<(Self, Option<Band>), String> {
+        for field in [
+            &mut self.who,
+            &mut self.kind,
+            &mut self.band,
+            &mut self.archetype,
+            &mut self.privacy,
+        ] {
+            *field = field
+                .take()
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty());
+        }
+        let band = match self.band.as_deref() {
+            None => None,
+            Some(b) => Some(Band::parse(b).ok_or("band must be survival, integrity or coherence")?),
+        };
+        for v in [self.min_intensity, self.max_intensity]
+            .into_iter()
+            .flatten()
+        {
+            if !(0.0..=1.0).contains(&v) {
+                return Err("intensity bounds must be 0..1".into());
+            }
+        }
+        if let (Some(lo), Some(hi)) = (self.min_intensity, self.max_intensity) {
+            if lo > hi {
+                return Err("min_intensity must not exceed max_intensity".into());
+            }
+        }
+        for (name, field) in [("since", &mut self.since), ("until", &mut self.until)] {
+            if let Some(raw) = field.take().filter(|s| !s.trim().is_empty()) {
+                let dt = chrono::DateTime::parse_from_rfc3339(raw.trim())
+                    .map_err(|_| format!("{name} must be RFC3339"))?;
+                *field = Some(dt.with_timezone(&Utc).to_rfc3339());
+            }
+        }
+        Ok((self, band))
+    }
+
+    /// Map `kind` onto its canonical name so `anxious` finds `anxiety` rows.
+    fn canonical(&self, c: &rusqlite::Connection) -> rusqlite::Result<Self> {
+        let mut f = self.clone();
+        if let Some(k) = f.kind.as_deref() {
+            f.kind = Some(taxonomy::normalize(c, k)?.kind);
+        }
+        Ok(f)
+    }
+
+    fn params(&self) -> [rusqlite::types::Value; 8] {
+        use rusqlite::types::Value;
+        let text = |v: &Option<String>| v.clone().map_or(Value::Null, Value::Text);
+        let real = |v: Option<f32>| v.map_or(Value::Null, |x| Value::Real(x as f64));
+        [
+            text(&self.who),
+            text(&self.kind),
+            text(&self.archetype),
+            text(&self.privacy),
+            real(self.min_intensity),
+            real(self.max_intensity),
+            text(&self.since),
+            text(&self.until),
+        ]
+    }
+}
+
+/// Page of rows, newest first. `before`/`after` are id cursors; `band` is applied while
+/// scanning since it is derived, not stored.
+pub fn list(
+    c: &rusqlite::Connection,
+    f: &EmotionFilter,
+    band: Option<Band>,
+    before: Option<i64>,
+    after: Option<i64>,
+    limit: usize,
+) -> rusqlite::Result<Vec<EmotionOut>> {
+    let mut stmt = c.prepare(&format!(
+        "SELECT {EMOTION_COLS} FROM emotions
+         WHERE {FILTER_SQL} AND (?9 IS NULL OR id < ?9) AND (?10 IS NULL OR id > ?10)
+         ORDER BY id DESC"
+    ))?;
+    let [a, b, d, e, g, h, i, j] = f.canonical(c)?.params();
+    let mut rows = stmt.query(rusqlite::params![a, b, d, e, g, h, i, j, before, after])?;
+    let mut out = Vec::new();
+    while out.len() < limit {
+        let Some(row) = rows.next()? else { break };
+        let item = row_to_emotion(row)?;
+        if band.is_none_or(|b| item.band == b) {
+            out.push(item);
+        }
+    }
+    Ok(out)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Bucket {
+    Day,
+    /// ISO weeks, starting Monday (UTC).
+    Week,
+}
+
+impl Bucket {
+    pub fn parse(s: &str) -> Option<Bucket> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "day" | "daily" => Some(Bucket::Day),
+            "week" | "weekly" => Some(Bucket::Week),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Bucket::Day => "day",
+            Bucket::Week => "week",
+        }
+    }
+
+    /// First day of the bucket holding `ts` (UTC); None for unparsable timestamps.
+    pub fn start(self, ts: &str) -> Option<chrono::NaiveDate> {
+        let day = chrono::DateTime::parse_from_rfc3339(ts)
+            .ok()?
+            .with_timezone(&Utc)
+            .date_naive();
+        Some(match self {
+            Bucket::Day => day,
+            Bucket::Week => {
+                day - chrono::Duration::days(day.weekday().num_days_from_monday() as i64)
+            }
+        })
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct BandCounts {
+    pub survival: i64,
+    pub integrity: i64,
+    pub coherence: i64,
+}
+
+impl BandCounts {
+    fn add(&mut self, band: Band) {
+        match band {
+            Band::Survival => self.survival += 1,
+            Band::Integrity => self.integrity += 1,
+            Band::Coherence => self.coherence += 1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct BucketStats {
+    /// First day of the bucket (`YYYY-MM-DD`, UTC).
+    pub start: String,
+    pub count: i64,
+    pub mean_intensity: f64,
+    pub bands: BandCounts,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct KindStats {
+    pub kind: String,
+    pub count: i64,
+    pub mean_intensity: f64,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct Aggregate {
+    pub bucket: &'static str,
+    pub total: i64,
+    pub mean_intensity: Option<f64>,
+    /// Oldest first; buckets without rows are omitted.
+    pub buckets: Vec<BucketStats>,
+    /// Most frequent first (kinds lowercased).
+    pub kinds: Vec<KindStats>,
+    pub bands: BandCounts,
+}
+
+fn mean(sum: f64, n: i64) -> f64 {
+    if n == 0 {
+        0.0
+    } else {
+        (sum / n as f64 * 1000.0).round() / 1000.0
+    }
+}
+
+/// Bucketed counts, mean intensity per kind and band distribution for rows matching `f`.
+pub fn aggregate(
+    c: &rusqlite::Connection,
+    f: &EmotionFilter,
+    band: Option<Band>,
+    bucket: Bucket,
+) -> rusqlite::Result<Aggregate> {
+    use std::collections::BTreeMap;
+    let mut stmt = c.prepare(&format!(
+        "SELECT ts, kind, intensity, {} FROM emotions WHERE {FILTER_SQL}",
+        taxonomy::BAND_SQL
+    ))?;
+    let [a, b, d, e, g, h, i, j] = f.canonical(c)?.params();
+    let mut rows = stmt.query(rusqlite::params![a, b, d, e, g, h, i, j])?;
+
+    let (mut total, mut sum) = (0i64, 0f64);
+    let mut bands = BandCounts::default();
+    let mut buckets: BTreeMap<chrono::NaiveDate, (i64, f64, BandCounts)> = BTreeMap::new();
+    let mut kinds: BTreeMap<String, (i64, f64)> = BTreeMap::new();
+    while let Some(row) = rows.next()? {
+        let ts: String = row.get(0)?;
+        let kind: String = row.get(1)?;
+        let intensity: f32 = row.get(2)?;
+        let b = band_of(row.get(3)?, intensity);
+        if band.is_some_and(|want| want != b) {
+            continue;
+        }
+        let x = intensity as f64;
+        total += 1;
+        sum += x;
+        bands.add(b);
+        if let Some(start) = bucket.start(&ts) {
+            let slot = buckets.entry(start).or_default();
+            slot.0 += 1;
+            slot.1 += x;
+            slot.2.add(b);
+        }
+        let k = kinds.entry(kind.to_lowercase()).or_default();
+        k.0 += 1;
+        k.1 += x;
+    }
+
+    let mut kinds: Vec<KindStats> = kinds
+        .into_iter()
+        .map(|(kind, (n, s))| KindStats {
+            kind,
+            count: n,
+            mean_intensity: mean(s, n),
+        })
+        .collect();
+    kinds.sort_by(|x, y| y.count.cmp(&x.count).then_with(|| x.kind.cmp(&y.kind)));
+    Ok(Aggregate {
+        bucket: bucket.as_str(),
+        total,
+        mean_intensity: (total > 0).then(|| mean(sum, total)),
+        buckets: buckets
+            .into_iter()
+            .map(|(start, (n, s, bands))| BucketStats {
+                start: start.to_string(),
+                count: n,
+                mean_intensity: mean(s, n),
+                bands,
+            })
+            .collect(),
+        kinds,
+        bands,
+    })
+}
+
+#[derive(Debug, Deserialize)]
+struct PageParams {
+    #[serde(default)]
+    before: Option<i64>,
+    #[serde(default)]
+    after: Option<i64>,
+    #[serde(default)]
+    limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+struct EmotionPage {
+    items: Vec<EmotionOut>,
+    /// Pass as `before` for the next (older) page; absent on the last page.
+    next_before: Option<i64>,
+}
+
+/// GET /emotions?who=&kind=&band=&archetype=&privacy=&min_intensity=&max_intensity=
+///               &since=&until=&before=&after=&limit=&band_model=
+async fn list_http(
+    State(state): State<AppState>,
+    Query(filter): Query<EmotionFilter>,
+    Query(q): Query<PageParams>,
+    Query(bq): Query<BandQuery>,
+) -> Result<Json<EmotionPage>, ApiErr> {
+    let (filter, band) = filter.normalized().map_err(unprocessable)?;
+    let model = band_model(&bq).map_err(unprocessable)?;
+    let limit = q.limit.unwrap_or(50).clamp(1, 500);
+    let (before, after) = (q.before, q.after);
+    let items = state
+        .db
+        .0
+        .call(move |c| Ok(list(c, &filter, band, before, after, limit)?))
+        .await
+        .map_err(internal)?;
+    let next_before = (items.len() == limit)
+        .then(|| items.last().map(|e| e.id))
+        .flatten();
+    let items = items.into_iter().map(|e| e.in_model(model)).collect();
+    Ok(Json(EmotionPage { items, next_before }))
+}
+
+#[derive(Debug, Deserialize)]
+struct AggregateParams {
+    /// day (default) | week
+    #[serde(default)]
+    bucket: Option<String>,
+}
+
+/// GET /emotions/aggregate?bucket=day|week + the list filters
+async fn aggregate_http(
+    State(state): State<AppState>,
+    Query(filter): Query<EmotionFilter>,
+    Query(q): Query<AggregateParams>,
+) -> Result<Json<Aggregate>, ApiErr> {
+    let (filter, band) = filter.normalized().map_err(unprocessable)?;
+    let bucket = match q.bucket.as_deref() {
+        None | Some("") => Bucket::Day,
+        Some(b) => Bucket::parse(b).ok_or_else(|| unprocessable("bucket must be day or week"))?,
+    };
+    let out = state
+        .db
+        .0
+        .call(move |c| Ok(aggregate(c, &filter, band, bucket)?))
+        .await
+        .map_err(internal)?;
     Ok(Json(out))
 }
 
-async fn feel_bridge(Json(body): Json<BridgeIn>) -> Result<Json<BridgeOut>, StatusCode> {
+/// POST /emotions/bridge — served from the bridge library (`bridges::suggest`).
+async fn feel_bridge(
+    State(state): State<AppState>,
+    Json(body): Json<BridgeIn>,
+) -> Result<Json<bridges::Bridge>, StatusCode> {
     // validate inputs (mirror EmotionIn rules)
     if !(0.0_f32..=1.0_f32).contains(&body.intensity) {
         return Err(StatusCode::UNPROCESSABLE_ENTITY);
     }
-    let kind = body.kind.trim();
+    let kind = body.kind.trim().to_owned();
     if kind.is_empty() {
         return Err(StatusCode::UNPROCESSABLE_ENTITY);
     }
+    let intensity = body.intensity as f64;
 
-    Ok(Json(bridge_table(kind, body.intensity)))
+    state
+        .db
+        .0
+        .call(move |c| Ok(bridges::suggest(c, &kind, intensity, body.who.as_deref())?))
+        .await
+        .map(Json)
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
 }
 
 pub fn router() -> Router<AppState> {
     Router::new()
+        .route("/", get(list_http))
+        .route("/aggregate", get(aggregate_http))
         .route("/add", post(add_emotion))
         .route("/recent", get(recent_emotions))
         .route("/bridge", post(feel_bridge))
         .route("/resolve", post(resolve_emotion))
+        .nest("/kinds", taxonomy::router())
+        .nest("/import", imports::router())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    #[test]
-    fn bridge_anxiety_low_uses_double_exhale() {
-        let out = bridge_table("anxiety", 0.3);
-        assert_eq!(out.breath, "double_exhale × 6");
-        assert_eq!(out.doorway, "sip water, feet on floor");
-        assert_eq!(out.anchor, "Name 3 objects you see.");
-    }
-
-    #[test]
-    fn bridge_anxiety_high_uses_box_breath() {
-        let out = bridge_table("fear", 0.8);
-        assert_eq!(out.breath, "box: in4-hold4-out6 × 4");
-        assert_eq!(out.doorway, "sip water, feet on floor");
-        assert_eq!(out.anchor, "Name 3 objects you see.");
+    fn seeded() -> rusqlite::Connection {
+        let mut c = rusqlite::Connection::open_in_memory().unwrap();
+        crate::db::ensure_schema(&mut c).unwrap();
+        for (ts, who, kind, intensity, archetype) in [
+            ("2026-03-02T08:00:00+00:00", "Raz", "fear", 0.8, None),
+            (
+                "2026-03-03T08:00:00+00:00",
+                "Raz",
+                "Joy",
+                0.6,
+                Some("Mother"),
+            ),
+            ("2026-03-04T08:00:00+00:00", "Sawsan", "fear", 0.4, None),
+            ("2026-03-09T08:00:00+00:00", "Raz", "anxiety", 0.1, None),
+            ("2026-03-10T08:00:00+00:00", "Raz", "joy", 0.9, None),
+        ] {
+            c.execute(
+                "INSERT INTO emotions(ts, who, kind, intensity, archetype) VALUES(?1, ?2, ?3, ?4, ?5)",
+                rusqlite::params![ts, who, kind, intensity, archetype],
+            )
+            .unwrap();
+        }
+        c
     }
 
     #[test]
-    fn bridge_anger_pattern() {
-        let out = bridge_table("anger", 0.5);
-        assert_eq!(out.breath, "in4-out8 × 6");
-        assert_eq!(out.doorway, "shake arms 30s, step outside");
-        assert_eq!(out.anchor, "Lower shoulders, soften jaw.");
+    fn list_filters_by_band_and_pages_by_id() {
+        let c = seeded();
+        let raz = EmotionFilter {
+            who: Some("Raz".into()),
+            ..Default::default()
+        };
+        let page = list(&c, &raz, None, None, None, 2).unwrap();
+        assert_eq!(page.len(), 2);
+        let rest = list(&c, &raz, None, Some(page[1].id), None, 10).unwrap();
+        assert_eq!(rest.len(), 2);
+        assert_eq!(rest[1].kind, "fear");
+
+        let survival = list(&c, &raz, Some(Band::Survival), None, None, 10).unwrap();
+        let kinds: Vec<_> = survival.iter().map(|e| e.kind.as_str()).collect();
+        assert_eq!(kinds, vec!["anxiety", "fear"]);
+
+        let (f, band) = EmotionFilter {
+            kind: Some("JOY".into()),
+            min_intensity: Some(0.7),
+            band: Some("Coherence".into()),
+            ..Default::default()
+        }
+        .normalized()
+        .unwrap();
+        assert_eq!(band, Some(Band::Coherence));
+        assert_eq!(list(&c, &f, band, None, None, 10).unwrap().len(), 1);
+
+        let bad = EmotionFilter {
+            min_intensity: Some(0.8),
+            max_intensity: Some(0.2),
+            ..Default::default()
+        };
+        assert!(bad.normalized().is_err());
     }
 
     #[test]
-    fn bridge_shame_pattern() {
-        let out = bridge_table("shame", 0.2);
-        assert_eq!(out.breath, "4-6 breath × 6");
-        assert_eq!(out.doorway, "write 3 objective facts (no story)");
-        assert_eq!(out.anchor, "Hand over heart: 'still worthy'.");
-    }
-
-    #[test]
-    fn bridge_paradox_pattern() {
-        let out = bridge_table("paradox", 0.5);
-        assert_eq!(out.breath, "in4-out4 × 8");
-        assert_eq!(
-            out.doorway,
-            "touch ground + name 1 effort, 1 gift (no comparison)"
-        );
+    fn aggregate_buckets_by_iso_week() {
+        let c = seeded();
+        let agg = aggregate(&c, &EmotionFilter::default(), None, Bucket::Week).unwrap();
+        assert_eq!(agg.total, 5);
+        let starts: Vec<_> = agg.buckets.iter().map(|b| b.start.as_str()).collect();
+        assert_eq!(starts, vec!["2026-03-02", "2026-03-09"]);
+        assert_eq!(agg.buckets[0].count, 3);
+        assert_eq!(agg.buckets[0].mean_intensity, 0.6);
+        assert_eq!(agg.buckets[0].bands.survival, 2);
+        assert_eq!(agg.kinds[0].count, 2);
         assert_eq!(
-            out.anchor,
-            "Whisper: 'neither above nor below — simply alive.'"
+            agg.bands,
+            BandCounts {
+                survival: 3,
+                integrity: 0,
+                coherence: 2
+            }
         );
-    }
-
-    #[test]
-    fn bridge_gratitude_has_whisper() {
-        let out = bridge_table("gratitude", 0.4);
-        assert_eq!(out.breath, "soft inhale, long exhale × 3");
-        assert_eq!(out.doorway, "write 3 one-line gratitudes");
-        assert_eq!(out.anchor, "🌬️ whisper: I am already held.");
-    }
-
-    #[test]
-    fn bridge_default_fallback() {
-        let out = bridge_table("unknown", 0.9);
-        assert_eq!(out.breath, "double_exhale × 6");
-        assert_eq!(out.doorway, "stand_up + shoulder_roll");
-        assert_eq!(out.anchor, "Return to center.");
-    }
-
-    #[test]
-    fn intensity_is_clamped() {
-        // below 0.0 should clamp to 0.0 -> low
-        let low = bridge_table("anxiety", -5.0);
-        assert_eq!(low.breath, "double_exhale × 6");
-
-        // above 1.0 should clamp to 1.0 -> high
-        let high = bridge_table("anxiety", 5.0);
-        assert_eq!(high.breath, "box: in4-hold4-out6 × 4");
-    }
-
-    #[test]
-    fn kind_case_insensitive() {
-        let out1 = bridge_table("AnXiEtY", 0.7);
-        let out2 = bridge_table("ANXIETY", 0.7);
-        assert_eq!(out1.breath, out2.breath);
-        assert_eq!(out1.doorway, out2.doorway);
-        assert_eq!(out1.anchor, out2.anchor);
+        let daily = aggregate(
+            &c,
+            &EmotionFilter::default(),
+            Some(Band::Coherence),
+            Bucket::Day,
+        )
+        .unwrap();
+        assert_eq!(daily.buckets.len(), 2);
+        assert_eq!(daily.mean_intensity, Some(0.75));
     }
 }
@@ -1,13 +1,50 @@
+//! Energy marks — levels over time, per person and kind
+//! ----------------------------------------------------
+//! Whisper: "the tide has a shape." 🌊
+//!
+//! `energy_marks.ts` is RFC3339 UTC like every other table. Rows written as
+//! `datetime('now')` before are rewritten once in `db::ensure_schema`. A mark can be
+//! backdated with `ts`, so "latest" means the newest `ts`, not the newest row.
+//!
+//! Endpoints (mounted under `/energy`)
+//!   POST /energy/mark    → { who, kind, level, note?, ts? }; level 0..=1, ts RFC3339 and
+//!                          not in the future; 422 otherwise. `?band_model=`
+//!   GET  /energy/state   → latest level per kind, `?who=&band_model=`
+//!   GET  /energy/series  → `?who=&kind=&from=&to=&bucket=1h`; min/mean/max per bucket and
+//!                          kind, only buckets with marks. `from` is inclusive, `to` is not.
+//!   GET  /energy/now     → band and fitting tasks, `?who=` — see calendar.rs, which also
+//!                          serves `/energy/calendar/*` and records band crossings on mark
+//!   GET  /energy/profile, /energy/forecast → the learned daily curve, see forecast.rs
+
+use crate::calendar::{self, Transition};
+use crate::consciousness::{energy_band, BandQuery, ModelBand};
+use crate::forecast;
 use crate::AppState;
 use axum::http::StatusCode;
 use axum::{
     extract::Json,
-    extract::State,
+    extract::{Query, State},
     routing::{get, post},
     Router,
 };
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+
+type ApiErr = (StatusCode, String);
+
+fn unprocessable(msg: impl Into<String>) -> ApiErr {
+    (StatusCode::UNPROCESSABLE_ENTITY, msg.into())
+}
+
+fn internal(e: impl std::fmt::Display) -> ApiErr {
+    (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+}
+
+/// Clock skew tolerated on a client-supplied `ts`.
+const MAX_SKEW_SECS: i64 = 5 * 60;
+/// Buckets line up on a Monday 00:00 UTC (1970-01-05), so `1d` and `1w` start at midnight.
+const BUCKET_ORIGIN: i64 = 4 * 86_400;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct EnergyMark {
@@ -15,38 +52,100 @@ pub struct EnergyMark {
     pub ts: String,
     pub who: String,
     pub kind: String,
-    pub level: f32,
+    pub level: f64,
     pub note: Option<String>,
+    /// With `?band_model=three|five` (see consciousness.rs).
+    #[serde(default, skip_deserializing, skip_serializing_if = "Option::is_none")]
+    pub band: Option<ModelBand>,
+    /// Set when this mark moved `who` into another band (calendar.rs).
+    #[serde(default, skip_deserializing, skip_serializing_if = "Option::is_none")]
+    pub transition: Option<Transition>,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct NewEnergyMark {
     pub who: String,
     pub kind: String,
-    pub level: f32,
+    pub level: f64,
     pub note: Option<String>,
+    /// RFC3339; now when absent.
+    #[serde(default)]
+    pub ts: Option<String>,
+}
+
+/// A stored timestamp as UTC (RFC3339, or the old `datetime('now')` text).
+pub(crate) fn parse_stored(ts: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(ts)
+        .map(|dt| dt.with_timezone(&Utc))
+        .ok()
+        .or_else(|| {
+            NaiveDateTime::parse_from_str(ts, "%Y-%m-%d %H:%M:%S")
+                .ok()
+                .map(|n| n.and_utc())
+        })
+}
+
+/// `from` / `to`: RFC3339 or a date (midnight UTC), normalized to RFC3339 UTC.
+pub(crate) fn parse_bound(raw: &str) -> Option<String> {
+    let raw = raw.trim();
+    DateTime::parse_from_rfc3339(raw)
+        .map(|dt| dt.with_timezone(&Utc))
+        .ok()
+        .or_else(|| {
+            NaiveDate::parse_from_str(raw, "%Y-%m-%d")
+                .ok()
+                .and_then(|d| d.and_hms_opt(0, 0, 0))
+                .map(|n| n.and_utc())
+        })
+        .map(|dt| dt.to_rfc3339())
+}
+
+/// Check a new mark before it reaches the `level` CHECK; returns (who, kind, ts).
+fn validate(m: &NewEnergyMark, now: DateTime<Utc>) -> Result<(String, String, String), String> {
+    let who = m.who.trim();
+    if who.is_empty() {
+        return Err("who cannot be empty".into());
+    }
+    let kind = m.kind.trim();
+    if kind.is_empty() {
+        return Err("kind cannot be empty".into());
+    }
+    if !(0.0..=1.0).contains(&m.level) {
+        return Err("level must be within 0..=1".into());
+    }
+    let ts = match m.ts.as_deref().map(str::trim).filter(|t| !t.is_empty()) {
+        None => now,
+        Some(raw) => {
+            let ts = DateTime::parse_from_rfc3339(raw)
+                .map_err(|_| format!("ts must be RFC3339, got `{raw}`"))?
+                .with_timezone(&Utc);
+            if (ts - now).num_seconds() > MAX_SKEW_SECS {
+                return Err("ts cannot be in the future".into());
+            }
+            ts
+        }
+    };
+    Ok((who.to_owned(), kind.to_owned(), ts.to_rfc3339()))
 }
 
 pub async fn mark_energy(
     State(state): State<AppState>,
+    Query(bq): Query<BandQuery>,
     Json(payload): Json<NewEnergyMark>,
 ) -> Result<Json<EnergyMark>, (StatusCode, String)> {
-    let NewEnergyMark {
-        who,
-        kind,
-        level,
-        note,
-    } = payload;
+    let model = bq.model(None).map_err(unprocessable)?;
+    let (who, kind, ts) = validate(&payload, Utc::now()).map_err(unprocessable)?;
+    let NewEnergyMark { level, note, .. } = payload;
     let db = state.db.clone();
 
-    let insert_result = db
-        .0
-        .call(
+    let insert_result =
+        db.0.call(
             move |conn: &mut rusqlite::Connection| -> tokio_rusqlite::Result<EnergyMark> {
                 use rusqlite::params;
+                let before = calendar::band_now(conn, &who)?;
                 conn.execute(
-                    "INSERT INTO energy_marks (ts, who, kind, level, note) VALUES (datetime('now'), ?, ?, ?, ?)",
-                    params![who, kind, level, note],
+                    "INSERT INTO energy_marks (ts, who, kind, level, note) VALUES (?, ?, ?, ?, ?)",
+                    params![ts, who, kind, level, note],
                 )?;
                 let id = conn.last_insert_rowid();
                 let mut stmt = conn.prepare(
@@ -60,54 +159,276 @@ pub async fn mark_energy(
                         kind: row.get(3)?,
                         level: row.get(4)?,
                         note: row.get(5)?,
+                        band: None,
+                        transition: None,
                     })
                 })?;
-                Ok(energy_mark)
+                drop(stmt);
+                let transition = calendar::after_mark(conn, &who, before, id)?;
+                Ok(EnergyMark {
+                    transition,
+                    ..energy_mark
+                })
             },
         )
         .await;
 
     match insert_result {
-        Ok(energy_mark) => Ok(Json(energy_mark)),
-        Err(e) => Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+        Ok(mut energy_mark) => {
+            energy_mark.band = model.map(|m| energy_band(&energy_mark.kind, energy_mark.level, m));
+            Ok(Json(energy_mark))
+        }
+        Err(e) => Err(internal(e)),
     }
 }
 
+/// Latest level per kind; with `?band_model=` each entry becomes `{ level, band }`.
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum StateEntry {
+    Level(f64),
+    Banded { level: f64, band: ModelBand },
+}
+
+/// Newest mark per kind by `ts` (then id), for one person or everyone.
+pub fn latest(
+    c: &rusqlite::Connection,
+    who: Option<&str>,
+) -> rusqlite::Result<HashMap<String, f64>> {
+    let mut stmt = c.prepare(
+        "SELECT kind, level FROM (
+           SELECT kind, level,
+                  ROW_NUMBER() OVER (PARTITION BY kind ORDER BY ts DESC, id DESC) AS n
+             FROM energy_marks
+            WHERE ?1 IS NULL OR who = ?1)
+          WHERE n = 1",
+    )?;
+    let rows = stmt.query_map([who], |r| Ok((r.get(0)?, r.get(1)?)))?;
+    rows.collect()
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct StateParams {
+    #[serde(default)]
+    pub who: Option<String>,
+}
+
 pub async fn get_state(
     State(state): State<AppState>,
-) -> Result<Json<HashMap<String, f32>>, (StatusCode, String)> {
+    Query(q): Query<StateParams>,
+    Query(bq): Query<BandQuery>,
+) -> Result<Json<HashMap<String, StateEntry>>, (StatusCode, String)> {
+    let model = bq.model(None).map_err(unprocessable)?;
+    let who = q.who.map(|w| w.trim().to_owned()).filter(|w| !w.is_empty());
     let db = state.db.clone();
 
-    let query_result =
-        db.0.call(
-            |conn: &mut rusqlite::Connection| -> tokio_rusqlite::Result<HashMap<String, f32>> {
-                let mut stmt = conn.prepare(
-                    "SELECT kind, level FROM energy_marks WHERE id IN (
-                        SELECT MAX(id) FROM energy_marks GROUP BY kind
-                    )",
-                )?;
-                let mut rows = stmt.query([])?;
-                let mut state_map: HashMap<String, f32> = HashMap::new();
-                while let Some(row) = rows.next()? {
-                    let kind: String = row.get(0)?;
-                    let level: f32 = row.get(1)?;
-                    state_map.insert(kind, level);
-                }
-                Ok(state_map)
-            },
-        )
-        .await;
+    let query_result = db.0.call(move |c| Ok(latest(c, who.as_deref())?)).await;
 
     match query_result {
-        Ok(state) => Ok(Json(state)),
-        Err(e) => Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+        Ok(state) => Ok(Json(
+            state
+                .into_iter()
+                .map(|(kind, level)| {
+                    let entry = match model {
+                        None => StateEntry::Level(level),
+                        Some(m) => StateEntry::Banded {
+                            level,
+                            band: energy_band(&kind, level, m),
+                        },
+                    };
+                    (kind, entry)
+                })
+                .collect(),
+        )),
+        Err(e) => Err(internal(e)),
+    }
+}
+
+/// Bucket width: `<n>m`, `<n>h`, `<n>d` or `<n>w`, up to a year.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Step(i64);
+
+impl Step {
+    pub fn parse(s: &str) -> Option<Step> {
+        let s = s.trim();
+        if !s.is_ascii() || s.len() < 2 {
+            return None;
+        }
+        let (n, unit) = s.split_at(s.len() - 1);
+        let n: i64 = n.parse().ok().filter(|n| *n > 0)?;
+        let unit = match unit {
+            "m" => 60,
+            "h" => 3_600,
+            "d" => 86_400,
+            "w" => 7 * 86_400,
+            _ => return None,
+        };
+        n.checked_mul(unit).filter(|s| *s <= 366 * 86_400).map(Step)
+    }
+
+    /// Start of the bucket holding `t`.
+    pub fn start(self, t: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let secs = t.timestamp();
+        DateTime::from_timestamp(secs - (secs - BUCKET_ORIGIN).rem_euclid(self.0), 0)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SeriesPoint {
+    pub start: String,
+    pub count: i64,
+    pub min: f32,
+    pub mean: f32,
+    pub max: f32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct KindSeries {
+    pub kind: String,
+    pub points: Vec<SeriesPoint>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct SeriesParams {
+    #[serde(default)]
+    pub who: Option<String>,
+    #[serde(default)]
+    pub kind: Option<String>,
+    #[serde(default)]
+    pub from: Option<String>,
+    #[serde(default)]
+    pub to: Option<String>,
+    /// Default `1h`.
+    #[serde(default)]
+    pub bucket: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Series {
+    pub who: Option<String>,
+    pub kind: Option<String>,
+    pub bucket: String,
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub series: Vec<KindSeries>,
+}
+
+impl SeriesParams {
+    /// Trimmed filters and RFC3339 bounds; `Err` is a 422 message.
+    pub fn normalized(self) -> Result<(Self, Step), String> {
+        let clean = |v: Option<String>| v.map(|v| v.trim().to_owned()).filter(|v| !v.is_empty());
+        let bucket = clean(self.bucket).unwrap_or_else(|| "1h".into());
+        let step = Step::parse(&bucket).ok_or("bucket must look like 15m, 1h, 1d or 1w")?;
+        let bound = |name: &str, v: Option<String>| match clean(v) {
+            None => Ok(None),
+            Some(raw) => parse_bound(&raw)
+                .map(Some)
+                .ok_or(format!("{name} must be RFC3339 or YYYY-MM-DD")),
+        };
+        let from = bound("from", self.from)?;
+        let to = bound("to", self.to)?;
+        if let (Some(f), Some(t)) = (&from, &to) {
+            if f >= t {
+                return Err("from must be before to".into());
+            }
+        }
+        Ok((
+            SeriesParams {
+                who: clean(self.who),
+                kind: clean(self.kind),
+                from,
+                to,
+                bucket: Some(bucket),
+            },
+            step,
+        ))
     }
 }
 
+/// Min/mean/max per kind and bucket, oldest first; `p` comes from `normalized()`.
+pub fn series(
+    c: &rusqlite::Connection,
+    p: &SeriesParams,
+    step: Step,
+) -> rusqlite::Result<Vec<KindSeries>> {
+    let mut stmt = c.prepare(
+        "SELECT ts, kind, level FROM energy_marks
+          WHERE (?1 IS NULL OR who = ?1) AND (?2 IS NULL OR kind = ?2)
+            AND (?3 IS NULL OR ts >= ?3) AND (?4 IS NULL OR ts < ?4)",
+    )?;
+    // (kind, bucket start) → (count, min, max, sum)
+    let mut acc: BTreeMap<(String, i64), (i64, f32, f32, f64)> = BTreeMap::new();
+    let mut rows = stmt.query(rusqlite::params![p.who, p.kind, p.from, p.to])?;
+    while let Some(r) = rows.next()? {
+        let ts: String = r.get(0)?;
+        let Some(start) = parse_stored(&ts).and_then(|t| step.start(t)) else {
+            continue;
+        };
+        let level: f32 = r.get(2)?;
+        let e = acc
+            .entry((r.get(1)?, start.timestamp()))
+            .or_insert((0, level, level, 0.0));
+        e.0 += 1;
+        e.1 = e.1.min(level);
+        e.2 = e.2.max(level);
+        e.3 += f64::from(level);
+    }
+    let mut out: Vec<KindSeries> = Vec::new();
+    for ((kind, start), (count, min, max, sum)) in acc {
+        let point = SeriesPoint {
+            start: DateTime::from_timestamp(start, 0)
+                .map(|t| t.to_rfc3339())
+                .unwrap_or_default(),
+            count,
+            min,
+            mean: (sum / count as f64) as f32,
+            max,
+        };
+        match out.last_mut() {
+            Some(s) if s.kind == kind => s.points.push(point),
+            _ => out.push(KindSeries {
+                kind,
+                points: vec![point],
+            }),
+        }
+    }
+    Ok(out)
+}
+
+/// GET /energy/series?who=&kind=&from=&to=&bucket=
+pub async fn get_series(
+    State(state): State<AppState>,
+    Query(q): Query<SeriesParams>,
+) -> Result<Json<Series>, ApiErr> {
+    let (p, step) = q.normalized().map_err(unprocessable)?;
+    state
+        .db
+        .0
+        .call(move |c| {
+            let series = series(c, &p, step)?;
+            Ok(Series {
+                who: p.who,
+                kind: p.kind,
+                bucket: p.bucket.unwrap_or_default(),
+                from: p.from,
+                to: p.to,
+                series,
+            })
+        })
+        .await
+        .map(Json)
+        .map_err(internal)
+}
+
 pub fn router() -> Router<AppState> {
     Router::new()
         .route("/mark", post(mark_energy))
         .route("/state", get(get_state))
+        .route("/series", get(get_series))
+        .route("/profile", get(forecast::get_profile))
+        .route("/forecast", get(forecast::get_forecast))
+        .route("/now", get(calendar::now_http))
+        .nest("/calendar", calendar::router())
 }
 
 #[cfg(test)]
@@ -248,4 +569,109 @@ mod tests {
             "expected latest level 0.9, got {latest_level}"
         );
     }
+
+    #[test]
+    fn latest_follows_ts_and_series_buckets_per_kind() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        crate::db::ensure_schema(&mut conn).unwrap();
+        for (ts, who, kind, level) in [
+            ("2025-01-01T10:05:00+00:00", "Raz", "dragon", 0.2_f64),
+            ("2025-01-01T10:40:00+00:00", "Raz", "dragon", 0.8),
+            ("2025-01-01T11:10:00+00:00", "Raz", "dragon", 0.5),
+            ("2025-01-01T10:20:00+00:00", "Nico", "dragon", 1.0),
+            ("2025-01-01T10:30:00+00:00", "Raz", "rest", 0.4),
+            // backdated after the others: an older ts must not become "latest"
+            ("2025-01-01T09:00:00+00:00", "Raz", "dragon", 0.1),
+        ] {
+            conn.execute(
+                "INSERT INTO energy_marks (ts, who, kind, level) VALUES (?1, ?2, ?3, ?4)",
+                params![ts, who, kind, level],
+            )
+            .unwrap();
+        }
+
+        // a row from before marks were RFC3339 is rewritten on the next start
+        conn.execute(
+            "INSERT INTO energy_marks (ts, who, kind, level) VALUES ('2025-01-01 08:00:00', 'Ana', 'rest', 0.3)",
+            [],
+        )
+        .unwrap();
+        crate::db::ensure_schema(&mut conn).unwrap();
+        let legacy: String = conn
+            .query_row("SELECT ts FROM energy_marks WHERE who = 'Ana'", [], |r| {
+                r.get(0)
+            })
+            .unwrap();
+        assert_eq!(legacy, "2025-01-01T08:00:00+00:00");
+
+        let raz = super::latest(&conn, Some("Raz")).unwrap();
+        assert_eq!(raz.get("dragon").copied(), Some(0.5));
+        assert_eq!(raz.get("rest").copied(), Some(0.4));
+        let nico = super::latest(&conn, Some("Nico")).unwrap();
+        assert_eq!(nico, HashMap::from([("dragon".to_string(), 1.0)]));
+
+        let q = super::SeriesParams {
+            who: Some(" Raz ".into()),
+            from: Some("2025-01-01T10:00:00Z".into()),
+            to: Some("2025-01-02".into()),
+            ..Default::default()
+        };
+        let (p, step) = q.normalized().unwrap();
+        let out = super::series(&conn, &p, step).unwrap();
+        assert_eq!(out.len(), 2);
+        let dragon = &out[0];
+        assert_eq!(dragon.kind, "dragon");
+        assert_eq!(dragon.points.len(), 2);
+        let first = &dragon.points[0];
+        assert_eq!(first.start, "2025-01-01T10:00:00+00:00");
+        assert_eq!((first.count, first.min, first.max), (2, 0.2, 0.8));
+        assert!((first.mean - 0.5).abs() < 1e-6);
+        assert_eq!(dragon.points[1].start, "2025-01-01T11:00:00+00:00");
+        assert_eq!(out[1].kind, "rest");
+
+        let (p, step) = super::SeriesParams {
+            bucket: Some("1d".into()),
+            ..Default::default()
+        }
+        .normalized()
+        .unwrap();
+        let out = super::series(&conn, &p, step).unwrap();
+        assert_eq!(out[0].points[0].count, 5, "everyone's dragon on one day");
+        assert_eq!(out[0].points[0].start, "2025-01-01T00:00:00+00:00");
+    }
+
+    #[test]
+    fn buckets_and_marks_are_validated() {
+        use super::{validate, NewEnergyMark, SeriesParams, Step};
+        assert_eq!(Step::parse("15m"), Some(Step(900)));
+        assert_eq!(Step::parse("1w"), Some(Step(604_800)));
+        for bad in ["", "h", "0h", "-1h", "1y", "400d", "1ч"] {
+            assert_eq!(Step::parse(bad), None, "{bad}");
+        }
+        let reversed = SeriesParams {
+            from: Some("2025-01-02".into()),
+            to: Some("2025-01-01".into()),
+            ..Default::default()
+        };
+        assert!(reversed.normalized().is_err());
+
+        let now = chrono::Utc::now();
+        let mark = |level: f64, ts: Option<&str>| NewEnergyMark {
+            who: " Raz ".into(),
+            kind: "dragon".into(),
+            level,
+            note: None,
+            ts: ts.map(str::to_owned),
+        };
+        let (who, _, ts) = validate(&mark(0.5, Some("2025-01-01T12:00:00+02:00")), now).unwrap();
+        assert_eq!(
+            (who.as_str(), ts.as_str()),
+            ("Raz", "2025-01-01T10:00:00+00:00")
+        );
+        assert!(validate(&mark(1.5, None), now).is_err());
+        assert!(validate(&mark(-0.1, None), now).is_err());
+        assert!(validate(&mark(0.5, Some("yesterday")), now).is_err());
+        let later = (now + chrono::Duration::hours(1)).to_rfc3339();
+        assert!(validate(&mark(0.5, Some(&later)), now).is_err());
+    }
 }
@@ -0,0 +1,33 @@
+//! Exports directory — where logs and dumps land
+//! ---------------------------------------------
+//! Whisper: "one shelf for every trace." 🗂️
+//!
+//! Shared by the binary (panic logs, thread exports) and the library (thanks log), so
+//! CLI, UI and hermetic tests all agree on the same tree:
+//!   • `M3_EXPORTS_DIR` absolute → used as-is
+//!   • `M3_EXPORTS_DIR` relative → resolved from the repo root
+//!   • unset → `<repo>/exports`
+
+use std::path::PathBuf;
+
+/// repo root = parent of the `server/` crate dir
+pub fn repo_root() -> PathBuf {
+    let server_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    server_dir
+        .parent()
+        .map(|p| p.to_path_buf())
+        .unwrap_or(server_dir)
+}
+
+/// Resolve the exports directory (see the module header).
+pub fn resolve_exports_dir() -> PathBuf {
+    if let Ok(val) = std::env::var("M3_EXPORTS_DIR") {
+        let p = PathBuf::from(val);
+        return if p.is_absolute() {
+            p
+        } else {
+            repo_root().join(p)
+        };
+    }
+    repo_root().join("exports")
+}
@@ -0,0 +1,148 @@
+//! Panic follow-up — "did that help?" check-ins
+//! --------------------------------------------
+//! Whisper: "ask after the storm, not during it." 🌤️
+//!
+//! Panic runs carry a `checkin_due_at` (`M3_PANIC_CHECKIN_MIN` after the run, see
+//! `panic::checkin_due`). A background sweep picks up due runs that have no outcome
+//! yet and, once per run:
+//!   • publishes `checkin:<json>` on the bus → named `checkin` SSE event on /status/stream
+//!   • emits a consent-gated `panic.checkin` relational event (webhooks)
+//!
+//! The answer comes back through `POST /panic/:id/outcome` (panic.rs), which feeds
+//! `/panic/effectiveness` and the `effective` rotation.
+//!
+//! Shared with the lib: `spawn_background` (lib.rs) starts it for embedders/tests.
+
+use crate::{panic, relational, AppState};
+use serde_json::json;
+
+/// Bus prefix for check-in prompts.
+pub const CHECKIN_TOPIC: &str = "checkin:";
+
+const SWEEP_SECS: u64 = 30;
+
+/// What the prompt says; the steps are echoed so the person remembers what they tried.
+pub fn prompt(e: &panic::PanicEvent) -> serde_json::Value {
+    json!({
+        "event_id": e.id,
+        "who": e.who,
+        "ts": e.ts,
+        "question": "Did that help? How strong is it now (0–1)?",
+        "doorway": e.doorway,
+        "breath": e.breath,
+        "anchor": e.anchor,
+        "before": e.before_intensity.unwrap_or(e.intensity),
+        "outcome_url": format!("/panic/{}/outcome", e.id),
+    })
+}
+
+fn intent() -> relational::Intent {
+    relational::Intent {
+        why: "signal-checkin".into(),
+        whisper: "Checking in after the redirect.".into(),
+        suggested_responses: vec!["record-outcome".into(), "hold-space".into()],
+        care_window_minutes: 60,
+    }
+}
+
+/// Prompt every due run once. Returns how many were sent.
+pub async fn sweep(state: &AppState) -> usize {
+    let now = chrono::Utc::now().to_rfc3339();
+    let due = match state
+        .db
+        .0
+        .call(move |c| Ok(panic::take_due_checkins(c, &now)?))
+        .await
+    {
+        Ok(due) => due,
+        Err(e) => {
+            tracing::warn!(error = ?e, "panic.checkin: sweep failed");
+            return 0;
+        }
+    };
+    for e in &due {
+        let body = prompt(e);
+        state.bus.publish(&format!("{CHECKIN_TOPIC}{body}"));
+        relational::emit(
+            state,
+            "panic.checkin",
+            &relational::Actor::from_display(&e.who),
+            intent(),
+            json!({ "checkin": body, "suggested_bridge": e.suggested_bridge }),
+        )
+        .await;
+    }
+    due.len()
+}
+
+/// Background loop; a no-op when follow-ups are off.
+pub fn spawn(state: AppState) {
+    if panic::checkin_minutes() == 0 {
+        return;
+    }
+    tokio::spawn(async move {
+        let mut tick = tokio::time::interval(std::time::Duration::from_secs(SWEEP_SECS));
+        loop {
+            tick.tick().await;
+            sweep(&state).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[tokio::test]
+    async fn due_runs_are_prompted_once_and_answered_runs_never() {
+        let state = crate::test_state().await;
+        let past = (chrono::Utc::now() - chrono::Duration::minutes(1)).to_rfc3339();
+        let future = (chrono::Utc::now() + chrono::Duration::minutes(30)).to_rfc3339();
+        let ids = state
+            .db
+            .0
+            .call(move |c| {
+                let mut ids = Vec::new();
+                for due in [&past, &past, &future] {
+                    ids.push(panic::record(
+                        c,
+                        &panic::PanicEvent {
+                            ts: past.clone(),
+                            who: "Raz".into(),
+                            mode: "default".into(),
+                            source: "ui".into(),
+                            doorway: "drink_water".into(),
+                            emotion_kind: "anxiety".into(),
+                            intensity: 0.55,
+                            checkin_due_at: Some(due.clone()),
+                            ..Default::default()
+                        },
+                    )?);
+                }
+                Ok(ids)
+            })
+            .await
+            .unwrap();
+        let answered = ids[1];
+        state
+            .db
+            .0
+            .call(move |c| {
+                let now = chrono::Utc::now().to_rfc3339();
+                Ok(panic::record_outcome(c, answered, None, 0.2, None, &now)?)
+            })
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(sweep(&state).await, 1);
+        let published = state.bus.drain();
+        let checkins: Vec<_> = published
+            .iter()
+            .filter(|m| m.starts_with(CHECKIN_TOPIC))
+            .collect();
+        assert_eq!(checkins.len(), 1);
+        assert!(checkins[0].contains(&format!("\"event_id\":{}", ids[0])));
+
+        assert_eq!(sweep(&state).await, 0, "prompts are sent once");
+    }
+}
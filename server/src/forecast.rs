@@ -0,0 +1,612 @@
+//! Energy forecast — learn the daily curve
+//! ---------------------------------------
+//! Whisper: "the tide comes back at the same hour." 🌗
+//!
+//! Built on demand from a person's `energy_marks` in the last `days` (default 28):
+//!   • profile — per kind, the mean and spread for each local hour of the day and for
+//!     each weekday × hour. Newer marks weigh more (half-life `HALF_LIFE_DAYS`).
+//!   • forecast — the next `hours` (default 24) hour by hour. The weekday × hour mean is
+//!     pulled towards the hour-of-day mean until it has a few marks of its own (`SHRINK`);
+//!     an hour with no marks borrows its neighbours, then the overall mean.
+//!     `low`/`high` is an 80% band (mean ± 1.28 sd), `confidence` is n / (n + 3) for the
+//!     marks at that hour of the day (0 for a borrowed hour).
+//!   • windows — runs of forecast hours at or above the `deep` band (default E3) and below
+//!     the `rest` band (default E2), using the energy-calendar thresholds (calendar.rs).
+//!     Hours nobody has marked yet never make a window.
+//!
+//! Hours are local to `utc_offset` (default UTC). Without `kind` the forecast pools every
+//! kind, like `/energy/now`.
+//!
+//! Endpoints (mounted under `/energy`)
+//!   GET /energy/profile   → ?who=&kind=&days=&utc_offset=
+//!   GET /energy/forecast  → ?who=&kind=&days=&hours=&from=&utc_offset=&deep=&rest=
+//!                           404 when `who` has no marks in the window
+
+use crate::energy::parse_stored;
+use crate::{calendar, imports, AppState};
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::{DateTime, Datelike, Duration, DurationRound, FixedOffset, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+type ApiErr = (StatusCode, String);
+
+fn unprocessable(msg: impl Into<String>) -> ApiErr {
+    (StatusCode::UNPROCESSABLE_ENTITY, msg.into())
+}
+
+fn internal(e: impl std::fmt::Display) -> ApiErr {
+    (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+}
+
+const DEFAULT_DAYS: i64 = 28;
+const MAX_DAYS: i64 = 365;
+const DEFAULT_HOURS: i64 = 24;
+const MAX_HOURS: i64 = 168;
+/// A mark this many days old counts half.
+const HALF_LIFE_DAYS: f64 = 14.0;
+/// Marks a weekday × hour needs before it outweighs the hour-of-day mean.
+const SHRINK: f64 = 3.0;
+/// z for an 80% band.
+const Z80: f64 = 1.28;
+/// Floor on the spread, so two identical marks don't promise certainty.
+const MIN_SD: f64 = 0.05;
+const MAX_WINDOWS: usize = 3;
+const WEEKDAYS: [&str; 7] = ["mon", "tue", "wed", "thu", "fri", "sat", "sun"];
+
+/// Weighted running stats for one slot.
+#[derive(Debug, Clone, Copy, Default)]
+struct Acc {
+    n: usize,
+    w: f64,
+    sum: f64,
+    sum2: f64,
+}
+
+impl Acc {
+    fn add(&mut self, level: f64, weight: f64) {
+        self.n += 1;
+        self.w += weight;
+        self.sum += weight * level;
+        self.sum2 += weight * level * level;
+    }
+
+    fn merge(mut self, o: &Acc) -> Acc {
+        self.n += o.n;
+        self.w += o.w;
+        self.sum += o.sum;
+        self.sum2 += o.sum2;
+        self
+    }
+
+    fn mean(&self) -> Option<f64> {
+        (self.n > 0 && self.w > 0.0).then(|| self.sum / self.w)
+    }
+
+    /// Spread, once there are two marks.
+    fn sd(&self) -> Option<f64> {
+        let mean = self.mean()?;
+        (self.n > 1).then(|| (self.sum2 / self.w - mean * mean).max(0.0).sqrt())
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct Curve {
+    all: Acc,
+    hours: [Acc; 24],
+    cells: [[Acc; 24]; 7],
+}
+
+impl Curve {
+    fn add(&mut self, local: DateTime<FixedOffset>, level: f64, weight: f64) {
+        let (d, h) = (
+            local.weekday().num_days_from_monday() as usize,
+            local.hour() as usize,
+        );
+        self.all.add(level, weight);
+        self.hours[h].add(level, weight);
+        self.cells[d][h].add(level, weight);
+    }
+
+    /// The hour-of-day slot, or its neighbours when empty.
+    fn hour(&self, h: usize) -> Acc {
+        if self.hours[h].n > 0 {
+            return self.hours[h];
+        }
+        self.hours[(h + 23) % 24].merge(&self.hours[(h + 1) % 24])
+    }
+
+    /// (level, low, high, confidence) for weekday `d`, hour `h`; `None` without marks.
+    fn predict(&self, d: usize, h: usize) -> Option<(f64, f64, f64, f64)> {
+        let hour = self.hour(h);
+        let base = hour.mean().or_else(|| self.all.mean())?;
+        let cell = &self.cells[d][h];
+        let level = match cell.mean() {
+            Some(m) => {
+                let w = cell.n as f64 / (cell.n as f64 + SHRINK);
+                w * m + (1.0 - w) * base
+            }
+            None => base,
+        };
+        let sd = (if cell.n >= 3 { cell.sd() } else { None })
+            .or_else(|| hour.sd())
+            .or_else(|| self.all.sd())
+            .unwrap_or(0.0)
+            .max(MIN_SD);
+        // borrowed neighbours give a level, not confidence
+        let n = self.hours[h].n as f64;
+        Some((
+            level,
+            (level - Z80 * sd).max(0.0),
+            (level + Z80 * sd).min(1.0),
+            n / (n + SHRINK),
+        ))
+    }
+}
+
+fn round(v: f64) -> f64 {
+    (v * 1000.0).round() / 1000.0
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SlotStat {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub weekday: Option<&'static str>,
+    pub hour: u32,
+    pub samples: usize,
+    pub mean: f64,
+    pub low: f64,
+    pub high: f64,
+}
+
+impl SlotStat {
+    fn from_acc(weekday: Option<&'static str>, hour: usize, a: &Acc) -> Option<SlotStat> {
+        let mean = a.mean()?;
+        let sd = a.sd().unwrap_or(0.0).max(MIN_SD);
+        Some(SlotStat {
+            weekday,
+            hour: hour as u32,
+            samples: a.n,
+            mean: round(mean),
+            low: round((mean - Z80 * sd).max(0.0)),
+            high: round((mean + Z80 * sd).min(1.0)),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct KindProfile {
+    pub kind: String,
+    pub samples: usize,
+    pub mean: f64,
+    /// Hours of the day with marks.
+    pub hours: Vec<SlotStat>,
+    /// Weekday × hour slots with marks.
+    pub weekdays: Vec<SlotStat>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Profile {
+    pub who: String,
+    pub days: i64,
+    pub utc_offset: String,
+    pub kinds: Vec<KindProfile>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ForecastPoint {
+    /// Start of the hour, RFC3339 UTC.
+    pub start: String,
+    pub weekday: &'static str,
+    /// Local hour of day.
+    pub hour: u32,
+    pub level: f64,
+    pub low: f64,
+    pub high: f64,
+    pub confidence: f64,
+    /// Energy-calendar band for `level`.
+    pub band: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Window {
+    pub start: String,
+    /// Exclusive.
+    pub end: String,
+    pub hours: usize,
+    pub mean: f64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Windows {
+    /// Best first.
+    pub deep: Vec<Window>,
+    /// Lowest first.
+    pub rest: Vec<Window>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Forecast {
+    pub who: String,
+    pub kind: Option<String>,
+    pub days: i64,
+    pub utc_offset: String,
+    pub samples: usize,
+    pub points: Vec<ForecastPoint>,
+    pub windows: Windows,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct ForecastParams {
+    #[serde(default)]
+    pub who: Option<String>,
+    #[serde(default)]
+    pub kind: Option<String>,
+    #[serde(default)]
+    pub days: Option<i64>,
+    #[serde(default)]
+    pub hours: Option<i64>,
+    /// Forecast start (RFC3339), default now; history is the `days` before it.
+    #[serde(default)]
+    pub from: Option<String>,
+    #[serde(default)]
+    pub utc_offset: Option<String>,
+    /// Band code deep-work windows need (default E3).
+    #[serde(default)]
+    pub deep: Option<String>,
+    /// Rest windows are below this band (default E2).
+    #[serde(default)]
+    pub rest: Option<String>,
+}
+
+/// Checked `ForecastParams`.
+#[derive(Debug, Clone)]
+pub struct ForecastQuery {
+    pub who: String,
+    pub kind: Option<String>,
+    pub days: i64,
+    pub hours: i64,
+    pub from: DateTime<Utc>,
+    pub offset: FixedOffset,
+    pub deep: String,
+    pub rest: String,
+}
+
+impl ForecastParams {
+    /// `Err` is a 422 message.
+    pub fn normalized(self, now: DateTime<Utc>) -> Result<ForecastQuery, String> {
+        let clean = |v: Option<String>| v.map(|v| v.trim().to_owned()).filter(|v| !v.is_empty());
+        let who = clean(self.who).ok_or("who is required")?;
+        let days = self.days.unwrap_or(DEFAULT_DAYS);
+        if !(1..=MAX_DAYS).contains(&days) {
+            return Err(format!("days must be within 1..={MAX_DAYS}"));
+        }
+        let hours = self.hours.unwrap_or(DEFAULT_HOURS);
+        if !(1..=MAX_HOURS).contains(&hours) {
+            return Err(format!("hours must be within 1..={MAX_HOURS}"));
+        }
+        let from = match clean(self.from) {
+            Some(raw) => DateTime::parse_from_rfc3339(&raw)
+                .map_err(|_| "from must be RFC3339")?
+                .with_timezone(&Utc),
+            None => now,
+        };
+        let offset = match clean(self.utc_offset) {
+            Some(raw) => imports::parse_offset(&raw).ok_or("utc_offset must look like +02:00")?,
+            None => FixedOffset::east_opt(0).expect("zero offset"),
+        };
+        let band = |v: Option<String>, default: &str| {
+            clean(v).map_or_else(|| default.to_owned(), |b| b.to_uppercase())
+        };
+        Ok(ForecastQuery {
+            who,
+            kind: clean(self.kind),
+            days,
+            hours,
+            from: from.duration_trunc(Duration::hours(1)).unwrap_or(from),
+            offset,
+            deep: band(self.deep, "E3"),
+            rest: band(self.rest, "E2"),
+        })
+    }
+}
+
+/// One curve per kind (or one pooled curve under `None`) from the window before `q.from`.
+fn curves(
+    c: &rusqlite::Connection,
+    q: &ForecastQuery,
+    pooled: bool,
+) -> rusqlite::Result<BTreeMap<Option<String>, Curve>> {
+    let since = (q.from - Duration::days(q.days)).to_rfc3339();
+    let until = q.from.to_rfc3339();
+    let mut stmt = c.prepare(
+        "SELECT ts, kind, level FROM energy_marks
+          WHERE who = ?1 AND (?2 IS NULL OR kind = ?2) AND ts >= ?3 AND ts < ?4",
+    )?;
+    let mut rows = stmt.query(rusqlite::params![q.who, q.kind, since, until])?;
+    let mut out: BTreeMap<Option<String>, Curve> = BTreeMap::new();
+    while let Some(r) = rows.next()? {
+        let ts: String = r.get(0)?;
+        let Some(at) = parse_stored(&ts) else {
+            continue;
+        };
+        let age_days = (q.from - at).num_seconds() as f64 / 86_400.0;
+        let weight = 0.5f64.powf(age_days / HALF_LIFE_DAYS);
+        let key = if pooled { None } else { Some(r.get(1)?) };
+        out.entry(key)
+            .or_default()
+            .add(at.with_timezone(&q.offset), r.get::<_, f64>(2)?, weight);
+    }
+    Ok(out)
+}
+
+pub fn profile(c: &rusqlite::Connection, q: &ForecastQuery) -> rusqlite::Result<Profile> {
+    let kinds = curves(c, q, false)?
+        .into_iter()
+        .map(|(kind, curve)| KindProfile {
+            kind: kind.unwrap_or_default(),
+            samples: curve.all.n,
+            mean: round(curve.all.mean().unwrap_or(0.0)),
+            hours: (0..24)
+                .filter_map(|h| SlotStat::from_acc(None, h, &curve.hours[h]))
+                .collect(),
+            weekdays: (0..7)
+                .flat_map(|d| (0..24).map(move |h| (d, h)))
+                .filter_map(|(d, h)| SlotStat::from_acc(Some(WEEKDAYS[d]), h, &curve.cells[d][h]))
+                .collect(),
+        })
+        .collect();
+    Ok(Profile {
+        who: q.who.clone(),
+        days: q.days,
+        utc_offset: q.offset.to_string(),
+        kinds,
+    })
+}
+
+/// Runs of consecutive points that satisfy `keep`, as windows.
+fn runs(points: &[ForecastPoint], keep: impl Fn(&ForecastPoint) -> bool) -> Vec<Window> {
+    let mut out = Vec::new();
+    let mut i = 0;
+    while i < points.len() {
+        if !keep(&points[i]) {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        while i < points.len() && keep(&points[i]) {
+            i += 1;
+        }
+        let run = &points[start..i];
+        let end = DateTime::parse_from_rfc3339(&run[run.len() - 1].start)
+            .map(|t| (t + Duration::hours(1)).with_timezone(&Utc).to_rfc3339())
+            .unwrap_or_default();
+        out.push(Window {
+            start: run[0].start.clone(),
+            end,
+            hours: run.len(),
+            mean: round(run.iter().map(|p| p.level).sum::<f64>() / run.len() as f64),
+        });
+    }
+    out
+}
+
+/// `Ok(None)` when `who` has no marks in the window; `Err` is a 422 message.
+pub fn forecast(
+    c: &rusqlite::Connection,
+    q: &ForecastQuery,
+) -> rusqlite::Result<Result<Option<Forecast>, String>> {
+    let bands = calendar::bands(c)?;
+    let threshold = |code: &str| {
+        bands
+            .iter()
+            .find(|b| b.code == code)
+            .map(|b| b.min_level)
+            .ok_or(format!("unknown band `{code}`"))
+    };
+    let (deep, rest) = match (threshold(&q.deep), threshold(&q.rest)) {
+        (Ok(d), Ok(r)) => (d, r),
+        (Err(e), _) | (_, Err(e)) => return Ok(Err(e)),
+    };
+    let Some(curve) = curves(c, q, true)?.remove(&None) else {
+        return Ok(Ok(None));
+    };
+    let points: Vec<ForecastPoint> = (0..q.hours)
+        .filter_map(|i| {
+            let at = q.from + Duration::hours(i);
+            let local = at.with_timezone(&q.offset);
+            let d = local.weekday().num_days_from_monday() as usize;
+            let (level, low, high, confidence) = curve.predict(d, local.hour() as usize)?;
+            Some(ForecastPoint {
+                start: at.to_rfc3339(),
+                weekday: WEEKDAYS[d],
+                hour: local.hour(),
+                level: round(level),
+                low: round(low),
+                high: round(high),
+                confidence: round(confidence),
+                band: calendar::band_for(&bands, level).map(|b| b.code.clone()),
+            })
+        })
+        .collect();
+    let mut windows = Windows {
+        deep: runs(&points, |p| p.confidence > 0.0 && p.level >= deep),
+        rest: runs(&points, |p| p.confidence > 0.0 && p.level < rest),
+    };
+    windows
+        .deep
+        .sort_by(|a, b| b.mean.total_cmp(&a.mean).then(a.start.cmp(&b.start)));
+    windows
+        .rest
+        .sort_by(|a, b| a.mean.total_cmp(&b.mean).then(a.start.cmp(&b.start)));
+    windows.deep.truncate(MAX_WINDOWS);
+    windows.rest.truncate(MAX_WINDOWS);
+    Ok(Ok(Some(Forecast {
+        who: q.who.clone(),
+        kind: q.kind.clone(),
+        days: q.days,
+        utc_offset: q.offset.to_string(),
+        samples: curve.all.n,
+        points,
+        windows,
+    })))
+}
+
+/// GET /energy/profile?who=&kind=&days=&utc_offset=
+pub async fn get_profile(
+    State(state): State<AppState>,
+    Query(p): Query<ForecastParams>,
+) -> Result<Json<Profile>, ApiErr> {
+    let q = p.normalized(Utc::now()).map_err(unprocessable)?;
+    state
+        .db
+        .0
+        .call(move |c| Ok(profile(c, &q)?))
+        .await
+        .map(Json)
+        .map_err(internal)
+}
+
+/// GET /energy/forecast?who=&kind=&days=&hours=&from=&utc_offset=&deep=&rest=
+pub async fn get_forecast(
+    State(state): State<AppState>,
+    Query(p): Query<ForecastParams>,
+) -> Result<Json<Forecast>, ApiErr> {
+    let q = p.normalized(Utc::now()).map_err(unprocessable)?;
+    let missing = format!("no energy marked for {} in the last {} days", q.who, q.days);
+    state
+        .db
+        .0
+        .call(move |c| Ok(forecast(c, &q)?))
+        .await
+        .map_err(internal)?
+        .map_err(unprocessable)?
+        .map(Json)
+        .ok_or((StatusCode::NOT_FOUND, missing))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conn() -> rusqlite::Connection {
+        let mut c = rusqlite::Connection::open_in_memory().unwrap();
+        crate::db::ensure_schema(&mut c).unwrap();
+        c
+    }
+
+    fn query(from: &str, extra: ForecastParams) -> ForecastQuery {
+        ForecastParams {
+            who: Some("Raz".into()),
+            from: Some(from.into()),
+            ..extra
+        }
+        .normalized(Utc::now())
+        .unwrap()
+    }
+
+    /// Two weeks of high mornings (09–11) and low afternoons (15–16) in +02:00.
+    fn seed(c: &rusqlite::Connection) {
+        for day in 1..=14 {
+            for (hour, level) in [(9, 0.85), (10, 0.8), (11, 0.75), (15, 0.25), (16, 0.2)] {
+                let ts = format!("2025-03-{day:02}T{hour:02}:20:00+02:00");
+                let ts = DateTime::parse_from_rfc3339(&ts)
+                    .unwrap()
+                    .with_timezone(&Utc);
+                c.execute(
+                    "INSERT INTO energy_marks(ts, who, kind, level) VALUES(?1, 'Raz', 'focus', ?2)",
+                    rusqlite::params![ts.to_rfc3339(), level],
+                )
+                .unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn forecast_finds_the_morning_peak_and_afternoon_dip() {
+        let c = conn();
+        seed(&c);
+        let q = query(
+            "2025-03-15T00:00:00+02:00",
+            ForecastParams {
+                utc_offset: Some("+02:00".into()),
+                ..Default::default()
+            },
+        );
+        let f = forecast(&c, &q).unwrap().unwrap().unwrap();
+        assert_eq!(f.samples, 70);
+        assert_eq!(f.points.len(), 24);
+        let at = |h: u32| f.points.iter().find(|p| p.hour == h).unwrap();
+        assert!((at(9).level - 0.85).abs() < 0.01);
+        assert!(at(9).low < at(9).level && at(9).high > at(9).level);
+        assert_eq!(at(9).band.as_deref(), Some("E3"));
+        assert!(at(9).confidence > 0.8);
+        assert_eq!(at(3).confidence, 0.0, "nobody marks 03:00");
+
+        assert_eq!(f.windows.deep.len(), 1);
+        assert_eq!(f.windows.deep[0].start, "2025-03-15T07:00:00+00:00");
+        assert_eq!(f.windows.deep[0].hours, 3);
+        assert_eq!(f.windows.rest[0].start, "2025-03-15T13:00:00+00:00");
+        assert_eq!(f.windows.rest[0].hours, 2);
+
+        let bad = query(
+            "2025-03-15T00:00:00Z",
+            ForecastParams {
+                deep: Some("E9".into()),
+                ..Default::default()
+            },
+        );
+        assert!(forecast(&c, &bad).unwrap().is_err());
+        let empty = query("2024-01-01T00:00:00Z", ForecastParams::default());
+        assert!(forecast(&c, &empty).unwrap().unwrap().is_none());
+    }
+
+    #[test]
+    fn profile_is_per_kind_and_local_and_newer_marks_weigh_more() {
+        let c = conn();
+        seed(&c);
+        // an old low mark and a recent high one at 09 local, another kind
+        for (ts, level) in [
+            ("2025-02-20T07:00:00+00:00", 0.1),
+            ("2025-03-14T07:00:00+00:00", 0.9),
+        ] {
+            c.execute(
+                "INSERT INTO energy_marks(ts, who, kind, level) VALUES(?1, 'Raz', 'heart', ?2)",
+                rusqlite::params![ts, level],
+            )
+            .unwrap();
+        }
+        let q = query(
+            "2025-03-15T00:00:00Z",
+            ForecastParams {
+                utc_offset: Some("+02:00".into()),
+                ..Default::default()
+            },
+        );
+        let p = profile(&c, &q).unwrap();
+        let kinds: Vec<&str> = p.kinds.iter().map(|k| k.kind.as_str()).collect();
+        assert_eq!(kinds, ["focus", "heart"]);
+        let focus_hours: Vec<u32> = p.kinds[0].hours.iter().map(|h| h.hour).collect();
+        assert_eq!(focus_hours, [9, 10, 11, 15, 16]);
+        assert_eq!(p.kinds[0].weekdays.len(), 35);
+        let heart = &p.kinds[1].hours[0];
+        assert_eq!((heart.hour, heart.samples), (9, 2));
+        assert!(
+            heart.mean > 0.5,
+            "the recent mark dominates: {}",
+            heart.mean
+        );
+
+        assert!(ForecastParams::default().normalized(Utc::now()).is_err());
+        let too_long = ForecastParams {
+            who: Some("Raz".into()),
+            hours: Some(500),
+            ..Default::default()
+        };
+        assert!(too_long.normalized(Utc::now()).is_err());
+    }
+}
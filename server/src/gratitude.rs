@@ -0,0 +1,687 @@
+//! Gratitude ledger — /thanks
+//! -------------------------
+//! Whisper: "count the days you remembered." 🙏
+//!
+//! Rows live in `gratitude(id, ts, who, subject, kind, note_id, details)` (schema in
+//! `db::ensure_schema`). Every insert also appends a line to
+//! `exports/thanks/YYYY-MM/thanks-YYYY-MM-DD.log`; panic runs land `redirect` rows
+//! through `panic::run` and append their line the same way.
+//!
+//! Endpoints (mounted under `/thanks`)
+//!   POST   /thanks          → { subject, details?, kind?, note_id?, who?, sealed? }
+//!   GET    /thanks          → { items, next_before } (filters: who, kind, since, until,
+//!                             q = subject text; cursor: before=<id>; limit ≤ 200)
+//!   PATCH  /thanks/:id      → { subject?, details?, kind?, note_id?, who?, sealed? } ("" clears
+//!                             text fields, `note_id: null` clears the link)
+//!   DELETE /thanks/:id      → 204
+//!   GET    /thanks/stats    → per person: total, active days, current/longest streak,
+//!                             per_day, top subjects, kinds (same filters; `top`)
+//!
+//! Sealed rows (`sealed: true`) keep their text in the DB only: reads, the timeline,
+//! the thanks log and `top_subjects` show `(sealed)` instead of subject and details, and
+//! `q` never matches them. They still count toward totals and streaks.
+//!
+//! Notes
+//!   • Days are UTC calendar days. A streak is still "current" until a full day is
+//!     missed: thanks yesterday but not yet today keeps it alive.
+//!   • Rows without `who` are grouped as one person (`who: null`).
+
+use crate::AppState;
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    routing::{get, patch},
+    Json, Router,
+};
+use chrono::{NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use tokio::io::AsyncWriteExt;
+
+type ApiErr = (StatusCode, String);
+
+fn unprocessable(msg: impl Into<String>) -> ApiErr {
+    (StatusCode::UNPROCESSABLE_ENTITY, msg.into())
+}
+
+fn internal(e: impl std::fmt::Display) -> ApiErr {
+    (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+}
+
+/// Body for `POST /thanks`.
+#[derive(Debug, Deserialize)]
+pub struct GratitudeIn {
+    pub subject: String,
+    #[serde(default)]
+    pub details: Option<String>,
+    /// e.g. "ancestor" | "tool" | "place" | "redirect"
+    #[serde(default)]
+    pub kind: Option<String>,
+    /// optional link to a message
+    #[serde(default)]
+    pub note_id: Option<i64>,
+    /// actor/profile
+    #[serde(default)]
+    pub who: Option<String>,
+    #[serde(default)]
+    pub sealed: bool,
+}
+
+/// What sealed rows show instead of their subject.
+pub const SEALED: &str = "(sealed)";
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct Gratitude {
+    pub id: i64,
+    pub ts: String,
+    pub subject: String,
+    pub details: Option<String>,
+    pub kind: Option<String>,
+    pub note_id: Option<i64>,
+    pub who: Option<String>,
+    pub sealed: bool,
+}
+
+impl Gratitude {
+    /// Hide subject and details of a sealed row.
+    pub fn redacted(mut self) -> Self {
+        if self.sealed {
+            self.subject = SEALED.into();
+            self.details = None;
+        }
+        self
+    }
+}
+
+const COLS: &str = "id, ts, who, subject, kind, note_id, details, sealed";
+
+fn row_to_gratitude(r: &rusqlite::Row) -> rusqlite::Result<Gratitude> {
+    Ok(Gratitude {
+        id: r.get(0)?,
+        ts: r.get(1)?,
+        who: r.get(2)?,
+        subject: r.get(3)?,
+        kind: r.get(4)?,
+        note_id: r.get(5)?,
+        details: r.get(6)?,
+        sealed: r.get(7)?,
+    }
+    .redacted())
+}
+
+pub fn get_one(c: &rusqlite::Connection, id: i64) -> rusqlite::Result<Option<Gratitude>> {
+    use rusqlite::OptionalExtension;
+    c.query_row(
+        &format!("SELECT {COLS} FROM gratitude WHERE id = ?1"),
+        [id],
+        row_to_gratitude,
+    )
+    .optional()
+}
+
+// ---- Log export -------------------------------------------------------------
+
+pub fn log_line(
+    ts: &str,
+    who: Option<&str>,
+    subject: &str,
+    details: Option<&str>,
+    kind: Option<&str>,
+    note_id: Option<i64>,
+) -> String {
+    let who = who.unwrap_or("-");
+    let kind = kind.unwrap_or("-");
+    let det = details.unwrap_or("-");
+    let nid = note_id.map(|v| v.to_string()).unwrap_or_else(|| "-".into());
+    format!("{ts} who=\"{who}\" kind=\"{kind}\" note_id=\"{nid}\" subject=\"{subject}\" details=\"{det}\"\n")
+}
+
+/// Append one line to `exports/thanks/YYYY-MM/thanks-YYYY-MM-DD.log` (best-effort).
+pub async fn append_log(line: &str) {
+    let now = Utc::now();
+    let dir = crate::exports::resolve_exports_dir()
+        .join("thanks")
+        .join(now.format("%Y-%m").to_string());
+    let _ = tokio::fs::create_dir_all(&dir).await;
+    let path = dir.join(now.format("thanks-%Y-%m-%d.log").to_string());
+    if let Ok(mut f) = tokio::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .await
+    {
+        let _ = f.write_all(line.as_bytes()).await;
+    }
+}
+
+// ---- Queries ----------------------------------------------------------------
+
+/// Filters shared by the list and the stats. Empty fields match everything.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct Filter {
+    #[serde(default)]
+    pub who: Option<String>,
+    #[serde(default)]
+    pub kind: Option<String>,
+    /// RFC3339, inclusive.
+    #[serde(default)]
+    pub since: Option<String>,
+    /// RFC3339, exclusive.
+    #[serde(default)]
+    pub until: Option<String>,
+    /// Case-insensitive substring of `subject` (sealed rows never match).
+    #[serde(default)]
+    pub q: Option<String>,
+}
+
+const FILTER_SQL: &str = "(?1 IS NULL OR who = ?1)
+     AND (?2 IS NULL OR kind = ?2)
+     AND (?3 IS NULL OR ts >= ?3)
+     AND (?4 IS NULL OR ts < ?4)
+     AND (?5 IS NULL OR (sealed = 0 AND instr(lower(subject), lower(?5)) > 0))";
+
+impl Filter {
+    /// Drop blanks and normalize timestamps to UTC RFC3339 so string comparison matches `ts`.
+    pub fn normalized(mut self) -> Result<Self, String> {
+        for field in [&mut self.who, &mut self.kind, &mut self.q] {
+            *field = field
+                .take()
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty());
+        }
+        for (name, field) in [("since", &mut self.since), ("until", &mut self.until)] {
+            if let Some(raw) = field.take().filter(|s| !s.trim().is_empty()) {
+                let dt = chrono::DateTime::parse_from_rfc3339(raw.trim())
+                    .map_err(|_| format!("{name} must be RFC3339"))?;
+                *field = Some(dt.with_timezone(&Utc).to_rfc3339());
+            }
+        }
+        Ok(self)
+    }
+
+    fn params(&self) -> [Option<&str>; 5] {
+        [
+            self.who.as_deref(),
+            self.kind.as_deref(),
+            self.since.as_deref(),
+            self.until.as_deref(),
+            self.q.as_deref(),
+        ]
+    }
+}
+
+/// Page of rows, newest first; `before` is an id cursor.
+pub fn list(
+    c: &rusqlite::Connection,
+    f: &Filter,
+    before: Option<i64>,
+    limit: i64,
+) -> rusqlite::Result<Vec<Gratitude>> {
+    let mut stmt = c.prepare(&format!(
+        "SELECT {COLS} FROM gratitude
+         WHERE {FILTER_SQL} AND (?6 IS NULL OR id < ?6)
+         ORDER BY id DESC LIMIT ?7"
+    ))?;
+    let [a, b, d, e, g] = f.params();
+    let rows = stmt
+        .query_map(
+            rusqlite::params![a, b, d, e, g, before, limit],
+            row_to_gratitude,
+        )?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(rows)
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct Count {
+    pub key: String,
+    pub n: i64,
+}
+
+/// One person's ritual at a glance.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct PersonStats {
+    pub who: Option<String>,
+    pub total: i64,
+    /// Distinct days with at least one row.
+    pub days_active: i64,
+    /// Consecutive days up to today (or yesterday, if today has none yet).
+    pub current_streak: i64,
+    pub longest_streak: i64,
+    pub first_day: Option<String>,
+    pub last_day: Option<String>,
+    /// `YYYY-MM-DD` → rows, oldest first.
+    pub per_day: Vec<Count>,
+    /// Most frequent subjects (case-insensitive), most first.
+    pub top_subjects: Vec<Count>,
+    /// Rows per kind (`-` = none), most first.
+    pub kinds: Vec<Count>,
+}
+
+/// Current and longest run of consecutive days in sorted, distinct `days`.
+pub fn streaks(days: &[NaiveDate], today: NaiveDate) -> (i64, i64) {
+    let (mut longest, mut run) = (0i64, 0i64);
+    let mut prev: Option<NaiveDate> = None;
+    for d in days {
+        run = match prev {
+            Some(p) if *d == p + chrono::Duration::days(1) => run + 1,
+            _ => 1,
+        };
+        longest = longest.max(run);
+        prev = Some(*d);
+    }
+    let current = match prev {
+        Some(last) if last == today || last == today - chrono::Duration::days(1) => run,
+        _ => 0,
+    };
+    (current, longest)
+}
+
+fn empty(who: Option<String>) -> PersonStats {
+    PersonStats {
+        who,
+        total: 0,
+        days_active: 0,
+        current_streak: 0,
+        longest_streak: 0,
+        first_day: None,
+        last_day: None,
+        per_day: Vec::new(),
+        top_subjects: Vec::new(),
+        kinds: Vec::new(),
+    }
+}
+
+/// `(who, key, rows)` grouped per person and `group`.
+fn counts(
+    c: &rusqlite::Connection,
+    f: &Filter,
+    select: &str,
+    group: &str,
+    order: &str,
+) -> rusqlite::Result<Vec<(Option<String>, String, i64)>> {
+    let mut stmt = c.prepare(&format!(
+        "SELECT who, {select}, COUNT(*) FROM gratitude
+         WHERE {FILTER_SQL}
+         GROUP BY who, {group} ORDER BY {order}"
+    ))?;
+    let [a, b, d, e, g] = f.params();
+    let rows = stmt
+        .query_map(rusqlite::params![a, b, d, e, g], |r| {
+            Ok((r.get(0)?, r.get(1)?, r.get(2)?))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(rows)
+}
+
+/// Per-person stats over the rows matching `f`; `top` caps `top_subjects`.
+pub fn stats(
+    c: &rusqlite::Connection,
+    f: &Filter,
+    top: usize,
+    today: NaiveDate,
+) -> rusqlite::Result<Vec<PersonStats>> {
+    let mut people: BTreeMap<Option<String>, PersonStats> = BTreeMap::new();
+    for (who, day, n) in counts(c, f, "substr(ts, 1, 10)", "substr(ts, 1, 10)", "2")? {
+        let p = people.entry(who.clone()).or_insert_with(|| empty(who));
+        p.total += n;
+        p.per_day.push(Count { key: day, n });
+    }
+    for (who, subject, n) in counts(
+        c,
+        f,
+        "MAX(CASE WHEN sealed = 1 THEN '(sealed)' ELSE subject END)",
+        "CASE WHEN sealed = 1 THEN '(sealed)' ELSE lower(subject) END",
+        "3 DESC, 2",
+    )? {
+        if let Some(p) = people.get_mut(&who) {
+            if p.top_subjects.len() < top {
+                p.top_subjects.push(Count { key: subject, n });
+            }
+        }
+    }
+    for (who, kind, n) in counts(
+        c,
+        f,
+        "COALESCE(kind, '-')",
+        "COALESCE(kind, '-')",
+        "3 DESC, 2",
+    )? {
+        if let Some(p) = people.get_mut(&who) {
+            p.kinds.push(Count { key: kind, n });
+        }
+    }
+    for p in people.values_mut() {
+        let days: Vec<NaiveDate> = p
+            .per_day
+            .iter()
+            .filter_map(|d| NaiveDate::parse_from_str(&d.key, "%Y-%m-%d").ok())
+            .collect();
+        (p.current_streak, p.longest_streak) = streaks(&days, today);
+        p.days_active = p.per_day.len() as i64;
+        p.first_day = p.per_day.first().map(|d| d.key.clone());
+        p.last_day = p.per_day.last().map(|d| d.key.clone());
+    }
+    Ok(people.into_values().collect())
+}
+
+// ---- HTTP -------------------------------------------------------------------
+
+/// POST /thanks — insert a row and append its line to the thanks log.
+async fn create(
+    State(state): State<AppState>,
+    Json(body): Json<GratitudeIn>,
+) -> Result<Json<Gratitude>, ApiErr> {
+    let subject = body.subject.trim().to_string();
+    if subject.is_empty() {
+        return Err(unprocessable("subject required"));
+    }
+    let row = Gratitude {
+        id: 0,
+        ts: Utc::now().to_rfc3339(),
+        subject,
+        details: body.details,
+        kind: body.kind,
+        note_id: body.note_id,
+        who: body.who,
+        sealed: body.sealed,
+    };
+    let db_row = row.clone();
+    let id = state
+        .db
+        .0
+        .call(move |c| {
+            c.execute(
+                "INSERT INTO gratitude(ts, who, subject, kind, note_id, details, sealed)
+                 VALUES(?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                rusqlite::params![
+                    db_row.ts,
+                    db_row.who,
+                    db_row.subject,
+                    db_row.kind,
+                    db_row.note_id,
+                    db_row.details,
+                    db_row.sealed
+                ],
+            )?;
+            Ok(c.last_insert_rowid())
+        })
+        .await
+        .map_err(internal)?;
+    let row = Gratitude { id, ..row }.redacted();
+    append_log(&log_line(
+        &row.ts,
+        row.who.as_deref(),
+        &row.subject,
+        row.details.as_deref(),
+        row.kind.as_deref(),
+        row.note_id,
+    ))
+    .await;
+    Ok(Json(row))
+}
+
+#[derive(Debug, Deserialize)]
+struct PageParams {
+    #[serde(default)]
+    before: Option<i64>,
+    #[serde(default)]
+    limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+struct Page {
+    items: Vec<Gratitude>,
+    /// Pass as `before` for the next page; absent on the last page.
+    next_before: Option<i64>,
+}
+
+/// GET /thanks?who=&kind=&since=&until=&q=&before=&limit=
+async fn list_http(
+    State(state): State<AppState>,
+    Query(filter): Query<Filter>,
+    Query(q): Query<PageParams>,
+) -> Result<Json<Page>, ApiErr> {
+    let filter = filter.normalized().map_err(unprocessable)?;
+    let limit = q.limit.unwrap_or(20).clamp(1, 200);
+    let before = q.before;
+    let items = state
+        .db
+        .0
+        .call(move |c| Ok(list(c, &filter, before, limit)?))
+        .await
+        .map_err(internal)?;
+    let next_before = (items.len() as i64 == limit)
+        .then(|| items.last().map(|g| g.id))
+        .flatten();
+    Ok(Json(Page { items, next_before }))
+}
+
+/// PATCH body; absent fields are kept, `""` clears the optional text fields and
+/// `"note_id": null` clears the note link.
+#[derive(Debug, Deserialize)]
+struct GratitudePatch {
+    #[serde(default)]
+    subject: Option<String>,
+    #[serde(default)]
+    details: Option<String>,
+    #[serde(default)]
+    kind: Option<String>,
+    /// Outer `None` = absent, `Some(None)` = `null`.
+    #[serde(default, deserialize_with = "present")]
+    note_id: Option<Option<i64>>,
+    #[serde(default)]
+    who: Option<String>,
+    #[serde(default)]
+    sealed: Option<bool>,
+}
+
+/// Marks a field as sent even when its value is `null`.
+fn present<'de, D, T>(d: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(d).map(Some)
+}
+
+/// PATCH /thanks/:id
+async fn update(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    Json(input): Json<GratitudePatch>,
+) -> Result<Json<Gratitude>, ApiErr> {
+    let subject = match input.subject.as_deref().map(str::trim) {
+        Some("") => return Err(unprocessable("subject cannot be empty")),
+        other => other.map(str::to_string),
+    };
+    let trim = |v: Option<String>| v.map(|s| s.trim().to_string());
+    let (details, kind, who) = (trim(input.details), trim(input.kind), trim(input.who));
+    let (set_note, note_id) = (input.note_id.is_some(), input.note_id.flatten());
+    let sealed = input.sealed;
+    state
+        .db
+        .0
+        .call(move |c| {
+            c.execute(
+                "UPDATE gratitude SET
+                   subject = COALESCE(?1, subject),
+                   details = CASE WHEN ?2 IS NULL THEN details ELSE NULLIF(?2, '') END,
+                   kind    = CASE WHEN ?3 IS NULL THEN kind ELSE NULLIF(?3, '') END,
+                   note_id = CASE WHEN ?8 THEN ?4 ELSE note_id END,
+                   who     = CASE WHEN ?5 IS NULL THEN who ELSE NULLIF(?5, '') END,
+                   sealed  = COALESCE(?6, sealed)
+                 WHERE id = ?7",
+                rusqlite::params![subject, details, kind, note_id, who, sealed, id, set_note],
+            )?;
+            Ok(get_one(c, id)?)
+        })
+        .await
+        .map_err(internal)?
+        .map(Json)
+        .ok_or((StatusCode::NOT_FOUND, "unknown gratitude id".into()))
+}
+
+/// DELETE /thanks/:id
+async fn remove(State(state): State<AppState>, Path(id): Path<i64>) -> Result<StatusCode, ApiErr> {
+    let n = state
+        .db
+        .0
+        .call(move |c| Ok(c.execute("DELETE FROM gratitude WHERE id = ?1", [id])?))
+        .await
+        .map_err(internal)?;
+    if n == 0 {
+        return Err((StatusCode::NOT_FOUND, "unknown gratitude id".into()));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Debug, Deserialize)]
+struct StatsParams {
+    #[serde(default)]
+    top: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+struct StatsOut {
+    /// UTC day the streaks are measured against.
+    today: String,
+    people: Vec<PersonStats>,
+}
+
+/// GET /thanks/stats?who=&kind=&since=&until=&q=&top=5
+async fn stats_http(
+    State(state): State<AppState>,
+    Query(filter): Query<Filter>,
+    Query(q): Query<StatsParams>,
+) -> Result<Json<StatsOut>, ApiErr> {
+    let filter = filter.normalized().map_err(unprocessable)?;
+    let top = q.top.unwrap_or(5).clamp(1, 50);
+    let today = Utc::now().date_naive();
+    let people = state
+        .db
+        .0
+        .call(move |c| Ok(stats(c, &filter, top, today)?))
+        .await
+        .map_err(internal)?;
+    Ok(Json(StatsOut {
+        today: today.to_string(),
+        people,
+    }))
+}
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_http).post(create))
+        .route("/stats", get(stats_http))
+        .route("/:id", patch(update).delete(remove))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn db() -> rusqlite::Connection {
+        let mut c = rusqlite::Connection::open_in_memory().unwrap();
+        crate::db::ensure_schema(&mut c).unwrap();
+        c
+    }
+
+    fn day(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn streaks_stay_current_until_a_day_is_missed() {
+        let days = [
+            day("2026-03-01"),
+            day("2026-03-02"),
+            day("2026-03-03"),
+            day("2026-03-07"),
+            day("2026-03-08"),
+        ];
+        assert_eq!(streaks(&days, day("2026-03-08")), (2, 3));
+        assert_eq!(streaks(&days, day("2026-03-09")), (2, 3));
+        assert_eq!(streaks(&days, day("2026-03-10")), (0, 3));
+        assert_eq!(streaks(&[], day("2026-03-10")), (0, 0));
+    }
+
+    #[test]
+    fn filters_pages_and_stats_per_person() {
+        let c = db();
+        for (ts, who, subject, kind) in [
+            (
+                "2026-03-01T08:00:00+00:00",
+                "Raz",
+                "Grandma Ana",
+                Some("ancestor"),
+            ),
+            (
+                "2026-03-02T08:00:00+00:00",
+                "Raz",
+                "grandma ana",
+                Some("ancestor"),
+            ),
+            (
+                "2026-03-02T09:00:00+00:00",
+                "Raz",
+                "the kettle",
+                Some("tool"),
+            ),
+            ("2026-03-03T21:00:00+00:00", "Raz", "Grandma Ana", None),
+            (
+                "2026-03-03T08:00:00+00:00",
+                "Sawsan",
+                "the sea",
+                Some("place"),
+            ),
+        ] {
+            c.execute(
+                "INSERT INTO gratitude(ts, who, subject, kind) VALUES(?1, ?2, ?3, ?4)",
+                rusqlite::params![ts, who, subject, kind],
+            )
+            .unwrap();
+        }
+        let raz = Filter {
+            who: Some("Raz".into()),
+            ..Default::default()
+        };
+        let page = list(&c, &raz, None, 3).unwrap();
+        assert_eq!(page.len(), 3);
+        let rest = list(&c, &raz, Some(page[2].id), 3).unwrap();
+        assert_eq!(rest.len(), 1);
+        assert_eq!(rest[0].ts, "2026-03-01T08:00:00+00:00");
+
+        let text = Filter {
+            q: Some("GRANDMA".into()),
+            since: Some("2026-03-02T00:00:00+02:00".into()),
+            ..Default::default()
+        }
+        .normalized()
+        .unwrap();
+        assert_eq!(list(&c, &text, None, 10).unwrap().len(), 2);
+
+        let people = stats(&c, &Filter::default(), 5, day("2026-03-04")).unwrap();
+        assert_eq!(people.len(), 2);
+        let r = &people[0];
+        assert_eq!(r.who.as_deref(), Some("Raz"));
+        assert_eq!((r.total, r.days_active), (4, 3));
+        assert_eq!((r.current_streak, r.longest_streak), (3, 3));
+        assert_eq!(
+            r.per_day[1],
+            Count {
+                key: "2026-03-02".into(),
+                n: 2
+            }
+        );
+        assert_eq!(r.top_subjects[0].n, 3);
+        assert_eq!(
+            r.kinds[0],
+            Count {
+                key: "ancestor".into(),
+                n: 2
+            }
+        );
+        assert_eq!(people[1].current_streak, 1);
+    }
+}
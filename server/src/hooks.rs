@@ -0,0 +1,945 @@
+//! Inbound relational hooks — care.ack / care.done receiver
+//! -------------------------------------------------------
+//! Whisper: "someone heard you." 🤍
+//!
+//! Spec: docs/specs/relational-webhooks.md (Receiver Events)
+//!
+//! Endpoints (mounted under `/hooks`)
+//!   POST /hooks/inbound            → signed `care.ack` | `care.done` for a known event id
+//!   GET  /hooks/care?event=evt_…   → acknowledgements stored for an event (newest last)
+//!   GET  /hooks/subscriptions      → outbound delivery targets (secrets redacted)
+//!   POST /hooks/subscriptions      → { name, url, format?, template?, content_type?, events?, secret? }
+//!   PATCH/DELETE /hooks/subscriptions/:id
+//!   POST /hooks/subscriptions/:id/test → deliver a sample `m3.test` event now
+//!
+//! Outbound fan-out
+//!   • `dispatch` renders each event per subscription (`webhook::Format`: m3, slack,
+//!     matrix, ntfy, form, custom) so no translation proxy is needed.
+//!   • `M3_WEBHOOK_URL`, when set, stays an implicit `m3` subscription for every event.
+//!   • Targets are posted concurrently (5 s connect / 10 s request timeout each), and
+//!     `relational::emit` runs the fan-out on its own task, off the request path.
+//!
+//! Rules
+//!   • `X-M3-Signature: m3=t=<ts>,v1=<hex hmac(ts + "." + raw body)>`, shared secret
+//!     `M3_WEBHOOK_SECRET`. No secret configured ⇒ the receiver is closed (503).
+//!   • `t` must be within `relational::TS_WINDOW_SEC` of now (401 otherwise).
+//!   • Each nonce is accepted once (409 on replay). The nonce comes from
+//!     `security.nonce` in the body, then `X-M3-Nonce`, then the signature itself.
+//!   • `for` must name an event we emitted (404 otherwise).
+//!   • Accepted acks are published on the bus as `care:<json>` and surface on
+//!     `/status/stream` as named `care` events ("Ana is with you").
+
+use crate::{relational, webhook, AppState};
+use axum::{
+    body::Bytes,
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    routing::{get, patch, post},
+    Json, Router,
+};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+
+/// Bus topic prefix for accepted care replies.
+pub const CARE_TOPIC: &str = "care:";
+
+#[derive(Debug, Deserialize)]
+struct InboundBy {
+    id: String,
+    #[serde(default)]
+    display: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct InboundSecurity {
+    #[serde(default)]
+    nonce: Option<String>,
+}
+
+/// Union of the `care.ack` and `care.done` bodies.
+#[derive(Debug, Deserialize)]
+struct InboundIn {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    ts: Option<String>,
+    #[serde(rename = "for")]
+    for_event: String,
+    #[serde(default)]
+    by: Option<InboundBy>,
+    #[serde(default)]
+    action: Option<String>,
+    #[serde(default)]
+    outcome: Option<String>,
+    #[serde(default)]
+    note: Option<String>,
+    #[serde(default)]
+    security: Option<InboundSecurity>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CareAck {
+    pub id: i64,
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub event: String,
+    pub by_id: Option<String>,
+    pub by_display: Option<String>,
+    pub action: Option<String>,
+    pub outcome: Option<String>,
+    pub note: Option<String>,
+    pub ts: String,
+    pub received_at: String,
+}
+
+/// Human line for the UI: "Ana is with you".
+pub fn care_message(kind: &str, by_display: Option<&str>, outcome: Option<&str>) -> String {
+    let who = by_display.filter(|s| !s.trim().is_empty());
+    match (kind, who) {
+        ("care.ack", Some(w)) => format!("{w} is with you"),
+        ("care.ack", None) => "Someone is with you".to_string(),
+        (_, Some(w)) => match outcome {
+            Some(o) => format!("{w} closed the loop ({o})"),
+            None => format!("{w} closed the loop"),
+        },
+        (_, None) => match outcome {
+            Some(o) => format!("Care loop closed ({o})"),
+            None => "Care loop closed".to_string(),
+        },
+    }
+}
+
+type ApiErr = (StatusCode, String);
+
+fn err(code: StatusCode, msg: impl Into<String>) -> ApiErr {
+    (code, msg.into())
+}
+
+/// POST /hooks/inbound — verify, de-duplicate, store, publish.
+async fn inbound(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Json<CareAck>, ApiErr> {
+    let Some(secret) = state.webhook.secret.clone() else {
+        return Err(err(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "inbound hooks need M3_WEBHOOK_SECRET",
+        ));
+    };
+    let sig = headers
+        .get("x-m3-signature")
+        .and_then(|v| v.to_str().ok())
+        .ok_or_else(|| err(StatusCode::UNAUTHORIZED, "missing X-M3-Signature"))?
+        .to_string();
+    let now = Utc::now();
+    webhook::verify(
+        &secret,
+        &sig,
+        &body,
+        now.timestamp(),
+        relational::TS_WINDOW_SEC,
+    )
+    .map_err(|e| err(StatusCode::UNAUTHORIZED, e.as_str()))?;
+
+    let input: InboundIn = serde_json::from_slice(&body)
+        .map_err(|e| err(StatusCode::UNPROCESSABLE_ENTITY, e.to_string()))?;
+    if input.kind != "care.ack" && input.kind != "care.done" {
+        return Err(err(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "type must be care.ack or care.done",
+        ));
+    }
+    if input.kind == "care.ack" && input.by.is_none() {
+        return Err(err(StatusCode::UNPROCESSABLE_ENTITY, "care.ack needs by"));
+    }
+
+    let nonce = input
+        .security
+        .as_ref()
+        .and_then(|s| s.nonce.clone())
+        .or_else(|| {
+            headers
+                .get("x-m3-nonce")
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string)
+        })
+        .unwrap_or_else(|| sig.clone());
+
+    let received_at = now.to_rfc3339();
+    let ts = input.ts.clone().unwrap_or_else(|| received_at.clone());
+    let by_id = input.by.as_ref().map(|b| relational::actor_id(&b.id));
+    let by_display = input
+        .by
+        .as_ref()
+        .map(|b| b.display.clone().unwrap_or_else(|| b.id.clone()));
+    let prune_before =
+        (now - chrono::Duration::seconds(relational::TS_WINDOW_SEC * 2)).to_rfc3339();
+
+    let row = (
+        input.kind.clone(),
+        input.for_event.clone(),
+        by_id.clone(),
+        by_display.clone(),
+        input.action.clone(),
+        input.outcome.clone(),
+        input.note.clone(),
+        ts.clone(),
+        received_at.clone(),
+    );
+    // Ok(Some(id)) stored · Ok(None) unknown event · Err(409) replay
+    let stored: Result<Option<i64>, ApiErr> = state
+        .db
+        .0
+        .call(move |c| {
+            let tx = c.transaction()?;
+            tx.execute(
+                "DELETE FROM hook_nonces WHERE seen_at < ?1",
+                [&prune_before],
+            )?;
+            let fresh = tx.execute(
+                "INSERT OR IGNORE INTO hook_nonces(nonce, seen_at) VALUES(?1, ?2)",
+                rusqlite::params![nonce, row.8],
+            )?;
+            if fresh == 0 {
+                return Ok(Err(err(StatusCode::CONFLICT, "nonce already used")));
+            }
+            let known: i64 = tx.query_row(
+                "SELECT COUNT(*) FROM relational_events WHERE id = ?1",
+                [&row.1],
+                |r| r.get(0),
+            )?;
+            if known == 0 {
+                // rollback: an unknown event does not burn the nonce
+                return Ok(Ok(None));
+            }
+            tx.execute(
+                "INSERT INTO care_acks(event_id, type, by_id, by_display, action, outcome, note, ts, received_at)
+                 VALUES(?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                rusqlite::params![row.1, row.0, row.2, row.3, row.4, row.5, row.6, row.7, row.8],
+            )?;
+            let id = tx.last_insert_rowid();
+            tx.commit()?;
+            Ok(Ok(Some(id)))
+        })
+        .await
+        .map_err(|e| err(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let Some(id) = stored? else {
+        return Err(err(StatusCode::NOT_FOUND, "unknown event"));
+    };
+
+    let ack = CareAck {
+        id,
+        kind: input.kind,
+        event: input.for_event,
+        by_id,
+        by_display,
+        action: input.action,
+        outcome: input.outcome,
+        note: input.note,
+        ts,
+        received_at,
+    };
+
+    let message = care_message(&ack.kind, ack.by_display.as_deref(), ack.outcome.as_deref());
+    let payload = serde_json::json!({
+        "type": ack.kind,
+        "event": ack.event,
+        "by": { "id": ack.by_id, "display": ack.by_display },
+        "action": ack.action,
+        "outcome": ack.outcome,
+        "ts": ack.ts,
+        "message": message,
+    });
+    state.bus.publish(&format!("{CARE_TOPIC}{payload}"));
+
+    Ok(Json(ack))
+}
+
+#[derive(Debug, Deserialize)]
+struct CareParams {
+    event: String,
+}
+
+/// GET /hooks/care?event= — acks/dones for one emitted event.
+async fn care_for_event(
+    State(state): State<AppState>,
+    Query(q): Query<CareParams>,
+) -> Result<Json<Vec<CareAck>>, StatusCode> {
+    let rows = state
+        .db
+        .0
+        .call(move |c| {
+            let mut stmt = c.prepare(
+                "SELECT id, type, event_id, by_id, by_display, action, outcome, note, ts, received_at
+                 FROM care_acks WHERE event_id = ?1 ORDER BY id ASC",
+            )?;
+            let rows = stmt
+                .query_map([&q.event], |r| {
+                    Ok(CareAck {
+                        id: r.get(0)?,
+                        kind: r.get(1)?,
+                        event: r.get(2)?,
+                        by_id: r.get(3)?,
+                        by_display: r.get(4)?,
+                        action: r.get(5)?,
+                        outcome: r.get(6)?,
+                        note: r.get(7)?,
+                        ts: r.get(8)?,
+                        received_at: r.get(9)?,
+                    })
+                })?
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(rows)
+        })
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    Ok(Json(rows))
+}
+
+// ---- Outbound subscriptions ------------------------------------------------
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Subscription {
+    pub id: i64,
+    pub name: String,
+    pub url: String,
+    pub format: String,
+    pub template: Option<String>,
+    pub content_type: Option<String>,
+    pub events: String,
+    #[serde(skip)]
+    pub secret: Option<String>,
+    pub has_secret: bool,
+    pub active: bool,
+    pub created_at: String,
+}
+
+impl Subscription {
+    /// `*`, exact type, or a `prefix.*` wildcard from the comma list.
+    pub fn wants(&self, event: &str) -> bool {
+        self.events.split(',').map(str::trim).any(|p| {
+            p == "*"
+                || p == event
+                || p.strip_suffix('*')
+                    .is_some_and(|prefix| !prefix.is_empty() && event.starts_with(prefix))
+        })
+    }
+}
+
+const SUB_COLUMNS: &str =
+    "id, name, url, format, template, content_type, events, secret, active, created_at";
+
+fn sub_from_row(r: &rusqlite::Row<'_>) -> rusqlite::Result<Subscription> {
+    let secret: Option<String> = r.get(7)?;
+    Ok(Subscription {
+        id: r.get(0)?,
+        name: r.get(1)?,
+        url: r.get(2)?,
+        format: r.get(3)?,
+        template: r.get(4)?,
+        content_type: r.get(5)?,
+        events: r.get(6)?,
+        has_secret: secret.is_some(),
+        secret,
+        active: r.get::<_, i64>(8)? != 0,
+        created_at: r.get(9)?,
+    })
+}
+
+async fn load_subscriptions(
+    db: &crate::db::Database,
+    only_active: bool,
+) -> tokio_rusqlite::Result<Vec<Subscription>> {
+    db.0.call(move |c| {
+        let sql = if only_active {
+            format!("SELECT {SUB_COLUMNS} FROM webhook_subscriptions WHERE active = 1 ORDER BY id")
+        } else {
+            format!("SELECT {SUB_COLUMNS} FROM webhook_subscriptions ORDER BY id")
+        };
+        let mut stmt = c.prepare(&sql)?;
+        let rows = stmt
+            .query_map([], sub_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(rows)
+    })
+    .await
+}
+
+async fn load_subscription(
+    db: &crate::db::Database,
+    id: i64,
+) -> tokio_rusqlite::Result<Option<Subscription>> {
+    db.0.call(move |c| {
+        let mut stmt = c.prepare(&format!(
+            "SELECT {SUB_COLUMNS} FROM webhook_subscriptions WHERE id = ?1"
+        ))?;
+        let mut rows = stmt.query_map([id], sub_from_row)?;
+        Ok(rows.next().transpose()?)
+    })
+    .await
+}
+
+/// Render + POST one event to one subscription.
+async fn deliver(
+    state: &AppState,
+    sub: &Subscription,
+    event: &str,
+    json: &serde_json::Value,
+) -> anyhow::Result<()> {
+    let format = webhook::Format::parse(&sub.format)
+        .ok_or_else(|| anyhow::anyhow!("unknown format {}", sub.format))?;
+    let rendered = webhook::render(
+        format,
+        event,
+        json,
+        sub.template.as_deref(),
+        sub.content_type.as_deref(),
+    )?;
+    let secret = sub.secret.as_deref().or(state.webhook.secret.as_deref());
+    state.webhook.post(&sub.url, secret, event, rendered).await
+}
+
+/// Fan an event out to the env URL and every active subscription that wants it.
+/// Targets are posted concurrently; the error lists the ones that failed.
+pub async fn dispatch(
+    state: &AppState,
+    event: &str,
+    json: &serde_json::Value,
+) -> anyhow::Result<()> {
+    let subs = load_subscriptions(&state.db, true).await?;
+    let env = async {
+        state
+            .webhook
+            .send(event, json)
+            .await
+            .map_err(|e| format!("env: {e}"))
+    };
+    let targets = subs
+        .iter()
+        .filter(|s| s.wants(event))
+        .map(|sub| async move {
+            deliver(state, sub, event, json).await.map_err(|e| {
+                tracing::warn!(error = ?e, subscription = sub.id, event, "webhook delivery failed");
+                format!("{}: {e}", sub.name)
+            })
+        });
+    let (env, targets) = tokio::join!(env, futures_util::future::join_all(targets));
+    let failures: Vec<String> = std::iter::once(env)
+        .chain(targets)
+        .filter_map(Result::err)
+        .collect();
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        anyhow::bail!(failures.join("; "))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct SubscriptionIn {
+    name: String,
+    url: String,
+    #[serde(default)]
+    format: Option<String>,
+    #[serde(default)]
+    template: Option<String>,
+    #[serde(default)]
+    content_type: Option<String>,
+    #[serde(default)]
+    events: Option<String>,
+    #[serde(default)]
+    secret: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SubscriptionPatch {
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    url: Option<String>,
+    #[serde(default)]
+    format: Option<String>,
+    #[serde(default)]
+    template: Option<String>,
+    #[serde(default)]
+    content_type: Option<String>,
+    #[serde(default)]
+    events: Option<String>,
+    #[serde(default)]
+    secret: Option<String>,
+    #[serde(default)]
+    active: Option<bool>,
+}
+
+/// Validate a target; returns the canonical format name ("hookshot" → "matrix").
+fn check_subscription(
+    url: &str,
+    format: &str,
+    template: Option<&str>,
+) -> Result<&'static str, ApiErr> {
+    if !(url.starts_with("http://") || url.starts_with("https://")) {
+        return Err(err(StatusCode::UNPROCESSABLE_ENTITY, "url must be http(s)"));
+    }
+    match webhook::Format::parse(format) {
+        None => Err(err(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "format must be one of m3, slack, matrix, ntfy, form, custom",
+        )),
+        Some(webhook::Format::Custom) if template.is_none_or(|t| t.trim().is_empty()) => Err(err(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "custom format needs a template",
+        )),
+        Some(f) => Ok(f.as_str()),
+    }
+}
+
+/// GET /hooks/subscriptions
+async fn list_subscriptions(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<Subscription>>, StatusCode> {
+    let subs = load_subscriptions(&state.db, false)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    Ok(Json(subs))
+}
+
+/// POST /hooks/subscriptions
+async fn create_subscription(
+    State(state): State<AppState>,
+    Json(input): Json<SubscriptionIn>,
+) -> Result<Json<Subscription>, ApiErr> {
+    let format = input.format.unwrap_or_else(|| "m3".into());
+    let format = check_subscription(&input.url, &format, input.template.as_deref())?;
+    let name = input.name.trim().to_string();
+    if name.is_empty() {
+        return Err(err(StatusCode::UNPROCESSABLE_ENTITY, "name required"));
+    }
+    let events = input
+        .events
+        .filter(|e| !e.trim().is_empty())
+        .unwrap_or_else(|| "*".into());
+    let created_at = Utc::now().to_rfc3339();
+    let id = state
+        .db
+        .0
+        .call(move |c| {
+            c.execute(
+                "INSERT INTO webhook_subscriptions(name, url, format, template, content_type, events, secret, created_at)
+                 VALUES(?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                rusqlite::params![
+                    name,
+                    input.url,
+                    format,
+                    input.template,
+                    input.content_type,
+                    events,
+                    input.secret,
+                    created_at
+                ],
+            )?;
+            Ok(c.last_insert_rowid())
+        })
+        .await
+        .map_err(|e| err(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    load_subscription(&state.db, id)
+        .await
+        .map_err(|e| err(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .map(Json)
+        .ok_or_else(|| err(StatusCode::NOT_FOUND, "subscription not found"))
+}
+
+/// PATCH /hooks/subscriptions/:id — partial update (empty `secret` clears it).
+async fn update_subscription(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    Json(input): Json<SubscriptionPatch>,
+) -> Result<Json<Subscription>, ApiErr> {
+    let Some(mut sub) = load_subscription(&state.db, id)
+        .await
+        .map_err(|e| err(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    else {
+        return Err(err(StatusCode::NOT_FOUND, "subscription not found"));
+    };
+    if let Some(v) = input.name.filter(|v| !v.trim().is_empty()) {
+        sub.name = v.trim().to_string();
+    }
+    if let Some(v) = input.url {
+        sub.url = v;
+    }
+    if let Some(v) = input.format {
+        sub.format = v;
+    }
+    if let Some(v) = input.template {
+        sub.template = Some(v);
+    }
+    if let Some(v) = input.content_type {
+        sub.content_type = Some(v);
+    }
+    if let Some(v) = input.events.filter(|v| !v.trim().is_empty()) {
+        sub.events = v;
+    }
+    if let Some(v) = input.secret {
+        sub.secret = (!v.is_empty()).then_some(v);
+    }
+    if let Some(v) = input.active {
+        sub.active = v;
+    }
+    sub.format = check_subscription(&sub.url, &sub.format, sub.template.as_deref())?.to_string();
+
+    let row = sub.clone();
+    state
+        .db
+        .0
+        .call(move |c| {
+            c.execute(
+                "UPDATE webhook_subscriptions
+                 SET name=?1, url=?2, format=?3, template=?4, content_type=?5, events=?6, secret=?7, active=?8
+                 WHERE id=?9",
+                rusqlite::params![
+                    row.name,
+                    row.url,
+                    row.format,
+                    row.template,
+                    row.content_type,
+                    row.events,
+                    row.secret,
+                    row.active as i64,
+                    row.id
+                ],
+            )?;
+            Ok(())
+        })
+        .await
+        .map_err(|e| err(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    sub.has_secret = sub.secret.is_some();
+    Ok(Json(sub))
+}
+
+/// DELETE /hooks/subscriptions/:id
+async fn delete_subscription(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    let n = state
+        .db
+        .0
+        .call(move |c| Ok(c.execute("DELETE FROM webhook_subscriptions WHERE id=?1", [id])?))
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    if n == 0 {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    Ok(Json(serde_json::json!({ "ok": true })))
+}
+
+/// POST /hooks/subscriptions/:id/test — sample panic-shaped envelope, delivered now.
+async fn test_subscription(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+) -> Result<Json<serde_json::Value>, ApiErr> {
+    let Some(sub) = load_subscription(&state.db, id)
+        .await
+        .map_err(|e| err(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    else {
+        return Err(err(StatusCode::NOT_FOUND, "subscription not found"));
+    };
+    let sample = serde_json::json!({
+        "id": relational::new_event_id(),
+        "type": "m3.test",
+        "ts": Utc::now().to_rfc3339(),
+        "actor": { "id": "m3", "display": "M3" },
+        "intent": { "whisper": "Test delivery — if you can read this, the bridge works." },
+        "context": {}
+    });
+    deliver(&state, &sub, "m3.test", &sample)
+        .await
+        .map_err(|e| err(StatusCode::BAD_GATEWAY, e.to_string()))?;
+    Ok(Json(serde_json::json!({ "ok": true })))
+}
+
+/// Router to be mounted under `/hooks`.
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/inbound", post(inbound))
+        .route("/care", get(care_for_event))
+        .route(
+            "/subscriptions",
+            get(list_subscriptions).post(create_subscription),
+        )
+        .route(
+            "/subscriptions/:id",
+            patch(update_subscription).delete(delete_subscription),
+        )
+        .route("/subscriptions/:id/test", post(test_subscription))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::Request;
+    use tower::ServiceExt; // for `oneshot`
+
+    const SECRET: &str = "s3cret";
+
+    async fn make_state_for_test() -> AppState {
+        let mut state = crate::test_state().await;
+        state.webhook = webhook::Webhook::new(None, Some(SECRET.into()));
+        state
+            .db
+            .0
+            .call(|c| {
+                c.execute(
+                    "INSERT INTO relational_events(id, type, ts, actor, delivered, envelope)
+                     VALUES('evt_TEST', 'panic.ui', '2026-01-07T10:12:00Z', 'raz', 1, '{}')",
+                    [],
+                )?;
+                Ok(())
+            })
+            .await
+            .unwrap();
+        state
+    }
+
+    fn signed(body: &str, ts: i64) -> Request<axum::body::Body> {
+        let sig = webhook::sign(SECRET, &ts.to_string(), body.as_bytes()).unwrap();
+        Request::builder()
+            .method("POST")
+            .uri("/hooks/inbound")
+            .header("content-type", "application/json")
+            .header("x-m3-signature", sig)
+            .body(axum::body::Body::from(body.to_string()))
+            .unwrap()
+    }
+
+    fn app(state: &AppState) -> Router {
+        Router::new()
+            .nest("/hooks", router())
+            .with_state(state.clone())
+    }
+
+    const ACK: &str = r#"{"type":"care.ack","ts":"2026-01-07T10:12:10Z","for":"evt_TEST","by":{"id":"ana","display":"Ana"},"action":"soft-checkin","security":{"nonce":"n1"}}"#;
+
+    #[test]
+    fn care_messages_are_human() {
+        assert_eq!(
+            care_message("care.ack", Some("Ana"), None),
+            "Ana is with you"
+        );
+        assert_eq!(
+            care_message("care.done", None, Some("held-space")),
+            "Care loop closed (held-space)"
+        );
+    }
+
+    #[tokio::test]
+    async fn signed_ack_is_stored_and_published() {
+        let state = make_state_for_test().await;
+        let res = app(&state)
+            .oneshot(signed(ACK, Utc::now().timestamp()))
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let published = state.bus.drain();
+        assert_eq!(published.len(), 1);
+        assert!(published[0].starts_with(CARE_TOPIC));
+        assert!(published[0].contains("Ana is with you"));
+
+        let stored: i64 = state
+            .db
+            .0
+            .call(|c| {
+                Ok(c.query_row(
+                    "SELECT COUNT(*) FROM care_acks WHERE event_id='evt_TEST' AND by_id='ana'",
+                    [],
+                    |r| r.get(0),
+                )?)
+            })
+            .await
+            .unwrap();
+        assert_eq!(stored, 1);
+    }
+
+    #[tokio::test]
+    async fn replayed_nonce_is_rejected() {
+        let state = make_state_for_test().await;
+        let now = Utc::now().timestamp();
+        let first = app(&state).oneshot(signed(ACK, now)).await.unwrap();
+        assert_eq!(first.status(), StatusCode::OK);
+        let again = app(&state).oneshot(signed(ACK, now)).await.unwrap();
+        assert_eq!(again.status(), StatusCode::CONFLICT);
+    }
+
+    #[tokio::test]
+    async fn stale_and_unsigned_requests_are_rejected() {
+        let state = make_state_for_test().await;
+        let stale = app(&state)
+            .oneshot(signed(ACK, Utc::now().timestamp() - 3600))
+            .await
+            .unwrap();
+        assert_eq!(stale.status(), StatusCode::UNAUTHORIZED);
+
+        let unsigned = Request::builder()
+            .method("POST")
+            .uri("/hooks/inbound")
+            .header("content-type", "application/json")
+            .body(axum::body::Body::from(ACK))
+            .unwrap();
+        let res = app(&state).oneshot(unsigned).await.unwrap();
+        assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+        assert!(state.bus.drain().is_empty());
+    }
+
+    #[tokio::test]
+    async fn unknown_event_is_not_found() {
+        let state = make_state_for_test().await;
+        let body = ACK.replace("evt_TEST", "evt_NOPE");
+        let res = app(&state)
+            .oneshot(signed(&body, Utc::now().timestamp()))
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn dispatch_renders_per_subscription_and_filters_events() {
+        let state = make_state_for_test().await;
+        let (url, seen) = crate::webhook::tests::stand_in().await;
+        for (name, format, events) in [
+            ("family", "slack", "panic.*"),
+            ("phone", "ntfy", "status.set"),
+        ] {
+            let body =
+                serde_json::json!({ "name": name, "url": url, "format": format, "events": events });
+            let res = app(&state)
+                .oneshot(
+                    Request::builder()
+                        .method("POST")
+                        .uri("/hooks/subscriptions")
+                        .header("content-type", "application/json")
+                        .body(axum::body::Body::from(body.to_string()))
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+            assert_eq!(res.status(), StatusCode::OK);
+        }
+
+        let envelope = serde_json::json!({
+            "actor": { "id": "raz", "display": "Raz" },
+            "intent": { "whisper": "I'm here. Not urgent." },
+        });
+        dispatch(&state, "panic.ui", &envelope).await.unwrap();
+
+        let seen = seen.lock();
+        assert_eq!(seen.len(), 1, "only the panic.* subscription should fire");
+        let v: serde_json::Value = serde_json::from_str(&seen[0].1).unwrap();
+        assert_eq!(v["text"], "🌬️ Raz: I'm here. Not urgent.");
+    }
+
+    #[tokio::test]
+    async fn emit_does_not_wait_for_a_slow_receiver() {
+        let state = make_state_for_test().await;
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let slow = Router::new().route(
+            "/",
+            post(|| async {
+                tokio::time::sleep(std::time::Duration::from_secs(30)).await;
+                "late"
+            }),
+        );
+        tokio::spawn(async move { axum::serve(listener, slow).await.unwrap() });
+        let body = serde_json::json!({ "name": "slow", "url": url, "events": "status.set" });
+        let res = app(&state)
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/hooks/subscriptions")
+                    .header("content-type", "application/json")
+                    .body(axum::body::Body::from(body.to_string()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        let raz = relational::Actor::from_display("Raz").id;
+        state
+            .db
+            .0
+            .call(move |c| {
+                c.execute(
+                    "INSERT INTO consents(granted_by, scope, audience, mode, granted_at)
+                     VALUES(?1, 'status.set', 'group:care', 'explicit', '2026-01-01T00:00:00Z')",
+                    [raz],
+                )?;
+                Ok(())
+            })
+            .await
+            .unwrap();
+
+        let emitted = tokio::time::timeout(
+            std::time::Duration::from_secs(2),
+            relational::emit(
+                &state,
+                "status.set",
+                &relational::Actor::from_display("Raz"),
+                relational::Intent::state_change(),
+                serde_json::json!({ "status": "red" }),
+            ),
+        )
+        .await
+        .expect("emit waited for the receiver");
+        assert!(emitted.queued);
+        let id = emitted.id.clone();
+        let recorded: i64 = state
+            .db
+            .0
+            .call(move |c| {
+                Ok(c.query_row(
+                    "SELECT COUNT(*) FROM relational_events WHERE id = ?1 AND delivered = 0",
+                    [id],
+                    |r| r.get(0),
+                )?)
+            })
+            .await
+            .unwrap();
+        assert_eq!(recorded, 1, "recorded before delivery finishes");
+    }
+
+    #[tokio::test]
+    async fn subscriptions_are_validated_and_canonicalized() {
+        let state = make_state_for_test().await;
+        let post_sub = |body: &'static str| {
+            Request::builder()
+                .method("POST")
+                .uri("/hooks/subscriptions")
+                .header("content-type", "application/json")
+                .body(axum::body::Body::from(body))
+                .unwrap()
+        };
+        let res = app(&state)
+            .oneshot(post_sub(
+                r#"{"name":"x","url":"http://h/","format":"custom"}"#,
+            ))
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+        let res = app(&state)
+            .oneshot(post_sub(
+                r#"{"name":"room","url":"http://h/","format":"hookshot","secret":"k"}"#,
+            ))
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        let subs = load_subscriptions(&state.db, false).await.unwrap();
+        assert_eq!(subs[0].format, "matrix");
+        assert!(subs[0].wants("status.set"));
+        let listed = serde_json::to_value(&subs[0]).unwrap();
+        assert_eq!(listed["has_secret"], true);
+        assert!(listed.get("secret").is_none());
+    }
+}
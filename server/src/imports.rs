@@ -0,0 +1,826 @@
+//! Emotion import — mood history from other trackers
+//! -------------------------------------------------
+//! Whisper: "old feelings, same map." 🧳
+//!
+//! A CSV export becomes emotion rows, each at the time written in the source (not the
+//! time of the import). Two layouts:
+//!   • `daylio` — `full_date,date,weekday,time,mood,activities,note_title,note`. Moods map
+//!     onto kinds (rad → joy 0.9, good → joy 0.6, meh → neutrality 0.5, bad → grief 0.6,
+//!     awful → grief 0.9); custom moods go through the taxonomy at 0.5.
+//!   • `generic` — a `mapping` names the columns: `{ ts, kind, time?, who?, intensity?,
+//!     note?, ts_format?, scale? }`. Intensities are divided by `scale` (e.g. 5 or 10;
+//!     default 1) and default to 0.5.
+//! Without `layout`, a `mapping` means generic and Daylio is recognized by its header.
+//!
+//! Rows are normalized like `/emotions/add` (kinds through the taxonomy, see taxonomy.rs;
+//! strict mode skips unknown kinds) and deduplicated on (who, ts, kind), so importing
+//! the same file twice is harmless. Timestamps without an offset are read in
+//! `utc_offset` (default UTC) and stored as RFC3339 UTC. Date-only values land at midnight.
+//!
+//! The work runs as a background job in chunks; its report lives in `import_jobs`
+//! (jobs cut off by a restart are marked failed, see `db::ensure_schema`).
+//!
+//! Endpoints (mounted under `/emotions/import`)
+//!   POST /emotions/import      → 202 job; body { csv, layout?, mapping?, who?, privacy?,
+//!                                 utc_offset? } (`who` is required without a who column)
+//!   GET  /emotions/import      → newest 20 jobs
+//!   GET  /emotions/import/:id  → { id, layout, state, total, processed, imported,
+//!                                 duplicates, skipped, unknown_kinds, errors, error?, … }
+
+use crate::db::Database;
+use crate::emotions::{self, EmotionIn};
+use crate::taxonomy;
+use crate::AppState;
+use axum::{
+    extract::{DefaultBodyLimit, Path, State},
+    http::StatusCode,
+    routing::get,
+    Json, Router,
+};
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use rusqlite::OptionalExtension;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+type ApiErr = (StatusCode, String);
+
+fn unprocessable(msg: impl Into<String>) -> ApiErr {
+    (StatusCode::UNPROCESSABLE_ENTITY, msg.into())
+}
+
+fn internal(e: impl std::fmt::Display) -> ApiErr {
+    (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+}
+
+/// Years of twice-a-day entries stay well under this.
+const MAX_BODY_BYTES: usize = 16 * 1024 * 1024;
+/// Rows per transaction; the report is updated after each.
+const CHUNK: usize = 500;
+/// Row errors kept in the report (`skipped` counts them all).
+const MAX_ERRORS: usize = 100;
+const DEFAULT_INTENSITY: f32 = 0.5;
+
+/// Daylio's five default moods → (kind, intensity).
+const DAYLIO_MOODS: &[(&str, &str, f32)] = &[
+    ("rad", "joy", 0.9),
+    ("good", "joy", 0.6),
+    ("meh", "neutrality", 0.5),
+    ("bad", "grief", 0.6),
+    ("awful", "grief", 0.9),
+];
+
+/// Source formats tried after RFC3339 and unix seconds (date and time joined by a space).
+const TS_FORMATS: &[&str] = &[
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%dT%H:%M:%S",
+    "%Y-%m-%d %H:%M",
+    "%Y-%m-%dT%H:%M",
+    "%Y-%m-%d %I:%M %p",
+    "%Y-%m-%d %I:%M:%S %p",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layout {
+    Daylio,
+    Generic,
+}
+
+impl Layout {
+    pub fn parse(s: &str) -> Option<Layout> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "daylio" => Some(Layout::Daylio),
+            "generic" => Some(Layout::Generic),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Layout::Daylio => "daylio",
+            Layout::Generic => "generic",
+        }
+    }
+}
+
+/// Column names (matched case-insensitively) for the generic layout.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct Mapping {
+    /// Date or date-time.
+    pub ts: String,
+    pub kind: String,
+    /// Time of day, when the source keeps it apart from the date.
+    #[serde(default)]
+    pub time: Option<String>,
+    #[serde(default)]
+    pub who: Option<String>,
+    #[serde(default)]
+    pub intensity: Option<String>,
+    #[serde(default)]
+    pub note: Option<String>,
+    /// chrono format for `ts` (`ts time` when `time` is mapped).
+    #[serde(default)]
+    pub ts_format: Option<String>,
+    /// Top of the source intensity scale.
+    #[serde(default)]
+    pub scale: Option<f32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ImportIn {
+    pub csv: String,
+    #[serde(default)]
+    pub layout: Option<String>,
+    #[serde(default)]
+    pub mapping: Option<Mapping>,
+    /// Person for every row when the file has no who column (or an empty cell).
+    #[serde(default)]
+    pub who: Option<String>,
+    /// private (default) | sealed | anonymized | public
+    #[serde(default)]
+    pub privacy: Option<String>,
+    /// e.g. "+02:00"; applies to source times without an offset.
+    #[serde(default)]
+    pub utc_offset: Option<String>,
+}
+
+/// How to turn one CSV record into an emotion.
+#[derive(Debug, Clone)]
+pub struct Plan {
+    layout: Layout,
+    ts: usize,
+    time: Option<usize>,
+    kind: usize,
+    who: Option<usize>,
+    intensity: Option<usize>,
+    notes: Vec<usize>,
+    ts_format: Option<String>,
+    scale: f32,
+    default_who: Option<String>,
+    privacy: String,
+    offset: FixedOffset,
+}
+
+/// One source row, ready to store.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Record {
+    pub who: String,
+    pub ts: String,
+    pub kind: String,
+    pub intensity: f32,
+    pub details: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RowError {
+    /// 1-based data row (the header is row 0).
+    pub row: usize,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportJob {
+    pub id: i64,
+    pub layout: String,
+    pub state: String,
+    pub total: i64,
+    pub processed: i64,
+    pub imported: i64,
+    pub duplicates: i64,
+    pub skipped: i64,
+    pub unknown_kinds: BTreeMap<String, i64>,
+    pub errors: Vec<RowError>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub created_at: String,
+    pub finished_at: Option<String>,
+}
+
+/// RFC 4180 records: quoted fields may hold commas, `""` and newlines. A BOM and blank
+/// lines are dropped.
+pub fn parse_csv(text: &str) -> Result<Vec<Vec<String>>, String> {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = text.chars().peekable();
+    while let Some(ch) = chars.next() {
+        if quoted {
+            match ch {
+                '"' if chars.peek() == Some(&'"') => {
+                    field.push('"');
+                    chars.next();
+                }
+                '"' => quoted = false,
+                _ => field.push(ch),
+            }
+            continue;
+        }
+        match ch {
+            '"' if field.is_empty() => quoted = true,
+            ',' => row.push(std::mem::take(&mut field)),
+            '\r' => {}
+            '\n' => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            _ => field.push(ch),
+        }
+    }
+    if quoted {
+        return Err("unterminated quoted field".into());
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+    rows.retain(|r| r.iter().any(|f| !f.trim().is_empty()));
+    Ok(rows)
+}
+
+/// "+02:00", "-0530", "Z" or "UTC".
+pub(crate) fn parse_offset(s: &str) -> Option<FixedOffset> {
+    let s = s.trim();
+    if s.is_empty() || s.eq_ignore_ascii_case("z") || s.eq_ignore_ascii_case("utc") {
+        return FixedOffset::east_opt(0);
+    }
+    s.parse().ok()
+}
+
+/// A source timestamp as RFC3339 UTC; naive values are read in `offset`.
+pub fn parse_ts(raw: &str, format: Option<&str>, offset: FixedOffset) -> Option<String> {
+    let raw = raw.trim();
+    if raw.is_empty() {
+        return None;
+    }
+    let utc = |dt: DateTime<Utc>| Some(dt.to_rfc3339());
+    let local = |naive: NaiveDateTime| {
+        offset
+            .from_local_datetime(&naive)
+            .single()
+            .and_then(|dt| utc(dt.with_timezone(&Utc)))
+    };
+    if let Some(fmt) = format {
+        if let Ok(dt) = DateTime::parse_from_str(raw, fmt) {
+            return utc(dt.with_timezone(&Utc));
+        }
+        if let Ok(naive) = NaiveDateTime::parse_from_str(raw, fmt) {
+            return local(naive);
+        }
+        return NaiveDate::parse_from_str(raw, fmt)
+            .ok()
+            .and_then(|d| local(d.and_hms_opt(0, 0, 0)?));
+    }
+    if let Ok(dt) = DateTime::parse_from_rfc3339(raw) {
+        return utc(dt.with_timezone(&Utc));
+    }
+    if raw.bytes().all(|b| b.is_ascii_digit()) {
+        let n: i64 = raw.parse().ok()?;
+        // 13 digits and up are milliseconds
+        let dt = if n >= 100_000_000_000 {
+            DateTime::from_timestamp_millis(n)
+        } else {
+            DateTime::from_timestamp(n, 0)
+        };
+        return dt.and_then(utc);
+    }
+    let upper = raw.to_ascii_uppercase();
+    for fmt in TS_FORMATS {
+        if let Ok(naive) = NaiveDateTime::parse_from_str(&upper, fmt) {
+            return local(naive);
+        }
+    }
+    NaiveDate::parse_from_str(raw, "%Y-%m-%d")
+        .ok()
+        .and_then(|d| local(d.and_hms_opt(0, 0, 0)?))
+}
+
+fn column(header: &[String], name: &str) -> Result<usize, String> {
+    let name = name.trim();
+    header
+        .iter()
+        .position(|h| h.trim().eq_ignore_ascii_case(name))
+        .ok_or_else(|| format!("no column `{name}` in the header"))
+}
+
+fn optional_column(header: &[String], name: Option<&str>) -> Result<Option<usize>, String> {
+    name.filter(|n| !n.trim().is_empty())
+        .map(|n| column(header, n))
+        .transpose()
+}
+
+impl Plan {
+    /// Check the request against the header; every problem here is a 422.
+    pub fn new(input: &ImportIn, header: &[String]) -> Result<Plan, String> {
+        let has = |name: &str| column(header, name).is_ok();
+        let layout = match input.layout.as_deref().filter(|l| !l.trim().is_empty()) {
+            Some(l) => Layout::parse(l).ok_or("layout must be daylio|generic")?,
+            None if input.mapping.is_some() => Layout::Generic,
+            None if has("full_date") && has("mood") => Layout::Daylio,
+            None => return Err("unrecognized layout; pass a `mapping` for the generic one".into()),
+        };
+        let privacy = input
+            .privacy
+            .as_deref()
+            .map(|p| p.trim().to_ascii_lowercase())
+            .filter(|p| !p.is_empty())
+            .unwrap_or_else(|| "private".into());
+        if !matches!(
+            privacy.as_str(),
+            "private" | "sealed" | "anonymized" | "public"
+        ) {
+            return Err("privacy must be private|sealed|anonymized|public".into());
+        }
+        let offset = match input.utc_offset.as_deref() {
+            Some(o) => parse_offset(o).ok_or("utc_offset must look like +02:00")?,
+            None => FixedOffset::east_opt(0).expect("zero offset"),
+        };
+        let default_who = input
+            .who
+            .as_deref()
+            .map(str::trim)
+            .filter(|w| !w.is_empty())
+            .map(str::to_owned);
+        let plan = match layout {
+            Layout::Daylio => {
+                if input.mapping.is_some() {
+                    return Err("mapping only applies to the generic layout".into());
+                }
+                Plan {
+                    layout,
+                    ts: column(header, "full_date")?,
+                    time: optional_column(header, Some("time"))?,
+                    kind: column(header, "mood")?,
+                    who: None,
+                    intensity: None,
+                    notes: ["note_title", "note"]
+                        .iter()
+                        .filter_map(|n| column(header, n).ok())
+                        .collect(),
+                    ts_format: None,
+                    scale: 1.0,
+                    default_who,
+                    privacy,
+                    offset,
+                }
+            }
+            Layout::Generic => {
+                let m = input
+                    .mapping
+                    .as_ref()
+                    .ok_or("the generic layout needs a `mapping`")?;
+                let scale = m.scale.unwrap_or(1.0);
+                if !(scale.is_finite() && scale > 0.0) {
+                    return Err("scale must be positive".into());
+                }
+                Plan {
+                    layout,
+                    ts: column(header, &m.ts)?,
+                    time: optional_column(header, m.time.as_deref())?,
+                    kind: column(header, &m.kind)?,
+                    who: optional_column(header, m.who.as_deref())?,
+                    intensity: optional_column(header, m.intensity.as_deref())?,
+                    notes: optional_column(header, m.note.as_deref())?
+                        .into_iter()
+                        .collect(),
+                    ts_format: m.ts_format.clone().filter(|f| !f.trim().is_empty()),
+                    scale,
+                    default_who,
+                    privacy,
+                    offset,
+                }
+            }
+        };
+        if plan.who.is_none() && plan.default_who.is_none() {
+            return Err("who is required when the file has no who column".into());
+        }
+        Ok(plan)
+    }
+
+    /// One data row; `Err` is the reason it is skipped.
+    pub fn record(&self, row: &[String]) -> Result<Record, String> {
+        let cell = |i: usize| row.get(i).map(|s| s.trim()).unwrap_or("");
+        let who = self
+            .who
+            .map(cell)
+            .filter(|w| !w.is_empty())
+            .or(self.default_who.as_deref())
+            .ok_or("empty who")?
+            .to_owned();
+        let raw_ts = match self.time.map(cell).filter(|t| !t.is_empty()) {
+            Some(time) => format!("{} {time}", cell(self.ts)),
+            None => cell(self.ts).to_owned(),
+        };
+        let ts = parse_ts(&raw_ts, self.ts_format.as_deref(), self.offset)
+            .ok_or_else(|| format!("unreadable timestamp `{raw_ts}`"))?;
+        let raw_kind = cell(self.kind);
+        if raw_kind.is_empty() {
+            return Err("empty kind".into());
+        }
+        let (kind, mut intensity) = match self.layout {
+            Layout::Daylio => DAYLIO_MOODS
+                .iter()
+                .find(|(mood, _, _)| raw_kind.eq_ignore_ascii_case(mood))
+                .map(|&(_, kind, intensity)| (kind.to_owned(), intensity))
+                .unwrap_or_else(|| (raw_kind.to_owned(), DEFAULT_INTENSITY)),
+            Layout::Generic => (raw_kind.to_owned(), DEFAULT_INTENSITY),
+        };
+        if let Some(raw) = self.intensity.map(cell).filter(|v| !v.is_empty()) {
+            let value: f32 = raw
+                .parse()
+                .map_err(|_| format!("unreadable intensity `{raw}`"))?;
+            intensity = value / self.scale;
+            if !(0.0..=1.0).contains(&intensity) {
+                return Err(format!("intensity `{raw}` is outside 0..={}", self.scale));
+            }
+        }
+        let notes: Vec<&str> = self
+            .notes
+            .iter()
+            .map(|&i| cell(i))
+            .filter(|n| !n.is_empty())
+            .collect();
+        Ok(Record {
+            who,
+            ts,
+            kind,
+            intensity,
+            details: (!notes.is_empty()).then(|| notes.join("\n")),
+        })
+    }
+}
+
+/// Running totals, written back after every chunk.
+#[derive(Debug, Default)]
+struct Tally {
+    processed: i64,
+    imported: i64,
+    duplicates: i64,
+    skipped: i64,
+    unknown_kinds: BTreeMap<String, i64>,
+    errors: Vec<RowError>,
+}
+
+impl Tally {
+    fn skip(&mut self, row: usize, reason: String) {
+        self.skipped += 1;
+        if self.errors.len() < MAX_ERRORS {
+            self.errors.push(RowError { row, reason });
+        }
+    }
+}
+
+/// Store one chunk (`first` = data row number of `rows[0]`) in a single transaction.
+fn import_chunk(
+    c: &mut rusqlite::Connection,
+    job: i64,
+    plan: &Plan,
+    first: usize,
+    rows: &[Vec<String>],
+    tally: &mut Tally,
+    strict: bool,
+) -> rusqlite::Result<()> {
+    let tx = c.transaction()?;
+    let note = format!("import {} #{job}", plan.layout.as_str());
+    for (i, row) in rows.iter().enumerate() {
+        tally.processed += 1;
+        let record = match plan.record(row) {
+            Ok(r) => r,
+            Err(reason) => {
+                tally.skip(first + i, reason);
+                continue;
+            }
+        };
+        let normalized = taxonomy::normalize(&tx, &record.kind)?;
+        let duplicate = tx
+            .query_row(
+                "SELECT 1 FROM emotions WHERE who = ?1 AND ts = ?2 AND kind = ?3",
+                rusqlite::params![record.who, record.ts, normalized.kind],
+                |_| Ok(()),
+            )
+            .optional()?
+            .is_some();
+        if duplicate {
+            tally.duplicates += 1;
+            continue;
+        }
+        let input = EmotionIn {
+            who: record.who,
+            kind: normalized.kind.clone(),
+            intensity: record.intensity,
+            note_id: None,
+            details: record.details,
+            sealed: plan.privacy == "sealed",
+            archetype: None,
+            privacy: plan.privacy.clone(),
+        };
+        match emotions::insert(&tx, record.ts, input, Some(note.clone()), strict)? {
+            Some(out) => {
+                tally.imported += 1;
+                if out.unknown_kind {
+                    *tally.unknown_kinds.entry(out.kind).or_default() += 1;
+                }
+            }
+            None => tally.skip(first + i, format!("unknown kind `{}`", record.kind)),
+        }
+    }
+    tx.execute(
+        "UPDATE import_jobs
+            SET processed = ?2, imported = ?3, duplicates = ?4, skipped = ?5,
+                unknown_kinds = ?6, errors = ?7
+          WHERE id = ?1",
+        rusqlite::params![
+            job,
+            tally.processed,
+            tally.imported,
+            tally.duplicates,
+            tally.skipped,
+            serde_json::to_string(&tally.unknown_kinds).unwrap_or_else(|_| "{}".into()),
+            serde_json::to_string(&tally.errors).unwrap_or_else(|_| "[]".into()),
+        ],
+    )?;
+    tx.commit()
+}
+
+fn finish(c: &rusqlite::Connection, job: i64, error: Option<String>) -> rusqlite::Result<()> {
+    let state = if error.is_some() { "failed" } else { "done" };
+    c.execute(
+        "UPDATE import_jobs SET state = ?2, error = ?3, finished_at = ?4 WHERE id = ?1",
+        rusqlite::params![job, state, error, Utc::now().to_rfc3339()],
+    )?;
+    Ok(())
+}
+
+/// The background half of POST /emotions/import.
+async fn run(db: Database, job: i64, plan: Plan, rows: Vec<Vec<String>>) {
+    let strict = taxonomy::strict();
+    let mut tally = Tally::default();
+    let mut failure = None;
+    let started =
+        db.0.call(move |c| {
+            Ok(c.execute(
+                "UPDATE import_jobs SET state = 'running' WHERE id = ?1",
+                [job],
+            )?)
+        })
+        .await;
+    if let Err(e) = started {
+        failure = Some(e.to_string());
+    }
+    let mut first = 1;
+    for chunk in rows.chunks(CHUNK) {
+        if failure.is_some() {
+            break;
+        }
+        let chunk = chunk.to_vec();
+        let plan = plan.clone();
+        let mut t = std::mem::take(&mut tally);
+        let res =
+            db.0.call(move |c| {
+                import_chunk(c, job, &plan, first, &chunk, &mut t, strict)?;
+                Ok(t)
+            })
+            .await;
+        match res {
+            Ok(t) => tally = t,
+            Err(e) => failure = Some(e.to_string()),
+        }
+        first += CHUNK;
+    }
+    if let Some(e) = &failure {
+        tracing::warn!(job, error = %e, "emotions import failed");
+    }
+    let done = db.0.call(move |c| Ok(finish(c, job, failure)?)).await;
+    if let Err(e) = done {
+        tracing::warn!(job, error = %e, "emotions import: could not close the job");
+    }
+}
+
+const JOB_COLS: &str = "id, layout, state, total, processed, imported, duplicates, skipped,
+     unknown_kinds, errors, error, created_at, finished_at";
+
+fn row_to_job(row: &rusqlite::Row) -> rusqlite::Result<ImportJob> {
+    let unknown: String = row.get(8)?;
+    let errors: String = row.get(9)?;
+    Ok(ImportJob {
+        id: row.get(0)?,
+        layout: row.get(1)?,
+        state: row.get(2)?,
+        total: row.get(3)?,
+        processed: row.get(4)?,
+        imported: row.get(5)?,
+        duplicates: row.get(6)?,
+        skipped: row.get(7)?,
+        unknown_kinds: serde_json::from_str(&unknown).unwrap_or_default(),
+        errors: serde_json::from_str(&errors).unwrap_or_default(),
+        error: row.get(10)?,
+        created_at: row.get(11)?,
+        finished_at: row.get(12)?,
+    })
+}
+
+pub fn get_job(c: &rusqlite::Connection, id: i64) -> rusqlite::Result<Option<ImportJob>> {
+    c.query_row(
+        &format!("SELECT {JOB_COLS} FROM import_jobs WHERE id = ?1"),
+        [id],
+        row_to_job,
+    )
+    .optional()
+}
+
+pub fn list_jobs(c: &rusqlite::Connection) -> rusqlite::Result<Vec<ImportJob>> {
+    c.prepare(&format!(
+        "SELECT {JOB_COLS} FROM import_jobs ORDER BY id DESC LIMIT 20"
+    ))?
+    .query_map([], row_to_job)?
+    .collect()
+}
+
+/// POST /emotions/import — validate, queue, answer with the job.
+async fn start_http(
+    State(state): State<AppState>,
+    Json(input): Json<ImportIn>,
+) -> Result<(StatusCode, Json<ImportJob>), ApiErr> {
+    let mut rows = parse_csv(&input.csv).map_err(unprocessable)?;
+    if rows.is_empty() {
+        return Err(unprocessable("csv is empty"));
+    }
+    let header = rows.remove(0);
+    let plan = Plan::new(&input, &header).map_err(unprocessable)?;
+    let layout = plan.layout.as_str();
+    let total = rows.len() as i64;
+    let now = Utc::now().to_rfc3339();
+    let job = state
+        .db
+        .0
+        .call(move |c| {
+            c.execute(
+                "INSERT INTO import_jobs(layout, state, total, created_at)
+                 VALUES(?1, 'queued', ?2, ?3)",
+                rusqlite::params![layout, total, now],
+            )?;
+            Ok(get_job(c, c.last_insert_rowid())?)
+        })
+        .await
+        .map_err(internal)?
+        .ok_or_else(|| internal("import job vanished"))?;
+    tokio::spawn(run(state.db.clone(), job.id, plan, rows));
+    Ok((StatusCode::ACCEPTED, Json(job)))
+}
+
+/// GET /emotions/import
+async fn list_http(State(state): State<AppState>) -> Result<Json<Vec<ImportJob>>, ApiErr> {
+    state
+        .db
+        .0
+        .call(|c| Ok(list_jobs(c)?))
+        .await
+        .map(Json)
+        .map_err(internal)
+}
+
+/// GET /emotions/import/:id
+async fn get_http(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+) -> Result<Json<ImportJob>, ApiErr> {
+    state
+        .db
+        .0
+        .call(move |c| Ok(get_job(c, id)?))
+        .await
+        .map_err(internal)?
+        .map(Json)
+        .ok_or((StatusCode::NOT_FOUND, "unknown import".into()))
+}
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/",
+            get(list_http)
+                .post(start_http)
+                .layer(DefaultBodyLimit::max(MAX_BODY_BYTES)),
+        )
+        .route("/:id", get(get_http))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc() -> FixedOffset {
+        FixedOffset::east_opt(0).unwrap()
+    }
+
+    fn plan(input: &ImportIn, csv: &str) -> (Plan, Vec<Vec<String>>) {
+        let mut rows = parse_csv(csv).unwrap();
+        let header = rows.remove(0);
+        (Plan::new(input, &header).unwrap(), rows)
+    }
+
+    fn request(layout: Option<&str>, mapping: Option<Mapping>) -> ImportIn {
+        ImportIn {
+            csv: String::new(),
+            layout: layout.map(str::to_owned),
+            mapping,
+            who: Some("Raz".into()),
+            privacy: None,
+            utc_offset: Some("+02:00".into()),
+        }
+    }
+
+    #[test]
+    fn csv_handles_quotes_newlines_and_bom() {
+        let rows = parse_csv("\u{feff}a,b\r\n\"x, \"\"y\"\"\",\"two\nlines\"\n\n1,\n").unwrap();
+        assert_eq!(
+            rows,
+            vec![
+                vec!["a".to_string(), "b".into()],
+                vec!["x, \"y\"".into(), "two\nlines".into()],
+                vec!["1".into(), "".into()],
+            ]
+        );
+        assert!(parse_csv("a,\"b").is_err());
+    }
+
+    #[test]
+    fn source_timestamps_become_utc() {
+        let plus2 = FixedOffset::east_opt(2 * 3600).unwrap();
+        assert_eq!(
+            parse_ts("2024-01-15 8:30 pm", None, plus2).as_deref(),
+            Some("2024-01-15T18:30:00+00:00")
+        );
+        assert_eq!(
+            parse_ts("2024-01-15T20:30:00+02:00", None, utc()).as_deref(),
+            Some("2024-01-15T18:30:00+00:00")
+        );
+        assert_eq!(
+            parse_ts("1705350600", None, utc()).as_deref(),
+            Some("2024-01-15T20:30:00+00:00")
+        );
+        assert_eq!(
+            parse_ts("2024-01-15", None, utc()).as_deref(),
+            Some("2024-01-15T00:00:00+00:00")
+        );
+        assert_eq!(
+            parse_ts("15/01/2024 20:30", Some("%d/%m/%Y %H:%M"), utc()).as_deref(),
+            Some("2024-01-15T20:30:00+00:00")
+        );
+        assert_eq!(parse_ts("yesterday", None, utc()), None);
+    }
+
+    #[test]
+    fn daylio_moods_map_onto_kinds() {
+        let csv = "full_date,date,weekday,time,mood,activities,note_title,note\n\
+                   2024-01-15,January 15,Monday,20:30,rad,friends,Dinner,\"long, good talk\"\n\
+                   2024-01-16,January 16,Tuesday,07:05,tired,,,\n\
+                   2024-01-17,January 17,Wednesday,07:05,,,,\n";
+        let (plan, rows) = plan(&request(None, None), csv);
+        let a = plan.record(&rows[0]).unwrap();
+        assert_eq!(
+            a,
+            Record {
+                who: "Raz".into(),
+                ts: "2024-01-15T18:30:00+00:00".into(),
+                kind: "joy".into(),
+                intensity: 0.9,
+                details: Some("Dinner\nlong, good talk".into()),
+            }
+        );
+        let b = plan.record(&rows[1]).unwrap();
+        assert_eq!((b.kind.as_str(), b.intensity), ("tired", 0.5));
+        assert_eq!(plan.record(&rows[2]), Err("empty kind".to_string()));
+    }
+
+    #[test]
+    fn generic_mapping_reads_columns_and_scale() {
+        let mapping = Mapping {
+            ts: "When".into(),
+            kind: "Feeling".into(),
+            who: Some("Person".into()),
+            intensity: Some("Score".into()),
+            scale: Some(10.0),
+            ..Mapping::default()
+        };
+        let csv = "Person,When,Feeling,Score\n\
+                   Nico,2024-03-01 09:00,anxious,8\n\
+                   ,2024-03-01 10:00,calm,3\n\
+                   Nico,2024-03-01 11:00,calm,12\n";
+        let (plan, rows) = plan(&request(None, Some(mapping.clone())), csv);
+        let a = plan.record(&rows[0]).unwrap();
+        assert_eq!(
+            (a.who.as_str(), a.ts.as_str()),
+            ("Nico", "2024-03-01T07:00:00+00:00")
+        );
+        assert!((a.intensity - 0.8).abs() < 1e-6);
+        assert_eq!(plan.record(&rows[1]).unwrap().who, "Raz");
+        assert!(plan.record(&rows[2]).unwrap_err().contains("outside"));
+
+        let header = vec!["Person".to_string(), "When".into()];
+        let err = Plan::new(&request(None, Some(mapping)), &header).unwrap_err();
+        assert_eq!(err, "no column `Feeling` in the header");
+        let err = Plan::new(&request(Some("moodbook"), None), &header).unwrap_err();
+        assert_eq!(err, "layout must be daylio|generic");
+    }
+}
@@ -5,34 +5,63 @@
 //! public HTTP surface you mount in `main.rs` without requiring a TCP port.
 //!
 //! What you get here:
-//! - `AppState` (minimal shared state: DB handle)
-//! - `init_state()` (open DB and ensure schema)
+//! - `AppState` (shared state: DB handle, event bus, webhook sender)
+//! - `init_state()` (open DB and ensure schema; webhook from `M3_WEBHOOK_*`)
 //! - `app_router(state)` (Axum router with the same nests as the binary)
+//! - `spawn_background(state)` (status expiry, panic follow-ups, energy transitions and
+//!   the rhythm clock, as the binary runs them)
 //!
 //! Notes for contributors:
 //! • Keep `AppState` minimal and cloneable.
 //! • Add new routers here when you add new modules (e.g., /panic).
 //! • Prefer keeping module headers canonical (see Garden stamps guide 🌱).
 
+pub mod bus;
+pub mod config;
 pub mod db;
 pub mod models;
+pub mod webhook;
 
 // HTTP feature modules (mounted under their prefixes)
+pub mod arcs;
+pub mod bridges;
+pub mod calendar;
 pub mod consciousness;
 pub mod cycles;
+pub mod dashboard;
 pub mod emotions;
+pub mod energy;
+pub mod exports;
+pub mod followup;
+pub mod forecast;
+pub mod gratitude;
+pub mod hooks;
+pub mod imports;
+pub mod panic;
+pub mod patterns;
+pub mod privacy;
+pub mod relational;
+pub mod rhythm;
+pub mod rhythm_clock;
+pub mod status;
+pub mod taxonomy;
+pub mod team;
 pub mod tells;
 pub mod towns;
+pub mod transitions;
 pub mod value;
 
 use axum::{routing::get, Json, Router};
 use serde::Serialize;
 
 /// Shared application state (minimal). Must stay in sync with module usage.
-/// Other modules read `state.db` today; grow carefully.
+/// Modules read `state.db`; the panic service, status and relational events also
+/// publish on `state.bus` and deliver through `state.webhook`. Grow carefully.
 #[derive(Clone)]
 pub struct AppState {
     pub db: db::Database,
+    pub bus: bus::Bus,
+    pub webhook: webhook::Webhook,
 }
 
 #[derive(Serialize)]
@@ -45,11 +74,24 @@ struct Health {
 pub fn app_router(state: AppState) -> Router<AppState> {
     Router::new()
         .route("/health", get(health))
+        .nest("/state", dashboard::router())
+        .nest("/team", team::router())
         .nest("/tells", tells::router())
         .nest("/emotions", emotions::router())
+        .nest("/arcs", arcs::router())
+        .nest("/consciousness", consciousness::router())
+        .nest("/energy", energy::router())
+        .nest("/rhythm", rhythm::router())
         .nest("/value", value::router())
         .nest("/cycles", cycles::router())
         .nest("/towns", towns::router())
+        .nest("/panic", panic::router())
+        .nest("/consent", relational::router())
+        .nest("/hooks", hooks::router())
+        .nest("/patterns", patterns::router())
+        .nest("/bridges", bridges::router())
+        .nest("/thanks", gratitude::router())
+        .nest("/privacy", privacy::router())
         .with_state(state)
 }
 
@@ -57,8 +99,35 @@ async fn health() -> Json<Health> {
     Json(Health { ok: true })
 }
 
+/// Start the shared sweepers: status TTL expiry, panic check-ins, energy band crossings
+/// and the rhythm clock (flips auto-advance blocks, announces pending marks). Call once
+/// per state, inside a Tokio runtime.
+pub fn spawn_background(state: &AppState) {
+    status::spawn_expiry(state.clone());
+    followup::spawn(state.clone());
+    transitions::spawn(state.clone());
+    rhythm_clock::spawn(state.clone());
+}
+
 /// Initialize state (open DB + run schema). Use in tests or embedding.
 pub async fn init_state() -> anyhow::Result<AppState> {
     let db = db::init_db().await?;
-    Ok(AppState { db })
+    let config = config::Config::from_env();
+    Ok(AppState {
+        db,
+        bus: bus::Bus::default(),
+        webhook: webhook::Webhook::new(config.webhook_url, config.webhook_secret),
+    })
+}
+
+/// In-memory state with the full schema, for unit tests of shared modules.
+#[cfg(test)]
+pub(crate) async fn test_state() -> AppState {
+    let conn = tokio_rusqlite::Connection::open_in_memory().await.unwrap();
+    conn.call(db::ensure_schema).await.unwrap();
+    AppState {
+        db: db::Database(conn),
+        bus: bus::Bus::default(),
+        webhook: webhook::Webhook::new(None, None),
+    }
 }
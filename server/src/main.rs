@@ -24,11 +24,15 @@
 //! - /ingest, /retrieve, /snapshot — message stream primitives
 //! - /export, /export_csv — thread exports
 //! - /import_openai — bulk importer from ChatGPT exports
-//! - /status*, /status/stream — readiness lights
-//! - /state/* — dashboard model
+//! - /status*, /status/stream — readiness lights; /status/history, /status/summary — analytics
+//! - /state/* — dashboard model (versioned, ETag / If-Match)
 //! - /reply, /replies/preview — lightweight reply engine
-//! - /panic, /panic/run, /panic/last — redirect oracle + audit
-//! - /emotions/*, /patterns/*, /energy/*, /rhythm/*, /tells/*, /timeline/*, /cycles/*, /value/*, /towns/* — nested routers
+//! - /panic/* — redirect oracle runs, presets, history, outcomes (panic.rs)
+//! - /thanks/* — gratitude ledger (filters, edit, stats + streaks; see gratitude.rs)
+//! - /consent/* — relational webhook consent registry
+//! - /hooks/inbound, /hooks/care — signed care.ack / care.done receiver
+//! - /emotions/*, /arcs/*, /consciousness/*, /patterns/*, /bridges/*, /energy/*, /rhythm/*, /tells/*,
+//!   /timeline/*, /cycles/*, /value/*, /towns/* — nested routers
 mod config;
 mod webhook;
 // mod auth; // keep if you actually use guards later
@@ -37,19 +41,37 @@ use config::Config;
 use webhook::Webhook;
 // use auth::WriteGuard;
 
+mod arcs;
+mod bridges;
 mod bus;
+mod calendar;
 mod consciousness;
 mod cycles;
+mod dashboard;
 mod db;
 mod emotions;
 mod energy;
+mod exports;
+mod followup;
+mod forecast;
+mod gratitude;
+mod hooks;
+mod imports;
 mod models;
+mod panic;
 mod patterns;
+mod privacy;
+mod relational;
 mod replies;
 mod rhythm;
+mod rhythm_clock;
+mod status;
+mod taxonomy;
+mod team;
 mod tells;
 mod timeline;
 mod towns;
+mod transitions;
 mod value; // community bulletin board (news feed): /towns/*
 
 use bus::Bus;
@@ -64,21 +86,15 @@ use std::{
 
 use axum::extract::State;
 use axum::{
-    extract::Query,
     http::{HeaderMap, Method, StatusCode},
     response::sse::{Event, Sse},
     response::IntoResponse,
     routing::{get, post},
     Json, Router,
 };
-use chrono::Datelike;
 use futures_util::stream::StreamExt;
 use replies::ReplyEngine;
-use serde::{Deserialize, Serialize};
-use serde_json::{json, Value};
-use tokio::fs as afs;
-use tokio::fs::OpenOptions;
-use tokio::io::AsyncWriteExt;
+use serde::Deserialize;
 use tokio::net::TcpListener;
 use tokio_stream::wrappers::IntervalStream;
 use tower_http::cors::{Any, CorsLayer};
@@ -97,36 +113,10 @@ use chacha20poly1305::{
     aead::{Aead, KeyInit},
     Key, XChaCha20Poly1305, XNonce,
 };
-use rand::seq::SliceRandom;
+use exports::resolve_exports_dir;
 use rand::RngCore;
-use std::env;
 use std::path::PathBuf as StdPathBuf;
 
-/// repo root = parent of the `server/` crate dir
-fn repo_root() -> StdPathBuf {
-    let server_dir = StdPathBuf::from(env!("CARGO_MANIFEST_DIR"));
-    server_dir
-        .parent()
-        .map(|p| p.to_path_buf())
-        .unwrap_or(server_dir)
-}
-
-/// Resolve the exports directory:
-/// - if `M3_EXPORTS_DIR` is absolute, use it as-is
-/// - if `M3_EXPORTS_DIR` is relative, resolve from **repo root**
-/// - otherwise, default to `<repo>/exports`
-fn resolve_exports_dir() -> StdPathBuf {
-    if let Ok(val) = std::env::var("M3_EXPORTS_DIR") {
-        let p = StdPathBuf::from(val);
-        return if p.is_absolute() {
-            p
-        } else {
-            repo_root().join(p)
-        };
-    }
-    repo_root().join("exports")
-}
-
 /// Resolve a path from an ENV var relative to an anchor, with a default fallback.
 /// - If ENV is set to an absolute path → use it as-is
 /// - If ENV is set to a relative path → anchor.join(ENV)
@@ -180,27 +170,6 @@ fn open_text(key: &[u8; 32], blob_b64: &str) -> Option<String> {
     String::from_utf8(pt).ok()
 }
 
-// --- panic logs base dir resolver (unified) ---
-/// Base directory for panic logs; unified with CLI so both land under the same `exports/panic`.
-fn panic_dir_base() -> StdPathBuf {
-    resolve_exports_dir().join("panic")
-}
-
-fn default_team_state() -> Value {
-    json!({
-      "members": [
-        {"name":"Raz","energy":70},
-        {"name":"Sawsan","energy":65},
-        {"name":"Nico","energy":60}
-      ],
-      "pillars": {
-        "crown":"watch","void":"watch","play":"good","dragon":"watch","life_force":"watch"
-      },
-      "note": null,
-      "ts": chrono::Utc::now().to_rfc3339()
-    })
-}
-
 /// Shared application state injected into all routes.
 ///
 /// - `db`: tokio-rusqlite wrapper (see `db.rs`)
@@ -235,482 +204,12 @@ fn decrypt_if_needed(key_opt: &Option<[u8; 32]>, privacy: &str, text: String) ->
     }
 }
 
-/// Response payload for panic redirect endpoints.
-/// Carries the chosen micro-protocol plus an optional oracle suggestion and a `logged` flag.
-#[derive(Serialize)]
-struct PanicOut {
-    whisper: String,
-    breath: String,
-    doorway: String,
-    anchor: String,
-    /// Optional oracle suggestion derived from patterns::suggest_bridge
-    suggested_bridge: Option<String>,
-    logged: bool,
-}
-
-/// Input payload for `/panic` (UI trigger). All fields optional; presets apply when `mode` is set.
-#[derive(Deserialize)]
-struct PanicIn {
-    whisper: Option<String>,
-    breath: Option<String>,
-    doorway: Option<String>,
-    anchor: Option<String>,
-    #[serde(default)]
-    mode: Option<String>,
-}
-
-// --- Panic presets: small rotating palettes ---
-const FEAR_VISIBLE_WHISPERS: &[&str] = &[
-    "We can be seen and still be safe.",
-    "Visibility can be gentle; I choose soft edges.",
-    "Eyes on us; breath in us. Safe.",
-];
-const FEAR_VISIBLE_BREATHS: &[&str] = &["box:in4-hold2-out6-hold2 × 4", "in4-hold4-out6 × 4"];
-const FEAR_VISIBLE_DOORWAYS: &[&str] = &[
-    "dim_lights (20%), step back 2m, sip water",
-    "lower_voice, soften_gaze, one sip",
-];
-const FEAR_VISIBLE_ANCHORS: &[&str] = &[
-    "Blend-in posture; sovereignty stays inside.",
-    "I shrink the surface, not the core.",
-];
-
-const DEFAULT_WHISPERS: &[&str] = &[
-    "This is Empire’s choke, not my truth.",
-    "The field is loud; I choose signal.",
-    "Return to center; let noise pass.",
-];
-const DEFAULT_BREATHS: &[&str] = &["double_exhale:in2-out4", "4-6 breath × 6"];
-const DEFAULT_DOORWAYS: &[&str] = &["drink_water", "stand_up + shoulder_roll"];
-const DEFAULT_ANCHORS: &[&str] = &["Flow > Empire.", "Sovereignty first, pace second."];
-
-#[inline]
-fn pick<'a>(xs: &'a [&'a str], tick: i64) -> &'a str {
-    if xs.is_empty() {
-        ""
-    } else {
-        xs[(tick as usize) % xs.len()]
-    }
-}
-
-/// Append a compact, single-line record used by the UI + CLI. Best-effort; errors are swallowed upstream.
-// Async compact logger used by the /panic route (UI).
-async fn log_panic_compact(
-    whisper: &str,
-    breath: &str,
-    doorway: &str,
-    anchor: &str,
-    mode: Option<&str>,
-) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    // .../panic/YYYY-MM/panic-YYYY-MM-DD.log
-    let now = Utc::now();
-    let ym = format!("{:04}-{:02}", now.year(), now.month());
-    let ymd = format!("{:04}-{:02}-{:02}", now.year(), now.month(), now.day());
-    let dir = panic_dir_base().join(ym);
-    let path = dir.join(format!("panic-{}.log", ymd));
-
-    // ensure directory
-    afs::create_dir_all(&dir).await?;
-
-    // structured single line
-    let ts = now.to_rfc3339();
-    let mode_tag = mode.unwrap_or("default");
-    let line = format!(
-        "[{}] [{}] whisper=\"{}\" breath=\"{}\" doorway=\"{}\" anchor=\"{}\"\n",
-        ts, mode_tag, whisper, breath, doorway, anchor
-    );
-
-    let mut file = OpenOptions::new()
-        .create(true)
-        .append(true)
-        .open(&path)
-        .await?;
-    file.write_all(line.as_bytes()).await?;
-    file.flush().await?;
-    Ok(())
-}
-
-// --- add log export for panic runs ---
-// This ensures /panic/run mirrors panic.sh behavior by writing to ./exports/panic/YYYY-MM/panic-YYYY-MM-DD.log
-// so both CLI + UI invocations create the same audit trail.
-fn write_panic_log(out: &PanicOut) {
-    use std::io::Write;
-    let ts = chrono::Utc::now();
-    let dir = panic_dir_base().join(format!("{}", ts.format("%Y-%m")));
-    if let Err(e) = sfs::create_dir_all(&dir) {
-        eprintln!("panic log: mkdir failed: {:?}", e);
-        return;
-    }
-    let path = dir.join(format!("panic-{}.log", ts.format("%Y-%m-%d")));
-    let mut f = match sfs::OpenOptions::new()
-        .create(true)
-        .append(true)
-        .open(&path)
-    {
-        Ok(f) => f,
-        Err(e) => {
-            eprintln!("panic log: open failed: {:?}", e);
-            return;
-        }
-    };
-    let _ = writeln!(
-        f,
-        "🌬️  {}-PANIC REDIRECT ORACLE\n\nwhisper : {}\nbreath  : {}\ndoorway : {}\nanchor  : {}\n\n✅ one redirect step chosen — act now.\n📝  logged to {}\n",
-        std::process::id(),
-        out.whisper,
-        out.breath,
-        out.doorway,
-        out.anchor,
-        path.display()
-    );
-}
-
-/// Payload returned by `/panic/last`
-#[derive(serde::Serialize)]
-struct PanicLast {
-    ts: String,
-    whisper: String,
-    breath: String,
-    doorway: String,
-    anchor: String,
-    path: String,
-}
-
 /// Helper: where exports live (env or default)
 fn exports_base_dir() -> PathBuf {
-    // unify with panic_dir_base()/resolve_exports_dir() so CLI & UI read same logs
+    // same tree as the panic/thanks logs (see exports.rs) so CLI & UI agree
     resolve_exports_dir()
 }
 
-/// Read the newest panic compact log and expose it as JSON (used by the UI "last run" button).
-async fn panic_last() -> Result<Json<PanicLast>, StatusCode> {
-    use tokio::fs;
-    use tokio::io::AsyncReadExt;
-
-    let base = exports_base_dir().join("panic");
-    // Find newest YYYY-MM dir
-    let mut newest_dir: Option<(String, std::time::SystemTime)> = None;
-    let Ok(mut rd) = fs::read_dir(&base).await else {
-        return Err(StatusCode::NOT_FOUND);
-    };
-    while let Ok(Some(e)) = rd.next_entry().await {
-        let name = e.file_name().to_string_lossy().to_string();
-        if name.len() == 7 && name.chars().nth(4) == Some('-') {
-            if let Ok(md) = e.metadata().await {
-                if let Ok(modt) = md.modified() {
-                    if newest_dir.as_ref().map(|(_, t)| modt > *t).unwrap_or(true) {
-                        newest_dir = Some((name, modt));
-                    }
-                }
-            }
-        }
-    }
-    let Some((ym, _)) = newest_dir else {
-        return Err(StatusCode::NOT_FOUND);
-    };
-
-    // Find newest file in newest_dir (panic-YYYY-MM-DD.log)
-    let dir = base.join(&ym);
-    let mut newest_file: Option<(PathBuf, std::time::SystemTime)> = None;
-    let Ok(mut rd2) = fs::read_dir(&dir).await else {
-        return Err(StatusCode::NOT_FOUND);
-    };
-    while let Ok(Some(e)) = rd2.next_entry().await {
-        let path = e.path();
-        if path.extension().and_then(|s| s.to_str()) == Some("log") {
-            if let Ok(md) = e.metadata().await {
-                if let Ok(modt) = md.modified() {
-                    if newest_file.as_ref().map(|(_, t)| modt > *t).unwrap_or(true) {
-                        newest_file = Some((path, modt));
-                    }
-                }
-            }
-        }
-    }
-    let Some((file_path, _)) = newest_file else {
-        return Err(StatusCode::NOT_FOUND);
-    };
-
-    // Read file & take the last non-empty line
-    let mut file = fs::File::open(&file_path)
-        .await
-        .map_err(|_| StatusCode::NOT_FOUND)?;
-    let mut buf = String::new();
-    file.read_to_string(&mut buf)
-        .await
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
-    let last = buf
-        .lines()
-        .rev()
-        .find(|l| !l.trim().is_empty())
-        .ok_or(StatusCode::NOT_FOUND)?;
-
-    // Expected format (from panic.sh & server writer):
-    // [YYYY-MM-DDTHH:MM:SSZ] whisper="..." breath="..." doorway="..." anchor="..."
-    let ts = last
-        .split(']')
-        .next()
-        .unwrap_or("")
-        .trim_start_matches('[')
-        .to_string();
-    let mut whisper = String::new();
-    let mut breath = String::new();
-    let mut doorway = String::new();
-    let mut anchor = String::new();
-    for key in ["whisper", "breath", "doorway", "anchor"] {
-        if let Some(start) = last.find(&format!(r#"{key}=""#)) {
-            let s = start + key.len() + 2;
-            if let Some(end) = last[s..].find('"') {
-                let val = &last[s..s + end];
-                match key {
-                    "whisper" => whisper = val.to_string(),
-                    "breath" => breath = val.to_string(),
-                    "doorway" => doorway = val.to_string(),
-                    _ => anchor = val.to_string(),
-                }
-            }
-        }
-    }
-
-    Ok(Json(PanicLast {
-        ts,
-        whisper,
-        breath,
-        doorway,
-        anchor,
-        path: file_path.to_string_lossy().to_string(),
-    }))
-}
-
-/// Body shape for `/thanks` (gratitude ledger). Minimal fields with optional `who`/`kind`/`details`.
-#[derive(serde::Deserialize)]
-struct GratitudeIn {
-    subject: String,
-    #[serde(default)]
-    details: Option<String>,
-    #[serde(default)]
-    kind: Option<String>, // e.g. "ancestor" | "tool" | "place"
-    #[serde(default)]
-    note_id: Option<i64>, // optional link to a message
-    #[serde(default)]
-    who: Option<String>, // actor/profile
-}
-
-#[derive(serde::Serialize)]
-struct GratitudeOut {
-    id: i64,
-    ts: String,
-    subject: String,
-    details: Option<String>,
-    kind: Option<String>,
-    note_id: Option<i64>,
-    who: Option<String>,
-}
-
-#[derive(serde::Deserialize)]
-struct ThanksQuery {
-    limit: Option<usize>,
-}
-
-async fn ensure_gratitude_schema(db: &Database) -> tokio_rusqlite::Result<()> {
-    // closure returns tokio_rusqlite::Result<()>, `?` on rusqlite ops auto-converts
-    db.0.call(
-        |conn: &mut rusqlite::Connection| -> tokio_rusqlite::Result<()> {
-            use rusqlite::params;
-            conn.execute(
-                "CREATE TABLE IF NOT EXISTS gratitude(
-                id      INTEGER PRIMARY KEY AUTOINCREMENT,
-                ts      TEXT NOT NULL,
-                who     TEXT,
-                subject TEXT NOT NULL,
-                kind    TEXT,
-                note_id INTEGER,
-                details TEXT
-            )",
-                params![],
-            )?;
-            conn.execute(
-                "CREATE INDEX IF NOT EXISTS idx_grat_ts ON gratitude(ts DESC)",
-                params![],
-            )?;
-            Ok(())
-        },
-    )
-    .await
-}
-
-fn thanks_log_line(
-    ts: &str,
-    who: Option<&str>,
-    subject: &str,
-    details: Option<&str>,
-    kind: Option<&str>,
-    note_id: Option<i64>,
-) -> String {
-    let who = who.unwrap_or("-");
-    let kind = kind.unwrap_or("-");
-    let det = details.unwrap_or("-");
-    let nid = note_id.map(|v| v.to_string()).unwrap_or_else(|| "-".into());
-    format!("{ts} who=\"{who}\" kind=\"{kind}\" note_id=\"{nid}\" subject=\"{subject}\" details=\"{det}\"\n")
-}
-
-// --- gratitude_fast: async helper for fast gratitude insertion and log (best-effort) ---
-async fn gratitude_fast(state: &AppState, who: Option<&str>, subject: &str, details: Option<&str>) {
-    let ts = chrono::Utc::now().to_rfc3339();
-    let ts_db = ts.clone();
-    let who_s = who.map(|s| s.to_string());
-    let details_s = details.map(|s| s.to_string());
-    let subject_s = subject.to_string();
-    let kind_s = Some("redirect".to_string());
-    let note_id: Option<i64> = None;
-
-    // DB insert (best-effort)
-    if let Err(e) = state
-        .db
-        .0
-        .call(move |c| {
-            c.execute(
-                "INSERT INTO gratitude(ts,who,subject,kind,note_id,details) VALUES(?,?,?,?,?,?)",
-                rusqlite::params![ts_db, who_s, subject_s, kind_s, note_id, details_s],
-            )?;
-            Ok(())
-        })
-        .await
-    {
-        tracing::warn!(error=?e, "gratitude.fast: insert failed");
-    }
-
-    // Append to thanks log (best-effort)
-    let base = exports_base_dir().join("thanks");
-    let ym = chrono::Utc::now().format("%Y-%m").to_string();
-    let dayf = chrono::Utc::now().format("thanks-%Y-%m-%d.log").to_string();
-    let dir = base.join(ym);
-    let _ = tokio::fs::create_dir_all(&dir).await;
-    let path = dir.join(dayf);
-    let line = thanks_log_line(&ts, who, subject, details, Some("redirect"), None);
-    if let Ok(mut f) = tokio::fs::OpenOptions::new()
-        .create(true)
-        .append(true)
-        .open(&path)
-        .await
-    {
-        let _ = f.write_all(line.as_bytes()).await;
-    }
-}
-
-/// POST /thanks — insert a gratitude row and append a line into `exports/thanks/YYYY-MM/thanks-*.log`.
-async fn thanks_create(
-    State(state): State<AppState>,
-    Json(body): Json<GratitudeIn>,
-) -> Result<Json<GratitudeOut>, (StatusCode, String)> {
-    let ts = Utc::now().to_rfc3339();
-    let subject = body.subject.trim().to_string();
-    if subject.is_empty() {
-        return Err((StatusCode::UNPROCESSABLE_ENTITY, "subject required".into()));
-    }
-
-    // clone fields we’ll need both in DB write and after
-    let who = body.who.clone();
-    let details = body.details.clone();
-    let kind = body.kind.clone();
-    let note_id = body.note_id;
-    let subject_db = subject.clone();
-    let ts_db = ts.clone();
-    let who_db = who.clone();
-    let details_db = details.clone();
-    let kind_db = kind.clone();
-    let note_id_db = note_id;
-
-    // insert row
-    let id_res = state
-        .db
-        .0
-        .call(
-            move |conn: &mut rusqlite::Connection| -> tokio_rusqlite::Result<i64> {
-                use rusqlite::params;
-                conn.execute(
-                "INSERT INTO gratitude(ts,who,subject,kind,note_id,details) VALUES(?,?,?,?,?,?)",
-                params![ts_db, who_db, subject_db, kind_db, note_id_db, details_db],
-            )?;
-                Ok(conn.last_insert_rowid())
-            },
-        )
-        .await
-        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
-
-    // export line (append)
-    let base = exports_base_dir().join("thanks");
-    let ym = Utc::now().format("%Y-%m").to_string();
-    let dayf = Utc::now().format("thanks-%Y-%m-%d.log").to_string();
-    let dir = base.join(ym);
-    let _ = tokio::fs::create_dir_all(&dir).await;
-    let path = dir.join(dayf);
-    let line = thanks_log_line(
-        &ts,
-        who.as_deref(),
-        &subject,
-        details.as_deref(),
-        kind.as_deref(),
-        note_id,
-    );
-    if let Ok(mut f) = tokio::fs::OpenOptions::new()
-        .create(true)
-        .append(true)
-        .open(&path)
-        .await
-    {
-        let _ = f.write_all(line.as_bytes()).await;
-    }
-
-    Ok(Json(GratitudeOut {
-        id: id_res,
-        ts,
-        subject,
-        details: body.details,
-        kind: body.kind,
-        note_id: body.note_id,
-        who: body.who,
-    }))
-}
-
-/// GET /thanks — list recent gratitude rows; `?limit=` clamps to 200.
-async fn thanks_list(
-    State(state): State<AppState>,
-    Query(q): Query<ThanksQuery>,
-) -> Result<Json<Vec<GratitudeOut>>, StatusCode> {
-    let limit = q.limit.unwrap_or(20).min(200) as i64;
-    let rows = state
-        .db
-        .0
-        .call(
-            move |conn: &mut rusqlite::Connection| -> tokio_rusqlite::Result<Vec<GratitudeOut>> {
-                let mut stmt = conn.prepare(
-                    "SELECT id,ts,who,subject,kind,note_id,details
-             FROM gratitude ORDER BY ts DESC LIMIT ?1",
-                )?;
-                let it = stmt.query_map([limit], |r| {
-                    Ok(GratitudeOut {
-                        id: r.get(0)?,
-                        ts: r.get(1)?,
-                        who: r.get(2)?,
-                        subject: r.get(3)?,
-                        kind: r.get(4)?,
-                        note_id: r.get(5)?,
-                        details: r.get(6)?,
-                    })
-                })?;
-                let mut out = Vec::new();
-                for row in it {
-                    out.push(row?);
-                }
-                Ok(out)
-            },
-        )
-        .await
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
-    Ok(Json(rows))
-}
-
 /// POST /reply — thin adapter over `replies::ReplyEngine`; returns 204 when the engine yields nothing.
 async fn reply_handler(
     State(state): State<AppState>,
@@ -736,7 +235,6 @@ async fn main() -> anyhow::Result<()> {
     let _ = ensure_default_thread(&db).await;
     // make sure default profile exists so old rows don't get filtered by the JOIN
     let _ = ensure_profile(&db, "Raz").await;
-    ensure_gratitude_schema(&db).await?;
     let config = Config::from_env();
     let webhook = Webhook::new(config.webhook_url.clone(), config.webhook_secret.clone());
     // spin up the reply engine (reads env: M3_REPLIES_*)
@@ -766,14 +264,6 @@ async fn main() -> anyhow::Result<()> {
         reply_engine,
     };
 
-    #[derive(Deserialize)]
-    struct SetStateRequest {
-        // pass-through merges; keep them flexible
-        members: Option<serde_json::Value>,
-        pillars: Option<serde_json::Value>,
-        note: Option<String>,
-    }
-
     #[derive(Deserialize)]
     struct ReplyPreviewReq {
         input: String,
@@ -789,8 +279,8 @@ async fn main() -> anyhow::Result<()> {
     // POST /status/get, POST /status/set,
     // GET /state/get, POST /state/set,
     // POST /reply, POST /replies/preview,
-    // POST /panic, POST /panic/run, GET /panic/last,
-    // POST /thanks, GET /thanks
+    // /panic/* (panic.rs: runs, presets, history, outcomes),
+    // /thanks/* (gratitude.rs)
     // ============================================================================
     let app = Router::new()
         // --- passphrase / unlock ---
@@ -1286,60 +776,9 @@ async fn main() -> anyhow::Result<()> {
                 }
             }),
         )
-        // --- readiness lights: set ---
-        .route(
-            "/status",
-            post({
-                let state = state.clone();
-                move |Json(req): Json<StatusSet>| async move {
-                    // persist in kv
-                    let k = format!("status:{}", req.name);
-                    let v = req.status.to_string();
-                    let v_for_db = v.clone();
-                    let ts = chrono::Utc::now().to_rfc3339();
-
-                    state.db.0.call(move |c| {
-                        c.execute(
-                            "INSERT OR REPLACE INTO kv(key,value) VALUES(?1,?2)",
-                            rusqlite::params![k, v_for_db],
-                        )?;
-                        Ok(())
-                    }).await.ok();
-
-                    state.bus.publish(&format!("status:{}:{}", req.name, v));
-
-                    Json(serde_json::json!({ "ok": true, "name": req.name, "status": v, "ts": ts }))
-                }
-            }),
-        )
-        // --- readiness lights: get snapshot ---
-        .route(
-            "/status",
-            get({
-                let state = state.clone();
-                move || {
-                    let state = state.clone();
-                    async move {
-                        let rows: Vec<StatusItem> = state.db.0.call(
-                            |c: &mut rusqlite::Connection| -> tokio_rusqlite::Result<Vec<StatusItem>> {
-                                let mut out = Vec::new();
-                                let mut stmt = c.prepare("SELECT key, value FROM kv WHERE key LIKE 'status:%'")?;
-                                let mut it = stmt.query([])?;
-                                while let Some(row) = it.next()? {
-                                    let key: String = row.get(0)?;
-                                    let name = key.trim_start_matches("status:").to_string();
-                                    let status: String = row.get(1)?;
-                                    out.push(StatusItem { name, status, ts: String::new() });
-                                }
-                                Ok(out)
-                            }
-                        ).await.unwrap();
-
-                        Json(rows)
-                    }
-                }
-            }),
-        )
+        // --- readiness lights (compat views over member_status) ---
+        .route("/status", post(status::legacy_set_light).get(status::legacy_list_lights))
+        .route("/status/members", get(status::members).post(status::set_member_http))
         // --- readiness lights: SSE stream ---
         .route(
             "/status/stream",
@@ -1350,216 +789,57 @@ async fn main() -> anyhow::Result<()> {
                     async move {
                         let interval = IntervalStream::new(tokio::time::interval(std::time::Duration::from_millis(500)));
 
-                        let stream = interval.map(move |_| {
-                            let drained = state.bus.drain();
-                            let updates: Vec<_> = drained
-                                .into_iter()
-                                .filter(|e| e.starts_with("status:"))
-                                .map(|e| {
-                                    let mut parts = e.splitn(3, ':');
-                                    let _ = parts.next(); // "status"
+                        // status:* → unnamed JSON array (readiness lights)
+                        // care:*   → named `care` event ("Ana is with you")
+                        // checkin:* → named `checkin` event (panic follow-up prompt)
+                        // energy:*  → named `energy` event (band crossing)
+                        // rhythm:*  → named `rhythm` event (work/rest phase change)
+                        let stream = interval.flat_map(move |_| {
+                            let mut updates = Vec::new();
+                            let mut events = Vec::new();
+                            for e in state.bus.drain() {
+                                if let Some(rest) = e.strip_prefix("status:") {
+                                    let mut parts = rest.splitn(2, ':');
                                     let name = parts.next().unwrap_or("").to_string();
                                     let status = parts.next().unwrap_or("").to_string();
-                                    serde_json::json!({ "name": name, "status": status })
-                                })
-                                .collect();
-
-                            let event = if updates.is_empty() {
-                                Event::default().comment("hb")
-                            } else {
-                                Event::default()
-                                    .data(serde_json::to_string(&updates).unwrap_or_else(|_| "[]".to_string()))
-                            };
-
-                            Ok::<Event, Infallible>(event)
-                        });
-
-                        Sse::new(stream)
-                            .keep_alive(axum::response::sse::KeepAlive::new().interval(std::time::Duration::from_secs(15)).text("ping"))
-                    }
-                }
-            }),
-        )
-        // --- status.get ---
-        .route(
-            "/status/get",
-            post({
-                let state = state.clone();
-                move |_: Json<serde_json::Value>| async move {
-                    // Read current row (direct return)
-                    let (mut color, mut note, mut updated_at, expires_at_opt): (String, String, String, Option<String>) =
-                        state.db.0.call(|c: &mut rusqlite::Connection| -> tokio_rusqlite::Result<(String, String, String, Option<String>)> {
-                            let row = c.query_row(
-                                "SELECT color, note, updated_at, expires_at FROM status WHERE id=1",
-                                [],
-                                |r| Ok::<_, rusqlite::Error>((r.get(0)?, r.get(1)?, r.get(2)?, r.get(3)?)),
-                            )?;
-                            Ok(row)
-                        }).await.unwrap();
-
-                    // Auto-reset if expired
-                    let now = chrono::Utc::now();
-                    let mut expires_at: Option<chrono::DateTime<chrono::Utc>> = None;
-                    if let Some(expires) = expires_at_opt.as_deref() {
-                        match chrono::DateTime::parse_from_rfc3339(expires) {
-                            Ok(ts) => {
-                                let exp = ts.with_timezone(&chrono::Utc);
-                                if now >= exp && color != "green" {
-                                    color = "green".into();
-                                    note.clear();
-                                    updated_at = now.to_rfc3339();
-                                    let updated_at_db = updated_at.clone();
-                                    state.db.0.call(move |c: &mut rusqlite::Connection| {
-                                        c.execute(
-                                            "UPDATE status SET color='green', note='', updated_at=?1, expires_at=NULL WHERE id=1",
-                                            rusqlite::params![updated_at_db],
-                                        )?;
-                                        Ok(())
-                                    }).await.ok();
-                                    expires_at = None;
-                                } else {
-                                    expires_at = Some(exp);
+                                    updates.push(serde_json::json!({ "name": name, "status": status }));
+                                } else if let Some(json) = e.strip_prefix(hooks::CARE_TOPIC) {
+                                    events.push(Event::default().event("care").data(json));
+                                } else if let Some(json) = e.strip_prefix(followup::CHECKIN_TOPIC) {
+                                    events.push(Event::default().event("checkin").data(json));
+                                } else if let Some(json) = e.strip_prefix(transitions::TOPIC) {
+                                    events.push(Event::default().event("energy").data(json));
+                                } else if let Some(json) = e.strip_prefix(rhythm_clock::TOPIC) {
+                                    events.push(Event::default().event("rhythm").data(json));
                                 }
                             }
-                            Err(e) => {
-                                tracing::warn!("Invalid expires_at '{}': {:?}", expires, e);
-                                expires_at = None;
-                            }
-                        }
-                    }
-
-                    // Parse updated_at robustly; default to now if missing/invalid
-                    let updated_at_dt = match chrono::DateTime::parse_from_rfc3339(&updated_at) {
-                        Ok(ts) => ts.with_timezone(&chrono::Utc),
-                        Err(e) => {
-                            tracing::warn!("status.get: invalid updated_at '{}': {:?} — defaulting to now()", updated_at, e);
-                            chrono::Utc::now()
-                        }
-                    };
 
-                    Json(models::StatusGetResponse {
-                        color,
-                        note,
-                        updated_at: updated_at_dt,
-                        expires_at,
-                    })
-                }
-            }),
-        )
-        // --- status.set ---
-        .route(
-            "/status/set",
-            post({
-                let state = state.clone();
-                move |Json(req): Json<models::StatusSetRequest>| async move {
-                    let now = chrono::Utc::now();
-                    let ttl = req.ttl_minutes;
-                    let expires_at = ttl.map(|m| (now + chrono::Duration::minutes(m)).to_rfc3339());
-                    let note = req.note.unwrap_or_default();
-                    let color = req.color.clone(); // keep a copy for webhook after the DB write
-
-                    // DB write (use clones inside the closure)
-                    let ts_str = now.to_rfc3339();
-                    let color_db = color.clone();
-                    let note_db = note.clone();
-                    let expires_db = expires_at.clone();
-                    state
-                        .db
-                        .0
-                        .call(move |c: &mut rusqlite::Connection| -> tokio_rusqlite::Result<()> {
-                            c.execute(
-                                "UPDATE status SET color=?1, note=?2, updated_at=?3, expires_at=?4 WHERE id=1",
-                                rusqlite::params![color_db, note_db, ts_str, expires_db],
-                            )?;
-                            Ok(())
-                        })
-                        .await
-                        .unwrap();
-
-                    // Fire webhook (best-effort)
-                    let payload = serde_json::json!({
-                        "event": "status.set",
-                        "status": color,             // "green" | "yellow" | "red"
-                        "note": note,
-                        "updated_at": now.to_rfc3339(),
-                        "ttl_minutes": ttl
-                    });
-                    let _ = state.webhook.send("status.set", &payload).await;
-
-                    // Respond
-                    Json(models::StatusOk { ok: true })
-                }
-            }),
-        )
-        // --- dashboard state ---
-        .route(
-            "/state/get",
-            get({
-                let state = state.clone();
-                move || {
-                    let state = state.clone();
-                    async move {
-                        let v: serde_json::Value = state.db.0.call(
-                            |c: &mut rusqlite::Connection| -> tokio_rusqlite::Result<serde_json::Value> {
-                                let r: rusqlite::Result<Vec<u8>> = c.query_row(
-                                    "SELECT value FROM kv WHERE key='dashboard_state'",
-                                    [],
-                                    |row| row.get(0),
+                            if !updates.is_empty() {
+                                events.insert(
+                                    0,
+                                    Event::default()
+                                        .data(serde_json::to_string(&updates).unwrap_or_else(|_| "[]".to_string())),
                                 );
-                                let v = r
-                                    .ok()
-                                    .and_then(|b| serde_json::from_slice::<serde_json::Value>(&b).ok())
-                                    .unwrap_or_else(default_team_state);
-                                Ok(v)
                             }
-                        ).await.unwrap();
-
-                        Json(v)
-                    }
-                }
-            }),
-        )
-        .route(
-            "/state/set",
-            post({
-                let state = state.clone();
-                move |Json(req): Json<SetStateRequest>| async move {
-                    let now = chrono::Utc::now().to_rfc3339();
-                    let v: Value = state.db.0.call(
-                        move |c: &mut rusqlite::Connection| -> tokio_rusqlite::Result<Value> {
-                            // load current
-                            let cur: Value = c
-                                .query_row(
-                                    "SELECT value FROM kv WHERE key='dashboard_state'",
-                                    [],
-                                    |row| row.get::<_, Vec<u8>>(0),
-                                )
-                                .ok()
-                                .and_then(|b| serde_json::from_slice::<Value>(&b).ok())
-                                .unwrap_or_else(default_team_state);
-
-                            // merge
-                            let mut next = cur.clone();
-                            if let Some(ms) = req.members { next["members"] = serde_json::to_value(ms).unwrap(); }
-                            if let Some(ps) = req.pillars { next["pillars"] = serde_json::to_value(ps).unwrap(); }
-                            if let Some(n) = req.note { next["note"] = Value::String(n); }
-                            next["ts"] = Value::String(now.clone());
-
-                            // save
-                            let blob = serde_json::to_vec(&next).unwrap();
-                            c.execute(
-                                "INSERT OR REPLACE INTO kv(key,value) VALUES('dashboard_state',?)",
-                                rusqlite::params![blob],
-                            )?;
+                            if events.is_empty() {
+                                events.push(Event::default().comment("hb"));
+                            }
 
-                            Ok(next)
-                        }
-                    ).await.unwrap();
+                            futures_util::stream::iter(events.into_iter().map(Ok::<Event, Infallible>))
+                        });
 
-                    Json(v)
+                        Sse::new(stream)
+                            .keep_alive(axum::response::sse::KeepAlive::new().interval(std::time::Duration::from_secs(15)).text("ping"))
+                    }
                 }
             }),
         )
+        // --- readiness history + time-in-state ---
+        .route("/status/history", get(status::history))
+        .route("/status/summary", get(status::summary))
+        // --- team light (compat: member "team") ---
+        .route("/status/get", post(status::legacy_get_team))
+        .route("/status/set", post(status::legacy_set_team))
         // --- replies ---
         .route("/reply", post(reply_handler))
         .route(
@@ -1575,253 +855,37 @@ async fn main() -> anyhow::Result<()> {
                 }
             }),
         )
-        // --- panic (compact, UI; accepts optional body & mode) ---
-        .route(
-            "/panic",
-            post({
-                let _state = state.clone();
-                move |State(state): State<AppState>, Json(mut body): Json<PanicIn>| async move {
-                    // presets only if mode provided *and* all steps empty
-                    let all_empty = body.whisper.as_deref().unwrap_or("").is_empty()
-                        && body.breath.as_deref().unwrap_or("").is_empty()
-                        && body.doorway.as_deref().unwrap_or("").is_empty()
-                        && body.anchor.as_deref().unwrap_or("").is_empty();
-
-                    if body.mode.is_some() && all_empty {
-                        // rotate by time (no RNG needed here)
-                        let tick = chrono::Utc::now().timestamp();
-                        match body.mode.as_deref().unwrap_or("default") {
-                            "fearVisible" | "fear-visible" => {
-                                body.whisper = Some(pick(FEAR_VISIBLE_WHISPERS, tick).to_string());
-                                body.breath  = Some(pick(FEAR_VISIBLE_BREATHS,   tick).to_string());
-                                body.doorway = Some(pick(FEAR_VISIBLE_DOORWAYS,  tick).to_string());
-                                body.anchor  = Some(pick(FEAR_VISIBLE_ANCHORS,   tick).to_string());
-                            }
-                            _ => {
-                                body.whisper = Some(pick(DEFAULT_WHISPERS, tick).to_string());
-                                body.breath  = Some(pick(DEFAULT_BREATHS,  tick).to_string());
-                                body.doorway = Some(pick(DEFAULT_DOORWAYS, tick).to_string());
-                                body.anchor  = Some(pick(DEFAULT_ANCHORS,  tick).to_string());
-                            }
-                        }
-                    }
-
-                    // fill defaults if missing
-                    let whisper = body.whisper.unwrap_or_else(|| "This is Empire’s choke, not my truth.".into());
-                    let breath  = body.breath .unwrap_or_else(|| "double_exhale:in2-out4".into());
-                    let doorway = body.doorway.unwrap_or_else(|| "drink_water".into());
-                    let anchor  = body.anchor .unwrap_or_else(|| "Flow > Empire.".into());
-                    let mode    = body.mode.as_deref();
-
-                    // derive a bridge suggestion from patterns (mirror emotion intensity semantics)
-                    let (bridge_kind, bridge_intensity) = match mode {
-                        Some("fearVisible") | Some("fear-visible") => ("panic", 0.65f32),
-                        _ => ("anxiety", 0.55f32),
-                    };
-                    let suggested_bridge: Option<String> = Some(crate::patterns::suggest_bridge(bridge_kind, bridge_intensity).pattern.to_string());
-
-                    // write compact line (async, best-effort)
-                    let mut logged = false;
-                    if log_panic_compact(&whisper, &breath, &doorway, &anchor, mode).await.is_ok() {
-                        logged = true;
-                    }
-
-                    // 1) Tell for traceability (log error on failure)
-                    {
-                        let node = "panic".to_string();
-                        let pre = format!("whisper:{} | breath:{}", whisper, breath);
-                        let act = format!("doorway:{} | anchor:{}", doorway, anchor);
-                        let ts = chrono::Utc::now().to_rfc3339();
-                        if let Err(e) = state.db.0.call(move |c| {
-                            c.execute(
-                                "INSERT INTO tells(node,pre_activation,action,created_at) VALUES(?,?,?,?)",
-                                rusqlite::params![node, pre, act, ts],
-                            )?;
-                            Ok(())
-                        }).await {
-                            tracing::warn!(error = ?e, "panic.ui: failed to insert tell");
-                        }
-                    }
-
-                    // 2) Log an emotion row (fear/anxiety) so EmotionalOS sees the event (log error on failure)
-                    {
-                        let ts = chrono::Utc::now().to_rfc3339();
-                        let who = "Raz".to_string();
-                        let (kind, intensity): (String, f32) = match mode {
-                            Some("fearVisible") | Some("fear-visible") => ("fear".into(), 0.65),
-                            _ => ("anxiety".into(), 0.55),
-                        };
-                        let note = format!("panic ui: {} | {} | {}", whisper, breath, doorway);
-                        if let Err(e) = state.db.0.call(move |c| {
-                            c.execute(
-                                "INSERT INTO emotions(ts, who, kind, intensity, note) VALUES(?,?,?,?,?)",
-                                rusqlite::params![ts, who, kind, intensity, note],
-                            )?;
-                            Ok(())
-                        }).await {
-                            tracing::warn!(error = ?e, "panic.ui: failed to insert emotion");
-                        }
-                    }
-
-                    // 3) Auto-bridge readiness: yellow → green (kv + bus, log error on failure)
-                    {
-                        if let Err(e) = state.db.0.call(|c| {
-                            c.execute(
-                                "INSERT OR REPLACE INTO kv(key,value) VALUES('status:main','green')",
-                                [],
-                            )?;
-                            Ok(())
-                        }).await {
-                            tracing::warn!(error = ?e, "panic.ui: failed to set readiness kv");
-                        }
-                        state.bus.publish("status:main:green");
-                        // Land gratitude for redirect
-                        gratitude_fast(
-                            &state,
-                            Some("Raz"),
-                            &format!("I chose {}", doorway),
-                            Some(&format!("anchor: {}; whisper: {}", anchor, whisper)),
-                        ).await;
-                    }
-
-                    // 4) webhook (log error on failure)
-                    if let Err(e) = state.webhook.send("panic.ui", &serde_json::json!({
-                        "event": "panic.ui",
-                        "payload": { "whisper": whisper, "breath": breath, "doorway": doorway, "anchor": anchor, "suggested_bridge": suggested_bridge.clone() },
-                        "ts": chrono::Utc::now().to_rfc3339(),
-                    })).await {
-                        tracing::warn!(error = ?e, "panic.ui: webhook failed");
-                    }
-
-                    Json(PanicOut { whisper, breath, doorway, anchor, suggested_bridge, logged })
-                }
-            }),
-        )
-        // --- panic redirect oracle (POST /panic/run) ---
-        .route(
-            "/panic/run",
-            post({
-                let state = state.clone();
-                move || {
-                    let state = state.clone();
-                    async move {
-                        // 1) choose small, safe defaults
-                        let whispers = [
-                            "This is Empire’s choke, not my truth.",
-                            "Pause. Presence first, problems after.",
-                            "I don’t owe panic my attention.",
-                        ];
-                        let breaths = [
-                            "double_exhale:in2-out4",
-                            "box:in4-hold4-out4-hold2",
-                            "phys_sigh:inhale+top-up, slow exhale",
-                        ];
-                        let doorways = [
-                            "drink_water",
-                            "stand_and_stretch",
-                            "cold_splash",
-                            "step_outside",
-                        ];
-                        let anchors = [
-                            "Flow > Empire.",
-                            "Sovereignty over spectacle.",
-                            "One true next action.",
-                        ];
-
-                        let whisper = whispers.choose(&mut rand::thread_rng()).unwrap().to_string();
-                        let breath  = breaths .choose(&mut rand::thread_rng()).unwrap().to_string();
-                        let doorway = doorways.choose(&mut rand::thread_rng()).unwrap().to_string();
-                        let anchor  = anchors .choose(&mut rand::thread_rng()).unwrap().to_string();
-                        // derive suggestion for a generic panic run (intensity ~0.6)
-                        let suggested_bridge: Option<String> = Some(crate::patterns::suggest_bridge("panic", 0.6).pattern.to_string());
-                        // write log once (sync), then mark logged=true
-                        let mut out = PanicOut { whisper, breath, doorway, anchor, suggested_bridge, logged: false };
-                        write_panic_log(&out);
-                        out.logged = true;
-
-                        // 2) log to DB as a Tell (log error on failure)
-                        let node = "panic".to_string();
-                        let pre = format!("whisper:{} | breath:{}", out.whisper, out.breath);
-                        let act = format!("doorway:{} | anchor:{}", out.doorway, out.anchor);
-                        let ts = chrono::Utc::now().to_rfc3339();
-                        if let Err(e) = state.db.0.call(move |c| {
-                            c.execute(
-                                "INSERT INTO tells(node,pre_activation,action,created_at) VALUES(?,?,?,?)",
-                                rusqlite::params![node, pre, act, ts],
-                            )?;
-                            Ok(())
-                        }).await {
-                            tracing::warn!(error = ?e, "panic.run: failed to insert tell");
-                        }
-
-                        // emotions: log fear event so EmotionalOS can reflect the redirect (log error on failure)
-                        {
-                            let ts = chrono::Utc::now().to_rfc3339();
-                            let who = "Raz".to_string();
-                            let kind = "fear".to_string();
-                            let intensity = 0.6f32;
-                            let note = format!("panic run: {} | {} | {}", out.whisper, out.breath, out.doorway);
-                            if let Err(e) = state.db.0.call(move |c| {
-                                c.execute(
-                                    "INSERT INTO emotions(ts, who, kind, intensity, note) VALUES(?,?,?,?,?)",
-                                    rusqlite::params![ts, who, kind, intensity, note],
-                                )?;
-                                Ok(())
-                            }).await {
-                                tracing::warn!(error = ?e, "panic.run: failed to insert emotion");
-                            }
-                        }
-
-                        // readiness: auto-bridge to green (log error on failure)
-                        {
-                            if let Err(e) = state.db.0.call(|c| {
-                                c.execute(
-                                    "INSERT OR REPLACE INTO kv(key,value) VALUES('status:main','green')",
-                                    [],
-                                )?;
-                                Ok(())
-                            }).await {
-                                tracing::warn!(error = ?e, "panic.run: failed to set readiness kv");
-                            }
-                            state.bus.publish("status:main:green");
-                            // Land gratitude for redirect
-                            gratitude_fast(
-                                &state,
-                                Some("Raz"),
-                                &format!("I chose {}", out.doorway),
-                                Some(&format!("anchor: {}; whisper: {}", out.anchor, out.whisper)),
-                            ).await;
-                        }
-
-                        // 3) (optional) webhook (log error on failure)
-                        if let Err(e) = state.webhook.send("panic.run", &serde_json::json!({
-                            "event": "panic.run",
-                            "payload": out,
-                            "ts": chrono::Utc::now().to_rfc3339(),
-                        })).await {
-                            tracing::warn!(error = ?e, "panic.run: webhook failed");
-                        }
-
-                        Json(out)
-                    }
-                }
-            }),
-        )
-        .route("/panic/last", get(panic_last))
-        .route("/thanks", post(thanks_create).get(thanks_list));
+        .nest("/panic", panic::router())
+        .nest("/thanks", gratitude::router())
+        .nest("/privacy", privacy::router());
 
     // ---- CORS ----
     let cors = CorsLayer::new()
         .allow_origin(Any)
-        .allow_methods([Method::GET, Method::POST, Method::OPTIONS])
+        .allow_methods([
+            Method::GET,
+            Method::POST,
+            Method::PATCH,
+            Method::DELETE,
+            Method::OPTIONS,
+        ])
+        .expose_headers([axum::http::header::ETAG])
         .allow_headers(Any);
 
     // attach state after nesting emotions, patterns, energy, rhythm (apply CORS last so it covers nested routes)
     // Nested routers mounted under prefixes (see their modules):
-    // /emotions, /patterns, /energy, /rhythm, /tells, /timeline, /cycles, /value, /towns
+    // /emotions, /arcs, /consciousness, /patterns, /bridges, /energy, /rhythm, /tells, /timeline,
+    // /cycles, /value, /towns
     let app = app
+        .nest("/state", dashboard::router())
+        .nest("/team", team::router())
+        .nest("/consent", relational::router())
+        .nest("/hooks", hooks::router())
         .nest("/emotions", emotions::router())
+        .nest("/arcs", arcs::router())
+        .nest("/consciousness", consciousness::router())
         .nest("/patterns", patterns::router())
+        .nest("/bridges", bridges::router())
         .nest("/energy", energy::router())
         .nest("/rhythm", rhythm::router())
         .nest("/tells", tells::router())
@@ -1833,6 +897,8 @@ async fn main() -> anyhow::Result<()> {
         .with_state(state.clone());
 
     // ---- serve ----
+    spawn_background(&state);
+
     let listener = TcpListener::bind(&state.config.bind).await?;
     tracing::info!("listening on {}", state.config.bind);
 
@@ -1842,6 +908,15 @@ async fn main() -> anyhow::Result<()> {
     Ok(())
 }
 
+/// The sweepers that run next to the server; same set as `spawn_background` in lib.rs.
+fn spawn_background(state: &AppState) {
+    // readiness TTLs expire on time, not on the next read
+    status::spawn_expiry(state.clone());
+    followup::spawn(state.clone());
+    transitions::spawn(state.clone());
+    rhythm_clock::spawn(state.clone());
+}
+
 /// Ensure a profile row exists for `name` and return its id. Hot-path safe (no races with UNIQUE constraint).
 // ensure a profile name exists, return id
 async fn ensure_profile(db: &Database, name: &str) -> i64 {
@@ -2155,6 +1230,21 @@ async fn import_openai_folder(
     Ok((imported_total, titles_imported))
 }
 
+/// In-memory state with the full schema, for unit tests of modules shared with the lib.
+#[cfg(test)]
+async fn test_state() -> AppState {
+    let conn = tokio_rusqlite::Connection::open_in_memory().await.unwrap();
+    conn.call(db::ensure_schema).await.unwrap();
+    AppState {
+        db: Database(conn),
+        bus: Bus::default(),
+        key: Arc::new(Mutex::new(None)),
+        config: Config::from_env(),
+        webhook: Webhook::new(None, None),
+        reply_engine: ReplyEngine::from_env(),
+    }
+}
+
 #[cfg(test)]
 mod integration {
     use super::*;
@@ -2168,7 +1258,7 @@ mod integration {
     async fn make_state_for_test() -> AppState {
         let conn = AsyncConnection::open_in_memory().await.unwrap();
         // initialize full schema on the raw connection
-        conn.call(|c| crate::db::ensure_schema(c)).await.unwrap();
+        conn.call(crate::db::ensure_schema).await.unwrap();
 
         let db = Database(conn);
         AppState {
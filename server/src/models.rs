@@ -195,25 +195,20 @@ pub struct StatusOk {
     pub ok: bool,
 }
 
-/// Lightweight member energy reading (experimental).
-#[allow(dead_code)]
-#[derive(Debug, Serialize, Deserialize, Clone)]
+/// Member energy reading on the dashboard (names come from the team roster).
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct MemberEnergy {
     pub name: String,
     pub energy: i32, // 0..100
+    /// Filled from the roster on reads; ignored on writes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub avatar: Option<String>,
 }
 
-/// Coarse state for core pillars (experimental; subject to change).
-#[allow(dead_code)]
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct PillarStatus {
-    // values: "good" | "watch" | "rest"
-    pub crown: String,
-    pub void: String,
-    pub play: String,
-    pub dragon: String,
-    pub life_force: String,
-}
+/// Pillar key → value. Keys and allowed values are defined in the `pillars`
+/// table (see `team.rs`); the seed set is crown, void, play, dragon, life_force
+/// with values "good" | "watch" | "rest".
+pub type PillarStatus = std::collections::BTreeMap<String, String>;
 
 /// Aggregate team snapshot with members + pillars.
 #[allow(dead_code)]
@@ -225,12 +220,14 @@ pub struct TeamState {
     pub ts: String,
 }
 
-/// Partial update for team state (patch semantics).
-#[allow(dead_code)]
+/// Partial update for team state (patch semantics; pillars merge by key).
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct SetStateRequest {
+    #[serde(default)]
     pub members: Option<Vec<MemberEnergy>>,
+    #[serde(default)]
     pub pillars: Option<PillarStatus>,
+    #[serde(default)]
     pub note: Option<String>,
 }
 
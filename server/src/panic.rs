@@ -0,0 +1,2250 @@
+//! Panic — presets, runs, outcomes and effectiveness
+//! -------------------------------------------------
+//! Whisper: "the doorway that works for you is the one we keep." 🚪
+//!
+//! Presets: each mode is a row in `panic_presets`: its own whispers, breaths, doorways
+//! and anchors, the emotion it records (kind + intensity), the bridge kind used for the
+//! oracle suggestion, and a rotation policy:
+//!
+//!   • `tick`   — index by the current unix second (deterministic, no RNG)
+//!   • `random` — uniform pick per step
+//!   • `lru`    — least-recently-used value per step (never-used first, list order)
+//!   • `effective` — the value with the biggest recorded intensity drop for this person;
+//!                   values without an outcome yet are tried first (in `lru` order)
+//!
+//! Seeds (once, see `db::ensure_schema`): `fearVisible` and `default` from the old
+//! compiled palettes, `run` from the arrays `/panic/run` used inline.
+//!
+//! Events: every run is stored in `panic_events` with the steps it chose, the suggested
+//! bridge and the ids of the tell / emotion / gratitude / relational rows it wrote.
+//!
+//! Service: `run()` is what POST /panic and POST /panic/run both call. It fills the blank
+//! steps from the preset and writes the tell, the emotion, `main` → green (through
+//! `status::write_member`), the gratitude row and the event in one transaction, and
+//! returns every id as `Artifacts`. The actor comes from the request (`who`), then the
+//! `x-m3-actor` header, then `Raz`. The bus, the relational invitation and the log
+//! exports run after commit.
+//!
+//! Outcomes (`M3_PANIC_CHECKIN_MIN`, default 20; 0 = off): runs get a `checkin_due_at`;
+//! the binary's sweeper emits a `panic.checkin` prompt once it passes (see
+//! `followup.rs`). The before/after answer feeds the effectiveness ranking and the
+//! `effective` rotation.
+//!
+//! Endpoints
+//!   POST   /panic                     → UI run { who?, mode?, rotation?, whisper?, ... }
+//!   POST   /panic/run                 → oracle run ?who=&mode=&rotation=
+//!   GET    /panic/last                → newest run, else the newest compact log
+//!   GET    /panic/presets             → all modes
+//!   POST   /panic/presets             → create { mode, whispers, breaths, doorways, anchors, ... }
+//!   GET    /panic/presets/:mode       → one mode
+//!   PATCH  /panic/presets/:mode       → partial update
+//!   DELETE /panic/presets/:mode       → remove (the `default` mode is kept)
+//!   GET    /panic/history             → { items, next_before, stats } (filters: who, mode,
+//!                                       source, doorway, since, until; cursor: before)
+//!   POST   /panic/:id/outcome         → { after, before?, note? } (0..1) → event + drop
+//!   GET    /panic/effectiveness       → per person: breaths / doorways / anchors /
+//!                                       whispers / bridges ranked by average drop
+//!                                       (`?who=&min_samples=`)
+//!
+//! Notes
+//!   • Mode lookup ignores case, `-` and `_` (`fear-visible` finds `fearVisible`).
+//!   • Every pick bumps `panic_preset_usage`, whatever the policy, so `lru` stays fair
+//!     when a mode switches policy.
+
+use crate::AppState;
+use axum::{
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    routing::{get, post},
+    Json, Router,
+};
+use rand::seq::SliceRandom;
+use rusqlite::OptionalExtension;
+use serde::{Deserialize, Serialize};
+
+/// Mode used when none is given or the requested one is unknown.
+pub const DEFAULT_MODE: &str = "default";
+
+/// Mode used by `POST /panic/run`.
+pub const RUN_MODE: &str = "run";
+
+/// Seed rows: (mode, label, whispers, breaths, doorways, anchors, emotion kind,
+/// intensity, bridge kind, rotation).
+type Seed<'a> = (
+    &'a str,
+    &'a str,
+    &'a [&'a str],
+    &'a [&'a str],
+    &'a [&'a str],
+    &'a [&'a str],
+    &'a str,
+    f64,
+    &'a str,
+    &'a str,
+);
+
+const SEEDS: &[Seed<'static>] = &[
+    (
+        "fearVisible",
+        "Fear of being visible",
+        &[
+            "We can be seen and still be safe.",
+            "Visibility can be gentle; I choose soft edges.",
+            "Eyes on us; breath in us. Safe.",
+        ],
+        &["box:in4-hold2-out6-hold2 × 4", "in4-hold4-out6 × 4"],
+        &[
+            "dim_lights (20%), step back 2m, sip water",
+            "lower_voice, soften_gaze, one sip",
+        ],
+        &[
+            "Blend-in posture; sovereignty stays inside.",
+            "I shrink the surface, not the core.",
+        ],
+        "fear",
+        0.65,
+        "panic",
+        "tick",
+    ),
+    (
+        DEFAULT_MODE,
+        "Default",
+        &[
+            "This is Empire’s choke, not my truth.",
+            "The field is loud; I choose signal.",
+            "Return to center; let noise pass.",
+        ],
+        &["double_exhale:in2-out4", "4-6 breath × 6"],
+        &["drink_water", "stand_up + shoulder_roll"],
+        &["Flow > Empire.", "Sovereignty first, pace second."],
+        "anxiety",
+        0.55,
+        "anxiety",
+        "tick",
+    ),
+    (
+        RUN_MODE,
+        "Redirect oracle",
+        &[
+            "This is Empire’s choke, not my truth.",
+            "Pause. Presence first, problems after.",
+            "I don’t owe panic my attention.",
+        ],
+        &[
+            "double_exhale:in2-out4",
+            "box:in4-hold4-out4-hold2",
+            "phys_sigh:inhale+top-up, slow exhale",
+        ],
+        &[
+            "drink_water",
+            "stand_and_stretch",
+            "cold_splash",
+            "step_outside",
+        ],
+        &[
+            "Flow > Empire.",
+            "Sovereignty over spectacle.",
+            "One true next action.",
+        ],
+        "fear",
+        0.6,
+        "panic",
+        "random",
+    ),
+];
+
+/// Insert the seed presets (idempotent; called once from `db::ensure_schema`).
+pub fn seed(c: &rusqlite::Connection) -> rusqlite::Result<()> {
+    let now = chrono::Utc::now().to_rfc3339();
+    for (mode, label, w, b, d, a, kind, intensity, bridge, rotation) in SEEDS {
+        c.execute(
+            "INSERT OR IGNORE INTO panic_presets(mode, label, whispers, breaths, doorways, anchors,
+               emotion_kind, intensity, bridge_kind, rotation, updated_at)
+             VALUES(?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            rusqlite::params![
+                mode,
+                label,
+                serde_json::to_string(w).unwrap_or_default(),
+                serde_json::to_string(b).unwrap_or_default(),
+                serde_json::to_string(d).unwrap_or_default(),
+                serde_json::to_string(a).unwrap_or_default(),
+                kind,
+                intensity,
+                bridge,
+                rotation,
+                now
+            ],
+        )?;
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Rotation {
+    Tick,
+    Random,
+    Lru,
+    Effective,
+}
+
+impl Rotation {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "tick" | "time" => Some(Self::Tick),
+            "random" | "rand" => Some(Self::Random),
+            "lru" => Some(Self::Lru),
+            "effective" | "best" => Some(Self::Effective),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Tick => "tick",
+            Self::Random => "random",
+            Self::Lru => "lru",
+            Self::Effective => "effective",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct Preset {
+    pub mode: String,
+    pub label: String,
+    pub whispers: Vec<String>,
+    pub breaths: Vec<String>,
+    pub doorways: Vec<String>,
+    pub anchors: Vec<String>,
+    pub emotion_kind: String,
+    pub intensity: f64,
+    pub bridge_kind: String,
+    pub rotation: Rotation,
+    pub updated_at: String,
+}
+
+const PRESET_COLS: &str = "mode, label, whispers, breaths, doorways, anchors, emotion_kind, \
+                           intensity, bridge_kind, rotation, updated_at";
+
+fn list_col(r: &rusqlite::Row, i: usize) -> rusqlite::Result<Vec<String>> {
+    let raw: String = r.get(i)?;
+    Ok(serde_json::from_str(&raw).unwrap_or_default())
+}
+
+fn row_to_preset(r: &rusqlite::Row) -> rusqlite::Result<Preset> {
+    let rotation: String = r.get(9)?;
+    Ok(Preset {
+        mode: r.get(0)?,
+        label: r.get(1)?,
+        whispers: list_col(r, 2)?,
+        breaths: list_col(r, 3)?,
+        doorways: list_col(r, 4)?,
+        anchors: list_col(r, 5)?,
+        emotion_kind: r.get(6)?,
+        intensity: r.get(7)?,
+        bridge_kind: r.get(8)?,
+        rotation: Rotation::parse(&rotation).unwrap_or(Rotation::Tick),
+        updated_at: r.get(10)?,
+    })
+}
+
+fn fold(mode: &str) -> String {
+    mode.chars()
+        .filter(|c| *c != '-' && *c != '_')
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+pub fn list(c: &rusqlite::Connection) -> rusqlite::Result<Vec<Preset>> {
+    let mut stmt = c.prepare(&format!(
+        "SELECT {PRESET_COLS} FROM panic_presets ORDER BY mode"
+    ))?;
+    let rows = stmt
+        .query_map([], row_to_preset)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(rows)
+}
+
+/// Find a mode by name (exact first, then ignoring case / `-` / `_`).
+pub fn find(c: &rusqlite::Connection, mode: &str) -> rusqlite::Result<Option<Preset>> {
+    let exact = c
+        .query_row(
+            &format!("SELECT {PRESET_COLS} FROM panic_presets WHERE mode = ?1"),
+            [mode],
+            row_to_preset,
+        )
+        .optional()?;
+    if exact.is_some() {
+        return Ok(exact);
+    }
+    let want = fold(mode);
+    Ok(list(c)?.into_iter().find(|p| fold(&p.mode) == want))
+}
+
+/// Preset for `mode`, falling back to `default` when none is given or it is unknown.
+pub fn resolve(c: &rusqlite::Connection, mode: Option<&str>) -> rusqlite::Result<Option<Preset>> {
+    if let Some(p) = mode.map(|m| find(c, m)).transpose()?.flatten() {
+        return Ok(Some(p));
+    }
+    find(c, DEFAULT_MODE)
+}
+
+/// One concrete redirect picked from a preset.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct Choice {
+    pub mode: String,
+    pub whisper: String,
+    pub breath: String,
+    pub doorway: String,
+    pub anchor: String,
+    pub emotion_kind: String,
+    pub intensity: f64,
+    pub bridge_kind: String,
+}
+
+#[inline]
+fn pick_tick(xs: &[String], tick: i64) -> String {
+    if xs.is_empty() {
+        String::new()
+    } else {
+        xs[tick.rem_euclid(xs.len() as i64) as usize].clone()
+    }
+}
+
+/// Least-recently-used value for a step; never-used values win in list order.
+fn pick_lru(
+    c: &rusqlite::Connection,
+    mode: &str,
+    slot: &str,
+    xs: &[String],
+) -> rusqlite::Result<String> {
+    let mut best: Option<(Option<String>, &String)> = None;
+    for x in xs {
+        let last: Option<String> = c
+            .query_row(
+                "SELECT last_used_at FROM panic_preset_usage WHERE mode=?1 AND slot=?2 AND value=?3",
+                rusqlite::params![mode, slot, x],
+                |r| r.get(0),
+            )
+            .optional()?;
+        let older = match (&best, &last) {
+            (None, _) => true,
+            (Some((Some(_), _)), None) => true,
+            (Some((Some(b), _)), Some(l)) => l < b,
+            (Some((None, _)), _) => false,
+        };
+        if older {
+            best = Some((last, x));
+        }
+    }
+    Ok(best.map(|(_, x)| x.clone()).unwrap_or_default())
+}
+
+fn touch(
+    c: &rusqlite::Connection,
+    mode: &str,
+    slot: &str,
+    value: &str,
+    now: &str,
+) -> rusqlite::Result<()> {
+    c.execute(
+        "INSERT INTO panic_preset_usage(mode, slot, value, uses, last_used_at) VALUES(?1, ?2, ?3, 1, ?4)
+         ON CONFLICT(mode, slot, value) DO UPDATE SET uses = uses + 1, last_used_at = excluded.last_used_at",
+        rusqlite::params![mode, slot, value, now],
+    )?;
+    Ok(())
+}
+
+/// `effective`: untried values first (LRU among them), else the best average drop for `who`.
+fn pick_effective(
+    c: &rusqlite::Connection,
+    mode: &str,
+    who: Option<&str>,
+    slot: &str,
+    xs: &[String],
+) -> rusqlite::Result<String> {
+    let Some(who) = who else {
+        return pick_lru(c, mode, slot, xs);
+    };
+    let scored = scores(c, who, slot, 1)?;
+    let untried: Vec<String> = xs
+        .iter()
+        .filter(|x| !scored.iter().any(|s| &s.value == *x))
+        .cloned()
+        .collect();
+    if !untried.is_empty() {
+        return pick_lru(c, mode, slot, &untried);
+    }
+    // `scores` is already sorted best-first
+    Ok(scored
+        .into_iter()
+        .find(|s| xs.contains(&s.value))
+        .map(|s| s.value)
+        .unwrap_or_default())
+}
+
+/// Pick a redirect from `preset` using `rotation` (or the preset's own policy) and
+/// record the usage. `now` doubles as the tick source; `who` feeds `effective`.
+pub fn choose(
+    c: &rusqlite::Connection,
+    preset: &Preset,
+    rotation: Option<Rotation>,
+    who: Option<&str>,
+    now: chrono::DateTime<chrono::Utc>,
+) -> rusqlite::Result<Choice> {
+    fill(c, preset, rotation, who, &Steps::default(), now)
+}
+
+/// [`choose`] around caller steps: non-blank ones in `given` are kept as-is, only the
+/// blank ones are picked from `preset` (and only those count as usage).
+pub fn fill(
+    c: &rusqlite::Connection,
+    preset: &Preset,
+    rotation: Option<Rotation>,
+    who: Option<&str>,
+    given: &Steps,
+    now: chrono::DateTime<chrono::Utc>,
+) -> rusqlite::Result<Choice> {
+    let rotation = rotation.unwrap_or(preset.rotation);
+    let tick = now.timestamp();
+    let mut rng = rand::thread_rng();
+    // nanosecond stamps keep LRU ordering stable within one second
+    let stamp = now.to_rfc3339_opts(chrono::SecondsFormat::Nanos, true);
+    let slots: [(&str, &Vec<String>, &Option<String>); 4] = [
+        ("whisper", &preset.whispers, &given.whisper),
+        ("breath", &preset.breaths, &given.breath),
+        ("doorway", &preset.doorways, &given.doorway),
+        ("anchor", &preset.anchors, &given.anchor),
+    ];
+    let mut picked = Vec::with_capacity(4);
+    for (slot, xs, given) in slots {
+        if let Some(v) = given.as_deref().map(str::trim).filter(|s| !s.is_empty()) {
+            picked.push(v.to_string());
+            continue;
+        }
+        let v = match rotation {
+            Rotation::Tick => pick_tick(xs, tick),
+            Rotation::Random => xs.choose(&mut rng).cloned().unwrap_or_default(),
+            Rotation::Lru => pick_lru(c, &preset.mode, slot, xs)?,
+            Rotation::Effective => pick_effective(c, &preset.mode, who, slot, xs)?,
+        };
+        touch(c, &preset.mode, slot, &v, &stamp)?;
+        picked.push(v);
+    }
+    let mut picked = picked.into_iter();
+    Ok(Choice {
+        mode: preset.mode.clone(),
+        whisper: picked.next().unwrap_or_default(),
+        breath: picked.next().unwrap_or_default(),
+        doorway: picked.next().unwrap_or_default(),
+        anchor: picked.next().unwrap_or_default(),
+        emotion_kind: preset.emotion_kind.clone(),
+        intensity: preset.intensity,
+        bridge_kind: preset.bridge_kind.clone(),
+    })
+}
+
+// ---- Events -----------------------------------------------------------------
+
+/// A panic run as it landed: chosen steps plus the rows it touched elsewhere.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct PanicEvent {
+    #[serde(default)]
+    pub id: i64,
+    pub ts: String,
+    pub who: String,
+    pub mode: String,
+    /// `ui` (POST /panic) | `run` (POST /panic/run)
+    pub source: String,
+    pub whisper: String,
+    pub breath: String,
+    pub doorway: String,
+    pub anchor: String,
+    pub emotion_kind: String,
+    pub intensity: f64,
+    pub suggested_bridge: Option<String>,
+    pub tell_id: Option<i64>,
+    pub emotion_id: Option<i64>,
+    pub gratitude_id: Option<i64>,
+    pub relational_event_id: Option<String>,
+    /// Compact log file, when the export is enabled.
+    pub log_path: Option<String>,
+    /// When the follow-up prompt is due (None = no follow-up).
+    pub checkin_due_at: Option<String>,
+    pub checkin_sent_at: Option<String>,
+    /// Intensity before the redirect (defaults to the preset intensity).
+    pub before_intensity: Option<f64>,
+    pub after_intensity: Option<f64>,
+    pub outcome_at: Option<String>,
+    pub outcome_note: Option<String>,
+    /// Emotion row written for the "after" reading.
+    pub outcome_emotion_id: Option<i64>,
+}
+
+const EVENT_COLS: &str = "id, ts, who, mode, source, whisper, breath, doorway, anchor, \
+                          emotion_kind, intensity, suggested_bridge, tell_id, emotion_id, \
+                          gratitude_id, relational_event_id, log_path, checkin_due_at, \
+                          checkin_sent_at, before_intensity, after_intensity, outcome_at, \
+                          outcome_note, outcome_emotion_id";
+
+fn row_to_event(r: &rusqlite::Row) -> rusqlite::Result<PanicEvent> {
+    Ok(PanicEvent {
+        id: r.get(0)?,
+        ts: r.get(1)?,
+        who: r.get(2)?,
+        mode: r.get(3)?,
+        source: r.get(4)?,
+        whisper: r.get(5)?,
+        breath: r.get(6)?,
+        doorway: r.get(7)?,
+        anchor: r.get(8)?,
+        emotion_kind: r.get(9)?,
+        intensity: r.get(10)?,
+        suggested_bridge: r.get(11)?,
+        tell_id: r.get(12)?,
+        emotion_id: r.get(13)?,
+        gratitude_id: r.get(14)?,
+        relational_event_id: r.get(15)?,
+        log_path: r.get(16)?,
+        checkin_due_at: r.get(17)?,
+        checkin_sent_at: r.get(18)?,
+        before_intensity: r.get(19)?,
+        after_intensity: r.get(20)?,
+        outcome_at: r.get(21)?,
+        outcome_note: r.get(22)?,
+        outcome_emotion_id: r.get(23)?,
+    })
+}
+
+/// Insert an event (its `id` and outcome fields are ignored) and return the new id.
+/// `before_intensity` falls back to `intensity`.
+pub fn record(c: &rusqlite::Connection, e: &PanicEvent) -> rusqlite::Result<i64> {
+    c.execute(
+        "INSERT INTO panic_events(ts, who, mode, source, whisper, breath, doorway, anchor,
+           emotion_kind, intensity, suggested_bridge, tell_id, emotion_id, gratitude_id,
+           relational_event_id, log_path, checkin_due_at, before_intensity)
+         VALUES(?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18)",
+        rusqlite::params![
+            e.ts,
+            e.who,
+            e.mode,
+            e.source,
+            e.whisper,
+            e.breath,
+            e.doorway,
+            e.anchor,
+            e.emotion_kind,
+            e.intensity,
+            e.suggested_bridge,
+            e.tell_id,
+            e.emotion_id,
+            e.gratitude_id,
+            e.relational_event_id,
+            e.log_path,
+            e.checkin_due_at,
+            e.before_intensity.unwrap_or(e.intensity)
+        ],
+    )?;
+    Ok(c.last_insert_rowid())
+}
+
+/// Minutes until the follow-up prompt (`M3_PANIC_CHECKIN_MIN`, default 20; 0 = off).
+pub fn checkin_minutes() -> i64 {
+    std::env::var("M3_PANIC_CHECKIN_MIN")
+        .ok()
+        .and_then(|v| v.trim().parse::<i64>().ok())
+        .filter(|m| *m >= 0)
+        .unwrap_or(20)
+}
+
+/// `checkin_due_at` for a run at `now`, or None when follow-ups are off.
+pub fn checkin_due(now: chrono::DateTime<chrono::Utc>) -> Option<String> {
+    let min = checkin_minutes();
+    (min > 0).then(|| (now + chrono::Duration::minutes(min)).to_rfc3339())
+}
+
+/// Events whose follow-up is due, not yet prompted and not yet answered; marks them sent.
+pub fn take_due_checkins(
+    c: &mut rusqlite::Connection,
+    now: &str,
+) -> rusqlite::Result<Vec<PanicEvent>> {
+    let tx = c.transaction()?;
+    let due = {
+        let mut stmt = tx.prepare(&format!(
+            "SELECT {EVENT_COLS} FROM panic_events
+             WHERE checkin_due_at IS NOT NULL AND checkin_due_at <= ?1
+               AND checkin_sent_at IS NULL AND outcome_at IS NULL
+             ORDER BY checkin_due_at LIMIT 100"
+        ))?;
+        let rows = stmt
+            .query_map([now], row_to_event)?
+            .collect::<Result<Vec<_>, _>>()?;
+        rows
+    };
+    for e in &due {
+        tx.execute(
+            "UPDATE panic_events SET checkin_sent_at = ?1 WHERE id = ?2",
+            rusqlite::params![now, e.id],
+        )?;
+    }
+    tx.commit()?;
+    Ok(due)
+}
+
+/// Why an outcome could not be stored.
+#[derive(Debug, PartialEq)]
+pub enum OutcomeError {
+    Missing,
+    Invalid(String),
+}
+
+/// Record before/after intensity for a run. The "after" reading is also written to
+/// `emotions` (updated in place when the outcome is corrected).
+pub fn record_outcome(
+    c: &mut rusqlite::Connection,
+    id: i64,
+    before: Option<f64>,
+    after: f64,
+    note: Option<String>,
+    now: &str,
+) -> rusqlite::Result<Result<PanicEvent, OutcomeError>> {
+    for v in std::iter::once(after).chain(before) {
+        if !(0.0..=1.0).contains(&v) {
+            return Ok(Err(OutcomeError::Invalid(
+                "intensities must be 0..1".into(),
+            )));
+        }
+    }
+    let tx = c.transaction()?;
+    let Some(e) = get_event(&tx, id)? else {
+        return Ok(Err(OutcomeError::Missing));
+    };
+    let before = before.or(e.before_intensity).unwrap_or(e.intensity);
+    let emotion_note = format!("panic outcome #{id}: {before:.2} → {after:.2}");
+    let emotion_id = match e.outcome_emotion_id {
+        Some(eid)
+            if tx.execute(
+                "UPDATE emotions SET ts = ?1, intensity = ?2, note = ?3 WHERE id = ?4",
+                rusqlite::params![now, after, emotion_note, eid],
+            )? > 0 =>
+        {
+            eid
+        }
+        _ => {
+            tx.execute(
+                "INSERT INTO emotions(ts, who, kind, intensity, note) VALUES(?1, ?2, ?3, ?4, ?5)",
+                rusqlite::params![now, e.who, e.emotion_kind, after, emotion_note],
+            )?;
+            tx.last_insert_rowid()
+        }
+    };
+    tx.execute(
+        "UPDATE panic_events SET before_intensity = ?1, after_intensity = ?2, outcome_at = ?3,
+           outcome_note = ?4, outcome_emotion_id = ?5
+         WHERE id = ?6",
+        rusqlite::params![before, after, now, note, emotion_id, id],
+    )?;
+    let updated = get_event(&tx, id)?;
+    tx.commit()?;
+    Ok(updated.ok_or(OutcomeError::Missing))
+}
+
+pub fn get_event(c: &rusqlite::Connection, id: i64) -> rusqlite::Result<Option<PanicEvent>> {
+    c.query_row(
+        &format!("SELECT {EVENT_COLS} FROM panic_events WHERE id = ?1"),
+        [id],
+        row_to_event,
+    )
+    .optional()
+}
+
+// ---- Service ----------------------------------------------------------------
+
+/// Actor recorded when neither the request nor `x-m3-actor` names one.
+pub const DEFAULT_ACTOR: &str = "Raz";
+
+/// Header carrying the actor for clients that can't put `who` in the request.
+pub const ACTOR_HEADER: &str = "x-m3-actor";
+
+/// Member whose readiness a redirect turns green.
+pub const READINESS_MEMBER: &str = "main";
+
+/// `status_events.reason` for the readiness change a run makes.
+pub const STATUS_REASON: &str = "panic";
+
+/// `who` from the request, else the `x-m3-actor` header, else [`DEFAULT_ACTOR`].
+pub fn actor(requested: Option<&str>, headers: &HeaderMap) -> String {
+    requested
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .or_else(|| {
+            headers
+                .get(ACTOR_HEADER)
+                .and_then(|v| v.to_str().ok())
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+        })
+        .unwrap_or(DEFAULT_ACTOR)
+        .to_string()
+}
+
+/// Steps given by the caller; blanks are picked from the run's preset.
+#[derive(Debug, Clone, Default)]
+pub struct Steps {
+    pub whisper: Option<String>,
+    pub breath: Option<String>,
+    pub doorway: Option<String>,
+    pub anchor: Option<String>,
+}
+
+/// One redirect to run.
+#[derive(Debug, Clone)]
+pub struct RunRequest {
+    pub who: String,
+    /// `ui` (POST /panic) | `run` (POST /panic/run)
+    pub source: String,
+    /// Preset mode (unknown or None → `default`).
+    pub mode: Option<String>,
+    pub rotation: Option<Rotation>,
+    /// Caller-supplied steps; blank ones are picked from the preset.
+    pub steps: Steps,
+}
+
+/// Ids of every row a run wrote. The HTTP handlers fill `relational_event_id` and
+/// `log_path` after the transaction commits.
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct Artifacts {
+    pub event_id: i64,
+    pub tell_id: i64,
+    pub emotion_id: i64,
+    pub gratitude_id: i64,
+    pub status_event_id: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub relational_event_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub log_path: Option<String>,
+}
+
+/// A committed run.
+#[derive(Debug, Clone)]
+pub struct Run {
+    pub event: PanicEvent,
+    pub artifacts: Artifacts,
+    /// The gratitude row as written (subject, details).
+    pub gratitude: (String, String),
+    /// The readiness light the run set (announce it with `status::publish`).
+    pub readiness: crate::status::MemberStatus,
+}
+
+/// Why a run could not start.
+#[derive(Debug, PartialEq)]
+pub enum RunError {
+    /// No preset to pick steps from.
+    NoPreset,
+}
+
+/// Run one redirect: fill the blank steps from the preset, then write the tell, the
+/// emotion, `main` → green (via `status::write_member`), the gratitude row and the
+/// `panic_events` row in one transaction. Nothing is written when any of them fails.
+pub fn run(
+    c: &mut rusqlite::Connection,
+    req: &RunRequest,
+    now: chrono::DateTime<chrono::Utc>,
+) -> rusqlite::Result<Result<Run, RunError>> {
+    let tx = c.transaction()?;
+    let Some(preset) = resolve(&tx, req.mode.as_deref())? else {
+        return Ok(Err(RunError::NoPreset));
+    };
+    let choice = fill(&tx, &preset, req.rotation, Some(&req.who), &req.steps, now)?;
+    let ts = now.to_rfc3339();
+    let suggested_bridge =
+        crate::bridges::suggest(&tx, &choice.bridge_kind, choice.intensity, Some(&req.who))?
+            .pattern;
+
+    // 1) tell for traceability
+    tx.execute(
+        "INSERT INTO tells(node, pre_activation, action, created_at) VALUES('panic', ?1, ?2, ?3)",
+        rusqlite::params![
+            format!("whisper:{} | breath:{}", choice.whisper, choice.breath),
+            format!("doorway:{} | anchor:{}", choice.doorway, choice.anchor),
+            ts
+        ],
+    )?;
+    let tell_id = tx.last_insert_rowid();
+
+    // 2) emotion row so EmotionalOS sees the event
+    tx.execute(
+        "INSERT INTO emotions(ts, who, kind, intensity, note) VALUES(?1, ?2, ?3, ?4, ?5)",
+        rusqlite::params![
+            ts,
+            req.who,
+            choice.emotion_kind,
+            choice.intensity,
+            format!(
+                "panic {}: {} | {} | {}",
+                req.source, choice.whisper, choice.breath, choice.doorway
+            )
+        ],
+    )?;
+    let emotion_id = tx.last_insert_rowid();
+
+    // 3) auto-bridge readiness → green, written like any other light. The run is
+    //    announced by its own care invitation (`panic.ui` / `panic.run`), not `status.set`.
+    let readiness = crate::status::MemberStatus {
+        who: READINESS_MEMBER.to_string(),
+        color: "green".to_string(),
+        note: String::new(),
+        updated_at: ts.clone(),
+        expires_at: None,
+    };
+    let status_event_id = crate::status::write_member(&tx, &readiness, None, STATUS_REASON)?;
+
+    // 4) gratitude for the redirect
+    let gratitude = (
+        format!("I chose {}", choice.doorway),
+        format!("anchor: {}; whisper: {}", choice.anchor, choice.whisper),
+    );
+    tx.execute(
+        "INSERT INTO gratitude(ts, who, subject, kind, details) VALUES(?1, ?2, ?3, 'redirect', ?4)",
+        rusqlite::params![ts, req.who, gratitude.0, gratitude.1],
+    )?;
+    let gratitude_id = tx.last_insert_rowid();
+
+    // 5) the event tying the rows above together
+    let mut event = PanicEvent {
+        ts,
+        who: req.who.clone(),
+        mode: choice.mode,
+        source: req.source.clone(),
+        whisper: choice.whisper,
+        breath: choice.breath,
+        doorway: choice.doorway,
+        anchor: choice.anchor,
+        emotion_kind: choice.emotion_kind,
+        intensity: choice.intensity,
+        suggested_bridge: Some(suggested_bridge),
+        tell_id: Some(tell_id),
+        emotion_id: Some(emotion_id),
+        gratitude_id: Some(gratitude_id),
+        checkin_due_at: checkin_due(now),
+        ..Default::default()
+    };
+    event.before_intensity = Some(event.intensity);
+    event.id = record(&tx, &event)?;
+    tx.commit()?;
+
+    Ok(Ok(Run {
+        artifacts: Artifacts {
+            event_id: event.id,
+            tell_id,
+            emotion_id,
+            gratitude_id,
+            status_event_id,
+            ..Default::default()
+        },
+        event,
+        gratitude,
+        readiness,
+    }))
+}
+
+/// Attach what the handlers did after commit (relational envelope, log export).
+pub fn link_exports(
+    c: &rusqlite::Connection,
+    id: i64,
+    relational_event_id: Option<&str>,
+    log_path: Option<&str>,
+) -> rusqlite::Result<()> {
+    c.execute(
+        "UPDATE panic_events
+         SET relational_event_id = COALESCE(?1, relational_event_id),
+             log_path = COALESCE(?2, log_path)
+         WHERE id = ?3",
+        rusqlite::params![relational_event_id, log_path, id],
+    )?;
+    Ok(())
+}
+
+/// How well one value worked for one person.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct Score {
+    pub value: String,
+    pub samples: i64,
+    pub avg_before: f64,
+    pub avg_after: f64,
+    /// Mean of before − after; higher is better.
+    pub avg_drop: f64,
+}
+
+/// Slot name → `panic_events` column (fixed list; never interpolate user input).
+fn slot_column(slot: &str) -> Option<&'static str> {
+    match slot {
+        "whisper" => Some("whisper"),
+        "breath" => Some("breath"),
+        "doorway" => Some("doorway"),
+        "anchor" => Some("anchor"),
+        "bridge" => Some("suggested_bridge"),
+        _ => None,
+    }
+}
+
+/// Values of `slot` ranked by average intensity drop for `who` (runs with an outcome).
+pub fn scores(
+    c: &rusqlite::Connection,
+    who: &str,
+    slot: &str,
+    min_samples: i64,
+) -> rusqlite::Result<Vec<Score>> {
+    let Some(col) = slot_column(slot) else {
+        return Ok(Vec::new());
+    };
+    let mut stmt = c.prepare(&format!(
+        "SELECT {col}, COUNT(*), AVG(before_intensity), AVG(after_intensity),
+                AVG(before_intensity - after_intensity) AS drop_avg
+         FROM panic_events
+         WHERE who = ?1 AND after_intensity IS NOT NULL AND {col} IS NOT NULL
+         GROUP BY {col} HAVING COUNT(*) >= ?2
+         ORDER BY drop_avg DESC, COUNT(*) DESC, {col}"
+    ))?;
+    let rows = stmt
+        .query_map(rusqlite::params![who, min_samples], |r| {
+            Ok(Score {
+                value: r.get(0)?,
+                samples: r.get(1)?,
+                avg_before: r.get(2)?,
+                avg_after: r.get(3)?,
+                avg_drop: r.get(4)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(rows)
+}
+
+/// Rankings for one person across every slot.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct Effectiveness {
+    pub who: String,
+    pub outcomes: i64,
+    pub breaths: Vec<Score>,
+    pub doorways: Vec<Score>,
+    pub anchors: Vec<Score>,
+    pub whispers: Vec<Score>,
+    pub bridges: Vec<Score>,
+}
+
+pub fn effectiveness(
+    c: &rusqlite::Connection,
+    who: Option<&str>,
+    min_samples: i64,
+) -> rusqlite::Result<Vec<Effectiveness>> {
+    let mut stmt = c.prepare(
+        "SELECT who, COUNT(*) FROM panic_events
+         WHERE after_intensity IS NOT NULL AND (?1 IS NULL OR who = ?1)
+         GROUP BY who ORDER BY who",
+    )?;
+    let people = stmt
+        .query_map([who], |r| Ok((r.get::<_, String>(0)?, r.get::<_, i64>(1)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+    people
+        .into_iter()
+        .map(|(who, outcomes)| {
+            Ok(Effectiveness {
+                breaths: scores(c, &who, "breath", min_samples)?,
+                doorways: scores(c, &who, "doorway", min_samples)?,
+                anchors: scores(c, &who, "anchor", min_samples)?,
+                whispers: scores(c, &who, "whisper", min_samples)?,
+                bridges: scores(c, &who, "bridge", min_samples)?,
+                who,
+                outcomes,
+            })
+        })
+        .collect()
+}
+
+/// Newest event, if any.
+pub fn last(c: &rusqlite::Connection) -> rusqlite::Result<Option<PanicEvent>> {
+    c.query_row(
+        &format!("SELECT {EVENT_COLS} FROM panic_events ORDER BY ts DESC, id DESC LIMIT 1"),
+        [],
+        row_to_event,
+    )
+    .optional()
+}
+
+/// Filters shared by the page and its stats. Empty fields match everything.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct HistoryFilter {
+    #[serde(default)]
+    pub who: Option<String>,
+    #[serde(default)]
+    pub mode: Option<String>,
+    #[serde(default)]
+    pub source: Option<String>,
+    #[serde(default)]
+    pub doorway: Option<String>,
+    /// RFC3339, inclusive.
+    #[serde(default)]
+    pub since: Option<String>,
+    /// RFC3339, exclusive.
+    #[serde(default)]
+    pub until: Option<String>,
+}
+
+const FILTER_SQL: &str = "(?1 IS NULL OR who = ?1)
+     AND (?2 IS NULL OR mode = ?2)
+     AND (?3 IS NULL OR source = ?3)
+     AND (?4 IS NULL OR doorway = ?4)
+     AND (?5 IS NULL OR ts >= ?5)
+     AND (?6 IS NULL OR ts < ?6)";
+
+impl HistoryFilter {
+    /// Normalize timestamps to UTC RFC3339 so string comparison matches `ts`.
+    fn normalized(mut self) -> Result<Self, String> {
+        for (name, field) in [("since", &mut self.since), ("until", &mut self.until)] {
+            if let Some(raw) = field.take().filter(|s| !s.trim().is_empty()) {
+                let dt = chrono::DateTime::parse_from_rfc3339(raw.trim())
+                    .map_err(|_| format!("{name} must be RFC3339"))?;
+                *field = Some(dt.with_timezone(&chrono::Utc).to_rfc3339());
+            }
+        }
+        Ok(self)
+    }
+
+    fn params(&self) -> [Option<&str>; 6] {
+        [
+            self.who.as_deref(),
+            self.mode.as_deref(),
+            self.source.as_deref(),
+            self.doorway.as_deref(),
+            self.since.as_deref(),
+            self.until.as_deref(),
+        ]
+    }
+}
+
+/// Page of events, newest first; `before` is an event id cursor.
+pub fn history(
+    c: &rusqlite::Connection,
+    f: &HistoryFilter,
+    before: Option<i64>,
+    limit: i64,
+) -> rusqlite::Result<Vec<PanicEvent>> {
+    let mut stmt = c.prepare(&format!(
+        "SELECT {EVENT_COLS} FROM panic_events
+         WHERE {FILTER_SQL} AND (?7 IS NULL OR id < ?7)
+         ORDER BY id DESC LIMIT ?8"
+    ))?;
+    let [a, b, d, e, g, h] = f.params();
+    let rows = stmt
+        .query_map(
+            rusqlite::params![a, b, d, e, g, h, before, limit],
+            row_to_event,
+        )?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(rows)
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct Count {
+    pub value: String,
+    pub count: i64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct HistoryStats {
+    pub total: i64,
+    pub first_ts: Option<String>,
+    pub last_ts: Option<String>,
+    pub by_mode: Vec<Count>,
+    pub by_who: Vec<Count>,
+    pub by_source: Vec<Count>,
+    /// Most used doorways first.
+    pub doorways: Vec<Count>,
+    /// UTC day (`YYYY-MM-DD`) → events.
+    pub per_day: Vec<Count>,
+}
+
+/// Aggregates over everything matching `f` (not just one page).
+pub fn stats(c: &rusqlite::Connection, f: &HistoryFilter) -> rusqlite::Result<HistoryStats> {
+    let [a, b, d, e, g, h] = f.params();
+    let p = rusqlite::params![a, b, d, e, g, h];
+    let (total, first_ts, last_ts) = c.query_row(
+        &format!("SELECT COUNT(*), MIN(ts), MAX(ts) FROM panic_events WHERE {FILTER_SQL}"),
+        p,
+        |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)),
+    )?;
+    let group = |expr: &str, order: &str| -> rusqlite::Result<Vec<Count>> {
+        let mut stmt = c.prepare(&format!(
+            "SELECT {expr} AS v, COUNT(*) AS n FROM panic_events WHERE {FILTER_SQL}
+             GROUP BY v ORDER BY {order}"
+        ))?;
+        let rows = stmt
+            .query_map(p, |r| {
+                Ok(Count {
+                    value: r.get(0)?,
+                    count: r.get(1)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(rows)
+    };
+    Ok(HistoryStats {
+        total,
+        first_ts,
+        last_ts,
+        by_mode: group("mode", "n DESC, v")?,
+        by_who: group("who", "n DESC, v")?,
+        by_source: group("source", "n DESC, v")?,
+        doorways: group("doorway", "n DESC, v")?,
+        per_day: group("substr(ts, 1, 10)", "v")?,
+    })
+}
+
+// ---- HTTP -------------------------------------------------------------------
+
+type ApiErr = (StatusCode, String);
+
+fn unprocessable(msg: impl Into<String>) -> ApiErr {
+    (StatusCode::UNPROCESSABLE_ENTITY, msg.into())
+}
+
+fn internal(e: impl std::fmt::Display) -> ApiErr {
+    (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+}
+
+#[derive(Debug, Deserialize)]
+struct PresetIn {
+    mode: String,
+    #[serde(default)]
+    label: Option<String>,
+    whispers: Vec<String>,
+    breaths: Vec<String>,
+    doorways: Vec<String>,
+    anchors: Vec<String>,
+    #[serde(default)]
+    emotion_kind: Option<String>,
+    #[serde(default)]
+    intensity: Option<f64>,
+    #[serde(default)]
+    bridge_kind: Option<String>,
+    #[serde(default)]
+    rotation: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct PresetPatch {
+    #[serde(default)]
+    label: Option<String>,
+    #[serde(default)]
+    whispers: Option<Vec<String>>,
+    #[serde(default)]
+    breaths: Option<Vec<String>>,
+    #[serde(default)]
+    doorways: Option<Vec<String>>,
+    #[serde(default)]
+    anchors: Option<Vec<String>>,
+    #[serde(default)]
+    emotion_kind: Option<String>,
+    #[serde(default)]
+    intensity: Option<f64>,
+    #[serde(default)]
+    bridge_kind: Option<String>,
+    #[serde(default)]
+    rotation: Option<String>,
+}
+
+fn clean_list(name: &str, xs: Vec<String>) -> Result<Vec<String>, ApiErr> {
+    let mut out: Vec<String> = Vec::new();
+    for x in xs.into_iter().map(|x| x.trim().to_string()) {
+        if !x.is_empty() && !out.contains(&x) {
+            out.push(x);
+        }
+    }
+    if out.is_empty() {
+        return Err(unprocessable(format!("{name} needs at least one entry")));
+    }
+    Ok(out)
+}
+
+/// Apply a patch onto a preset, validating every touched field.
+fn apply(p: &mut Preset, patch: PresetPatch) -> Result<(), ApiErr> {
+    if let Some(l) = patch.label {
+        p.label = l.trim().to_string();
+    }
+    if let Some(xs) = patch.whispers {
+        p.whispers = clean_list("whispers", xs)?;
+    }
+    if let Some(xs) = patch.breaths {
+        p.breaths = clean_list("breaths", xs)?;
+    }
+    if let Some(xs) = patch.doorways {
+        p.doorways = clean_list("doorways", xs)?;
+    }
+    if let Some(xs) = patch.anchors {
+        p.anchors = clean_list("anchors", xs)?;
+    }
+    if let Some(k) = patch.emotion_kind {
+        let k = k.trim().to_lowercase();
+        if k.is_empty() {
+            return Err(unprocessable("emotion_kind cannot be empty"));
+        }
+        p.emotion_kind = k;
+    }
+    if let Some(i) = patch.intensity {
+        if !(0.0..=1.0).contains(&i) {
+            return Err(unprocessable("intensity must be 0..1"));
+        }
+        p.intensity = i;
+    }
+    if let Some(b) = patch.bridge_kind {
+        p.bridge_kind = b.trim().to_lowercase();
+    }
+    if let Some(r) = patch.rotation {
+        p.rotation = parse_rotation(&r)?;
+    }
+    Ok(())
+}
+
+fn save(c: &rusqlite::Connection, p: &Preset) -> rusqlite::Result<()> {
+    c.execute(
+        "INSERT INTO panic_presets(mode, label, whispers, breaths, doorways, anchors,
+           emotion_kind, intensity, bridge_kind, rotation, updated_at)
+         VALUES(?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
+         ON CONFLICT(mode) DO UPDATE SET
+           label=excluded.label, whispers=excluded.whispers, breaths=excluded.breaths,
+           doorways=excluded.doorways, anchors=excluded.anchors, emotion_kind=excluded.emotion_kind,
+           intensity=excluded.intensity, bridge_kind=excluded.bridge_kind,
+           rotation=excluded.rotation, updated_at=excluded.updated_at",
+        rusqlite::params![
+            p.mode,
+            p.label,
+            serde_json::to_string(&p.whispers).unwrap_or_default(),
+            serde_json::to_string(&p.breaths).unwrap_or_default(),
+            serde_json::to_string(&p.doorways).unwrap_or_default(),
+            serde_json::to_string(&p.anchors).unwrap_or_default(),
+            p.emotion_kind,
+            p.intensity,
+            p.bridge_kind,
+            p.rotation.as_str(),
+            p.updated_at
+        ],
+    )?;
+    Ok(())
+}
+
+/// GET /panic/presets
+async fn list_presets(State(state): State<AppState>) -> Result<Json<Vec<Preset>>, ApiErr> {
+    state
+        .db
+        .0
+        .call(|c| Ok(list(c)?))
+        .await
+        .map(Json)
+        .map_err(internal)
+}
+
+/// GET /panic/presets/:mode
+async fn get_preset(
+    State(state): State<AppState>,
+    Path(mode): Path<String>,
+) -> Result<Json<Preset>, ApiErr> {
+    state
+        .db
+        .0
+        .call(move |c| Ok(find(c, &mode)?))
+        .await
+        .map_err(internal)?
+        .map(Json)
+        .ok_or((StatusCode::NOT_FOUND, "unknown mode".into()))
+}
+
+/// POST /panic/presets — 409 when the mode (or a spelling of it) exists.
+async fn create_preset(
+    State(state): State<AppState>,
+    Json(input): Json<PresetIn>,
+) -> Result<(StatusCode, Json<Preset>), ApiErr> {
+    let mode = input.mode.trim().to_string();
+    if mode.is_empty() || mode.contains('/') {
+        return Err(unprocessable("mode must be a non-empty name without '/'"));
+    }
+    let mut preset = Preset {
+        label: mode.clone(),
+        mode,
+        whispers: Vec::new(),
+        breaths: Vec::new(),
+        doorways: Vec::new(),
+        anchors: Vec::new(),
+        emotion_kind: "anxiety".into(),
+        intensity: 0.55,
+        bridge_kind: String::new(),
+        rotation: Rotation::Tick,
+        updated_at: chrono::Utc::now().to_rfc3339(),
+    };
+    apply(
+        &mut preset,
+        PresetPatch {
+            label: input.label,
+            whispers: Some(input.whispers),
+            breaths: Some(input.breaths),
+            doorways: Some(input.doorways),
+            anchors: Some(input.anchors),
+            emotion_kind: input.emotion_kind,
+            intensity: input.intensity,
+            bridge_kind: input.bridge_kind,
+            rotation: input.rotation,
+        },
+    )?;
+    if preset.bridge_kind.is_empty() {
+        preset.bridge_kind = preset.emotion_kind.clone();
+    }
+    let out = preset.clone();
+    let created = state
+        .db
+        .0
+        .call(move |c| {
+            if find(c, &preset.mode)?.is_some() {
+                return Ok(false);
+            }
+            save(c, &preset)?;
+            Ok(true)
+        })
+        .await
+        .map_err(internal)?;
+    if !created {
+        return Err((StatusCode::CONFLICT, "mode already exists".into()));
+    }
+    Ok((StatusCode::CREATED, Json(out)))
+}
+
+/// PATCH /panic/presets/:mode
+async fn update_preset(
+    State(state): State<AppState>,
+    Path(mode): Path<String>,
+    Json(patch): Json<PresetPatch>,
+) -> Result<Json<Preset>, ApiErr> {
+    let found = state
+        .db
+        .0
+        .call(move |c| Ok(find(c, &mode)?))
+        .await
+        .map_err(internal)?;
+    let Some(mut preset) = found else {
+        return Err((StatusCode::NOT_FOUND, "unknown mode".into()));
+    };
+    apply(&mut preset, patch)?;
+    preset.updated_at = chrono::Utc::now().to_rfc3339();
+    let out = preset.clone();
+    state
+        .db
+        .0
+        .call(move |c| Ok(save(c, &preset)?))
+        .await
+        .map_err(internal)?;
+    Ok(Json(out))
+}
+
+/// DELETE /panic/presets/:mode
+async fn delete_preset(
+    State(state): State<AppState>,
+    Path(mode): Path<String>,
+) -> Result<StatusCode, ApiErr> {
+    let removed = state
+        .db
+        .0
+        .call(move |c| {
+            let Some(p) = find(c, &mode)? else {
+                return Ok(None);
+            };
+            if p.mode == DEFAULT_MODE {
+                return Ok(Some(false));
+            }
+            c.execute("DELETE FROM panic_presets WHERE mode = ?1", [&p.mode])?;
+            c.execute("DELETE FROM panic_preset_usage WHERE mode = ?1", [&p.mode])?;
+            Ok(Some(true))
+        })
+        .await
+        .map_err(internal)?;
+    match removed {
+        None => Err((StatusCode::NOT_FOUND, "unknown mode".into())),
+        Some(false) => Err(unprocessable("the default mode cannot be deleted")),
+        Some(true) => Ok(StatusCode::NO_CONTENT),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct PageParams {
+    #[serde(default)]
+    before: Option<i64>,
+    #[serde(default)]
+    limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+struct HistoryOut {
+    items: Vec<PanicEvent>,
+    /// Pass as `before` for the next page; absent on the last page.
+    next_before: Option<i64>,
+    stats: HistoryStats,
+}
+
+/// GET /panic/history?who=&mode=&source=&doorway=&since=&until=&before=&limit=
+async fn history_http(
+    State(state): State<AppState>,
+    Query(filter): Query<HistoryFilter>,
+    Query(q): Query<PageParams>,
+) -> Result<Json<HistoryOut>, ApiErr> {
+    let filter = filter.normalized().map_err(unprocessable)?;
+    let limit = q.limit.unwrap_or(50).clamp(1, 500);
+    let before = q.before;
+    let (items, stats) = state
+        .db
+        .0
+        .call(move |c| Ok((history(c, &filter, before, limit)?, stats(c, &filter)?)))
+        .await
+        .map_err(internal)?;
+    let next_before = (items.len() as i64 == limit)
+        .then(|| items.last().map(|e| e.id))
+        .flatten();
+    Ok(Json(HistoryOut {
+        items,
+        next_before,
+        stats,
+    }))
+}
+
+#[derive(Debug, Deserialize)]
+struct OutcomeIn {
+    after: f64,
+    #[serde(default)]
+    before: Option<f64>,
+    #[serde(default)]
+    note: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct OutcomeOut {
+    event: PanicEvent,
+    /// before − after (positive = it helped).
+    drop: f64,
+}
+
+/// POST /panic/:id/outcome
+async fn outcome_http(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    Json(input): Json<OutcomeIn>,
+) -> Result<Json<OutcomeOut>, ApiErr> {
+    let now = chrono::Utc::now().to_rfc3339();
+    let note = input.note.filter(|n| !n.trim().is_empty());
+    let out = state
+        .db
+        .0
+        .call(move |c| {
+            Ok(record_outcome(
+                c,
+                id,
+                input.before,
+                input.after,
+                note,
+                &now,
+            )?)
+        })
+        .await
+        .map_err(internal)?;
+    match out {
+        Ok(event) => {
+            let drop = event.before_intensity.unwrap_or(event.intensity)
+                - event.after_intensity.unwrap_or_default();
+            Ok(Json(OutcomeOut { event, drop }))
+        }
+        Err(OutcomeError::Missing) => Err((StatusCode::NOT_FOUND, "unknown panic event".into())),
+        Err(OutcomeError::Invalid(msg)) => Err(unprocessable(msg)),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct EffectivenessParams {
+    #[serde(default)]
+    who: Option<String>,
+    #[serde(default)]
+    min_samples: Option<i64>,
+}
+
+/// GET /panic/effectiveness?who=&min_samples=
+async fn effectiveness_http(
+    State(state): State<AppState>,
+    Query(q): Query<EffectivenessParams>,
+) -> Result<Json<Vec<Effectiveness>>, ApiErr> {
+    let min = q.min_samples.unwrap_or(1).max(1);
+    state
+        .db
+        .0
+        .call(move |c| Ok(effectiveness(c, q.who.as_deref(), min)?))
+        .await
+        .map(Json)
+        .map_err(internal)
+}
+
+// ---- Runs (POST /panic, POST /panic/run, GET /panic/last) -------------------
+
+/// Response payload for the run endpoints: the chosen micro-protocol, the oracle
+/// suggestion, whether a compact log line was written, and every artifact id.
+#[derive(Serialize)]
+struct RunOut {
+    whisper: String,
+    breath: String,
+    doorway: String,
+    anchor: String,
+    /// Optional oracle suggestion derived from bridges::suggest
+    suggested_bridge: Option<String>,
+    logged: bool,
+    /// Row in `panic_events` (see `/panic/history`).
+    event_id: i64,
+    /// Actor the run was recorded for.
+    who: String,
+    /// Every row the run wrote (see [`Artifacts`]).
+    artifacts: Artifacts,
+}
+
+/// Input payload for `POST /panic` (UI trigger). All fields optional; blank steps are
+/// picked from the preset (`mode`, else `default`).
+#[derive(Deserialize)]
+struct RunIn {
+    /// Actor; falls back to the `x-m3-actor` header, then `Raz`.
+    #[serde(default)]
+    who: Option<String>,
+    whisper: Option<String>,
+    breath: Option<String>,
+    doorway: Option<String>,
+    anchor: Option<String>,
+    #[serde(default)]
+    mode: Option<String>,
+    /// Override the preset's rotation policy for this pick: `tick` | `random` | `lru` | `effective`.
+    #[serde(default)]
+    rotation: Option<String>,
+}
+
+/// Query for `POST /panic/run`: preset mode (default `run`), optional rotation override and actor.
+#[derive(Deserialize, Default)]
+struct RunQuery {
+    #[serde(default)]
+    who: Option<String>,
+    #[serde(default)]
+    mode: Option<String>,
+    #[serde(default)]
+    rotation: Option<String>,
+}
+
+/// Payload returned by `GET /panic/last`.
+#[derive(Serialize)]
+struct LastOut {
+    ts: String,
+    whisper: String,
+    breath: String,
+    doorway: String,
+    anchor: String,
+    /// Compact log file ("" when the run was not exported).
+    path: String,
+    /// `panic_events` fields; absent when read from a log written by `panic.sh`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    who: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    mode: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    suggested_bridge: Option<String>,
+}
+
+/// Base directory for panic logs; shared with `panic.sh` so both land under `exports/panic`.
+fn log_dir() -> std::path::PathBuf {
+    crate::exports::resolve_exports_dir().join("panic")
+}
+
+/// Compact panic logs are an optional export next to `panic_events`; `M3_PANIC_LOG=0` turns them off.
+fn log_enabled() -> bool {
+    !matches!(
+        std::env::var("M3_PANIC_LOG").ok().as_deref().map(str::trim),
+        Some("0" | "false" | "off")
+    )
+}
+
+/// Append a compact, single-line record used by the UI + CLI (`POST /panic`).
+/// Best-effort; the caller drops the error. Returns the file written.
+async fn log_compact(e: &PanicEvent) -> std::io::Result<std::path::PathBuf> {
+    use tokio::io::AsyncWriteExt;
+    // .../panic/YYYY-MM/panic-YYYY-MM-DD.log
+    let now = chrono::Utc::now();
+    let dir = log_dir().join(now.format("%Y-%m").to_string());
+    let path = dir.join(now.format("panic-%Y-%m-%d.log").to_string());
+    tokio::fs::create_dir_all(&dir).await?;
+
+    let line = format!(
+        "[{}] [{}] whisper=\"{}\" breath=\"{}\" doorway=\"{}\" anchor=\"{}\"\n",
+        now.to_rfc3339(),
+        e.mode,
+        e.whisper,
+        e.breath,
+        e.doorway,
+        e.anchor
+    );
+    let mut file = tokio::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .await?;
+    file.write_all(line.as_bytes()).await?;
+    file.flush().await?;
+    Ok(path)
+}
+
+/// Oracle block for `POST /panic/run`, mirroring what `panic.sh` writes so CLI and UI
+/// leave the same audit trail. Best-effort.
+fn write_oracle_log(e: &PanicEvent) -> Option<std::path::PathBuf> {
+    use std::io::Write;
+    let now = chrono::Utc::now();
+    let dir = log_dir().join(now.format("%Y-%m").to_string());
+    if let Err(err) = std::fs::create_dir_all(&dir) {
+        tracing::warn!(error = ?err, "panic log: mkdir failed");
+        return None;
+    }
+    let path = dir.join(now.format("panic-%Y-%m-%d.log").to_string());
+    let mut f = match std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+    {
+        Ok(f) => f,
+        Err(err) => {
+            tracing::warn!(error = ?err, "panic log: open failed");
+            return None;
+        }
+    };
+    let _ = writeln!(
+        f,
+        "🌬️  {}-PANIC REDIRECT ORACLE\n\nwhisper : {}\nbreath  : {}\ndoorway : {}\nanchor  : {}\n\n✅ one redirect step chosen — act now.\n📝  logged to {}\n",
+        std::process::id(),
+        e.whisper,
+        e.breath,
+        e.doorway,
+        e.anchor,
+        path.display()
+    );
+    Some(path)
+}
+
+/// Run the service in one transaction (see [`run`]); 503 when no preset is left.
+async fn start_run(state: &AppState, req: RunRequest) -> Result<Run, ApiErr> {
+    let now = chrono::Utc::now();
+    state
+        .db
+        .0
+        .call(move |c| Ok(run(c, &req, now)?))
+        .await
+        .map_err(internal)?
+        .map_err(|e| match e {
+            RunError::NoPreset => (
+                StatusCode::SERVICE_UNAVAILABLE,
+                "no panic presets".to_string(),
+            ),
+        })
+}
+
+/// Side effects of a committed run that live outside the DB: readiness on the bus, the
+/// thanks log line, the relational invitation (withheld without consent). Their ids are
+/// linked back onto the event best-effort.
+async fn finish_run(
+    state: &AppState,
+    done: Run,
+    log_path: Option<std::path::PathBuf>,
+    what: &'static str,
+) -> RunOut {
+    let Run {
+        event,
+        mut artifacts,
+        gratitude: (subject, details),
+        readiness,
+    } = done;
+    crate::status::publish(state, &readiness);
+    crate::gratitude::append_log(&crate::gratitude::log_line(
+        &event.ts,
+        Some(&event.who),
+        &subject,
+        Some(&details),
+        Some("redirect"),
+        None,
+    ))
+    .await;
+
+    let emitted = crate::relational::emit(
+        state,
+        what,
+        &crate::relational::Actor::from_display(&event.who),
+        crate::relational::Intent::care(crate::relational::CARE_WHISPER),
+        serde_json::json!({
+            "severity": "gentle",
+            "event_id": event.id,
+            "redirect": {
+                "whisper": event.whisper,
+                "breath": event.breath,
+                "doorway": event.doorway,
+                "anchor": event.anchor,
+                "suggested_bridge": event.suggested_bridge,
+            },
+        }),
+    )
+    .await;
+    artifacts.relational_event_id = Some(emitted.id);
+    artifacts.log_path = log_path.map(|p| p.to_string_lossy().to_string());
+    let (id, rel, log) = (
+        event.id,
+        artifacts.relational_event_id.clone(),
+        artifacts.log_path.clone(),
+    );
+    if let Err(e) = state
+        .db
+        .0
+        .call(move |c| Ok(link_exports(c, id, rel.as_deref(), log.as_deref())?))
+        .await
+    {
+        tracing::warn!(error = ?e, "{what}: failed to link panic exports");
+    }
+
+    RunOut {
+        logged: artifacts.log_path.is_some(),
+        event_id: event.id,
+        who: event.who,
+        whisper: event.whisper,
+        breath: event.breath,
+        doorway: event.doorway,
+        anchor: event.anchor,
+        suggested_bridge: event.suggested_bridge,
+        artifacts,
+    }
+}
+
+/// A rotation from a preset patch or a per-run override; 422 when unknown.
+fn parse_rotation(raw: &str) -> Result<Rotation, ApiErr> {
+    Rotation::parse(raw).ok_or_else(|| unprocessable("rotation must be tick|random|lru|effective"))
+}
+
+/// POST /panic — UI run; caller steps win, blanks come from the preset.
+async fn run_ui_http(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(body): Json<RunIn>,
+) -> Result<Json<RunOut>, ApiErr> {
+    let req = RunRequest {
+        who: actor(body.who.as_deref(), &headers),
+        source: "ui".into(),
+        mode: body.mode,
+        rotation: body.rotation.as_deref().map(parse_rotation).transpose()?,
+        steps: Steps {
+            whisper: body.whisper,
+            breath: body.breath,
+            doorway: body.doorway,
+            anchor: body.anchor,
+        },
+    };
+    let done = start_run(&state, req).await?;
+    // compact line (optional export; the tag is the mode that ran)
+    let log_path = if log_enabled() {
+        log_compact(&done.event).await.ok()
+    } else {
+        None
+    };
+    Ok(Json(finish_run(&state, done, log_path, "panic.ui").await))
+}
+
+/// POST /panic/run?who=&mode=&rotation= — redirect oracle; every step from the preset
+/// (`run` unless `mode` says otherwise).
+async fn run_oracle_http(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    q: Option<Query<RunQuery>>,
+) -> Result<Json<RunOut>, ApiErr> {
+    let Query(q) = q.unwrap_or_default();
+    let req = RunRequest {
+        who: actor(q.who.as_deref(), &headers),
+        source: "run".into(),
+        mode: Some(q.mode.unwrap_or_else(|| RUN_MODE.to_string())),
+        rotation: q.rotation.as_deref().map(parse_rotation).transpose()?,
+        steps: Steps::default(),
+    };
+    let done = start_run(&state, req).await?;
+    let log_path = if log_enabled() {
+        write_oracle_log(&done.event)
+    } else {
+        None
+    };
+    Ok(Json(finish_run(&state, done, log_path, "panic.run").await))
+}
+
+/// GET /panic/last — newest `panic_events` row; falls back to the compact logs
+/// (CLI runs via `panic.sh` only write the log).
+async fn last_http(State(state): State<AppState>) -> Result<Json<LastOut>, StatusCode> {
+    let newest = state
+        .db
+        .0
+        .call(|c| Ok(last(c)?))
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    match newest {
+        Some(e) => Ok(Json(LastOut {
+            ts: e.ts,
+            whisper: e.whisper,
+            breath: e.breath,
+            doorway: e.doorway,
+            anchor: e.anchor,
+            path: e.log_path.unwrap_or_default(),
+            id: Some(e.id),
+            who: Some(e.who),
+            mode: Some(e.mode),
+            suggested_bridge: e.suggested_bridge,
+        })),
+        None => last_from_logs().await,
+    }
+}
+
+/// Read the newest panic compact log and expose it as JSON (fallback for `/panic/last`).
+async fn last_from_logs() -> Result<Json<LastOut>, StatusCode> {
+    use tokio::fs;
+    use tokio::io::AsyncReadExt;
+
+    let base = log_dir();
+    // Find newest YYYY-MM dir
+    let mut newest_dir: Option<(String, std::time::SystemTime)> = None;
+    let Ok(mut rd) = fs::read_dir(&base).await else {
+        return Err(StatusCode::NOT_FOUND);
+    };
+    while let Ok(Some(e)) = rd.next_entry().await {
+        let name = e.file_name().to_string_lossy().to_string();
+        if name.len() == 7 && name.chars().nth(4) == Some('-') {
+            if let Ok(md) = e.metadata().await {
+                if let Ok(modt) = md.modified() {
+                    if newest_dir.as_ref().map(|(_, t)| modt > *t).unwrap_or(true) {
+                        newest_dir = Some((name, modt));
+                    }
+                }
+            }
+        }
+    }
+    let Some((ym, _)) = newest_dir else {
+        return Err(StatusCode::NOT_FOUND);
+    };
+
+    // Find newest file in newest_dir (panic-YYYY-MM-DD.log)
+    let dir = base.join(&ym);
+    let mut newest_file: Option<(std::path::PathBuf, std::time::SystemTime)> = None;
+    let Ok(mut rd2) = fs::read_dir(&dir).await else {
+        return Err(StatusCode::NOT_FOUND);
+    };
+    while let Ok(Some(e)) = rd2.next_entry().await {
+        let path = e.path();
+        if path.extension().and_then(|s| s.to_str()) == Some("log") {
+            if let Ok(md) = e.metadata().await {
+                if let Ok(modt) = md.modified() {
+                    if newest_file.as_ref().map(|(_, t)| modt > *t).unwrap_or(true) {
+                        newest_file = Some((path, modt));
+                    }
+                }
+            }
+        }
+    }
+    let Some((file_path, _)) = newest_file else {
+        return Err(StatusCode::NOT_FOUND);
+    };
+
+    // Read file & take the last non-empty line
+    let mut file = fs::File::open(&file_path)
+        .await
+        .map_err(|_| StatusCode::NOT_FOUND)?;
+    let mut buf = String::new();
+    file.read_to_string(&mut buf)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let last = buf
+        .lines()
+        .rev()
+        .find(|l| !l.trim().is_empty())
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    // Expected format (from panic.sh & server writer):
+    // [YYYY-MM-DDTHH:MM:SSZ] whisper="..." breath="..." doorway="..." anchor="..."
+    let ts = last
+        .split(']')
+        .next()
+        .unwrap_or("")
+        .trim_start_matches('[')
+        .to_string();
+    let mut whisper = String::new();
+    let mut breath = String::new();
+    let mut doorway = String::new();
+    let mut anchor = String::new();
+    for key in ["whisper", "breath", "doorway", "anchor"] {
+        if let Some(start) = last.find(&format!(r#"{key}=""#)) {
+            let s = start + key.len() + 2;
+            if let Some(end) = last[s..].find('"') {
+                let val = &last[s..s + end];
+                match key {
+                    "whisper" => whisper = val.to_string(),
+                    "breath" => breath = val.to_string(),
+                    "doorway" => doorway = val.to_string(),
+                    _ => anchor = val.to_string(),
+                }
+            }
+        }
+    }
+
+    Ok(Json(LastOut {
+        ts,
+        whisper,
+        breath,
+        doorway,
+        anchor,
+        path: file_path.to_string_lossy().to_string(),
+        id: None,
+        who: None,
+        mode: None,
+        suggested_bridge: None,
+    }))
+}
+
+/// Router to be mounted under `/panic` (runs, presets, history, outcomes).
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/", post(run_ui_http))
+        .route("/run", post(run_oracle_http))
+        .route("/last", get(last_http))
+        .route("/history", get(history_http))
+        .route("/effectiveness", get(effectiveness_http))
+        .route("/:id/outcome", post(outcome_http))
+        .route("/presets", get(list_presets).post(create_preset))
+        .route(
+            "/presets/:mode",
+            get(get_preset).patch(update_preset).delete(delete_preset),
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn db() -> rusqlite::Connection {
+        let mut c = rusqlite::Connection::open_in_memory().unwrap();
+        crate::db::ensure_schema(&mut c).unwrap();
+        c
+    }
+
+    fn at(secs: i64) -> chrono::DateTime<chrono::Utc> {
+        chrono::DateTime::from_timestamp(secs, 0).unwrap()
+    }
+
+    #[test]
+    fn seeds_carry_the_old_palettes() {
+        let c = db();
+        let modes: Vec<String> = list(&c).unwrap().into_iter().map(|p| p.mode).collect();
+        assert_eq!(modes, vec!["default", "fearVisible", "run"]);
+        let fv = find(&c, "fear-visible").unwrap().unwrap();
+        assert_eq!(fv.mode, "fearVisible");
+        assert_eq!(fv.emotion_kind, "fear");
+        assert_eq!(fv.whispers.len(), 3);
+        assert_eq!(find(&c, "run").unwrap().unwrap().rotation, Rotation::Random);
+    }
+
+    #[test]
+    fn tick_rotation_matches_the_old_indexing() {
+        let c = db();
+        let p = find(&c, DEFAULT_MODE).unwrap().unwrap();
+        let a = choose(&c, &p, None, None, at(10)).unwrap();
+        assert_eq!(a.whisper, p.whispers[10 % 3]);
+        assert_eq!(a.breath, p.breaths[0]);
+        let b = choose(&c, &p, None, None, at(11)).unwrap();
+        assert_eq!(b.breath, p.breaths[1]);
+    }
+
+    #[test]
+    fn lru_visits_every_doorway_before_repeating() {
+        let c = db();
+        let p = find(&c, RUN_MODE).unwrap().unwrap();
+        let mut seen = Vec::new();
+        for i in 0..p.doorways.len() {
+            let pick = choose(&c, &p, Some(Rotation::Lru), None, at(100 + i as i64)).unwrap();
+            seen.push(pick.doorway);
+        }
+        assert_eq!(seen, p.doorways);
+        let again = choose(&c, &p, Some(Rotation::Lru), None, at(200)).unwrap();
+        assert_eq!(again.doorway, p.doorways[0]);
+    }
+
+    #[test]
+    fn patch_validates_fields() {
+        let c = db();
+        let mut p = find(&c, DEFAULT_MODE).unwrap().unwrap();
+        let bad = PresetPatch {
+            doorways: Some(vec!["  ".into()]),
+            ..Default::default()
+        };
+        assert!(apply(&mut p, bad).is_err());
+        let bad = PresetPatch {
+            rotation: Some("sometimes".into()),
+            ..Default::default()
+        };
+        assert!(apply(&mut p, bad).is_err());
+        let ok = PresetPatch {
+            doorways: Some(vec!["cold_splash".into(), "cold_splash".into()]),
+            rotation: Some("LRU".into()),
+            ..Default::default()
+        };
+        apply(&mut p, ok).unwrap();
+        assert_eq!(p.doorways, vec!["cold_splash"]);
+        assert_eq!(p.rotation, Rotation::Lru);
+    }
+
+    fn event(ts: &str, who: &str, mode: &str, doorway: &str) -> PanicEvent {
+        PanicEvent {
+            ts: ts.into(),
+            who: who.into(),
+            mode: mode.into(),
+            source: "ui".into(),
+            doorway: doorway.into(),
+            emotion_kind: "fear".into(),
+            intensity: 0.6,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn history_filters_pages_and_counts() {
+        let c = db();
+        for (ts, who, mode, door) in [
+            ("2026-03-01T08:00:00+00:00", "Raz", "default", "drink_water"),
+            (
+                "2026-03-01T09:00:00+00:00",
+                "Raz",
+                "fearVisible",
+                "lower_voice",
+            ),
+            (
+                "2026-03-02T08:00:00+00:00",
+                "Sawsan",
+                "default",
+                "drink_water",
+            ),
+            ("2026-03-03T08:00:00+00:00", "Raz", "default", "drink_water"),
+        ] {
+            record(&c, &event(ts, who, mode, door)).unwrap();
+        }
+        let id = last(&c).unwrap().unwrap().id;
+        link_exports(&c, id, Some("evt-1"), None).unwrap();
+        assert_eq!(
+            last(&c).unwrap().unwrap().relational_event_id.as_deref(),
+            Some("evt-1")
+        );
+
+        let raz = HistoryFilter {
+            who: Some("Raz".into()),
+            ..Default::default()
+        };
+        let page = history(&c, &raz, None, 2).unwrap();
+        assert_eq!(page.len(), 2);
+        assert_eq!(page[0].ts, "2026-03-03T08:00:00+00:00");
+        let rest = history(&c, &raz, Some(page[1].id), 2).unwrap();
+        assert_eq!(rest.len(), 1);
+
+        let st = stats(&c, &raz).unwrap();
+        assert_eq!(st.total, 3);
+        assert_eq!(
+            st.doorways[0],
+            Count {
+                value: "drink_water".into(),
+                count: 2
+            }
+        );
+        assert_eq!(st.per_day.len(), 2);
+        assert_eq!(st.by_mode[0].value, "default");
+
+        let window = HistoryFilter {
+            since: Some("2026-03-01T10:30:00+02:00".into()),
+            until: Some("2026-03-03T00:00:00Z".into()),
+            ..Default::default()
+        }
+        .normalized()
+        .unwrap();
+        assert_eq!(stats(&c, &window).unwrap().total, 2);
+        assert!(HistoryFilter {
+            since: Some("yesterday".into()),
+            ..Default::default()
+        }
+        .normalized()
+        .is_err());
+    }
+
+    #[test]
+    fn outcomes_rank_doorways_and_drive_effective_rotation() {
+        let mut c = db();
+        let now = "2026-03-05T10:00:00+00:00";
+        let mut ids = Vec::new();
+        for (who, door, after) in [
+            ("Raz", "drink_water", 0.5),
+            ("Raz", "cold_splash", 0.1),
+            ("Raz", "cold_splash", 0.2),
+            ("Sawsan", "drink_water", 0.1),
+        ] {
+            let mut e = event(now, who, RUN_MODE, door);
+            e.before_intensity = Some(0.6);
+            let id = record(&c, &e).unwrap();
+            let got = record_outcome(&mut c, id, None, after, None, now)
+                .unwrap()
+                .unwrap();
+            assert_eq!(got.after_intensity, Some(after));
+            ids.push(id);
+        }
+        // correcting an outcome updates its emotion row instead of adding one
+        let first = get_event(&c, ids[0]).unwrap().unwrap();
+        record_outcome(&mut c, ids[0], Some(0.7), 0.6, Some("meh".into()), now)
+            .unwrap()
+            .unwrap();
+        let fixed = get_event(&c, ids[0]).unwrap().unwrap();
+        assert_eq!(fixed.outcome_emotion_id, first.outcome_emotion_id);
+        assert_eq!(fixed.before_intensity, Some(0.7));
+
+        assert_eq!(
+            record_outcome(&mut c, 9999, None, 0.1, None, now).unwrap(),
+            Err(OutcomeError::Missing)
+        );
+        assert!(matches!(
+            record_outcome(&mut c, ids[1], None, 1.5, None, now).unwrap(),
+            Err(OutcomeError::Invalid(_))
+        ));
+
+        let raz = effectiveness(&c, Some("Raz"), 1).unwrap();
+        assert_eq!(raz.len(), 1);
+        assert_eq!(raz[0].outcomes, 3);
+        assert_eq!(raz[0].doorways[0].value, "cold_splash");
+        assert_eq!(raz[0].doorways[0].samples, 2);
+        assert_eq!(effectiveness(&c, None, 1).unwrap().len(), 2);
+
+        // untried doorways first, then the best one for this person
+        let mut p = find(&c, RUN_MODE).unwrap().unwrap();
+        p.doorways = vec![
+            "drink_water".into(),
+            "cold_splash".into(),
+            "step_outside".into(),
+        ];
+        let pick = choose(&c, &p, Some(Rotation::Effective), Some("Raz"), at(300)).unwrap();
+        assert_eq!(pick.doorway, "step_outside");
+        p.doorways.pop();
+        let pick = choose(&c, &p, Some(Rotation::Effective), Some("Raz"), at(301)).unwrap();
+        assert_eq!(pick.doorway, "cold_splash");
+    }
+
+    fn count(c: &rusqlite::Connection, table: &str) -> i64 {
+        c.query_row(&format!("SELECT COUNT(*) FROM {table}"), [], |r| r.get(0))
+            .unwrap()
+    }
+
+    #[test]
+    fn run_writes_every_artifact_or_nothing() {
+        let mut c = db();
+        let req = RunRequest {
+            who: "Sawsan".into(),
+            source: "run".into(),
+            mode: Some(RUN_MODE.into()),
+            rotation: Some(Rotation::Lru),
+            steps: Steps::default(),
+        };
+        let first = run(&mut c, &req, at(500)).unwrap().unwrap();
+        let a = &first.artifacts;
+        assert_eq!(first.event.who, "Sawsan");
+        assert_eq!(get_event(&c, a.event_id).unwrap().unwrap(), first.event);
+        assert_eq!(first.event.tell_id, Some(a.tell_id));
+        let who: String = c
+            .query_row(
+                "SELECT who FROM emotions WHERE id = ?1",
+                [a.emotion_id],
+                |r| r.get(0),
+            )
+            .unwrap();
+        assert_eq!(who, "Sawsan");
+        let (who, kind): (String, String) = c
+            .query_row(
+                "SELECT who, kind FROM gratitude WHERE id = ?1",
+                [a.gratitude_id],
+                |r| Ok((r.get(0)?, r.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!((who.as_str(), kind.as_str()), ("Sawsan", "redirect"));
+        let (color, reason): (String, String) = c
+            .query_row(
+                "SELECT color, reason FROM status_events WHERE id = ?1",
+                [a.status_event_id],
+                |r| Ok((r.get(0)?, r.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!((color.as_str(), reason.as_str()), ("green", STATUS_REASON));
+
+        // caller steps win; blanks are picked from the preset (usage only for those)
+        let usage = |c: &rusqlite::Connection, slot: &str| -> i64 {
+            c.query_row(
+                "SELECT COALESCE(SUM(uses), 0) FROM panic_preset_usage WHERE mode = ?1 AND slot = ?2",
+                [DEFAULT_MODE, slot],
+                |r| r.get(0),
+            )
+            .unwrap()
+        };
+        let steps = Steps {
+            doorway: Some("cold_splash".into()),
+            whisper: Some(" ".into()),
+            ..Default::default()
+        };
+        let own = run_with(&mut c, steps);
+        let default = find(&c, DEFAULT_MODE).unwrap().unwrap();
+        assert_eq!(own.event.doorway, "cold_splash");
+        assert_eq!(own.event.mode, DEFAULT_MODE);
+        assert!(default.whispers.contains(&own.event.whisper));
+        assert!(default.anchors.contains(&own.event.anchor));
+        assert_eq!((usage(&c, "whisper"), usage(&c, "doorway")), (1, 0));
+
+        // a failing write rolls the whole run back, preset usage included
+        let before: Vec<i64> = ["tells", "emotions", "status_events", "panic_preset_usage"]
+            .iter()
+            .map(|t| count(&c, t))
+            .collect();
+        c.execute_batch("DROP TABLE gratitude").unwrap();
+        assert!(run(&mut c, &req, at(501)).is_err());
+        let after: Vec<i64> = ["tells", "emotions", "status_events", "panic_preset_usage"]
+            .iter()
+            .map(|t| count(&c, t))
+            .collect();
+        assert_eq!(before, after);
+        assert_eq!(count(&c, "panic_events"), 2);
+
+        c.execute_batch("DELETE FROM panic_presets").unwrap();
+        assert_eq!(
+            run(&mut c, &req, at(502)).unwrap().unwrap_err(),
+            RunError::NoPreset
+        );
+    }
+
+    fn run_with(c: &mut rusqlite::Connection, steps: Steps) -> Run {
+        let req = RunRequest {
+            who: DEFAULT_ACTOR.into(),
+            source: "ui".into(),
+            mode: None,
+            rotation: None,
+            steps,
+        };
+        run(c, &req, at(600)).unwrap().unwrap()
+    }
+
+    #[test]
+    fn actor_prefers_request_then_header() {
+        let mut h = HeaderMap::new();
+        assert_eq!(actor(None, &h), DEFAULT_ACTOR);
+        h.insert(ACTOR_HEADER, "Ana".parse().unwrap());
+        assert_eq!(actor(Some("  "), &h), "Ana");
+        assert_eq!(actor(Some("Sawsan"), &h), "Sawsan");
+    }
+}
@@ -1,5 +1,6 @@
 use axum::{
-    extract::Query,
+    extract::{Query, State},
+    http::StatusCode,
     routing::{get, post},
     Json, Router,
 };
@@ -262,6 +263,8 @@ pub async fn detect_victim_aggressor(Json(req): Json<DetectRequest>) -> Json<Det
 }
 
 /// -------- Bridge suggestion (Emotion → micro-logic) --------
+/// Served from the bridge library (`bridges.rs`); the keys pattern/hint/breath/doorway/anchor
+/// are unchanged, plus kind/tier/profile from the library.
 
 #[derive(Deserialize)]
 pub struct BridgeQuery {
@@ -269,122 +272,31 @@ pub struct BridgeQuery {
     pub kind: Option<String>,
     /// 0.0..=1.0; default 0.5 if omitted
     pub intensity: Option<f32>,
+    /// Profile whose bridge overrides apply.
+    pub who: Option<String>,
 }
 
-#[derive(Serialize)]
-#[serde(rename_all = "snake_case")]
-pub struct BridgeSuggestion {
-    pub pattern: &'static str, // high-level label
-    pub hint: &'static str,    // compact micro-logic
-    breath: &'static str,
-    doorway: &'static str,
-    anchor: &'static str,
-}
-
-fn bridge_table(kind: &str, intensity: f32) -> BridgeSuggestion {
-    let k = kind.trim().to_ascii_lowercase();
-    let i = intensity.clamp(0.0, 1.0);
-
-    match k.as_str() {
-        "panic" | "fear" | "anxiety" => {
-            let high = i >= 0.6;
-            BridgeSuggestion {
-                pattern: "stabilize-first",
-                hint: if high {
-                    "Box breath, feet on floor, water sip"
-                } else {
-                    "Double exhale, orient to room"
-                },
-                breath: if high {
-                    "box: in4-hold4-out6 × 4"
-                } else {
-                    "double_exhale × 6"
-                },
-                doorway: "sip water, feet on floor",
-                anchor: "Name 3 objects you see.",
-            }
-        }
-        "anger" => BridgeSuggestion {
-            pattern: "decharge-then-name",
-            hint: "Long exhale, shake arms 30s, soften jaw",
-            breath: "in4-out8 × 6",
-            doorway: "shake arms 30s, step outside",
-            anchor: "Lower shoulders, soften jaw.",
-        },
-        "shame" => BridgeSuggestion {
-            pattern: "worth-reminder",
-            hint: "4–6 breath, hand to heart, 3 facts (no story)",
-            breath: "4-6 breath × 6",
-            doorway: "write 3 objective facts (no story)",
-            anchor: "Hand over heart: 'still worthy'.",
-        },
-        "attachment_test" | "attachment-testing" | "attachment" => BridgeSuggestion {
-            pattern: "ask-not-test",
-            hint: "Name the fear plainly; ask for reassurance, not proof",
-            breath: "in4-hold2-out6 × 3",
-            doorway: "say: 'I feel scared of X. Can you reassure me?'",
-            anchor: "One honest request, then pause.",
-        },
-        "sibling_trust" | "sibling-trust" | "sibling" => BridgeSuggestion {
-            pattern: "boundary-then-bridge",
-            hint: "State one boundary; decline forever-pact renewal",
-            breath: "double_exhale × 6",
-            doorway: "write a one-sentence boundary",
-            anchor: "Love stays; pact ends.",
-        },
-        "parent_planted" | "parent-planted" | "parent" => BridgeSuggestion {
-            pattern: "language-interrupt",
-            hint: "Name the parental line; replace with a present-tense truth",
-            breath: "4-6 breath × 6",
-            doorway: "say: 'That’s mom’s line; my line is…'",
-            anchor: "Author the present.",
-        },
-        "over_analysis" | "over-analysis" | "analysis" => BridgeSuggestion {
-            pattern: "close-the-loop",
-            hint: "Set 2‑min timer; one next step; archive and stop",
-            breath: "in4-out6 × 6",
-            doorway: "start a 2‑minute timer",
-            anchor: "Clarity lands, then rest.",
-        },
-        "phantom_rival"
-        | "phantom-rival"
-        | "comparison_jealousy"
-        | "comparison-jealousy"
-        | "jealousy" => BridgeSuggestion {
-            pattern: "reality-then-ask",
-            hint: "In 3 / Out 6; list 3 facts vs 3 guesses; then ask, don’t accuse",
-            breath: "in3-out6 × 6",
-            doorway: "write 3 facts vs 3 guesses; one clear ask",
-            anchor: "Presence over phantom.",
-        },
-        _ => BridgeSuggestion {
-            pattern: "return-to-center",
-            hint: "Stand up, shoulder roll, one true sentence",
-            breath: "double_exhale × 6",
-            doorway: "stand_up + shoulder_roll",
-            anchor: "Return to center.",
-        },
-    }
-}
-
-/// Library helper: compute a bridge suggestion without going through HTTP.
-/// Useful for other handlers (e.g., `/panic`) to reuse the same logic.
-pub fn suggest_bridge(kind: &str, intensity: f32) -> BridgeSuggestion {
-    bridge_table(kind, intensity)
-}
-
-/// GET /patterns/bridge_suggest?kind=panic&intensity=0.7
-pub async fn bridge_suggest(Query(q): Query<BridgeQuery>) -> Json<serde_json::Value> {
-    let kind = q.kind.as_deref().unwrap_or("panic");
-    let intensity = q.intensity.filter(|v| v.is_finite()).unwrap_or(0.5);
-    let out = bridge_table(kind, intensity);
-    Json(json!({
-        "pattern": out.pattern,
-        "hint": out.hint,
-        "breath": out.breath,
-        "doorway": out.doorway,
-        "anchor": out.anchor,
-    }))
+/// GET /patterns/bridge_suggest?kind=panic&intensity=0.7&who=Raz
+pub async fn bridge_suggest(
+    State(state): State<AppState>,
+    Query(q): Query<BridgeQuery>,
+) -> Result<Json<crate::bridges::Bridge>, (StatusCode, String)> {
+    let kind = q.kind.unwrap_or_else(|| "panic".to_string());
+    let intensity = q.intensity.filter(|v| v.is_finite()).unwrap_or(0.5) as f64;
+    state
+        .db
+        .0
+        .call(move |c| {
+            Ok(crate::bridges::suggest(
+                c,
+                &kind,
+                intensity,
+                q.who.as_deref(),
+            )?)
+        })
+        .await
+        .map(Json)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
 }
 
 /// -------- Three-lane map (victim / aggressor / sovereign) --------
@@ -500,13 +412,39 @@ pub async fn productivity_map() -> Json<serde_json::Value> {
     }))
 }
 
+/// GET /patterns/productivity?who=&utc_offset=
+/// With `who`, adds `energy_windows`: the deep-work and rest windows from their learned
+/// energy curve over the next 24 hours (see forecast.rs); omitted while they have no marks.
+async fn productivity(
+    State(state): State<AppState>,
+    Query(p): Query<crate::forecast::ForecastParams>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    let Json(mut map) = productivity_map().await;
+    if p.who.as_deref().is_some_and(|w| !w.trim().is_empty()) {
+        let q = p
+            .normalized(chrono::Utc::now())
+            .map_err(|e| (StatusCode::UNPROCESSABLE_ENTITY, e))?;
+        let forecast = state
+            .db
+            .0
+            .call(move |c| Ok(crate::forecast::forecast(c, &q)?))
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+            .map_err(|e| (StatusCode::UNPROCESSABLE_ENTITY, e))?;
+        if let Some(f) = forecast {
+            map["energy_windows"] = json!(f.windows);
+        }
+    }
+    Ok(Json(map))
+}
+
 /// Router to be mounted under `/patterns`
 pub fn router() -> Router<AppState> {
     Router::new()
         .route("/detect", post(detect_victim_aggressor))
         .route("/bridge_suggest", get(bridge_suggest))
         .route("/lanes", get(lanes))
-        .route("/productivity", get(productivity_map))
+        .route("/productivity", get(productivity))
         .route("/number_signal", post(number_signal))
 }
 
@@ -515,98 +453,6 @@ mod tests {
     use super::*;
     use axum::Json;
 
-    // --- bridge_suggest / bridge_table ---
-
-    #[test]
-    fn bridge_anxiety_low_uses_double_exhale() {
-        let b = super::bridge_table("anxiety", 0.3);
-        assert_eq!(b.pattern, "stabilize-first");
-        assert!(b.breath.contains("double_exhale"));
-        assert_eq!(b.anchor, "Name 3 objects you see.");
-    }
-
-    #[test]
-    fn bridge_panic_high_uses_box_breath() {
-        let b = super::bridge_table("panic", 0.9);
-        assert_eq!(b.pattern, "stabilize-first");
-        assert!(b.breath.contains("box"));
-        assert_eq!(b.doorway, "sip water, feet on floor");
-    }
-
-    #[test]
-    fn bridge_anger_pattern() {
-        let b = super::bridge_table("anger", 0.5);
-        assert_eq!(b.pattern, "decharge-then-name");
-        assert!(b.breath.contains("in4-out8"));
-        assert_eq!(b.anchor, "Lower shoulders, soften jaw.");
-    }
-
-    #[test]
-    fn bridge_shame_pattern() {
-        let b = super::bridge_table("shame", 0.4);
-        assert_eq!(b.pattern, "worth-reminder");
-        assert!(b.hint.contains("facts"));
-        assert!(b.anchor.contains("still worthy"));
-    }
-
-    #[test]
-    fn bridge_default_fallback() {
-        let b = super::bridge_table("unknown-kind", 0.4);
-        assert_eq!(b.pattern, "return-to-center");
-        assert!(b.breath.contains("double_exhale"));
-        assert_eq!(b.doorway, "stand_up + shoulder_roll");
-    }
-
-    #[test]
-    fn bridge_attachment_test_kind() {
-        let b = super::bridge_table("attachment_test", 0.5);
-        assert_eq!(b.pattern, "ask-not-test");
-        assert!(b.hint.contains("reassurance"));
-        assert!(b.breath.contains("out6"));
-    }
-
-    #[test]
-    fn bridge_sibling_trust_kind() {
-        let b = super::bridge_table("sibling_trust", 0.7);
-        assert_eq!(b.pattern, "boundary-then-bridge");
-        assert!(b.hint.contains("boundary"));
-        assert!(b.anchor.contains("Love stays"));
-    }
-
-    #[test]
-    fn bridge_parent_planted_kind() {
-        let b = super::bridge_table("parent_planted", 0.4);
-        assert_eq!(b.pattern, "language-interrupt");
-        assert!(b.hint.contains("parental line"));
-        assert!(b.anchor.contains("Author"));
-    }
-
-    #[test]
-    fn bridge_over_analysis_kind() {
-        let b = super::bridge_table("over_analysis", 0.3);
-        assert_eq!(b.pattern, "close-the-loop");
-        assert!(b.hint.contains("2‑min") || b.hint.contains("2-min"));
-        assert!(b.anchor.to_lowercase().contains("rest"));
-    }
-
-    #[test]
-    fn bridge_phantom_rival_kind() {
-        let b = super::bridge_table("phantom_rival", 0.5);
-        assert_eq!(b.pattern, "reality-then-ask");
-        assert!(b.hint.to_lowercase().contains("facts"));
-        assert!(b.hint.to_lowercase().contains("ask"));
-        assert!(b.anchor.to_lowercase().contains("phantom"));
-    }
-
-    #[test]
-    fn intensity_is_clamped() {
-        let hi = super::bridge_table("panic", 100.0);
-        let lo = super::bridge_table("panic", -5.0);
-        // one of them must end up using a branch without panic
-        assert!(hi.breath.contains("box"));
-        assert!(lo.breath.contains("double_exhale"));
-    }
-
     // --- detect_victim_aggressor ---
 
     #[tokio::test]
@@ -0,0 +1,560 @@
+//! Anonymized export — share the refined, guard the raw
+//! ----------------------------------------------------
+//! Whisper: "patterns travel; people stay home." 🫥
+//!
+//! Pattern data for research, without the people in it (see docs/privacy.md):
+//!   • `who` becomes a stable pseudonym `p_<10 hex>` = HMAC-SHA256(salt, who). The salt is
+//!     `M3_PSEUDONYM_SALT`, else a random one kept in `kv.pseudonym_salt`, so the same
+//!     person maps to the same pseudonym across exports but not across installations.
+//!   • timestamps are coarsened to the first day of their day or week (UTC)
+//!   • intensities and levels are rounded to one decimal
+//!   • `details`, `note`, `note_id` and `archetype` never leave; sealed emotions are dropped
+//!     (the `sealed` flag or `privacy = 'sealed'`)
+//!   • emotions are exported when `privacy` is `anonymized` or `public`; `private` rows
+//!     only with `include_private=true`
+//!   • k-anonymity: a group (period, source, kind) with fewer than `k` distinct people is
+//!     suppressed entirely
+//!
+//! Endpoint (mounted under `/privacy`)
+//!   GET /privacy/export?source=emotions|energy|all&granularity=day|week&k=3
+//!                      &format=json|csv&since=&until=&include_private=
+//!     json → { source, granularity, k, rows, suppressed, dropped_sealed }
+//!     csv  → `source,who,period,kind,value` (counts in `x-m3-suppressed` / `x-m3-dropped-sealed`)
+
+use crate::emotions::Bucket;
+use crate::AppState;
+use axum::{
+    extract::{Query, State},
+    http::{header, HeaderName, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use rusqlite::OptionalExtension;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::collections::{BTreeMap, BTreeSet};
+
+type ApiErr = (StatusCode, String);
+
+fn unprocessable(msg: impl Into<String>) -> ApiErr {
+    (StatusCode::UNPROCESSABLE_ENTITY, msg.into())
+}
+
+fn internal(e: impl std::fmt::Display) -> ApiErr {
+    (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+}
+
+/// Smallest group size an export may use.
+pub const MIN_K: usize = 2;
+pub const DEFAULT_K: usize = 3;
+
+/// The pseudonym salt: `M3_PSEUDONYM_SALT`, else the stored one (created on first use).
+pub fn salt(c: &rusqlite::Connection) -> rusqlite::Result<String> {
+    if let Some(s) = std::env::var("M3_PSEUDONYM_SALT")
+        .ok()
+        .filter(|s| !s.trim().is_empty())
+    {
+        return Ok(s);
+    }
+    let stored: Option<String> = c
+        .query_row(
+            "SELECT value FROM kv WHERE key = 'pseudonym_salt'",
+            [],
+            |r| r.get(0),
+        )
+        .optional()?;
+    if let Some(s) = stored {
+        return Ok(s);
+    }
+    let mut bytes = [0u8; 32];
+    rand::RngCore::fill_bytes(&mut rand::thread_rng(), &mut bytes);
+    c.execute(
+        "INSERT OR IGNORE INTO kv(key, value) VALUES('pseudonym_salt', ?1)",
+        [hex::encode(bytes)],
+    )?;
+    c.query_row(
+        "SELECT value FROM kv WHERE key = 'pseudonym_salt'",
+        [],
+        |r| r.get(0),
+    )
+}
+
+/// Stable pseudonym for `who` (trimmed, case-insensitive) under `salt`.
+pub fn pseudonym(salt: &str, who: &str) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(salt.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(who.trim().to_lowercase().as_bytes());
+    let digest = mac.finalize().into_bytes();
+    format!("p_{}", &hex::encode(digest)[..10])
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Source {
+    Emotions,
+    Energy,
+    All,
+}
+
+impl Source {
+    pub fn parse(s: &str) -> Option<Source> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "emotions" => Some(Source::Emotions),
+            "energy" => Some(Source::Energy),
+            "all" => Some(Source::All),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Source::Emotions => "emotions",
+            Source::Energy => "energy",
+            Source::All => "all",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ExportOptions {
+    pub source: Source,
+    pub granularity: Bucket,
+    pub k: usize,
+    pub include_private: bool,
+    /// RFC3339 UTC, inclusive.
+    pub since: Option<String>,
+    /// RFC3339 UTC, exclusive.
+    pub until: Option<String>,
+}
+
+/// One exported row. `value` is the emotion intensity or the energy level.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct AnonRow {
+    pub source: &'static str,
+    pub who: String,
+    /// First day of the day/week bucket (`YYYY-MM-DD`).
+    pub period: String,
+    pub kind: String,
+    pub value: f64,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct Export {
+    pub source: &'static str,
+    pub granularity: &'static str,
+    pub k: usize,
+    /// Sorted by period, source, kind, who.
+    pub rows: Vec<AnonRow>,
+    /// Rows left out because their group had fewer than `k` people.
+    pub suppressed: usize,
+    /// Sealed emotions left out.
+    pub dropped_sealed: usize,
+}
+
+fn round1(x: f64) -> f64 {
+    (x * 10.0).round() / 10.0
+}
+
+/// Build the export. Rows come out already pseudonymized, coarsened and suppressed.
+pub fn export(c: &rusqlite::Connection, opts: &ExportOptions) -> rusqlite::Result<Export> {
+    let salt = salt(c)?;
+    let mut raw: Vec<(AnonRow, String)> = Vec::new();
+    let mut dropped_sealed = 0;
+    let (since, until) = (opts.since.as_deref(), opts.until.as_deref());
+
+    if matches!(opts.source, Source::Emotions | Source::All) {
+        let mut stmt = c.prepare(
+            "SELECT ts, who, kind, intensity, sealed, privacy FROM emotions
+             WHERE (?1 IS NULL OR ts >= ?1) AND (?2 IS NULL OR ts < ?2)",
+        )?;
+        let mut rows = stmt.query(rusqlite::params![since, until])?;
+        while let Some(r) = rows.next()? {
+            let sealed: bool = r.get(4)?;
+            let privacy: String = r.get(5)?;
+            if sealed || privacy == "sealed" {
+                dropped_sealed += 1;
+                continue;
+            }
+            let shared = match privacy.as_str() {
+                "anonymized" | "public" => true,
+                "private" => opts.include_private,
+                _ => false,
+            };
+            if !shared {
+                continue;
+            }
+            let ts: String = r.get(0)?;
+            let Some(period) = opts.granularity.start(&ts) else {
+                continue;
+            };
+            let who: String = r.get(1)?;
+            raw.push((
+                AnonRow {
+                    source: "emotions",
+                    who: pseudonym(&salt, &who),
+                    period: period.to_string(),
+                    kind: r.get(2)?,
+                    value: round1(r.get(3)?),
+                },
+                who.trim().to_lowercase(),
+            ));
+        }
+    }
+
+    if matches!(opts.source, Source::Energy | Source::All) {
+        // energy_marks.ts is RFC3339 UTC, like emotions.ts (older rows are rewritten by
+        // db::ensure_schema), so the range check is the same string compare
+        let mut stmt = c.prepare(
+            "SELECT ts, who, kind, level FROM energy_marks
+             WHERE (?1 IS NULL OR ts >= ?1) AND (?2 IS NULL OR ts < ?2)",
+        )?;
+        let mut rows = stmt.query(rusqlite::params![since, until])?;
+        while let Some(r) = rows.next()? {
+            let ts: String = r.get(0)?;
+            let Some(period) = opts.granularity.start(&ts) else {
+                continue;
+            };
+            let who: String = r.get(1)?;
+            let kind: String = r.get(2)?;
+            raw.push((
+                AnonRow {
+                    source: "energy",
+                    who: pseudonym(&salt, &who),
+                    period: period.to_string(),
+                    kind: crate::taxonomy::fold(&kind),
+                    value: round1(r.get(3)?),
+                },
+                who.trim().to_lowercase(),
+            ));
+        }
+    }
+
+    // k-anonymity over (period, source, kind): count distinct people per group
+    let mut people: BTreeMap<(String, &'static str, String), BTreeSet<String>> = BTreeMap::new();
+    for (row, who) in &raw {
+        people
+            .entry((row.period.clone(), row.source, row.kind.clone()))
+            .or_default()
+            .insert(who.clone());
+    }
+    let total = raw.len();
+    let mut rows: Vec<AnonRow> = raw
+        .into_iter()
+        .map(|(row, _)| row)
+        .filter(|row| people[&(row.period.clone(), row.source, row.kind.clone())].len() >= opts.k)
+        .collect();
+    rows.sort_by(|a, b| {
+        (&a.period, a.source, &a.kind, &a.who)
+            .cmp(&(&b.period, b.source, &b.kind, &b.who))
+            .then(a.value.total_cmp(&b.value))
+    });
+    Ok(Export {
+        source: opts.source.as_str(),
+        granularity: opts.granularity.as_str(),
+        k: opts.k,
+        suppressed: total - rows.len(),
+        rows,
+        dropped_sealed,
+    })
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+/// `source,who,period,kind,value` with a header line.
+pub fn to_csv(rows: &[AnonRow]) -> String {
+    let mut out = String::from("source,who,period,kind,value\n");
+    for r in rows {
+        out.push_str(&format!(
+            "{},{},{},{},{}\n",
+            r.source,
+            r.who,
+            r.period,
+            csv_field(&r.kind),
+            r.value
+        ));
+    }
+    out
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ExportParams {
+    /// emotions | energy | all (default)
+    #[serde(default)]
+    source: Option<String>,
+    /// day | week (default)
+    #[serde(default)]
+    granularity: Option<String>,
+    #[serde(default)]
+    k: Option<usize>,
+    /// json (default) | csv
+    #[serde(default)]
+    format: Option<String>,
+    #[serde(default)]
+    since: Option<String>,
+    #[serde(default)]
+    until: Option<String>,
+    #[serde(default)]
+    include_private: Option<bool>,
+}
+
+fn blank(v: &Option<String>) -> Option<&str> {
+    v.as_deref().map(str::trim).filter(|s| !s.is_empty())
+}
+
+fn rfc3339(name: &str, v: &Option<String>) -> Result<Option<String>, ApiErr> {
+    blank(v)
+        .map(|raw| {
+            chrono::DateTime::parse_from_rfc3339(raw)
+                .map(|dt| dt.with_timezone(&Utc).to_rfc3339())
+                .map_err(|_| unprocessable(format!("{name} must be RFC3339")))
+        })
+        .transpose()
+}
+
+/// GET /privacy/export
+async fn export_http(
+    State(state): State<AppState>,
+    Query(q): Query<ExportParams>,
+) -> Result<Response, ApiErr> {
+    let source = match blank(&q.source) {
+        None => Source::All,
+        Some(s) => Source::parse(s)
+            .ok_or_else(|| unprocessable("source must be emotions, energy or all"))?,
+    };
+    let granularity = match blank(&q.granularity) {
+        None => Bucket::Week,
+        Some(g) => {
+            Bucket::parse(g).ok_or_else(|| unprocessable("granularity must be day or week"))?
+        }
+    };
+    let k = q.k.unwrap_or(DEFAULT_K);
+    if k < MIN_K {
+        return Err(unprocessable(format!("k must be at least {MIN_K}")));
+    }
+    let csv = match blank(&q.format) {
+        None => false,
+        Some(f) if f.eq_ignore_ascii_case("json") => false,
+        Some(f) if f.eq_ignore_ascii_case("csv") => true,
+        Some(_) => return Err(unprocessable("format must be json or csv")),
+    };
+    let opts = ExportOptions {
+        source,
+        granularity,
+        k,
+        include_private: q.include_private.unwrap_or(false),
+        since: rfc3339("since", &q.since)?,
+        until: rfc3339("until", &q.until)?,
+    };
+    let out = state
+        .db
+        .0
+        .call(move |c| Ok(export(c, &opts)?))
+        .await
+        .map_err(internal)?;
+    if !csv {
+        return Ok(Json(out).into_response());
+    }
+    let count = |n: usize| HeaderValue::from(n as u64);
+    Ok((
+        [
+            (
+                header::CONTENT_TYPE,
+                HeaderValue::from_static("text/csv; charset=utf-8"),
+            ),
+            (
+                header::CONTENT_DISPOSITION,
+                HeaderValue::from_static("attachment; filename=\"m3-anonymized.csv\""),
+            ),
+            (
+                HeaderName::from_static("x-m3-suppressed"),
+                count(out.suppressed),
+            ),
+            (
+                HeaderName::from_static("x-m3-dropped-sealed"),
+                count(out.dropped_sealed),
+            ),
+        ],
+        to_csv(&out.rows),
+    )
+        .into_response())
+}
+
+pub fn router() -> Router<AppState> {
+    Router::new().route("/export", get(export_http))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conn() -> rusqlite::Connection {
+        let mut c = rusqlite::Connection::open_in_memory().unwrap();
+        crate::db::ensure_schema(&mut c).unwrap();
+        c
+    }
+
+    fn emotion(
+        c: &rusqlite::Connection,
+        ts: &str,
+        who: &str,
+        kind: &str,
+        sealed: bool,
+        privacy: &str,
+    ) {
+        c.execute(
+            "INSERT INTO emotions(ts, who, kind, intensity, details, note, sealed, privacy)
+             VALUES(?1, ?2, ?3, 0.66, 'named my sister', 'panic ui: kitchen', ?4, ?5)",
+            rusqlite::params![ts, who, kind, sealed, privacy],
+        )
+        .unwrap();
+    }
+
+    fn opts(source: Source, granularity: Bucket, k: usize) -> ExportOptions {
+        ExportOptions {
+            source,
+            granularity,
+            k,
+            include_private: false,
+            since: None,
+            until: None,
+        }
+    }
+
+    #[test]
+    fn pseudonyms_are_stable_and_salted() {
+        let a = pseudonym("salt-1", "Raz");
+        assert_eq!(a, pseudonym("salt-1", " raz "));
+        assert_ne!(a, pseudonym("salt-1", "Nico"));
+        assert_ne!(a, pseudonym("salt-2", "Raz"));
+        assert!(a.starts_with("p_") && a.len() == 12);
+        let c = conn();
+        assert_eq!(salt(&c).unwrap(), salt(&c).unwrap(), "stored once");
+    }
+
+    #[test]
+    fn export_coarsens_strips_drops_sealed_and_suppresses_small_groups() {
+        let c = conn();
+        // week of 2025-09-01: three people felt fear, only Raz felt shame
+        emotion(
+            &c,
+            "2025-09-01T08:00:00+00:00",
+            "Raz",
+            "fear",
+            false,
+            "anonymized",
+        );
+        emotion(
+            &c,
+            "2025-09-02T09:30:00+00:00",
+            "Nico",
+            "fear",
+            false,
+            "public",
+        );
+        emotion(
+            &c,
+            "2025-09-04T22:10:00+00:00",
+            "Ana",
+            "fear",
+            false,
+            "anonymized",
+        );
+        emotion(
+            &c,
+            "2025-09-03T08:00:00+00:00",
+            "Raz",
+            "shame",
+            false,
+            "anonymized",
+        );
+        emotion(
+            &c,
+            "2025-09-03T08:00:00+00:00",
+            "Ana",
+            "fear",
+            true,
+            "anonymized",
+        );
+        emotion(
+            &c,
+            "2025-09-03T08:00:00+00:00",
+            "Mara",
+            "fear",
+            false,
+            "sealed",
+        );
+        emotion(
+            &c,
+            "2025-09-03T08:00:00+00:00",
+            "Mara",
+            "fear",
+            false,
+            "private",
+        );
+
+        let out = export(&c, &opts(Source::Emotions, Bucket::Week, 3)).unwrap();
+        assert_eq!((out.dropped_sealed, out.suppressed), (2, 1));
+        assert_eq!(out.rows.len(), 3);
+        assert!(out
+            .rows
+            .iter()
+            .all(|r| r.period == "2025-09-01" && r.kind == "fear"));
+        assert!(out
+            .rows
+            .iter()
+            .all(|r| r.value == 0.7 && r.who.starts_with("p_")));
+        let json = serde_json::to_string(&out).unwrap();
+        for leak in ["Raz", "Nico", "sister", "kitchen", "T08:00"] {
+            assert!(!json.contains(leak), "{leak} leaked");
+        }
+
+        // by day every group has one person → all suppressed
+        let daily = export(&c, &opts(Source::Emotions, Bucket::Day, 2)).unwrap();
+        assert!(daily.rows.is_empty());
+
+        // private rows need an explicit opt-in
+        let mut with_private = opts(Source::Emotions, Bucket::Week, 2);
+        with_private.include_private = true;
+        let out = export(&c, &with_private).unwrap();
+        assert_eq!(out.rows.len(), 4, "Mara's private fear joins the group");
+    }
+
+    #[test]
+    fn energy_rows_join_and_csv_has_a_header() {
+        let c = conn();
+        for who in ["Raz", "Nico"] {
+            c.execute(
+                "INSERT INTO energy_marks(ts, who, kind, level, note)
+                 VALUES('2025-09-02T10:00:00+00:00', ?1, 'Dragon', 0.84, 'secret')",
+                [who],
+            )
+            .unwrap();
+        }
+        emotion(
+            &c,
+            "2025-09-01T08:00:00+00:00",
+            "Raz",
+            "fear",
+            false,
+            "public",
+        );
+        let out = export(&c, &opts(Source::All, Bucket::Week, 2)).unwrap();
+        assert_eq!(out.rows.len(), 2);
+        assert_eq!(out.suppressed, 1);
+        let csv = to_csv(&out.rows);
+        let mut lines = csv.lines();
+        assert_eq!(lines.next(), Some("source,who,period,kind,value"));
+        let first = lines.next().unwrap();
+        assert!(first.starts_with("energy,p_") && first.ends_with(",2025-09-01,dragon,0.8"));
+        assert!(!csv.contains("secret"));
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+    }
+}
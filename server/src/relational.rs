@@ -0,0 +1,851 @@
+//! Relational webhooks — envelope, consent registry, emitter
+//! ---------------------------------------------------------
+//! Whisper: "never automate power; only automate invitations." 🌬️
+//!
+//! Spec: docs/specs/relational-webhooks.md
+//!
+//! Purpose
+//!   • Wrap every outbound care signal (`panic.ui`, `panic.run`, `status.set`) in the
+//!     spec envelope: id, type, ts, actor, audience, intent, consent, context, security.
+//!   • Keep a consent registry: an actor grants an event type to an audience
+//!     (`group:<name>` or `user:<id>`), optionally until `expires_at`.
+//!   • Withhold events unless consent for the event type is explicit, unrevoked and
+//!     unexpired. Withheld events are recorded as "pending consent" markers.
+//!
+//! Endpoints (mounted under `/consent`)
+//!   GET  /consent?granted_by=raz          → active + past grants
+//!   POST /consent                         → { granted_by, scope:[..], audience:{groups,targets}, expires_at?, ttl_minutes? }
+//!   POST /consent/revoke                  → { id }
+//!   GET  /consent/pending?actor=raz       → events withheld for lack of consent
+//!
+//! Notes
+//!   • Panic intents are invitations: gentle severity, a response window, suggested
+//!     responses. Nothing here retries, escalates or nudges.
+//!   • Security stays separate: the transport signature lives in `X-M3-Signature`;
+//!     the envelope only carries a nonce and the accepted timestamp window.
+
+use crate::AppState;
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    routing::{get, post},
+    Json, Router,
+};
+use chrono::{DateTime, Utc};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Accepted clock skew for signed transport, echoed in `security.ts_window_sec`.
+pub const TS_WINDOW_SEC: i64 = 300;
+
+/// Longest `ttl_minutes` a grant may ask for (one year).
+pub const MAX_GRANT_TTL_MINUTES: i64 = 525_600;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Actor {
+    pub id: String,
+    pub display: String,
+}
+
+impl Actor {
+    /// Actor ids are the lowercased display name (`"Raz"` → `"raz"`), as in the spec examples.
+    pub fn from_display(display: &str) -> Self {
+        let display = display.trim();
+        Self {
+            id: actor_id(display),
+            display: display.to_string(),
+        }
+    }
+}
+
+/// Normalize a display name or id into the registry key.
+pub fn actor_id(name: &str) -> String {
+    name.trim().to_lowercase()
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct Audience {
+    #[serde(default)]
+    pub groups: Vec<String>,
+    #[serde(default)]
+    pub targets: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Intent {
+    pub why: String,
+    pub whisper: String,
+    pub suggested_responses: Vec<String>,
+    /// Response window, not an expiry.
+    pub care_window_minutes: i64,
+}
+
+impl Intent {
+    /// Invitation to be witnessed (panic flows). Never an escalation.
+    pub fn care(whisper: &str) -> Self {
+        Self {
+            why: "signal-care".into(),
+            whisper: whisper.into(),
+            suggested_responses: vec!["soft-checkin".into(), "hold-space".into()],
+            care_window_minutes: 30,
+        }
+    }
+
+    /// Quiet state change (readiness lights).
+    pub fn state_change() -> Self {
+        Self {
+            why: "signal-state".into(),
+            whisper: "State change, not urgent.".into(),
+            suggested_responses: vec!["quiet-update".into()],
+            care_window_minutes: 120,
+        }
+    }
+}
+
+/// Default whisper for panic invitations (the redirect whisper itself is self-talk).
+pub const CARE_WHISPER: &str = "I'm here. Not urgent. I'd like company.";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Consent {
+    pub mode: String,
+    pub scope: Vec<String>,
+    pub granted_by: String,
+    pub granted_at: Option<String>,
+    pub expires_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Security {
+    pub nonce: String,
+    pub ts_window_sec: i64,
+}
+
+/// The spec envelope shared by all relational events.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Envelope {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub ts: String,
+    pub actor: Actor,
+    pub audience: Audience,
+    pub intent: Intent,
+    pub consent: Consent,
+    pub context: Value,
+    pub security: Security,
+}
+
+/// One row of the consent registry.
+#[derive(Debug, Clone, Serialize)]
+pub struct ConsentRow {
+    pub id: i64,
+    pub granted_by: String,
+    pub scope: String,
+    pub audience: String,
+    pub mode: String,
+    pub granted_at: String,
+    pub expires_at: Option<String>,
+    pub revoked_at: Option<String>,
+}
+
+impl ConsentRow {
+    /// Explicit, not revoked, not expired at `now`.
+    fn is_active(&self, now: DateTime<Utc>) -> bool {
+        if self.mode != "explicit" || self.revoked_at.is_some() {
+            return false;
+        }
+        match self.expires_at.as_deref() {
+            None => true,
+            Some(exp) => DateTime::parse_from_rfc3339(exp)
+                .map(|e| e.with_timezone(&Utc) > now)
+                // unparseable expiry ⇒ treat as expired (safe default)
+                .unwrap_or(false),
+        }
+    }
+}
+
+/// Router decision for one event type.
+#[derive(Debug)]
+pub enum Decision {
+    Deliver {
+        audience: Audience,
+        consent: Consent,
+    },
+    Withhold {
+        reason: &'static str,
+    },
+}
+
+/// Apply the consent rules to an actor's grants for `kind`.
+///
+/// Consent must be explicit, in scope, unrevoked and unexpired; the audience is the
+/// union of every matching grant. No matching grant ⇒ withhold (better to miss a DM
+/// than to violate consent).
+pub fn evaluate(
+    grants: &[ConsentRow],
+    granted_by: &str,
+    kind: &str,
+    now: DateTime<Utc>,
+) -> Decision {
+    let active: Vec<&ConsentRow> = grants
+        .iter()
+        .filter(|g| g.granted_by == granted_by && g.is_active(now))
+        .collect();
+    if active.is_empty() {
+        return Decision::Withhold {
+            reason: "no-consent",
+        };
+    }
+    let matching: Vec<&ConsentRow> = active.iter().copied().filter(|g| g.scope == kind).collect();
+    if matching.is_empty() {
+        return Decision::Withhold {
+            reason: "out-of-scope",
+        };
+    }
+
+    let mut audience = Audience::default();
+    for g in &matching {
+        if let Some(user) = g.audience.strip_prefix("user:") {
+            let t = format!("user:{user}");
+            if !audience.targets.contains(&t) {
+                audience.targets.push(t);
+            }
+        } else {
+            let group = g.audience.strip_prefix("group:").unwrap_or(&g.audience);
+            if !audience.groups.iter().any(|x| x == group) {
+                audience.groups.push(group.to_string());
+            }
+        }
+    }
+
+    let mut scope: Vec<String> = Vec::new();
+    for g in &active {
+        if !scope.contains(&g.scope) {
+            scope.push(g.scope.clone());
+        }
+    }
+    let granted_at = matching.iter().map(|g| g.granted_at.clone()).min();
+    let expires_at = matching.iter().filter_map(|g| g.expires_at.clone()).min();
+
+    Decision::Deliver {
+        audience,
+        consent: Consent {
+            mode: "explicit".into(),
+            scope,
+            granted_by: granted_by.to_string(),
+            granted_at,
+            expires_at,
+        },
+    }
+}
+
+/// `evt_` + millis (hex) + random suffix; sortable by time like the spec ids.
+pub fn new_event_id() -> String {
+    let mut r = [0u8; 6];
+    rand::thread_rng().fill_bytes(&mut r);
+    format!(
+        "evt_{:012X}{}",
+        Utc::now().timestamp_millis(),
+        hex::encode_upper(r)
+    )
+}
+
+fn new_nonce() -> String {
+    let mut r = [0u8; 12];
+    rand::thread_rng().fill_bytes(&mut r);
+    hex::encode(r)
+}
+
+/// Load every grant issued by `granted_by` (active or not; `evaluate` filters).
+pub async fn load_grants(
+    db: &crate::db::Database,
+    granted_by: Option<String>,
+) -> tokio_rusqlite::Result<Vec<ConsentRow>> {
+    db.0.call(move |c| {
+        let mut sql = String::from(
+            "SELECT id, granted_by, scope, audience, mode, granted_at, expires_at, revoked_at
+             FROM consents",
+        );
+        if granted_by.is_some() {
+            sql.push_str(" WHERE granted_by = ?1");
+        }
+        sql.push_str(" ORDER BY id ASC");
+        let mut stmt = c.prepare(&sql)?;
+        let map = |r: &rusqlite::Row<'_>| {
+            Ok(ConsentRow {
+                id: r.get(0)?,
+                granted_by: r.get(1)?,
+                scope: r.get(2)?,
+                audience: r.get(3)?,
+                mode: r.get(4)?,
+                granted_at: r.get(5)?,
+                expires_at: r.get(6)?,
+                revoked_at: r.get(7)?,
+            })
+        };
+        let rows = match granted_by.as_ref() {
+            Some(g) => stmt.query_map([g], map)?.collect::<Result<Vec<_>, _>>()?,
+            None => stmt.query_map([], map)?.collect::<Result<Vec<_>, _>>()?,
+        };
+        Ok(rows)
+    })
+    .await
+}
+
+/// What happened to an emitted event.
+#[derive(Debug, Clone, Serialize)]
+pub struct Emitted {
+    pub id: String,
+    /// Delivery was started (it finishes in the background).
+    pub queued: bool,
+    /// Why it was withheld.
+    pub reason: Option<String>,
+}
+
+/// Build the envelope for `kind`, apply consent, record it, and deliver when allowed.
+///
+/// The event row is written before this returns (so `care.ack` can name it); the
+/// webhook fan-out runs on its own task and flips `delivered` (or records the
+/// `transport:` failure) when it finishes, so a slow receiver never holds up the
+/// caller. Best-effort like the rest of the webhook surface: failures are logged,
+/// never bubbled to the HTTP caller.
+pub async fn emit(
+    state: &AppState,
+    kind: &str,
+    actor: &Actor,
+    intent: Intent,
+    context: Value,
+) -> Emitted {
+    let now = Utc::now();
+    let grants = load_grants(&state.db, Some(actor.id.clone()))
+        .await
+        .unwrap_or_else(|e| {
+            tracing::warn!(error = ?e, "relational: failed to load consent grants");
+            Vec::new()
+        });
+
+    let (audience, consent, withheld) = match evaluate(&grants, &actor.id, kind, now) {
+        Decision::Deliver { audience, consent } => (audience, consent, None),
+        Decision::Withhold { reason } => (
+            Audience::default(),
+            Consent {
+                mode: "pending".into(),
+                scope: Vec::new(),
+                granted_by: actor.id.clone(),
+                granted_at: None,
+                expires_at: None,
+            },
+            Some(reason),
+        ),
+    };
+
+    let env = Envelope {
+        id: new_event_id(),
+        kind: kind.to_string(),
+        ts: now.to_rfc3339(),
+        actor: actor.clone(),
+        audience,
+        intent,
+        consent,
+        context,
+        security: Security {
+            nonce: new_nonce(),
+            ts_window_sec: TS_WINDOW_SEC,
+        },
+    };
+    let body = serde_json::to_value(&env).unwrap_or(Value::Null);
+
+    let reason = withheld.map(|reason| {
+        tracing::info!(event = kind, actor = %actor.id, reason, "relational: withheld (pending consent)");
+        format!("pending-consent:{reason}")
+    });
+
+    let row = (
+        env.id.clone(),
+        env.kind.clone(),
+        env.ts.clone(),
+        env.actor.id.clone(),
+        reason.clone(),
+        body.to_string(),
+    );
+    if let Err(e) = state
+        .db
+        .0
+        .call(move |c| {
+            c.execute(
+                "INSERT INTO relational_events(id, type, ts, actor, delivered, reason, envelope)
+                 VALUES(?1, ?2, ?3, ?4, 0, ?5, ?6)",
+                rusqlite::params![row.0, row.1, row.2, row.3, row.4, row.5],
+            )?;
+            Ok(())
+        })
+        .await
+    {
+        tracing::warn!(error = ?e, "relational: failed to record event");
+    }
+
+    let queued = reason.is_none();
+    if queued {
+        tokio::spawn(deliver(
+            state.clone(),
+            env.id.clone(),
+            env.kind.clone(),
+            body,
+        ));
+    }
+
+    Emitted {
+        id: env.id,
+        queued,
+        reason,
+    }
+}
+
+/// Fan one recorded event out and note the result on its row.
+async fn deliver(state: AppState, id: String, kind: String, body: Value) {
+    let reason = match crate::hooks::dispatch(&state, &kind, &body).await {
+        Ok(()) => None,
+        Err(e) => {
+            tracing::warn!(error = ?e, event = %kind, "relational: webhook failed");
+            Some(format!("transport:{e}"))
+        }
+    };
+    if let Err(e) = state
+        .db
+        .0
+        .call(move |c| {
+            c.execute(
+                "UPDATE relational_events SET delivered = ?1, reason = ?2 WHERE id = ?3",
+                rusqlite::params![reason.is_none(), reason, id],
+            )?;
+            Ok(())
+        })
+        .await
+    {
+        tracing::warn!(error = ?e, "relational: failed to record delivery");
+    }
+}
+
+// ---- HTTP: consent registry ------------------------------------------------
+
+#[derive(Debug, Deserialize)]
+struct GrantIn {
+    granted_by: String,
+    scope: Vec<String>,
+    #[serde(default)]
+    audience: Audience,
+    /// RFC3339; wins over `ttl_minutes` when both are set.
+    #[serde(default)]
+    expires_at: Option<String>,
+    #[serde(default)]
+    ttl_minutes: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ListParams {
+    #[serde(default)]
+    granted_by: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RevokeIn {
+    id: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct PendingParams {
+    #[serde(default)]
+    actor: Option<String>,
+    #[serde(default)]
+    limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+struct PendingOut {
+    id: String,
+    #[serde(rename = "type")]
+    kind: String,
+    ts: String,
+    actor: String,
+    reason: Option<String>,
+}
+
+/// GET /consent — list grants (optionally for one actor).
+async fn list_grants(
+    State(state): State<AppState>,
+    Query(q): Query<ListParams>,
+) -> Result<Json<Vec<ConsentRow>>, StatusCode> {
+    let rows = load_grants(&state.db, q.granted_by.as_deref().map(actor_id))
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    Ok(Json(rows))
+}
+
+/// POST /consent — grant one or more event types to an audience.
+async fn grant(
+    State(state): State<AppState>,
+    Json(input): Json<GrantIn>,
+) -> Result<Json<Vec<ConsentRow>>, (StatusCode, String)> {
+    let granted_by = actor_id(&input.granted_by);
+    if granted_by.is_empty() {
+        return Err((
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "granted_by required".into(),
+        ));
+    }
+    let scope: Vec<String> = input
+        .scope
+        .iter()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect();
+    if scope.is_empty() {
+        return Err((StatusCode::UNPROCESSABLE_ENTITY, "scope required".into()));
+    }
+    let mut audience: Vec<String> = Vec::new();
+    for g in input
+        .audience
+        .groups
+        .iter()
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+    {
+        audience.push(format!("group:{}", g.trim_start_matches("group:")));
+    }
+    for t in input
+        .audience
+        .targets
+        .iter()
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+    {
+        audience.push(format!("user:{}", t.trim_start_matches("user:")));
+    }
+    if audience.is_empty() {
+        return Err((StatusCode::UNPROCESSABLE_ENTITY, "audience required".into()));
+    }
+
+    let now = Utc::now();
+    let expires_at = match (input.expires_at, input.ttl_minutes) {
+        (Some(e), _) => {
+            let parsed = DateTime::parse_from_rfc3339(&e).map_err(|_| {
+                (
+                    StatusCode::UNPROCESSABLE_ENTITY,
+                    "expires_at must be RFC3339".to_string(),
+                )
+            })?;
+            let parsed = parsed.with_timezone(&Utc);
+            if parsed <= now {
+                return Err((
+                    StatusCode::UNPROCESSABLE_ENTITY,
+                    "expires_at must be in the future".to_string(),
+                ));
+            }
+            Some(parsed.to_rfc3339())
+        }
+        (None, Some(m)) if !(1..=MAX_GRANT_TTL_MINUTES).contains(&m) => {
+            return Err((
+                StatusCode::UNPROCESSABLE_ENTITY,
+                format!("ttl_minutes must be between 1 and {MAX_GRANT_TTL_MINUTES}"),
+            ));
+        }
+        (None, Some(m)) => Some((now + chrono::Duration::minutes(m)).to_rfc3339()),
+        (None, None) => None,
+    };
+    let granted_at = now.to_rfc3339();
+
+    let ids: Vec<i64> = state
+        .db
+        .0
+        .call(move |c| {
+            let tx = c.transaction()?;
+            let mut ids = Vec::new();
+            for s in &scope {
+                for a in &audience {
+                    tx.execute(
+                        "INSERT INTO consents(granted_by, scope, audience, mode, granted_at, expires_at)
+                         VALUES(?1, ?2, ?3, 'explicit', ?4, ?5)",
+                        rusqlite::params![granted_by, s, a, granted_at, expires_at],
+                    )?;
+                    ids.push(tx.last_insert_rowid());
+                }
+            }
+            tx.commit()?;
+            Ok(ids)
+        })
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let all = load_grants(&state.db, None)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(Json(
+        all.into_iter().filter(|r| ids.contains(&r.id)).collect(),
+    ))
+}
+
+/// POST /consent/revoke — stop a grant now (kept for audit).
+async fn revoke(
+    State(state): State<AppState>,
+    Json(input): Json<RevokeIn>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    let now = Utc::now().to_rfc3339();
+    let n = state
+        .db
+        .0
+        .call(move |c| {
+            Ok(c.execute(
+                "UPDATE consents SET revoked_at=?1 WHERE id=?2 AND revoked_at IS NULL",
+                rusqlite::params![now, input.id],
+            )?)
+        })
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    if n == 0 {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    Ok(Json(serde_json::json!({ "ok": true })))
+}
+
+/// GET /consent/pending — events withheld for missing/expired/out-of-scope consent.
+/// Lets the UI ask the actor: "Grant consent to invite your circle?"
+async fn pending(
+    State(state): State<AppState>,
+    Query(q): Query<PendingParams>,
+) -> Result<Json<Vec<PendingOut>>, StatusCode> {
+    let actor = q.actor.as_deref().map(actor_id);
+    let limit = q.limit.unwrap_or(20).clamp(1, 200);
+    let rows = state
+        .db
+        .0
+        .call(move |c| {
+            let mut sql = String::from(
+                "SELECT id, type, ts, actor, reason FROM relational_events
+                 WHERE delivered = 0 AND reason LIKE 'pending-consent:%'",
+            );
+            if actor.is_some() {
+                sql.push_str(" AND actor = ?2");
+            }
+            sql.push_str(" ORDER BY ts DESC LIMIT ?1");
+            let mut stmt = c.prepare(&sql)?;
+            let map = |r: &rusqlite::Row<'_>| {
+                Ok(PendingOut {
+                    id: r.get(0)?,
+                    kind: r.get(1)?,
+                    ts: r.get(2)?,
+                    actor: r.get(3)?,
+                    reason: r.get(4)?,
+                })
+            };
+            let out = match actor.as_ref() {
+                Some(a) => stmt
+                    .query_map(rusqlite::params![limit, a], map)?
+                    .collect::<Result<Vec<_>, _>>()?,
+                None => stmt
+                    .query_map(rusqlite::params![limit], map)?
+                    .collect::<Result<Vec<_>, _>>()?,
+            };
+            Ok(out)
+        })
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    Ok(Json(rows))
+}
+
+/// Router to be mounted under `/consent`.
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_grants).post(grant))
+        .route("/revoke", post(revoke))
+        .route("/pending", get(pending))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(id: i64, scope: &str, audience: &str, expires_at: Option<&str>) -> ConsentRow {
+        ConsentRow {
+            id,
+            granted_by: "raz".into(),
+            scope: scope.into(),
+            audience: audience.into(),
+            mode: "explicit".into(),
+            granted_at: "2026-01-01T00:00:00Z".into(),
+            expires_at: expires_at.map(str::to_string),
+            revoked_at: None,
+        }
+    }
+
+    fn at(ts: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(ts)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn no_grants_withholds() {
+        let d = evaluate(&[], "raz", "panic.ui", at("2026-01-07T10:12:00Z"));
+        assert!(matches!(
+            d,
+            Decision::Withhold {
+                reason: "no-consent"
+            }
+        ));
+    }
+
+    #[test]
+    fn status_set_needs_a_grant_like_every_kind() {
+        let d = evaluate(&[], "raz", "status.set", at("2026-01-07T10:12:00Z"));
+        assert!(matches!(
+            d,
+            Decision::Withhold {
+                reason: "no-consent"
+            }
+        ));
+
+        let grants = [row(1, "status.set", "user:ana", None)];
+        match evaluate(&grants, "raz", "status.set", at("2026-01-07T10:12:00Z")) {
+            Decision::Deliver { audience, consent } => {
+                assert_eq!(audience.targets, vec!["user:ana"]);
+                assert_eq!(consent.mode, "explicit");
+            }
+            other => panic!("expected delivery, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn status_set_is_withheld_when_no_grant_exists() {
+        let state = crate::test_state().await;
+        let out = emit(
+            &state,
+            "status.set",
+            &Actor::from_display("Raz"),
+            Intent::state_change(),
+            serde_json::json!({ "who": "Raz", "status": "red" }),
+        )
+        .await;
+        assert!(!out.queued);
+        assert_eq!(out.reason.as_deref(), Some("pending-consent:no-consent"));
+    }
+
+    #[tokio::test]
+    async fn grants_need_a_positive_ttl_and_a_future_expiry() {
+        use tower::ServiceExt; // for `oneshot`
+        let app = router().with_state(crate::test_state().await);
+        let post = |extra: &str| {
+            axum::http::Request::post("/")
+                .header("content-type", "application/json")
+                .body(axum::body::Body::from(format!(
+                    r#"{{"granted_by":"raz","scope":["panic.ui"],"audience":{{"groups":["care"]}}{extra}}}"#
+                )))
+                .unwrap()
+        };
+        for extra in [
+            r#","ttl_minutes":0"#,
+            r#","ttl_minutes":-5"#,
+            r#","ttl_minutes":525601"#,
+            r#","expires_at":"2020-01-01T00:00:00Z""#,
+        ] {
+            let res = app.clone().oneshot(post(extra)).await.unwrap();
+            assert_eq!(res.status(), StatusCode::UNPROCESSABLE_ENTITY, "{extra}");
+        }
+        let res = app.oneshot(post(r#","ttl_minutes":60"#)).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+
+    #[test]
+    fn out_of_scope_withholds() {
+        let grants = [row(1, "status.set", "group:care-circle", None)];
+        let d = evaluate(&grants, "raz", "panic.ui", at("2026-01-07T10:12:00Z"));
+        assert!(matches!(
+            d,
+            Decision::Withhold {
+                reason: "out-of-scope"
+            }
+        ));
+    }
+
+    #[test]
+    fn expired_and_revoked_grants_do_not_count() {
+        let mut revoked = row(2, "panic.ui", "user:ana", None);
+        revoked.revoked_at = Some("2026-01-02T00:00:00Z".into());
+        let grants = [
+            row(
+                1,
+                "panic.ui",
+                "group:care-circle",
+                Some("2026-01-05T00:00:00Z"),
+            ),
+            revoked,
+        ];
+        let d = evaluate(&grants, "raz", "panic.ui", at("2026-01-07T10:12:00Z"));
+        assert!(matches!(d, Decision::Withhold { .. }));
+    }
+
+    #[test]
+    fn grants_from_other_actors_are_ignored() {
+        let mut other = row(1, "panic.ui", "group:care-circle", None);
+        other.granted_by = "ana".into();
+        let d = evaluate(&[other], "raz", "panic.ui", at("2026-01-07T10:12:00Z"));
+        assert!(matches!(d, Decision::Withhold { .. }));
+    }
+
+    #[test]
+    fn matching_grants_build_audience_and_consent() {
+        let grants = [
+            row(1, "panic.ui", "group:care-circle", None),
+            row(2, "panic.ui", "user:ana", Some("2026-02-01T00:00:00Z")),
+            row(3, "status.set", "group:care-circle", None),
+        ];
+        match evaluate(&grants, "raz", "panic.ui", at("2026-01-07T10:12:00Z")) {
+            Decision::Deliver { audience, consent } => {
+                assert_eq!(audience.groups, vec!["care-circle"]);
+                assert_eq!(audience.targets, vec!["user:ana"]);
+                assert_eq!(consent.mode, "explicit");
+                assert_eq!(consent.scope, vec!["panic.ui", "status.set"]);
+                assert_eq!(consent.granted_by, "raz");
+                assert_eq!(consent.expires_at.as_deref(), Some("2026-02-01T00:00:00Z"));
+            }
+            other => panic!("expected delivery, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn panic_intent_is_an_invitation() {
+        let i = Intent::care(CARE_WHISPER);
+        assert_eq!(i.why, "signal-care");
+        assert_eq!(i.care_window_minutes, 30);
+        assert!(i.suggested_responses.contains(&"hold-space".to_string()));
+    }
+
+    #[test]
+    fn envelope_serializes_type_field() {
+        let env = Envelope {
+            id: new_event_id(),
+            kind: "panic.ui".into(),
+            ts: "2026-01-07T10:12:00Z".into(),
+            actor: Actor::from_display("Raz"),
+            audience: Audience::default(),
+            intent: Intent::care(CARE_WHISPER),
+            consent: Consent {
+                mode: "explicit".into(),
+                scope: vec!["panic.ui".into()],
+                granted_by: "raz".into(),
+                granted_at: None,
+                expires_at: None,
+            },
+            context: serde_json::json!({ "severity": "gentle" }),
+            security: Security {
+                nonce: "a1".into(),
+                ts_window_sec: TS_WINDOW_SEC,
+            },
+        };
+        let v = serde_json::to_value(&env).unwrap();
+        assert_eq!(v["type"], "panic.ui");
+        assert_eq!(v["actor"]["id"], "raz");
+        assert!(v["id"].as_str().unwrap().starts_with("evt_"));
+        assert_eq!(v["security"]["ts_window_sec"], 300);
+    }
+}
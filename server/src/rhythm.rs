@@ -1,14 +1,67 @@
+//! Rhythm — work/rest cycles per person, kept in SQLite
+//! ----------------------------------------------------
+//! Whisper: "the bell rings for you, not for the room." 🔔
+//!
+//! A simple ultradian rhythm: alternate Work/Rest blocks. Every person (`?who=`, default
+//! `team` — the shared rhythm) has:
+//!   • `rhythm_configs` — their block lengths (default 50/10).
+//!   • `rhythm_sessions` — one cycle each, from `POST /rhythm/reset` (or first use) until the
+//!     next reset. At most one session per person is open.
+//!   • `rhythm_marks` — phase boundaries inside a session. The current phase is the latest
+//!     mark of the open session, so a restart picks up where it left off.
+//!
+//! Auto-advance (`auto_advance: true` in the config): the phase flips by itself at
+//! `ends_at` + `grace_secs`. The binary runs the clock (rhythm_clock.rs), so it keeps time
+//! with no tab open; reading `/rhythm/next` also catches up. A server that was down for
+//! more than the next block starts it at the moment it noticed. `POST /rhythm/snooze`
+//! pushes the running block's end out (60 min per block at most).
+//!
+//! Every flip (manual or automatic, not a session start) is announced once as `rhythm:<json>`
+//! on the bus → named `rhythm` SSE event, and as a `rhythm.phase` relational event. With
+//! `doorway_mode` (a panic preset, e.g. `default`), flips into Rest carry a suggested
+//! whisper/breath/doorway/anchor picked like a panic run.
+//!
+//! Endpoints (mounted under `/rhythm`, all take `?who=`)
+//!   GET  /rhythm/next      → { who, session_id, phase, started_at, ends_at, remaining_secs,
+//!                              config }
+//!   POST /rhythm/mark      → flip the phase now → the new mark
+//!   POST /rhythm/reset     → close the session, start a new one in Work → pulse
+//!   GET  /rhythm/config    → the person's config
+//!   POST /rhythm/config    → { work_minutes, rest_minutes, auto_advance?, grace_secs?,
+//!                              doorway_mode? } (minutes 1..=480, grace ≤ 3600)
+//!   POST /rhythm/snooze    → { minutes? } (default 5) → pulse; 409 past the cap
+//!   GET  /rhythm/history   → marks, newest first: `from`, `to` (RFC3339 or YYYY-MM-DD),
+//!                            `session`, cursor `before=<id>`, `limit` ≤ 500
+//!   GET  /rhythm/sessions  → sessions, newest first: `before`, `limit` ≤ 200
+
+use crate::energy::parse_bound;
 use crate::AppState;
 use axum::{
+    extract::{Query, State},
+    http::StatusCode,
     routing::{get, post},
     Json, Router,
 };
+use rusqlite::OptionalExtension;
 use serde::{Deserialize, Serialize};
-use std::{
-    sync::{Arc, OnceLock},
-    time::{Duration, SystemTime},
-};
-use tokio::sync::RwLock;
+
+type ApiErr = (StatusCode, String);
+
+fn unprocessable(msg: impl Into<String>) -> ApiErr {
+    (StatusCode::UNPROCESSABLE_ENTITY, msg.into())
+}
+
+fn internal(e: impl std::fmt::Display) -> ApiErr {
+    (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+}
+
+/// Whose rhythm it is when `who` is absent.
+pub const SHARED: &str = "team";
+const MAX_MINUTES: u32 = 8 * 60;
+const MAX_GRACE_SECS: u32 = 60 * 60;
+const DEFAULT_SNOOZE_MINUTES: i64 = 5;
+/// Snooze allowed per block, in total.
+const MAX_SNOOZE_SECS: i64 = 60 * 60;
 
 /// Which phase are we currently in?
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
@@ -18,13 +71,46 @@ pub enum Phase {
     Rest,
 }
 
+impl Phase {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Phase::Work => "work",
+            Phase::Rest => "rest",
+        }
+    }
+
+    fn parse(s: &str) -> Phase {
+        if s == "rest" {
+            Phase::Rest
+        } else {
+            Phase::Work
+        }
+    }
+
+    fn flip(self) -> Phase {
+        match self {
+            Phase::Work => Phase::Rest,
+            Phase::Rest => Phase::Work,
+        }
+    }
+}
+
 /// Config for a simple ultradian rhythm: alternate Work/Rest blocks.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct RhythmConfig {
     /// Length of a work block, in minutes.
     pub work_minutes: u32,
     /// Length of a rest block, in minutes.
     pub rest_minutes: u32,
+    /// Flip phases on the server when a block ends.
+    #[serde(default)]
+    pub auto_advance: bool,
+    /// Extra seconds before an automatic flip.
+    #[serde(default)]
+    pub grace_secs: u32,
+    /// Panic preset to suggest a doorway from when a rest block starts.
+    #[serde(default)]
+    pub doorway_mode: Option<String>,
 }
 
 impl Default for RhythmConfig {
@@ -32,158 +118,755 @@ impl Default for RhythmConfig {
         Self {
             work_minutes: 50,
             rest_minutes: 10,
+            auto_advance: false,
+            grace_secs: 0,
+            doorway_mode: None,
         }
     }
 }
 
+impl RhythmConfig {
+    /// Seconds a block of `phase` lasts.
+    pub fn secs(&self, phase: Phase) -> i64 {
+        let minutes = match phase {
+            Phase::Work => self.work_minutes,
+            Phase::Rest => self.rest_minutes,
+        };
+        i64::from(minutes) * 60
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        let ok = |m: u32| (1..=MAX_MINUTES).contains(&m);
+        if !ok(self.work_minutes) || !ok(self.rest_minutes) {
+            return Err(format!(
+                "work_minutes and rest_minutes must be within 1..={MAX_MINUTES}"
+            ));
+        }
+        if self.grace_secs > MAX_GRACE_SECS {
+            return Err(format!("grace_secs must be at most {MAX_GRACE_SECS}"));
+        }
+        Ok(())
+    }
+}
+
 /// A single boundary marker (the moment we switched phases).
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Mark {
+    pub id: i64,
+    pub who: String,
+    pub session_id: i64,
     /// Unix epoch seconds
     pub at: i64,
+    /// `at` as RFC3339 UTC
+    pub ts: String,
     pub phase: Phase,
+    /// start | manual | auto
+    pub source: String,
+    /// Seconds this block was snoozed.
+    pub snoozed_secs: i64,
+    /// Doorway for a rest block (see `doorway_mode`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub suggestion: Option<Suggestion>,
 }
 
-/// State is intentionally tiny and in-memory.
-/// If the server restarts, we simply start a new cycle.
-#[derive(Debug)]
-struct RhythmState {
-    config: RhythmConfig,
-    /// When did the current phase start? (Unix epoch seconds)
-    phase_started_at: i64,
-    current: Phase,
-    history: Vec<Mark>,
-}
-
-impl Default for RhythmState {
-    fn default() -> Self {
-        let now = now_secs();
-        Self {
-            config: RhythmConfig::default(),
-            phase_started_at: now,
-            current: Phase::Work,
-            history: vec![Mark {
-                at: now,
-                phase: Phase::Work,
-            }],
-        }
-    }
-}
-
-static STATE: OnceLock<Arc<RwLock<RhythmState>>> = OnceLock::new();
-
-fn state() -> &'static Arc<RwLock<RhythmState>> {
-    STATE.get_or_init(|| Arc::new(RwLock::new(RhythmState::default())))
+/// Steps picked from a panic preset for a rest block.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Suggestion {
+    pub mode: String,
+    pub whisper: String,
+    pub breath: String,
+    pub doorway: String,
+    pub anchor: String,
 }
 
 /// Public shape returned to the UI for "what's happening now?"
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Pulse {
+    pub who: String,
+    pub session_id: i64,
     pub phase: Phase,
-    /// Epoch seconds when the current phase ends
+    /// Epoch seconds when the current phase started
+    pub started_at: i64,
+    /// Epoch seconds when the current phase ends (snoozes included)
     pub ends_at: i64,
     /// Seconds remaining in the current phase (clamped to ≥ 0)
     pub remaining_secs: i64,
+    /// With auto-advance: when the server flips the phase (`ends_at` + grace)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub advances_at: Option<i64>,
+    pub snoozed_secs: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub suggestion: Option<Suggestion>,
     /// The active config, echoed for the client to render
     pub config: RhythmConfig,
 }
 
-/// GET /rhythm/next — describe the active phase and when it ends
-pub async fn get_next() -> Json<Pulse> {
-    let st = state().read().await;
-    let duration = match st.current {
-        Phase::Work => st.config.work_minutes,
-        Phase::Rest => st.config.rest_minutes,
-    } as i64
-        * 60;
-
-    let ends_at = st.phase_started_at + duration;
-    let remaining = (ends_at - now_secs()).max(0);
-
-    Json(Pulse {
-        phase: st.current,
-        ends_at,
-        remaining_secs: remaining,
-        config: st.config.clone(),
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Session {
+    pub id: i64,
+    pub who: String,
+    pub started_at: String,
+    /// Absent while the session is open.
+    pub ended_at: Option<String>,
+    pub marks: i64,
+}
+
+fn rfc3339(secs: i64) -> String {
+    chrono::DateTime::from_timestamp(secs, 0)
+        .unwrap_or_default()
+        .to_rfc3339()
+}
+
+fn epoch(ts: &str) -> i64 {
+    crate::energy::parse_stored(ts)
+        .map(|t| t.timestamp())
+        .unwrap_or_default()
+}
+
+const MARK_COLS: &str = "id, who, session_id, ts, phase, source, extend_secs, suggestion";
+
+fn row_to_mark(r: &rusqlite::Row) -> rusqlite::Result<Mark> {
+    let ts: String = r.get(3)?;
+    Ok(Mark {
+        id: r.get(0)?,
+        who: r.get(1)?,
+        session_id: r.get(2)?,
+        at: epoch(&ts),
+        ts,
+        phase: Phase::parse(&r.get::<_, String>(4)?),
+        source: r.get(5)?,
+        snoozed_secs: r.get(6)?,
+        suggestion: r
+            .get::<_, Option<String>>(7)?
+            .and_then(|raw| serde_json::from_str(&raw).ok()),
     })
 }
 
-/// POST /rhythm/mark — acknowledge & flip phase boundary "now"
-///
-/// This is idempotent in the sense that it always toggles the phase once
-/// and records a single mark at the time of the call.
-pub async fn post_mark() -> Json<Mark> {
-    let mut st = state().write().await;
-    st.current = match st.current {
-        Phase::Work => Phase::Rest,
-        Phase::Rest => Phase::Work,
+/// The person's config, or the default.
+pub fn config(c: &rusqlite::Connection, who: &str) -> rusqlite::Result<RhythmConfig> {
+    Ok(c.query_row(
+        "SELECT work_minutes, rest_minutes, auto_advance, grace_secs, doorway_mode
+           FROM rhythm_configs WHERE who = ?1",
+        [who],
+        |r| {
+            Ok(RhythmConfig {
+                work_minutes: r.get(0)?,
+                rest_minutes: r.get(1)?,
+                auto_advance: r.get(2)?,
+                grace_secs: r.get(3)?,
+                doorway_mode: r.get(4)?,
+            })
+        },
+    )
+    .optional()?
+    .unwrap_or_default())
+}
+
+pub fn set_config(
+    c: &rusqlite::Connection,
+    who: &str,
+    cfg: &RhythmConfig,
+    now: i64,
+) -> rusqlite::Result<Result<RhythmConfig, String>> {
+    if let Err(e) = cfg.validate() {
+        return Ok(Err(e));
+    }
+    let mut cfg = cfg.clone();
+    cfg.doorway_mode = cfg
+        .doorway_mode
+        .map(|m| m.trim().to_owned())
+        .filter(|m| !m.is_empty());
+    if let Some(mode) = &cfg.doorway_mode {
+        if crate::panic::find(c, mode)?.is_none() {
+            return Ok(Err(format!("unknown panic preset `{mode}`")));
+        }
+    }
+    c.execute(
+        "INSERT INTO rhythm_configs(who, work_minutes, rest_minutes, auto_advance, grace_secs,
+                                    doorway_mode, updated_at)
+         VALUES(?1, ?2, ?3, ?4, ?5, ?6, ?7)
+         ON CONFLICT(who) DO UPDATE SET work_minutes = excluded.work_minutes,
+           rest_minutes = excluded.rest_minutes, auto_advance = excluded.auto_advance,
+           grace_secs = excluded.grace_secs, doorway_mode = excluded.doorway_mode,
+           updated_at = excluded.updated_at",
+        rusqlite::params![
+            who,
+            cfg.work_minutes,
+            cfg.rest_minutes,
+            cfg.auto_advance,
+            cfg.grace_secs,
+            cfg.doorway_mode,
+            rfc3339(now)
+        ],
+    )?;
+    Ok(Ok(cfg))
+}
+
+/// Record a boundary; flips (not session starts) wait in `pending` to be announced.
+fn insert_mark(
+    c: &rusqlite::Connection,
+    who: &str,
+    session_id: i64,
+    at: i64,
+    phase: Phase,
+    source: &str,
+    suggestion: Option<Suggestion>,
+) -> rusqlite::Result<Mark> {
+    let ts = rfc3339(at);
+    let raw = suggestion
+        .as_ref()
+        .and_then(|s| serde_json::to_string(s).ok());
+    c.execute(
+        "INSERT INTO rhythm_marks(session_id, who, ts, phase, source, suggestion, pending)
+         VALUES(?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        rusqlite::params![
+            session_id,
+            who,
+            ts,
+            phase.as_str(),
+            source,
+            raw,
+            source != "start"
+        ],
+    )?;
+    Ok(Mark {
+        id: c.last_insert_rowid(),
+        who: who.to_owned(),
+        session_id,
+        at,
+        ts,
+        phase,
+        source: source.to_owned(),
+        snoozed_secs: 0,
+        suggestion,
+    })
+}
+
+fn start_session(c: &rusqlite::Connection, who: &str, now: i64) -> rusqlite::Result<Mark> {
+    c.execute(
+        "INSERT INTO rhythm_sessions(who, started_at) VALUES(?1, ?2)",
+        rusqlite::params![who, rfc3339(now)],
+    )?;
+    insert_mark(
+        c,
+        who,
+        c.last_insert_rowid(),
+        now,
+        Phase::Work,
+        "start",
+        None,
+    )
+}
+
+/// A doorway from the configured panic preset, for a rest block starting at `at`.
+fn suggest(
+    c: &rusqlite::Connection,
+    who: &str,
+    cfg: &RhythmConfig,
+    at: i64,
+) -> rusqlite::Result<Option<Suggestion>> {
+    let Some(mode) = &cfg.doorway_mode else {
+        return Ok(None);
     };
-    st.phase_started_at = now_secs();
-    let mark = Mark {
-        at: st.phase_started_at,
-        phase: st.current,
+    let Some(preset) = crate::panic::find(c, mode)? else {
+        return Ok(None);
     };
-    st.history.push(mark.clone());
-    Json(mark)
+    let now = chrono::DateTime::from_timestamp(at, 0).unwrap_or_default();
+    let pick = crate::panic::choose(c, &preset, None, Some(who), now)?;
+    Ok(Some(Suggestion {
+        mode: pick.mode,
+        whisper: pick.whisper,
+        breath: pick.breath,
+        doorway: pick.doorway,
+        anchor: pick.anchor,
+    }))
 }
 
-/// POST /rhythm/reset — start a brand-new cycle (Work begins now)
-pub async fn post_reset() -> Json<Pulse> {
-    // Update state and capture the values we need without holding the lock across awaits.
-    let (phase, started_at, cfg) = {
-        let mut st = state().write().await;
-        st.current = Phase::Work;
-        st.phase_started_at = now_secs();
-        st.history.clear();
-        // Build the mark without simultaneously reading from `st` during the `push`
-        let started_at = st.phase_started_at;
-        let mark = Mark {
-            at: started_at,
-            phase: Phase::Work,
-        };
-        st.history.push(mark);
-        (st.current, st.phase_started_at, st.config.clone())
+/// Move `cur`'s person into the other phase at `at`.
+fn flip(
+    c: &rusqlite::Connection,
+    cur: &Mark,
+    cfg: &RhythmConfig,
+    at: i64,
+    source: &str,
+) -> rusqlite::Result<Mark> {
+    let phase = cur.phase.flip();
+    let suggestion = match phase {
+        Phase::Rest => suggest(c, &cur.who, cfg, at)?,
+        Phase::Work => None,
     };
+    insert_mark(c, &cur.who, cur.session_id, at, phase, source, suggestion)
+}
 
-    // Compute the next pulse after releasing the write lock.
-    let duration_secs = match phase {
-        Phase::Work => cfg.work_minutes,
-        Phase::Rest => cfg.rest_minutes,
-    } as i64
-        * 60;
+/// When the block that `cur` started ends, snoozes included.
+fn ends_at(cur: &Mark, cfg: &RhythmConfig) -> i64 {
+    cur.at + cfg.secs(cur.phase) + cur.snoozed_secs
+}
 
-    let ends_at = started_at + duration_secs;
-    let remaining = (ends_at - now_secs()).max(0);
+/// With auto-advance on and the block (plus grace) over, flip once.
+fn advance(c: &rusqlite::Connection, who: &str, now: i64) -> rusqlite::Result<Option<Mark>> {
+    let cfg = config(c, who)?;
+    if !cfg.auto_advance {
+        return Ok(None);
+    }
+    let cur = current(c, who, now)?;
+    let due = ends_at(&cur, &cfg) + i64::from(cfg.grace_secs);
+    if due > now {
+        return Ok(None);
+    }
+    // back after missing a whole block: start the next one now, not in the past
+    let at = if now - due < cfg.secs(cur.phase.flip()) {
+        due
+    } else {
+        now
+    };
+    flip(c, &cur, &cfg, at, "auto").map(Some)
+}
 
-    Json(Pulse {
-        phase,
+/// Flip everyone whose auto-advanced block is over. Returns the new marks.
+pub fn advance_due(c: &mut rusqlite::Connection, now: i64) -> rusqlite::Result<Vec<Mark>> {
+    let tx = c.transaction()?;
+    let people = tx
+        .prepare(
+            "SELECT k.who FROM rhythm_configs k
+               JOIN rhythm_sessions s ON s.who = k.who AND s.ended_at IS NULL
+              WHERE k.auto_advance = 1",
+        )?
+        .query_map([], |r| r.get::<_, String>(0))?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    let mut out = Vec::new();
+    for who in people {
+        out.extend(advance(&tx, &who, now)?);
+    }
+    tx.commit()?;
+    Ok(out)
+}
+
+/// A flip to announce, with when its block ends.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PhaseEvent {
+    #[serde(flatten)]
+    pub mark: Mark,
+    pub ends_at: i64,
+}
+
+/// Flips not announced yet, marked as announced (oldest first).
+pub fn take_pending(c: &mut rusqlite::Connection) -> rusqlite::Result<Vec<PhaseEvent>> {
+    let tx = c.transaction()?;
+    let marks = tx
+        .prepare(&format!(
+            "SELECT {MARK_COLS} FROM rhythm_marks WHERE pending = 1 ORDER BY id LIMIT 100"
+        ))?
+        .query_map([], row_to_mark)?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    let mut out = Vec::with_capacity(marks.len());
+    for mark in marks {
+        tx.execute(
+            "UPDATE rhythm_marks SET pending = 0 WHERE id = ?1",
+            [mark.id],
+        )?;
+        let cfg = config(&tx, &mark.who)?;
+        out.push(PhaseEvent {
+            ends_at: ends_at(&mark, &cfg),
+            mark,
+        });
+    }
+    tx.commit()?;
+    Ok(out)
+}
+
+/// Anything for the clock to do: an open auto-advance session or a mark not announced
+/// yet. A plain read, so idle ticks never open a transaction.
+pub fn clock_has_work(c: &rusqlite::Connection) -> rusqlite::Result<bool> {
+    c.query_row(
+        "SELECT EXISTS(SELECT 1 FROM rhythm_configs k
+                         JOIN rhythm_sessions s ON s.who = k.who AND s.ended_at IS NULL
+                        WHERE k.auto_advance = 1)
+             OR EXISTS(SELECT 1 FROM rhythm_marks WHERE pending = 1)",
+        [],
+        |r| r.get(0),
+    )
+}
+
+/// The latest mark of the open session; starts one in Work when there is none.
+pub fn current(c: &rusqlite::Connection, who: &str, now: i64) -> rusqlite::Result<Mark> {
+    let latest = c
+        .query_row(
+            &format!(
+                "SELECT {MARK_COLS} FROM rhythm_marks
+                  WHERE session_id = (SELECT id FROM rhythm_sessions
+                                       WHERE who = ?1 AND ended_at IS NULL)
+                  ORDER BY id DESC LIMIT 1"
+            ),
+            [who],
+            row_to_mark,
+        )
+        .optional()?;
+    match latest {
+        Some(m) => Ok(m),
+        None => start_session(c, who, now),
+    }
+}
+
+fn pulse_of(c: &rusqlite::Connection, mark: Mark, now: i64) -> rusqlite::Result<Pulse> {
+    let config = config(c, &mark.who)?;
+    let ends_at = ends_at(&mark, &config);
+    Ok(Pulse {
+        who: mark.who,
+        session_id: mark.session_id,
+        phase: mark.phase,
+        started_at: mark.at,
         ends_at,
-        remaining_secs: remaining,
-        config: cfg,
+        remaining_secs: (ends_at - now).max(0),
+        advances_at: config
+            .auto_advance
+            .then(|| ends_at + i64::from(config.grace_secs)),
+        snoozed_secs: mark.snoozed_secs,
+        suggestion: mark.suggestion,
+        config,
     })
 }
 
-/// POST /rhythm/config — update durations (minutes)
-pub async fn post_config(Json(new_cfg): Json<RhythmConfig>) -> Json<RhythmConfig> {
-    let mut st = state().write().await;
-    st.config = new_cfg.clone();
-    Json(new_cfg)
+/// The current phase, after any automatic flip that is due.
+pub fn pulse(c: &mut rusqlite::Connection, who: &str, now: i64) -> rusqlite::Result<Pulse> {
+    let tx = c.transaction()?;
+    advance(&tx, who, now)?;
+    let mark = current(&tx, who, now)?;
+    let pulse = pulse_of(&tx, mark, now)?;
+    tx.commit()?;
+    Ok(pulse)
+}
+
+/// Flip the phase at `now`.
+pub fn mark(c: &mut rusqlite::Connection, who: &str, now: i64) -> rusqlite::Result<Mark> {
+    let tx = c.transaction()?;
+    let cur = current(&tx, who, now)?;
+    let cfg = config(&tx, who)?;
+    let mark = flip(&tx, &cur, &cfg, now, "manual")?;
+    tx.commit()?;
+    Ok(mark)
+}
+
+/// Push the running block's end out by `secs`; `Err` once the block's snooze is used up.
+pub fn snooze(
+    c: &mut rusqlite::Connection,
+    who: &str,
+    secs: i64,
+    now: i64,
+) -> rusqlite::Result<Result<Pulse, String>> {
+    let tx = c.transaction()?;
+    advance(&tx, who, now)?;
+    let cur = current(&tx, who, now)?;
+    if cur.snoozed_secs + secs > MAX_SNOOZE_SECS {
+        return Ok(Err(format!(
+            "this block was already snoozed for {} min (max {})",
+            cur.snoozed_secs / 60,
+            MAX_SNOOZE_SECS / 60
+        )));
+    }
+    tx.execute(
+        "UPDATE rhythm_marks SET extend_secs = extend_secs + ?2 WHERE id = ?1",
+        rusqlite::params![cur.id, secs],
+    )?;
+    let mark = Mark {
+        snoozed_secs: cur.snoozed_secs + secs,
+        ..cur
+    };
+    let pulse = pulse_of(&tx, mark, now)?;
+    tx.commit()?;
+    Ok(Ok(pulse))
+}
+
+/// Close the open session and start a new one in Work.
+pub fn reset(c: &mut rusqlite::Connection, who: &str, now: i64) -> rusqlite::Result<Pulse> {
+    let tx = c.transaction()?;
+    tx.execute(
+        "UPDATE rhythm_sessions SET ended_at = ?2 WHERE who = ?1 AND ended_at IS NULL",
+        rusqlite::params![who, rfc3339(now)],
+    )?;
+    let mark = start_session(&tx, who, now)?;
+    let pulse = pulse_of(&tx, mark, now)?;
+    tx.commit()?;
+    Ok(pulse)
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct HistoryParams {
+    #[serde(default)]
+    pub who: Option<String>,
+    #[serde(default)]
+    pub from: Option<String>,
+    #[serde(default)]
+    pub to: Option<String>,
+    #[serde(default)]
+    pub session: Option<i64>,
+    #[serde(default)]
+    pub before: Option<i64>,
+    #[serde(default)]
+    pub limit: Option<i64>,
+}
+
+impl HistoryParams {
+    /// Bounds as RFC3339 UTC; `Err` is a 422 message.
+    pub fn normalized(self) -> Result<Self, String> {
+        let bound = |name: &str, v: Option<String>| match v
+            .map(|v| v.trim().to_owned())
+            .filter(|v| !v.is_empty())
+        {
+            None => Ok(None),
+            Some(raw) => parse_bound(&raw)
+                .map(Some)
+                .ok_or(format!("{name} must be RFC3339 or YYYY-MM-DD")),
+        };
+        let from = bound("from", self.from)?;
+        let to = bound("to", self.to)?;
+        if let (Some(f), Some(t)) = (&from, &to) {
+            if f >= t {
+                return Err("from must be before to".into());
+            }
+        }
+        Ok(HistoryParams {
+            who: Some(who_or_shared(self.who)),
+            from,
+            to,
+            limit: Some(self.limit.unwrap_or(50).clamp(1, 500)),
+            ..self
+        })
+    }
+}
+
+/// Marks newest first; `p` comes from `normalized()`.
+pub fn history(c: &rusqlite::Connection, p: &HistoryParams) -> rusqlite::Result<Vec<Mark>> {
+    c.prepare(&format!(
+        "SELECT {MARK_COLS} FROM rhythm_marks
+          WHERE who = ?1 AND (?2 IS NULL OR ts >= ?2) AND (?3 IS NULL OR ts < ?3)
+            AND (?4 IS NULL OR session_id = ?4) AND (?5 IS NULL OR id < ?5)
+          ORDER BY id DESC LIMIT ?6"
+    ))?
+    .query_map(
+        rusqlite::params![p.who, p.from, p.to, p.session, p.before, p.limit],
+        row_to_mark,
+    )?
+    .collect()
+}
+
+/// Sessions newest first; `before` is an id cursor.
+pub fn sessions(
+    c: &rusqlite::Connection,
+    who: &str,
+    before: Option<i64>,
+    limit: i64,
+) -> rusqlite::Result<Vec<Session>> {
+    c.prepare(
+        "SELECT s.id, s.who, s.started_at, s.ended_at,
+                (SELECT COUNT(*) FROM rhythm_marks m WHERE m.session_id = s.id)
+           FROM rhythm_sessions s
+          WHERE s.who = ?1 AND (?2 IS NULL OR s.id < ?2)
+          ORDER BY s.id DESC LIMIT ?3",
+    )?
+    .query_map(rusqlite::params![who, before, limit], |r| {
+        Ok(Session {
+            id: r.get(0)?,
+            who: r.get(1)?,
+            started_at: r.get(2)?,
+            ended_at: r.get(3)?,
+            marks: r.get(4)?,
+        })
+    })?
+    .collect()
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct WhoParams {
+    #[serde(default)]
+    pub who: Option<String>,
+}
+
+fn who_or_shared(who: Option<String>) -> String {
+    who.map(|w| w.trim().to_owned())
+        .filter(|w| !w.is_empty())
+        .unwrap_or_else(|| SHARED.to_owned())
+}
+
+fn now_secs() -> i64 {
+    chrono::Utc::now().timestamp()
+}
+
+/// GET /rhythm/next — describe the active phase and when it ends
+pub async fn get_next(
+    State(state): State<AppState>,
+    Query(q): Query<WhoParams>,
+) -> Result<Json<Pulse>, ApiErr> {
+    let who = who_or_shared(q.who);
+    state
+        .db
+        .0
+        .call(move |c| Ok(pulse(c, &who, now_secs())?))
+        .await
+        .map(Json)
+        .map_err(internal)
+}
+
+/// POST /rhythm/mark — acknowledge & flip phase boundary "now"
+///
+/// This always toggles the phase once and records a single mark at the time of the call.
+pub async fn post_mark(
+    State(state): State<AppState>,
+    Query(q): Query<WhoParams>,
+) -> Result<Json<Mark>, ApiErr> {
+    let who = who_or_shared(q.who);
+    state
+        .db
+        .0
+        .call(move |c| Ok(mark(c, &who, now_secs())?))
+        .await
+        .map(Json)
+        .map_err(internal)
+}
+
+/// POST /rhythm/reset — start a brand-new cycle (Work begins now)
+pub async fn post_reset(
+    State(state): State<AppState>,
+    Query(q): Query<WhoParams>,
+) -> Result<Json<Pulse>, ApiErr> {
+    let who = who_or_shared(q.who);
+    state
+        .db
+        .0
+        .call(move |c| Ok(reset(c, &who, now_secs())?))
+        .await
+        .map(Json)
+        .map_err(internal)
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct SnoozeIn {
+    #[serde(default)]
+    pub minutes: Option<i64>,
 }
 
-/// GET /rhythm/history — very small, recent boundary list
+/// POST /rhythm/snooze — give the running block a few more minutes
+pub async fn post_snooze(
+    State(state): State<AppState>,
+    Query(q): Query<WhoParams>,
+    body: Option<Json<SnoozeIn>>,
+) -> Result<Json<Pulse>, ApiErr> {
+    let who = who_or_shared(q.who);
+    let minutes = body
+        .and_then(|Json(b)| b.minutes)
+        .unwrap_or(DEFAULT_SNOOZE_MINUTES);
+    if !(1..=MAX_SNOOZE_SECS / 60).contains(&minutes) {
+        return Err(unprocessable(format!(
+            "minutes must be within 1..={}",
+            MAX_SNOOZE_SECS / 60
+        )));
+    }
+    state
+        .db
+        .0
+        .call(move |c| Ok(snooze(c, &who, minutes * 60, now_secs())?))
+        .await
+        .map_err(internal)?
+        .map(Json)
+        .map_err(|e| (StatusCode::CONFLICT, e))
+}
+
+/// GET /rhythm/config
+pub async fn get_config(
+    State(state): State<AppState>,
+    Query(q): Query<WhoParams>,
+) -> Result<Json<RhythmConfig>, ApiErr> {
+    let who = who_or_shared(q.who);
+    state
+        .db
+        .0
+        .call(move |c| Ok(config(c, &who)?))
+        .await
+        .map(Json)
+        .map_err(internal)
+}
+
+/// POST /rhythm/config — update durations (minutes); applies to the running block too
+pub async fn post_config(
+    State(state): State<AppState>,
+    Query(q): Query<WhoParams>,
+    Json(new_cfg): Json<RhythmConfig>,
+) -> Result<Json<RhythmConfig>, ApiErr> {
+    let who = who_or_shared(q.who);
+    state
+        .db
+        .0
+        .call(move |c| Ok(set_config(c, &who, &new_cfg, now_secs())?))
+        .await
+        .map_err(internal)?
+        .map(Json)
+        .map_err(unprocessable)
+}
+
+/// GET /rhythm/history
 #[derive(Debug, Clone, Serialize)]
 pub struct History {
     pub marks: Vec<Mark>,
+    /// Pass as `before` for the next page; absent on the last page.
+    pub next_before: Option<i64>,
 }
 
-pub async fn get_history() -> Json<History> {
-    let st = state().read().await;
-    Json(History {
-        marks: st.history.clone(),
-    })
+pub async fn get_history(
+    State(state): State<AppState>,
+    Query(q): Query<HistoryParams>,
+) -> Result<Json<History>, ApiErr> {
+    let p = q.normalized().map_err(unprocessable)?;
+    let limit = p.limit.unwrap_or_default();
+    let marks = state
+        .db
+        .0
+        .call(move |c| Ok(history(c, &p)?))
+        .await
+        .map_err(internal)?;
+    let next_before = (marks.len() as i64 == limit)
+        .then(|| marks.last().map(|m| m.id))
+        .flatten();
+    Ok(Json(History { marks, next_before }))
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct SessionParams {
+    #[serde(default)]
+    who: Option<String>,
+    #[serde(default)]
+    before: Option<i64>,
+    #[serde(default)]
+    limit: Option<i64>,
 }
 
-/// Public router to mount from main.rs:
+#[derive(Debug, Serialize)]
+struct SessionPage {
+    items: Vec<Session>,
+    /// Pass as `before` for the next page; absent on the last page.
+    next_before: Option<i64>,
+}
+
+/// GET /rhythm/sessions?who=&before=&limit=
+async fn get_sessions(
+    State(state): State<AppState>,
+    Query(q): Query<SessionParams>,
+) -> Result<Json<SessionPage>, ApiErr> {
+    let who = who_or_shared(q.who);
+    let limit = q.limit.unwrap_or(20).clamp(1, 200);
+    let before = q.before;
+    let items = state
+        .db
+        .0
+        .call(move |c| Ok(sessions(c, &who, before, limit)?))
+        .await
+        .map_err(internal)?;
+    let next_before = (items.len() as i64 == limit)
+        .then(|| items.last().map(|s| s.id))
+        .flatten();
+    Ok(Json(SessionPage { items, next_before }))
+}
+
+/// Public router to mount from main.rs and lib.rs:
 ///
 /// ```ignore
 /// let app = Router::new()
@@ -196,13 +879,170 @@ pub fn router() -> Router<AppState> {
         .route("/next", get(get_next))
         .route("/mark", post(post_mark))
         .route("/reset", post(post_reset))
-        .route("/config", post(post_config))
+        .route("/snooze", post(post_snooze))
+        .route("/config", get(get_config).post(post_config))
         .route("/history", get(get_history))
+        .route("/sessions", get(get_sessions))
 }
 
-fn now_secs() -> i64 {
-    SystemTime::now()
-        .duration_since(SystemTime::UNIX_EPOCH)
-        .unwrap_or(Duration::from_secs(0))
-        .as_secs() as i64
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conn() -> rusqlite::Connection {
+        let mut c = rusqlite::Connection::open_in_memory().unwrap();
+        crate::db::ensure_schema(&mut c).unwrap();
+        c
+    }
+
+    const T0: i64 = 1_750_000_000;
+
+    #[test]
+    fn rhythms_are_per_person_and_survive_in_the_db() {
+        let mut c = conn();
+        set_config(
+            &c,
+            "Raz",
+            &RhythmConfig {
+                work_minutes: 25,
+                rest_minutes: 5,
+                ..Default::default()
+            },
+            T0,
+        )
+        .unwrap()
+        .unwrap();
+        let p = pulse(&mut c, "Raz", T0).unwrap();
+        assert_eq!((p.phase, p.ends_at), (Phase::Work, T0 + 25 * 60));
+        let m = mark(&mut c, "Raz", T0 + 60).unwrap();
+        assert_eq!((m.phase, m.session_id), (Phase::Rest, p.session_id));
+
+        // the shared rhythm is untouched and keeps the default config
+        let team = pulse(&mut c, SHARED, T0 + 60).unwrap();
+        assert_eq!(team.phase, Phase::Work);
+        assert_eq!(team.config, RhythmConfig::default());
+
+        // "restart": state comes back from the tables
+        let p = pulse(&mut c, "Raz", T0 + 120).unwrap();
+        assert_eq!((p.phase, p.started_at), (Phase::Rest, T0 + 60));
+        assert_eq!(p.remaining_secs, 5 * 60 - 60);
+        assert_eq!(pulse(&mut c, "Raz", T0 + 3600).unwrap().remaining_secs, 0);
+
+        let bad = RhythmConfig {
+            work_minutes: 0,
+            rest_minutes: 5,
+            ..Default::default()
+        };
+        assert!(set_config(&c, "Raz", &bad, T0).unwrap().is_err());
+    }
+
+    #[test]
+    fn clock_only_has_work_for_auto_advance_or_pending_marks() {
+        let mut c = conn();
+        assert!(!clock_has_work(&c).unwrap());
+        reset(&mut c, "Nico", T0).unwrap();
+        assert!(!clock_has_work(&c).unwrap(), "manual, nothing to announce");
+        mark(&mut c, "Nico", T0 + 60).unwrap();
+        assert!(clock_has_work(&c).unwrap());
+        take_pending(&mut c).unwrap();
+        assert!(!clock_has_work(&c).unwrap());
+        let auto = RhythmConfig {
+            work_minutes: 25,
+            rest_minutes: 5,
+            auto_advance: true,
+            ..Default::default()
+        };
+        set_config(&c, "Raz", &auto, T0).unwrap().unwrap();
+        reset(&mut c, "Raz", T0).unwrap();
+        assert!(clock_has_work(&c).unwrap());
+    }
+
+    #[test]
+    fn auto_advance_waits_for_grace_and_snooze_and_catches_up() {
+        let mut c = conn();
+        let auto = RhythmConfig {
+            work_minutes: 25,
+            rest_minutes: 5,
+            auto_advance: true,
+            grace_secs: 60,
+            doorway_mode: None,
+        };
+        set_config(&c, "Raz", &auto, T0).unwrap().unwrap();
+        let p = pulse(&mut c, "Raz", T0).unwrap();
+        assert_eq!(p.advances_at, Some(T0 + 1500 + 60));
+        assert_eq!(pulse(&mut c, "Raz", T0 + 1530).unwrap().phase, Phase::Work);
+
+        let snoozed = snooze(&mut c, "Raz", 300, T0 + 1530).unwrap().unwrap();
+        assert_eq!((snoozed.ends_at, snoozed.snoozed_secs), (T0 + 1800, 300));
+        assert_eq!(pulse(&mut c, "Raz", T0 + 1859).unwrap().phase, Phase::Work);
+        let rest = pulse(&mut c, "Raz", T0 + 1900).unwrap();
+        assert_eq!((rest.phase, rest.started_at), (Phase::Rest, T0 + 1860));
+
+        // away for longer than the next block: it starts when we notice
+        let back = pulse(&mut c, "Raz", T0 + 9000).unwrap();
+        assert_eq!((back.phase, back.started_at), (Phase::Work, T0 + 9000));
+        let pending = take_pending(&mut c).unwrap();
+        assert_eq!(pending.len(), 2);
+        assert!(pending.iter().all(|e| e.mark.source == "auto"));
+        assert!(take_pending(&mut c).unwrap().is_empty());
+
+        for _ in 0..12 {
+            snooze(&mut c, "Raz", 300, T0 + 9001).unwrap().unwrap();
+        }
+        assert!(snooze(&mut c, "Raz", 300, T0 + 9001).unwrap().is_err());
+        let unknown = RhythmConfig {
+            doorway_mode: Some("nope".into()),
+            ..auto
+        };
+        assert!(set_config(&c, "Raz", &unknown, T0).unwrap().is_err());
+    }
+
+    #[test]
+    fn reset_opens_a_new_session_and_history_pages_and_filters() {
+        let mut c = conn();
+        for i in 1..=4 {
+            mark(&mut c, "Raz", T0 + i * 3600).unwrap();
+        }
+        let fresh = reset(&mut c, "Raz", T0 + 5 * 3600).unwrap();
+        assert_eq!(fresh.phase, Phase::Work);
+        let all = sessions(&c, "Raz", None, 10).unwrap();
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[0].id, fresh.session_id);
+        assert!(all[0].ended_at.is_none() && all[1].ended_at.is_some());
+        assert_eq!(all[1].marks, 5);
+
+        let page = |p: HistoryParams| history(&c, &p.normalized().unwrap()).unwrap();
+        let first = page(HistoryParams {
+            who: Some("Raz".into()),
+            limit: Some(4),
+            ..Default::default()
+        });
+        assert_eq!(first.len(), 4);
+        assert_eq!(first[0].at, T0 + 5 * 3600);
+        let rest = page(HistoryParams {
+            who: Some("Raz".into()),
+            before: Some(first[3].id),
+            ..Default::default()
+        });
+        assert_eq!(rest.len(), 2);
+        let window = page(HistoryParams {
+            who: Some("Raz".into()),
+            from: Some(rfc3339(T0 + 3600)),
+            to: Some(rfc3339(T0 + 3 * 3600)),
+            ..Default::default()
+        });
+        assert_eq!(window.len(), 3, "the session start and two flips");
+        let old = page(HistoryParams {
+            who: Some("Raz".into()),
+            session: Some(all[1].id),
+            ..Default::default()
+        });
+        assert_eq!(old.len(), 5);
+        assert!(HistoryParams {
+            from: Some("yesterday".into()),
+            ..Default::default()
+        }
+        .normalized()
+        .is_err());
+    }
 }
@@ -0,0 +1,134 @@
+//! Rhythm clock — phases flip on the server
+//! ----------------------------------------
+//! Whisper: "the bell rings even when nobody is watching the clock." ⏰
+//!
+//! Every `SWEEP_SECS` the clock flips auto-advance rhythms whose block is over
+//! (`rhythm::advance_due`), then announces every new phase mark once, manual ones too:
+//!   • publishes `rhythm:<json>` on the bus → named `rhythm` SSE event on /status/stream
+//!   • emits a consent-gated `rhythm.phase` relational event (webhooks)
+//!
+//! Rest blocks carry the suggested doorway when the person's config has `doorway_mode`.
+//!
+//! Notes
+//!   • A tick with no open auto-advance session and no pending mark is one read
+//!     (`rhythm::clock_has_work`); no transaction is opened.
+//!   • Relational emits run in their own task, so a slow DB write there never holds
+//!     up the next tick.
+//!   • Shared with the lib: `spawn_background` (lib.rs) starts it for embedders/tests.
+
+use crate::{relational, rhythm, AppState};
+use serde_json::json;
+
+/// Bus prefix for phase changes.
+pub const TOPIC: &str = "rhythm:";
+
+const SWEEP_SECS: u64 = 1;
+
+/// Flip what is due and announce new marks. Returns how many were announced.
+pub async fn sweep(state: &AppState) -> usize {
+    let now = chrono::Utc::now().timestamp();
+    let events = match state
+        .db
+        .0
+        .call(move |c| {
+            if !rhythm::clock_has_work(c)? {
+                return Ok(Vec::new());
+            }
+            rhythm::advance_due(c, now)?;
+            Ok(rhythm::take_pending(c)?)
+        })
+        .await
+    {
+        Ok(events) => events,
+        Err(e) => {
+            tracing::warn!(error = ?e, "rhythm.phase: sweep failed");
+            return 0;
+        }
+    };
+    if events.is_empty() {
+        return 0;
+    }
+    let mut announced = Vec::with_capacity(events.len());
+    for e in &events {
+        let body = json!(e);
+        state.bus.publish(&format!("{TOPIC}{body}"));
+        announced.push((e.mark.who.clone(), body));
+    }
+    let emitter = state.clone();
+    tokio::spawn(async move {
+        for (who, body) in announced {
+            relational::emit(
+                &emitter,
+                "rhythm.phase",
+                &relational::Actor::from_display(&who),
+                relational::Intent::state_change(),
+                json!({ "rhythm": body }),
+            )
+            .await;
+        }
+    });
+    events.len()
+}
+
+/// Background loop; keeps time with no client connected.
+pub fn spawn(state: AppState) {
+    tokio::spawn(async move {
+        let mut tick = tokio::time::interval(std::time::Duration::from_secs(SWEEP_SECS));
+        loop {
+            tick.tick().await;
+            sweep(&state).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn due_blocks_flip_and_are_announced_once() {
+        let state = crate::test_state().await;
+        assert_eq!(sweep(&state).await, 0, "idle");
+        let started = chrono::Utc::now().timestamp() - 26 * 60;
+        state
+            .db
+            .0
+            .call(move |c| {
+                let auto = rhythm::RhythmConfig {
+                    work_minutes: 25,
+                    rest_minutes: 5,
+                    auto_advance: true,
+                    doorway_mode: Some("default".into()),
+                    ..Default::default()
+                };
+                rhythm::set_config(c, "Raz", &auto, started)
+                    .unwrap()
+                    .unwrap();
+                rhythm::reset(c, "Raz", started)?;
+                // manual rhythm, same age: stays in Work
+                rhythm::reset(c, "Nico", started)?;
+                Ok(())
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(sweep(&state).await, 1);
+        let published = state.bus.drain();
+        let sent: Vec<_> = published.iter().filter(|m| m.starts_with(TOPIC)).collect();
+        assert_eq!(sent.len(), 1);
+        let body: serde_json::Value = serde_json::from_str(&sent[0][TOPIC.len()..]).unwrap();
+        assert_eq!(body["who"], "Raz");
+        assert_eq!(body["phase"], "rest");
+        assert_eq!(body["source"], "auto");
+        assert_eq!(
+            body["at"],
+            started + 25 * 60,
+            "the block starts on the boundary"
+        );
+        assert!(body["suggestion"]["doorway"]
+            .as_str()
+            .is_some_and(|d| !d.is_empty()));
+
+        assert_eq!(sweep(&state).await, 0, "announced once");
+    }
+}
@@ -0,0 +1,1060 @@
+//! Readiness — per-member status with TTL, history, time-in-state analytics
+//! ------------------------------------------------------------------------
+//! Whisper: "red is information, not a verdict." 🚦
+//!
+//! One model: `member_status(who, color, note, updated_at, expires_at)`.
+//! The global team light is simply `who = "team"`. A background sweep (`spawn_expiry`)
+//! returns lapsed statuses to green on time, records the change, publishes
+//! `status:<who>:green` on the bus and emits a `status.set` relational event.
+//!
+//! Every change lands in `status_events` (who, color, prev, note, ttl, expires_at, reason).
+//!
+//! Endpoints
+//!   GET  /status/members                             → every member's current light
+//!   POST /status/members                             → { who, color, note?, ttl_minutes? }
+//!   GET  /status/history?who=&since=&until=&limit=   → newest first
+//!   GET /status/summary?who=&weeks=4                → per person, per ISO week:
+//!       seconds in green / yellow / red, red spells, longest red spell
+//!
+//! Compatibility views (older clients)
+//!   POST /status {name,status}   → set that member (no TTL)
+//!   GET  /status                 → members except "team", as StatusItem
+//!   POST /status/get             → the "team" member as StatusGetResponse
+//!   POST /status/set             → set the "team" member (TTL ok)
+//!
+//! Notes
+//!   • `reason` is `manual` for explicit sets and `ttl-expired` when a TTL lapsed;
+//!     expiry rows carry the expiry time, not the time someone noticed it. Panic runs
+//!     turn `main` green through `write_member` inside their own transaction (`panic`
+//!     reason, see `panic::run`).
+//!   • Timestamps are RFC3339 UTC; weeks start Monday 00:00 UTC.
+
+use crate::{models, relational, AppState};
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    Json,
+};
+use chrono::{DateTime, Datelike, Duration, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// `who` used for the global team light.
+pub const TEAM: &str = "team";
+
+pub const REASON_MANUAL: &str = "manual";
+pub const REASON_EXPIRED: &str = "ttl-expired";
+
+pub const COLORS: [&str; 3] = ["green", "yellow", "red"];
+
+/// Longest TTL a light may carry (one year).
+pub const MAX_TTL_MINUTES: i64 = 525_600;
+
+/// How often the expiry sweep runs (`M3_STATUS_SWEEP_SECS`, default 30).
+const DEFAULT_SWEEP_SECS: u64 = 30;
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct MemberStatus {
+    pub who: String,
+    pub color: String,
+    pub note: String,
+    pub updated_at: String,
+    pub expires_at: Option<String>,
+}
+
+/// Relational actor for a member light. The team light keeps the primary
+/// profile as actor (what `/status/set` has always emitted).
+fn actor_for(who: &str) -> relational::Actor {
+    if who == TEAM {
+        relational::Actor::from_display("Raz")
+    } else {
+        relational::Actor::from_display(who)
+    }
+}
+
+fn member_from_row(r: &rusqlite::Row<'_>) -> rusqlite::Result<MemberStatus> {
+    Ok(MemberStatus {
+        who: r.get(0)?,
+        color: r.get(1)?,
+        note: r.get(2)?,
+        updated_at: r.get(3)?,
+        expires_at: r.get(4)?,
+    })
+}
+
+pub async fn list_members(db: &crate::db::Database) -> tokio_rusqlite::Result<Vec<MemberStatus>> {
+    db.0.call(|c| {
+        let mut stmt = c.prepare(
+            "SELECT who, color, note, updated_at, expires_at FROM member_status ORDER BY who",
+        )?;
+        let rows = stmt
+            .query_map([], member_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(rows)
+    })
+    .await
+}
+
+pub async fn get_member(
+    db: &crate::db::Database,
+    who: &str,
+) -> tokio_rusqlite::Result<Option<MemberStatus>> {
+    let who = who.to_string();
+    db.0.call(move |c| {
+        let mut stmt = c.prepare(
+            "SELECT who, color, note, updated_at, expires_at FROM member_status WHERE who = ?1",
+        )?;
+        let mut rows = stmt.query_map([who], member_from_row)?;
+        Ok(rows.next().transpose()?)
+    })
+    .await
+}
+
+/// Write one member's light (+ history row) and publish it on the bus.
+/// Callers decide whether the change is also a relational `status.set` event.
+pub async fn set_member(
+    state: &AppState,
+    who: &str,
+    color: &str,
+    note: Option<String>,
+    ttl_minutes: Option<i64>,
+) -> Result<MemberStatus, (StatusCode, String)> {
+    let who = who.trim().to_string();
+    if who.is_empty() {
+        return Err((StatusCode::UNPROCESSABLE_ENTITY, "who required".into()));
+    }
+    if !COLORS.contains(&color) {
+        return Err((
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "color must be green, yellow or red".into(),
+        ));
+    }
+    if ttl_minutes.is_some_and(|m| m <= 0 || m > MAX_TTL_MINUTES) {
+        return Err((
+            StatusCode::UNPROCESSABLE_ENTITY,
+            format!("ttl_minutes must be between 1 and {MAX_TTL_MINUTES}"),
+        ));
+    }
+    let now = Utc::now();
+    let row = MemberStatus {
+        who,
+        color: color.to_string(),
+        note: note.unwrap_or_default(),
+        updated_at: now.to_rfc3339(),
+        expires_at: ttl_minutes.map(|m| (now + Duration::minutes(m)).to_rfc3339()),
+    };
+    let db_row = row.clone();
+    state
+        .db
+        .0
+        .call(move |c| {
+            let tx = c.transaction()?;
+            write_member(&tx, &db_row, ttl_minutes, REASON_MANUAL)?;
+            tx.commit()?;
+            Ok(())
+        })
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    publish(state, &row);
+    Ok(row)
+}
+
+/// Upsert one member's light and append its `status_events` row, inside the caller's
+/// transaction (validation is the caller's job). Returns the history row id.
+pub fn write_member(
+    c: &rusqlite::Connection,
+    row: &MemberStatus,
+    ttl_minutes: Option<i64>,
+    reason: &str,
+) -> rusqlite::Result<i64> {
+    let prev: Option<String> = c
+        .query_row(
+            "SELECT color FROM member_status WHERE who = ?1",
+            [&row.who],
+            |r| r.get(0),
+        )
+        .ok();
+    c.execute(
+        "INSERT INTO member_status(who, color, note, updated_at, expires_at)
+         VALUES(?1, ?2, ?3, ?4, ?5)
+         ON CONFLICT(who) DO UPDATE SET
+           color=excluded.color, note=excluded.note,
+           updated_at=excluded.updated_at, expires_at=excluded.expires_at",
+        rusqlite::params![row.who, row.color, row.note, row.updated_at, row.expires_at],
+    )?;
+    record_change(
+        c,
+        &row.updated_at,
+        &row.who,
+        &row.color,
+        prev.as_deref(),
+        Some(&row.note),
+        ttl_minutes,
+        row.expires_at.as_deref(),
+        reason,
+    )
+}
+
+/// Announce a committed light on the bus (`status:<who>:<color>` → /status/stream).
+pub fn publish(state: &AppState, row: &MemberStatus) {
+    state
+        .bus
+        .publish(&format!("status:{}:{}", row.who, row.color));
+}
+
+/// `status.set` relational event for a change (consent-gated like every kind).
+pub async fn emit_change(state: &AppState, row: &MemberStatus, prev: Option<&str>, reason: &str) {
+    let ttl_minutes = row.expires_at.as_deref().and_then(parse_ts).map(|exp| {
+        parse_ts(&row.updated_at)
+            .map(|u| (exp - u).num_minutes())
+            .unwrap_or_default()
+    });
+    relational::emit(
+        state,
+        "status.set",
+        &actor_for(&row.who),
+        relational::Intent::state_change(),
+        serde_json::json!({
+            "who": row.who,
+            "status": row.color,             // "green" | "yellow" | "red"
+            "prev": prev,
+            "note": row.note,
+            "updated_at": row.updated_at,
+            "ttl_minutes": ttl_minutes,
+            "reason": reason,
+        }),
+    )
+    .await;
+}
+
+/// Return every lapsed light to green. History rows carry the expiry time.
+pub async fn expire_due(state: &AppState) -> tokio_rusqlite::Result<Vec<(MemberStatus, String)>> {
+    let now = Utc::now();
+    let due: Vec<(MemberStatus, DateTime<Utc>)> = list_members(&state.db)
+        .await?
+        .into_iter()
+        .filter_map(|m| {
+            let exp = m.expires_at.as_deref().and_then(parse_ts)?;
+            (exp <= now).then_some((m, exp))
+        })
+        .collect();
+
+    let mut reset = Vec::new();
+    for (m, exp) in due {
+        let who = m.who.clone();
+        let prev = m.color.clone();
+        let expired_at = exp.to_rfc3339();
+        let seen_expiry = m.expires_at.clone();
+        let updated_at = now.to_rfc3339();
+        let changed = state
+            .db
+            .0
+            .call(move |c| {
+                Ok(reset_expired(
+                    c,
+                    &who,
+                    &prev,
+                    seen_expiry.as_deref(),
+                    &expired_at,
+                    &updated_at,
+                )?)
+            })
+            .await?;
+        if changed {
+            let row = MemberStatus {
+                who: m.who.clone(),
+                color: "green".into(),
+                note: String::new(),
+                updated_at: now.to_rfc3339(),
+                expires_at: None,
+            };
+            reset.push((row, m.color));
+        }
+    }
+    Ok(reset)
+}
+
+/// Return `who` to green, but only if its light still has the color and expiry the
+/// sweep saw (a re-set in between wins). True when a change was recorded.
+fn reset_expired(
+    c: &mut rusqlite::Connection,
+    who: &str,
+    prev: &str,
+    seen_expiry: Option<&str>,
+    expired_at: &str,
+    updated_at: &str,
+) -> rusqlite::Result<bool> {
+    let tx = c.transaction()?;
+    let n = tx.execute(
+        "UPDATE member_status SET color='green', note='', updated_at=?1, expires_at=NULL
+         WHERE who=?2 AND color=?3 AND expires_at=?4",
+        rusqlite::params![updated_at, who, prev, seen_expiry],
+    )?;
+    let changed = n > 0 && prev != "green";
+    if changed {
+        record_change(
+            &tx,
+            expired_at,
+            who,
+            "green",
+            Some(prev),
+            None,
+            None,
+            None,
+            REASON_EXPIRED,
+        )?;
+    }
+    tx.commit()?;
+    Ok(changed)
+}
+
+/// Background sweep: expire on time, publish + emit each auto-reset.
+pub fn spawn_expiry(state: AppState) {
+    let secs = std::env::var("M3_STATUS_SWEEP_SECS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .filter(|v| *v > 0)
+        .unwrap_or(DEFAULT_SWEEP_SECS);
+    tokio::spawn(async move {
+        let mut tick = tokio::time::interval(std::time::Duration::from_secs(secs));
+        loop {
+            tick.tick().await;
+            sweep(&state).await;
+        }
+    });
+}
+
+async fn sweep(state: &AppState) {
+    match expire_due(state).await {
+        Ok(reset) => {
+            for (row, prev) in reset {
+                tracing::info!(who = %row.who, prev = %prev, "status: ttl expired → green");
+                publish(state, &row);
+                emit_change(state, &row, Some(&prev), REASON_EXPIRED).await;
+            }
+        }
+        Err(e) => tracing::warn!(error = ?e, "status: expiry sweep failed"),
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StatusEvent {
+    pub id: i64,
+    pub ts: String,
+    pub who: String,
+    pub color: String,
+    pub prev: Option<String>,
+    pub note: Option<String>,
+    pub ttl_minutes: Option<i64>,
+    pub expires_at: Option<String>,
+    pub reason: String,
+}
+
+/// Append one change. `prev` is looked up from the last event for `who`,
+/// falling back to `fallback_prev` (the value being overwritten) for first rows.
+#[allow(clippy::too_many_arguments)]
+pub fn record_change(
+    c: &rusqlite::Connection,
+    ts: &str,
+    who: &str,
+    color: &str,
+    fallback_prev: Option<&str>,
+    note: Option<&str>,
+    ttl_minutes: Option<i64>,
+    expires_at: Option<&str>,
+    reason: &str,
+) -> rusqlite::Result<i64> {
+    let last: Option<String> = c
+        .query_row(
+            "SELECT color FROM status_events WHERE who = ?1 ORDER BY ts DESC, id DESC LIMIT 1",
+            [who],
+            |r| r.get(0),
+        )
+        .ok();
+    let prev = last.or_else(|| fallback_prev.map(str::to_string));
+    c.execute(
+        "INSERT INTO status_events(ts, who, color, prev, note, ttl_minutes, expires_at, reason)
+         VALUES(?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        rusqlite::params![
+            ts,
+            who,
+            color,
+            prev,
+            note.filter(|n| !n.is_empty()),
+            ttl_minutes,
+            expires_at,
+            reason
+        ],
+    )?;
+    Ok(c.last_insert_rowid())
+}
+
+fn event_from_row(r: &rusqlite::Row<'_>) -> rusqlite::Result<StatusEvent> {
+    Ok(StatusEvent {
+        id: r.get(0)?,
+        ts: r.get(1)?,
+        who: r.get(2)?,
+        color: r.get(3)?,
+        prev: r.get(4)?,
+        note: r.get(5)?,
+        ttl_minutes: r.get(6)?,
+        expires_at: r.get(7)?,
+        reason: r.get(8)?,
+    })
+}
+
+const EVENT_COLUMNS: &str = "id, ts, who, color, prev, note, ttl_minutes, expires_at, reason";
+
+fn parse_ts(s: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(s)
+        .ok()
+        .map(|t| t.with_timezone(&Utc))
+}
+
+/// Monday 00:00 UTC of the week containing `t`.
+pub fn week_start(t: DateTime<Utc>) -> DateTime<Utc> {
+    let day = t.date_naive() - Duration::days(t.weekday().num_days_from_monday() as i64);
+    day.and_time(NaiveTime::MIN).and_utc()
+}
+
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct WeekSummary {
+    pub who: String,
+    /// ISO week label, e.g. `2026-W02`.
+    pub week: String,
+    pub week_start: String,
+    pub green_secs: i64,
+    pub yellow_secs: i64,
+    pub red_secs: i64,
+    /// Times this person entered red during the week.
+    pub red_spells: i64,
+    /// Longest continuous red spell that started this week (whole spell, may cross weeks).
+    pub longest_red_secs: i64,
+}
+
+/// Fold events (any order, any people) into per-person weekly time-in-state over
+/// `[from, to)`. Each event's color holds until that person's next event; the
+/// last one holds until `to`. Events before `from` only set the opening color.
+pub fn summarize(
+    events: &[StatusEvent],
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Vec<WeekSummary> {
+    let mut by_who: BTreeMap<&str, Vec<(DateTime<Utc>, &str)>> = BTreeMap::new();
+    for e in events {
+        if let Some(t) = parse_ts(&e.ts) {
+            if t < to {
+                by_who.entry(&e.who).or_default().push((t, &e.color));
+            }
+        }
+    }
+
+    let mut out: BTreeMap<(String, DateTime<Utc>), WeekSummary> = BTreeMap::new();
+    for (who, mut evs) in by_who {
+        evs.sort_by_key(|(t, _)| *t);
+        let mut prev_color: Option<&str> = None;
+        for (i, (start, color)) in evs.iter().enumerate() {
+            let end = evs.get(i + 1).map(|(t, _)| *t).unwrap_or(to).min(to);
+            let entered_red = *color == "red" && prev_color != Some("red");
+            prev_color = Some(color);
+
+            // whole red spell (merged across consecutive red rows) for the spell stats
+            if entered_red && *start >= from {
+                let spell_end = evs[i + 1..]
+                    .iter()
+                    .find(|(_, c)| *c != "red")
+                    .map(|(t, _)| *t)
+                    .unwrap_or(to)
+                    .min(to);
+                let w = week_start(*start);
+                let s = out.entry((who.to_string(), w)).or_default();
+                s.red_spells += 1;
+                s.longest_red_secs = s.longest_red_secs.max((spell_end - *start).num_seconds());
+            }
+
+            // time-in-state, clipped to the window and split at week boundaries
+            let mut cur = (*start).max(from);
+            while cur < end {
+                let w = week_start(cur);
+                let seg_end = (w + Duration::weeks(1)).min(end);
+                let secs = (seg_end - cur).num_seconds();
+                let s = out.entry((who.to_string(), w)).or_default();
+                match *color {
+                    "green" => s.green_secs += secs,
+                    "yellow" => s.yellow_secs += secs,
+                    "red" => s.red_secs += secs,
+                    _ => {}
+                }
+                cur = seg_end;
+            }
+        }
+    }
+
+    out.into_iter()
+        .map(|((who, w), mut s)| {
+            let iso = w.iso_week();
+            s.who = who;
+            s.week = format!("{}-W{:02}", iso.year(), iso.week());
+            s.week_start = w.to_rfc3339();
+            s
+        })
+        .collect()
+}
+
+// ---- HTTP -------------------------------------------------------------------
+
+#[derive(Debug, Deserialize)]
+pub struct MemberSetIn {
+    who: String,
+    color: String,
+    #[serde(default)]
+    note: Option<String>,
+    #[serde(default)]
+    ttl_minutes: Option<i64>,
+}
+
+/// GET /status/members
+pub async fn members(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<MemberStatus>>, (StatusCode, String)> {
+    sweep(&state).await;
+    list_members(&state.db)
+        .await
+        .map(Json)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+/// POST /status/members — set one member's light (emits `status.set`).
+pub async fn set_member_http(
+    State(state): State<AppState>,
+    Json(input): Json<MemberSetIn>,
+) -> Result<Json<MemberStatus>, (StatusCode, String)> {
+    let prev = get_member(&state.db, input.who.trim())
+        .await
+        .ok()
+        .flatten()
+        .map(|m| m.color);
+    let row = set_member(
+        &state,
+        &input.who,
+        &input.color,
+        input.note,
+        input.ttl_minutes,
+    )
+    .await?;
+    emit_change(&state, &row, prev.as_deref(), REASON_MANUAL).await;
+    Ok(Json(row))
+}
+
+/// POST /status (compat) — per-name light, no TTL.
+pub async fn legacy_set_light(
+    State(state): State<AppState>,
+    Json(req): Json<models::StatusSet>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    let row = set_member(&state, &req.name, &req.status, None, None).await?;
+    Ok(Json(serde_json::json!({
+        "ok": true,
+        "name": row.who,
+        "status": row.color,
+        "ts": row.updated_at
+    })))
+}
+
+/// GET /status (compat) — per-name lights (the team light is served by /status/get).
+pub async fn legacy_list_lights(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<models::StatusItem>>, (StatusCode, String)> {
+    let rows = list_members(&state.db)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(Json(
+        rows.into_iter()
+            .filter(|m| m.who != TEAM)
+            .map(|m| models::StatusItem {
+                name: m.who,
+                status: m.color,
+                ts: m.updated_at,
+            })
+            .collect(),
+    ))
+}
+
+/// POST /status/get (compat) — the team light.
+pub async fn legacy_get_team(
+    State(state): State<AppState>,
+    _body: Json<serde_json::Value>,
+) -> Result<Json<models::StatusGetResponse>, (StatusCode, String)> {
+    // don't wait for the sweep to serve a stale color
+    sweep(&state).await;
+    let m = get_member(&state.db, TEAM)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let now = Utc::now();
+    let m = m.unwrap_or(MemberStatus {
+        who: TEAM.into(),
+        color: "green".into(),
+        note: String::new(),
+        updated_at: now.to_rfc3339(),
+        expires_at: None,
+    });
+    Ok(Json(models::StatusGetResponse {
+        color: m.color,
+        note: m.note,
+        updated_at: parse_ts(&m.updated_at).unwrap_or(now),
+        expires_at: m.expires_at.as_deref().and_then(parse_ts),
+    }))
+}
+
+/// POST /status/set (compat) — set the team light (emits `status.set`).
+pub async fn legacy_set_team(
+    State(state): State<AppState>,
+    Json(req): Json<models::StatusSetRequest>,
+) -> Result<Json<models::StatusOk>, (StatusCode, String)> {
+    let prev = get_member(&state.db, TEAM)
+        .await
+        .ok()
+        .flatten()
+        .map(|m| m.color);
+    let row = set_member(&state, TEAM, &req.color, req.note, req.ttl_minutes).await?;
+    emit_change(&state, &row, prev.as_deref(), REASON_MANUAL).await;
+    Ok(Json(models::StatusOk { ok: true }))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct HistoryParams {
+    #[serde(default)]
+    who: Option<String>,
+    #[serde(default)]
+    since: Option<String>,
+    #[serde(default)]
+    until: Option<String>,
+    #[serde(default)]
+    limit: Option<i64>,
+}
+
+fn parse_bound(v: Option<&str>, name: &str) -> Result<Option<DateTime<Utc>>, (StatusCode, String)> {
+    match v {
+        None => Ok(None),
+        Some(s) => parse_ts(s).map(Some).ok_or((
+            StatusCode::UNPROCESSABLE_ENTITY,
+            format!("{name} must be RFC3339"),
+        )),
+    }
+}
+
+/// GET /status/history — status changes, newest first.
+pub async fn history(
+    State(state): State<AppState>,
+    Query(q): Query<HistoryParams>,
+) -> Result<Json<Vec<StatusEvent>>, (StatusCode, String)> {
+    let since = parse_bound(q.since.as_deref(), "since")?.map(|t| t.to_rfc3339());
+    let until = parse_bound(q.until.as_deref(), "until")?.map(|t| t.to_rfc3339());
+    let limit = q.limit.unwrap_or(100).clamp(1, 1000);
+    let who = q.who;
+    let rows = state
+        .db
+        .0
+        .call(move |c| {
+            let mut stmt = c.prepare(&format!(
+                "SELECT {EVENT_COLUMNS} FROM status_events
+                 WHERE (?1 IS NULL OR who = ?1)
+                   AND (?2 IS NULL OR ts >= ?2)
+                   AND (?3 IS NULL OR ts < ?3)
+                 ORDER BY ts DESC, id DESC
+                 LIMIT ?4"
+            ))?;
+            let rows = stmt
+                .query_map(rusqlite::params![who, since, until, limit], event_from_row)?
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(rows)
+        })
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(Json(rows))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SummaryParams {
+    #[serde(default)]
+    who: Option<String>,
+    /// Whole weeks back from the current one (default 4, max 52).
+    #[serde(default)]
+    weeks: Option<i64>,
+}
+
+/// GET /status/summary — time in green / yellow / red per person per week.
+pub async fn summary(
+    State(state): State<AppState>,
+    Query(q): Query<SummaryParams>,
+) -> Result<Json<Vec<WeekSummary>>, (StatusCode, String)> {
+    let weeks = q.weeks.unwrap_or(4).clamp(1, 52);
+    let now = Utc::now();
+    let from = week_start(now) - Duration::weeks(weeks - 1);
+    let from_s = from.to_rfc3339();
+    let who = q.who;
+    let events = state
+        .db
+        .0
+        .call(move |c| {
+            // window rows + the last row before the window (opening color) per person
+            let mut stmt = c.prepare(&format!(
+                "SELECT {EVENT_COLUMNS} FROM status_events
+                 WHERE (?1 IS NULL OR who = ?1) AND ts >= ?2
+                 UNION ALL
+                 SELECT {EVENT_COLUMNS} FROM status_events e
+                 WHERE (?1 IS NULL OR who = ?1) AND ts < ?2
+                   AND id = (SELECT id FROM status_events x
+                             WHERE x.who = e.who AND x.ts < ?2
+                             ORDER BY x.ts DESC, x.id DESC LIMIT 1)"
+            ))?;
+            let rows = stmt
+                .query_map(rusqlite::params![who, from_s], event_from_row)?
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(rows)
+        })
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(Json(summarize(&events, from, now)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{http::Request, routing::post, Router};
+    use tower::ServiceExt; // for `oneshot`
+
+    async fn make_state_for_test() -> AppState {
+        crate::test_state().await
+    }
+
+    fn ev(ts: &str, who: &str, color: &str) -> StatusEvent {
+        StatusEvent {
+            id: 0,
+            ts: ts.into(),
+            who: who.into(),
+            color: color.into(),
+            prev: None,
+            note: None,
+            ttl_minutes: None,
+            expires_at: None,
+            reason: REASON_MANUAL.into(),
+        }
+    }
+
+    fn at(s: &str) -> DateTime<Utc> {
+        parse_ts(s).unwrap()
+    }
+
+    #[test]
+    fn week_starts_on_monday_utc() {
+        // 2026-01-07 is a Wednesday
+        assert_eq!(
+            week_start(at("2026-01-07T10:12:00Z")),
+            at("2026-01-05T00:00:00Z")
+        );
+        assert_eq!(
+            week_start(at("2026-01-05T00:00:00Z")),
+            at("2026-01-05T00:00:00Z")
+        );
+    }
+
+    #[test]
+    fn time_in_state_until_next_change() {
+        let events = [
+            ev("2026-01-05T09:00:00Z", "raz", "green"),
+            ev("2026-01-05T10:00:00Z", "raz", "red"),
+            ev("2026-01-05T10:30:00Z", "raz", "yellow"),
+        ];
+        let out = summarize(
+            &events,
+            at("2026-01-05T00:00:00Z"),
+            at("2026-01-05T12:00:00Z"),
+        );
+        assert_eq!(out.len(), 1);
+        let s = &out[0];
+        assert_eq!(s.week, "2026-W02");
+        assert_eq!(s.green_secs, 3600);
+        assert_eq!(s.red_secs, 1800);
+        assert_eq!(s.yellow_secs, 5400);
+        assert_eq!(s.red_spells, 1);
+        assert_eq!(s.longest_red_secs, 1800);
+    }
+
+    #[test]
+    fn opening_color_and_week_split() {
+        // red set before the window, lasting into it and across a week boundary
+        let events = [
+            ev("2026-01-04T22:00:00Z", "ana", "red"),
+            ev("2026-01-12T01:00:00Z", "ana", "green"),
+        ];
+        let out = summarize(
+            &events,
+            at("2026-01-11T23:00:00Z"),
+            at("2026-01-12T02:00:00Z"),
+        );
+        assert_eq!(out.len(), 2);
+        assert_eq!(out[0].week, "2026-W02");
+        assert_eq!(out[0].red_secs, 3600);
+        // spell started before the window → not counted as a new spell
+        assert_eq!(out[0].red_spells, 0);
+        assert_eq!(out[1].week, "2026-W03");
+        assert_eq!(out[1].red_secs, 3600);
+        assert_eq!(out[1].green_secs, 3600);
+    }
+
+    #[test]
+    fn consecutive_reds_are_one_spell_and_people_are_separate() {
+        let events = [
+            ev("2026-01-06T08:00:00Z", "raz", "red"),
+            ev("2026-01-06T09:00:00Z", "raz", "red"),
+            ev("2026-01-06T10:00:00Z", "raz", "green"),
+            ev("2026-01-06T08:00:00Z", "nico", "yellow"),
+        ];
+        let out = summarize(
+            &events,
+            at("2026-01-05T00:00:00Z"),
+            at("2026-01-06T11:00:00Z"),
+        );
+        let raz = out.iter().find(|s| s.who == "raz").unwrap();
+        assert_eq!(raz.red_spells, 1);
+        assert_eq!(raz.longest_red_secs, 7200);
+        assert_eq!(raz.red_secs, 7200);
+        let nico = out.iter().find(|s| s.who == "nico").unwrap();
+        assert_eq!(nico.yellow_secs, 3 * 3600);
+        assert_eq!(nico.red_secs, 0);
+    }
+
+    #[test]
+    fn record_change_links_prev() {
+        let mut c = rusqlite::Connection::open_in_memory().unwrap();
+        crate::db::ensure_schema(&mut c).unwrap();
+        record_change(
+            &c,
+            "2026-01-05T09:00:00+00:00",
+            TEAM,
+            "red",
+            Some("green"),
+            Some("heads down"),
+            Some(30),
+            Some("2026-01-05T09:30:00+00:00"),
+            REASON_MANUAL,
+        )
+        .unwrap();
+        record_change(
+            &c,
+            "2026-01-05T09:30:00+00:00",
+            TEAM,
+            "green",
+            None,
+            None,
+            None,
+            None,
+            REASON_EXPIRED,
+        )
+        .unwrap();
+        let (prev, reason): (String, String) = c
+            .query_row(
+                "SELECT prev, reason FROM status_events ORDER BY id DESC LIMIT 1",
+                [],
+                |r| Ok((r.get(0)?, r.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(prev, "red");
+        assert_eq!(reason, REASON_EXPIRED);
+    }
+
+    #[test]
+    fn legacy_rows_migrate_into_member_status() {
+        let mut c = rusqlite::Connection::open_in_memory().unwrap();
+        c.execute_batch(
+            "CREATE TABLE kv(key TEXT PRIMARY KEY, value BLOB);
+             INSERT INTO kv VALUES('status:Raz','red'), ('seal_salt', x'00');",
+        )
+        .unwrap();
+        crate::db::ensure_schema(&mut c).unwrap();
+        crate::db::ensure_schema(&mut c).unwrap(); // idempotent
+
+        let mut stmt = c
+            .prepare("SELECT who, color FROM member_status ORDER BY who")
+            .unwrap();
+        let rows: Vec<(String, String)> = stmt
+            .query_map([], |r| Ok((r.get(0)?, r.get(1)?)))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(
+            rows,
+            vec![
+                ("Raz".to_string(), "red".to_string()),
+                ("team".to_string(), "green".to_string())
+            ]
+        );
+        let left: Vec<String> = c
+            .prepare("SELECT key FROM kv WHERE key NOT LIKE '%_seeded' ORDER BY key")
+            .unwrap()
+            .query_map([], |r| r.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(left, vec!["seal_salt"], "only non-status kv rows remain");
+    }
+
+    #[tokio::test]
+    async fn expiry_resets_to_green_and_records_expiry_time() {
+        let state = make_state_for_test().await;
+        set_member(&state, "Ana", "red", Some("heads down".into()), Some(30))
+            .await
+            .unwrap();
+        state.bus.drain();
+        state
+            .db
+            .0
+            .call(|c| {
+                c.execute(
+                    "UPDATE member_status SET expires_at='2026-01-05T09:30:00+00:00' WHERE who='Ana'",
+                    [],
+                )?;
+                Ok(())
+            })
+            .await
+            .unwrap();
+
+        let reset = expire_due(&state).await.unwrap();
+        assert_eq!(reset.len(), 1);
+        assert_eq!(reset[0].1, "red");
+        let ana = get_member(&state.db, "Ana").await.unwrap().unwrap();
+        assert_eq!(ana.color, "green");
+        assert!(ana.expires_at.is_none());
+
+        let (ts, prev, reason): (String, String, String) = state
+            .db
+            .0
+            .call(|c| {
+                Ok(c.query_row(
+                    "SELECT ts, prev, reason FROM status_events WHERE who='Ana' ORDER BY id DESC LIMIT 1",
+                    [],
+                    |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)),
+                )?)
+            })
+            .await
+            .unwrap();
+        assert_eq!(ts, "2026-01-05T09:30:00+00:00");
+        assert_eq!(prev, "red");
+        assert_eq!(reason, REASON_EXPIRED);
+
+        // second sweep is a no-op
+        assert!(expire_due(&state).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn a_new_ttl_set_after_the_sweep_looked_survives() {
+        let state = make_state_for_test().await;
+        let row = set_member(&state, "Ana", "red", None, Some(30))
+            .await
+            .unwrap();
+        let seen = row.expires_at.clone();
+        // re-set to red with a fresh TTL between the sweep's read and its update
+        set_member(&state, "Ana", "red", None, Some(90))
+            .await
+            .unwrap();
+        let changed = state
+            .db
+            .0
+            .call(move |c| {
+                Ok(reset_expired(
+                    c,
+                    "Ana",
+                    "red",
+                    seen.as_deref(),
+                    "2026-01-05T09:30:00+00:00",
+                    "2026-01-05T09:30:01+00:00",
+                )?)
+            })
+            .await
+            .unwrap();
+        assert!(!changed);
+        let ana = get_member(&state.db, "Ana").await.unwrap().unwrap();
+        assert_eq!(ana.color, "red");
+        assert!(ana.expires_at.is_some());
+    }
+
+    #[tokio::test]
+    async fn compat_routes_are_views_over_members() {
+        let state = make_state_for_test().await;
+        let app = Router::new()
+            .route("/status", post(legacy_set_light).get(legacy_list_lights))
+            .route("/status/get", post(legacy_get_team))
+            .route("/status/set", post(legacy_set_team))
+            .with_state(state.clone());
+        let call = |method: &str, uri: &str, body: &str| {
+            Request::builder()
+                .method(method)
+                .uri(uri)
+                .header("content-type", "application/json")
+                .body(axum::body::Body::from(body.to_string()))
+                .unwrap()
+        };
+
+        let res = app
+            .clone()
+            .oneshot(call(
+                "POST",
+                "/status",
+                r#"{"name":"Nico","status":"yellow"}"#,
+            ))
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        let res = app
+            .clone()
+            .oneshot(call(
+                "POST",
+                "/status/set",
+                r#"{"color":"red","note":"storm","ttl_minutes":45}"#,
+            ))
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        let res = app
+            .clone()
+            .oneshot(call("POST", "/status/set", r#"{"color":"purple"}"#))
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        let res = app
+            .clone()
+            .oneshot(call(
+                "POST",
+                "/status/set",
+                r#"{"color":"red","ttl_minutes":9223372036854775807}"#,
+            ))
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+        let res = app
+            .clone()
+            .oneshot(call("POST", "/status/get", "{}"))
+            .await
+            .unwrap();
+        let body = axum::body::to_bytes(res.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let team: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(team["color"], "red");
+        assert_eq!(team["note"], "storm");
+        assert!(team["expires_at"].is_string());
+
+        let res = app.oneshot(call("GET", "/status", "")).await.unwrap();
+        let body = axum::body::to_bytes(res.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let lights: Vec<serde_json::Value> = serde_json::from_slice(&body).unwrap();
+        assert_eq!(lights.len(), 1, "team light is not a per-name light");
+        assert_eq!(lights[0]["name"], "Nico");
+        assert_eq!(lights[0]["status"], "yellow");
+
+        let published = state.bus.drain();
+        assert!(published.contains(&"status:team:red".to_string()));
+    }
+}
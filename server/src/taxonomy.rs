@@ -0,0 +1,760 @@
+//! Emotion taxonomy — canonical kinds, synonyms, bands
+//! ---------------------------------------------------
+//! Whisper: "one feeling, many names." 🪞
+//!
+//! `emotion_kinds` holds one row per canonical kind: its band, the default bridge kind
+//! (the key `bridges::suggest` falls back to; the kind itself when empty) and a
+//! color hint. `emotion_kind_synonyms` maps every other spelling — English variants
+//! and Romanian names — onto a canonical kind. Seeded once from `SEED` (see
+//! `db::ensure_schema`); existing emotion rows are renamed to their canonical kind then,
+//! keeping the old spelling in `emotions.kind_raw`.
+//!
+//! Lookups go through `fold()`: trimmed, lowercased, diacritics dropped, spaces and
+//! dashes turned into `_`. "Anxiety ", "anxious" and "neliniște" all land on `anxiety`.
+//!
+//! `/emotions/add` stores the canonical kind. Unknown kinds are stored folded and the
+//! response carries `unknown_kind: true`; with `M3_EMOTION_KINDS_STRICT=1` they are
+//! rejected with 422 instead.
+//!
+//! Endpoints (mounted under `/emotions/kinds`)
+//!   GET    /emotions/kinds              → all kinds with synonyms
+//!   POST   /emotions/kinds              → { name, band, bridge?, color?, synonyms? }
+//!   GET    /emotions/kinds/resolve?kind= → { input, kind, known, band?, bridge? }
+//!   GET    /emotions/kinds/:name        → one kind (synonyms resolve too)
+//!   PATCH  /emotions/kinds/:name        → { band?, bridge?, color?, synonyms? } ("" clears;
+//!                                          `synonyms` replaces the list)
+//!   DELETE /emotions/kinds/:name        → 204; rows keep their text and fall back to the
+//!                                          intensity band
+
+use crate::consciousness::Band;
+use crate::AppState;
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    routing::get,
+    Json, Router,
+};
+use rusqlite::OptionalExtension;
+use serde::{Deserialize, Serialize};
+
+type ApiErr = (StatusCode, String);
+
+fn unprocessable(msg: impl Into<String>) -> ApiErr {
+    (StatusCode::UNPROCESSABLE_ENTITY, msg.into())
+}
+
+fn internal(e: impl std::fmt::Display) -> ApiErr {
+    (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+}
+
+/// A compiled-in starting row; editable afterwards through `/emotions/kinds`.
+pub struct Seed {
+    pub name: &'static str,
+    pub band: Band,
+    pub bridge: Option<&'static str>,
+    pub color: &'static str,
+    pub synonyms: &'static [&'static str],
+}
+
+const fn seed(
+    name: &'static str,
+    band: Band,
+    bridge: Option<&'static str>,
+    color: &'static str,
+    synonyms: &'static [&'static str],
+) -> Seed {
+    Seed {
+        name,
+        band,
+        bridge,
+        color,
+        synonyms,
+    }
+}
+
+#[rustfmt::skip]
+pub const SEED: &[Seed] = &[
+    // survival
+    seed("fear", Band::Survival, None, "#e4572e", &["afraid", "scared", "frică", "teamă", "spaimă"]),
+    seed("anxiety", Band::Survival, None, "#f28f3b", &["anxious", "worry", "worried", "nervous", "anxietate", "îngrijorare", "neliniște"]),
+    seed("panic", Band::Survival, None, "#d62828", &["panicked", "panică", "atac de panică"]),
+    seed("anger", Band::Survival, None, "#c1121f", &["angry", "mad", "rage", "furious", "furie", "mânie"]),
+    seed("shame", Band::Survival, None, "#8d5a97", &["ashamed", "embarrassed", "rușine"]),
+    seed("guilt", Band::Survival, Some("shame"), "#7b2cbf", &["guilty", "vină", "vinovăție"]),
+    seed("grief", Band::Survival, None, "#5c677d", &["sad", "sadness", "sorrow", "loss", "tristețe", "durere", "doliu"]),
+    seed("apathy", Band::Survival, None, "#6c757d", &["numb", "indifferent", "apatie"]),
+    seed("desire", Band::Survival, None, "#ff006e", &["craving", "longing", "dorință"]),
+    seed("pride", Band::Survival, None, "#ffb703", &["proud", "mândrie"]),
+    seed("jealousy", Band::Survival, Some("phantom_rival"), "#2a9d8f", &["jealous", "envy", "comparison_jealousy", "gelozie", "invidie"]),
+    // integrity
+    seed("courage", Band::Integrity, None, "#43aa8b", &["brave", "curaj"]),
+    seed("neutrality", Band::Integrity, None, "#adb5bd", &["neutral", "neutru"]),
+    seed("willingness", Band::Integrity, None, "#90be6d", &["willing", "openness", "deschidere"]),
+    seed("acceptance", Band::Integrity, None, "#4d908e", &["accepting", "acceptare"]),
+    seed("reason", Band::Integrity, None, "#577590", &["clarity", "understanding", "claritate", "rațiune"]),
+    seed("paradox", Band::Integrity, None, "#8338ec", &["paradoxical", "paradoxal"]),
+    // coherence
+    seed("love", Band::Coherence, None, "#ff8fab", &["loving", "iubire", "dragoste"]),
+    seed("joy", Band::Coherence, None, "#ffd166", &["happy", "happiness", "glad", "bucurie", "fericire"]),
+    seed("peace", Band::Coherence, None, "#a2d2ff", &["calm", "serene", "pace", "liniște"]),
+    seed("gratitude", Band::Coherence, None, "#80ed99", &["grateful", "thankful", "thanks", "recunoștință", "mulțumire"]),
+];
+
+/// Lookup key: lowercase, no diacritics, words joined by `_`, other punctuation dropped.
+pub fn fold(raw: &str) -> String {
+    let mut out = String::with_capacity(raw.len());
+    for ch in raw.trim().to_lowercase().chars() {
+        let ch = match ch {
+            'ă' | 'â' | 'à' | 'á' | 'ä' | 'ã' | 'å' => 'a',
+            'î' | 'ì' | 'í' | 'ï' => 'i',
+            'ș' | 'ş' => 's',
+            'ț' | 'ţ' => 't',
+            'é' | 'è' | 'ê' | 'ë' => 'e',
+            'ó' | 'ò' | 'ô' | 'ö' | 'õ' => 'o',
+            'ú' | 'ù' | 'û' | 'ü' => 'u',
+            'ç' => 'c',
+            'ñ' => 'n',
+            c if c.is_whitespace() || c == '-' || c == '_' => '_',
+            c if c.is_alphanumeric() => c,
+            _ => continue,
+        };
+        if !(ch == '_' && (out.is_empty() || out.ends_with('_'))) {
+            out.push(ch);
+        }
+    }
+    out.trim_end_matches('_').to_string()
+}
+
+/// Band from the compiled seed (no DB); `consciousness::band_from_emotion` uses this.
+/// Code holding a connection reads `emotion_kinds` instead (`normalize`).
+pub fn seed_band(kind: &str) -> Option<Band> {
+    let key = fold(kind);
+    SEED.iter()
+        .find(|s| s.name == key || s.synonyms.iter().any(|syn| fold(syn) == key))
+        .map(|s| s.band)
+}
+
+/// Registry band for an `emotions` row, as a column expression (NULL when unknown).
+pub const BAND_SQL: &str = "(SELECT band FROM emotion_kinds k WHERE k.name = emotions.kind)";
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct EmotionKind {
+    pub name: String,
+    pub band: Band,
+    /// Bridge kind for `bridges::suggest`; None = the kind itself.
+    pub bridge: Option<String>,
+    /// UI color hint (e.g. `#e4572e`).
+    pub color: Option<String>,
+    /// Folded, alphabetical.
+    pub synonyms: Vec<String>,
+    pub updated_at: String,
+}
+
+impl EmotionKind {
+    /// The key to look up in the bridge library.
+    pub fn bridge_kind(&self) -> &str {
+        self.bridge.as_deref().unwrap_or(&self.name)
+    }
+}
+
+fn synonyms_of(c: &rusqlite::Connection, name: &str) -> rusqlite::Result<Vec<String>> {
+    c.prepare("SELECT synonym FROM emotion_kind_synonyms WHERE kind = ?1 ORDER BY synonym")?
+        .query_map([name], |r| r.get(0))?
+        .collect()
+}
+
+fn row_to_kind(r: &rusqlite::Row) -> rusqlite::Result<EmotionKind> {
+    let band: String = r.get(1)?;
+    let band = Band::parse(&band).ok_or_else(|| {
+        rusqlite::Error::FromSqlConversionFailure(
+            1,
+            rusqlite::types::Type::Text,
+            format!("unknown band {band:?}").into(),
+        )
+    })?;
+    Ok(EmotionKind {
+        name: r.get(0)?,
+        band,
+        bridge: r.get(2)?,
+        color: r.get(3)?,
+        synonyms: Vec::new(),
+        updated_at: r.get(4)?,
+    })
+}
+
+/// Exact canonical name (already folded).
+fn by_name(c: &rusqlite::Connection, name: &str) -> rusqlite::Result<Option<EmotionKind>> {
+    let Some(mut k) = c
+        .query_row(
+            "SELECT name, band, bridge, color, updated_at FROM emotion_kinds WHERE name = ?1",
+            [name],
+            row_to_kind,
+        )
+        .optional()?
+    else {
+        return Ok(None);
+    };
+    k.synonyms = synonyms_of(c, &k.name)?;
+    Ok(Some(k))
+}
+
+/// Canonical kind for any spelling: its name or one of its synonyms.
+pub fn resolve(c: &rusqlite::Connection, raw: &str) -> rusqlite::Result<Option<EmotionKind>> {
+    let key = fold(raw);
+    if key.is_empty() {
+        return Ok(None);
+    }
+    if let Some(k) = by_name(c, &key)? {
+        return Ok(Some(k));
+    }
+    let canonical: Option<String> = c
+        .query_row(
+            "SELECT kind FROM emotion_kind_synonyms WHERE synonym = ?1",
+            [&key],
+            |r| r.get(0),
+        )
+        .optional()?;
+    match canonical {
+        Some(name) => by_name(c, &name),
+        None => Ok(None),
+    }
+}
+
+/// What to store for a submitted kind.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Normalized {
+    /// Canonical name, or the folded input when unknown.
+    pub kind: String,
+    pub known: Option<EmotionKind>,
+}
+
+pub fn normalize(c: &rusqlite::Connection, raw: &str) -> rusqlite::Result<Normalized> {
+    Ok(match resolve(c, raw)? {
+        Some(k) => Normalized {
+            kind: k.name.clone(),
+            known: Some(k),
+        },
+        None => Normalized {
+            kind: fold(raw),
+            known: None,
+        },
+    })
+}
+
+/// `M3_EMOTION_KINDS_STRICT=1|true` rejects unknown kinds on `/emotions/add`.
+pub fn strict() -> bool {
+    std::env::var("M3_EMOTION_KINDS_STRICT")
+        .map(|v| matches!(v.trim().to_ascii_lowercase().as_str(), "1" | "true" | "yes"))
+        .unwrap_or(false)
+}
+
+pub fn list(c: &rusqlite::Connection) -> rusqlite::Result<Vec<EmotionKind>> {
+    let mut kinds = c
+        .prepare("SELECT name, band, bridge, color, updated_at FROM emotion_kinds ORDER BY name")?
+        .query_map([], row_to_kind)?
+        .collect::<Result<Vec<_>, _>>()?;
+    for k in &mut kinds {
+        k.synonyms = synonyms_of(c, &k.name)?;
+    }
+    Ok(kinds)
+}
+
+/// Why a write was refused.
+#[derive(Debug, PartialEq)]
+pub enum KindError {
+    Missing,
+    Conflict(String),
+    Invalid(String),
+}
+
+/// Point `synonyms` (folded, deduplicated) at `name`, replacing its previous list.
+fn set_synonyms(
+    c: &rusqlite::Connection,
+    name: &str,
+    synonyms: &[String],
+) -> rusqlite::Result<Result<(), KindError>> {
+    let mut keys: Vec<String> = synonyms
+        .iter()
+        .map(|s| fold(s))
+        .filter(|s| !s.is_empty() && s != name)
+        .collect();
+    keys.sort();
+    keys.dedup();
+    for key in &keys {
+        let owner: Option<String> = c
+            .query_row(
+                "SELECT name FROM emotion_kinds WHERE name = ?1
+                 UNION ALL
+                 SELECT kind FROM emotion_kind_synonyms WHERE synonym = ?1 AND kind != ?2",
+                rusqlite::params![key, name],
+                |r| r.get(0),
+            )
+            .optional()?;
+        if let Some(owner) = owner {
+            return Ok(Err(KindError::Conflict(format!(
+                "'{key}' already means '{owner}'"
+            ))));
+        }
+    }
+    c.execute("DELETE FROM emotion_kind_synonyms WHERE kind = ?1", [name])?;
+    for key in &keys {
+        c.execute(
+            "INSERT INTO emotion_kind_synonyms(synonym, kind) VALUES(?1, ?2)",
+            rusqlite::params![key, name],
+        )?;
+    }
+    Ok(Ok(()))
+}
+
+fn clean(v: Option<&str>) -> Option<String> {
+    v.map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+}
+
+/// Body for `POST /emotions/kinds`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct KindIn {
+    pub name: String,
+    pub band: String,
+    #[serde(default)]
+    pub bridge: Option<String>,
+    #[serde(default)]
+    pub color: Option<String>,
+    #[serde(default)]
+    pub synonyms: Vec<String>,
+}
+
+pub fn create(
+    c: &mut rusqlite::Connection,
+    input: &KindIn,
+    now: &str,
+) -> rusqlite::Result<Result<EmotionKind, KindError>> {
+    let name = fold(&input.name);
+    if name.is_empty() {
+        return Ok(Err(KindError::Invalid("name cannot be empty".into())));
+    }
+    let Some(band) = Band::parse(&input.band) else {
+        return Ok(Err(KindError::Invalid(
+            "band must be survival, integrity or coherence".into(),
+        )));
+    };
+    let tx = c.transaction()?;
+    if let Some(existing) = resolve(&tx, &name)? {
+        return Ok(Err(KindError::Conflict(format!(
+            "'{name}' already means '{}'",
+            existing.name
+        ))));
+    }
+    tx.execute(
+        "INSERT INTO emotion_kinds(name, band, bridge, color, updated_at) VALUES(?1, ?2, ?3, ?4, ?5)",
+        rusqlite::params![
+            name,
+            band.as_str(),
+            clean(input.bridge.as_deref()).map(|b| fold(&b)),
+            clean(input.color.as_deref()),
+            now
+        ],
+    )?;
+    if let Err(e) = set_synonyms(&tx, &name, &input.synonyms)? {
+        return Ok(Err(e));
+    }
+    let out = by_name(&tx, &name)?;
+    tx.commit()?;
+    Ok(out.ok_or(KindError::Missing))
+}
+
+/// PATCH body; absent fields are kept, `""` clears `bridge` / `color`.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct KindPatch {
+    #[serde(default)]
+    pub band: Option<String>,
+    #[serde(default)]
+    pub bridge: Option<String>,
+    #[serde(default)]
+    pub color: Option<String>,
+    #[serde(default)]
+    pub synonyms: Option<Vec<String>>,
+}
+
+pub fn update(
+    c: &mut rusqlite::Connection,
+    raw: &str,
+    patch: &KindPatch,
+    now: &str,
+) -> rusqlite::Result<Result<EmotionKind, KindError>> {
+    let band = match patch.band.as_deref() {
+        None => None,
+        Some(b) => match Band::parse(b) {
+            Some(b) => Some(b.as_str()),
+            None => {
+                return Ok(Err(KindError::Invalid(
+                    "band must be survival, integrity or coherence".into(),
+                )))
+            }
+        },
+    };
+    let tx = c.transaction()?;
+    let Some(kind) = resolve(&tx, raw)? else {
+        return Ok(Err(KindError::Missing));
+    };
+    let bridge = patch.bridge.as_deref().map(fold);
+    let color = patch.color.as_deref().map(|s| s.trim().to_string());
+    tx.execute(
+        "UPDATE emotion_kinds SET
+           band       = COALESCE(?1, band),
+           bridge     = CASE WHEN ?2 IS NULL THEN bridge ELSE NULLIF(?2, '') END,
+           color      = CASE WHEN ?3 IS NULL THEN color ELSE NULLIF(?3, '') END,
+           updated_at = ?4
+         WHERE name = ?5",
+        rusqlite::params![band, bridge, color, now, kind.name],
+    )?;
+    if let Some(synonyms) = &patch.synonyms {
+        if let Err(e) = set_synonyms(&tx, &kind.name, synonyms)? {
+            return Ok(Err(e));
+        }
+    }
+    let out = by_name(&tx, &kind.name)?;
+    tx.commit()?;
+    Ok(out.ok_or(KindError::Missing))
+}
+
+/// Remove a kind and its synonyms. Returns false when unknown.
+pub fn delete(c: &mut rusqlite::Connection, raw: &str) -> rusqlite::Result<bool> {
+    let tx = c.transaction()?;
+    let Some(kind) = resolve(&tx, raw)? else {
+        return Ok(false);
+    };
+    tx.execute(
+        "DELETE FROM emotion_kind_synonyms WHERE kind = ?1",
+        [&kind.name],
+    )?;
+    tx.execute("DELETE FROM emotion_kinds WHERE name = ?1", [&kind.name])?;
+    tx.commit()?;
+    Ok(true)
+}
+
+/// Insert `SEED` and rename existing emotion rows to their canonical (or folded) kind;
+/// the spelling they had is kept in `emotions.kind_raw`. Called once from `db::ensure_schema`.
+pub fn seed_registry(c: &rusqlite::Connection) -> rusqlite::Result<()> {
+    let now = chrono::Utc::now().to_rfc3339();
+    for s in SEED {
+        c.execute(
+            "INSERT OR IGNORE INTO emotion_kinds(name, band, bridge, color, updated_at)
+             VALUES(?1, ?2, ?3, ?4, ?5)",
+            rusqlite::params![s.name, s.band.as_str(), s.bridge, s.color, now],
+        )?;
+        for syn in s.synonyms {
+            let key = fold(syn);
+            if key != s.name {
+                c.execute(
+                    "INSERT OR IGNORE INTO emotion_kind_synonyms(synonym, kind) VALUES(?1, ?2)",
+                    rusqlite::params![key, s.name],
+                )?;
+            }
+        }
+    }
+    let kinds: Vec<String> = c
+        .prepare("SELECT DISTINCT kind FROM emotions")?
+        .query_map([], |r| r.get(0))?
+        .collect::<Result<_, _>>()?;
+    for raw in kinds {
+        let n = normalize(c, &raw)?;
+        if n.kind != raw && !n.kind.is_empty() {
+            c.execute(
+                "UPDATE emotions SET kind_raw = COALESCE(kind_raw, kind), kind = ?1 WHERE kind = ?2",
+                rusqlite::params![n.kind, raw],
+            )?;
+        }
+    }
+    Ok(())
+}
+
+fn http_err(e: KindError) -> ApiErr {
+    match e {
+        KindError::Missing => (StatusCode::NOT_FOUND, "unknown emotion kind".into()),
+        KindError::Conflict(msg) => (StatusCode::CONFLICT, msg),
+        KindError::Invalid(msg) => unprocessable(msg),
+    }
+}
+
+/// GET /emotions/kinds
+async fn list_http(State(state): State<AppState>) -> Result<Json<Vec<EmotionKind>>, ApiErr> {
+    state
+        .db
+        .0
+        .call(|c| Ok(list(c)?))
+        .await
+        .map(Json)
+        .map_err(internal)
+}
+
+/// POST /emotions/kinds
+async fn create_http(
+    State(state): State<AppState>,
+    Json(input): Json<KindIn>,
+) -> Result<(StatusCode, Json<EmotionKind>), ApiErr> {
+    let now = chrono::Utc::now().to_rfc3339();
+    state
+        .db
+        .0
+        .call(move |c| Ok(create(c, &input, &now)?))
+        .await
+        .map_err(internal)?
+        .map(|k| (StatusCode::CREATED, Json(k)))
+        .map_err(http_err)
+}
+
+#[derive(Debug, Deserialize)]
+struct ResolveParams {
+    kind: String,
+}
+
+#[derive(Debug, Serialize)]
+struct ResolveOut {
+    input: String,
+    /// What `/emotions/add` would store.
+    kind: String,
+    known: bool,
+    band: Option<Band>,
+    bridge: Option<String>,
+    color: Option<String>,
+}
+
+/// GET /emotions/kinds/resolve?kind=
+async fn resolve_http(
+    State(state): State<AppState>,
+    Query(q): Query<ResolveParams>,
+) -> Result<Json<ResolveOut>, ApiErr> {
+    let input = q.kind;
+    let raw = input.clone();
+    let n = state
+        .db
+        .0
+        .call(move |c| Ok(normalize(c, &raw)?))
+        .await
+        .map_err(internal)?;
+    if n.kind.is_empty() {
+        return Err(unprocessable("kind cannot be empty"));
+    }
+    Ok(Json(ResolveOut {
+        input,
+        known: n.known.is_some(),
+        band: n.known.as_ref().map(|k| k.band),
+        bridge: n.known.as_ref().map(|k| k.bridge_kind().to_string()),
+        color: n.known.and_then(|k| k.color),
+        kind: n.kind,
+    }))
+}
+
+/// GET /emotions/kinds/:name
+async fn get_http(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> Result<Json<EmotionKind>, ApiErr> {
+    state
+        .db
+        .0
+        .call(move |c| Ok(resolve(c, &name)?))
+        .await
+        .map_err(internal)?
+        .map(Json)
+        .ok_or_else(|| http_err(KindError::Missing))
+}
+
+/// PATCH /emotions/kinds/:name
+async fn update_http(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    Json(patch): Json<KindPatch>,
+) -> Result<Json<EmotionKind>, ApiErr> {
+    let now = chrono::Utc::now().to_rfc3339();
+    state
+        .db
+        .0
+        .call(move |c| Ok(update(c, &name, &patch, &now)?))
+        .await
+        .map_err(internal)?
+        .map(Json)
+        .map_err(http_err)
+}
+
+/// DELETE /emotions/kinds/:name
+async fn delete_http(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> Result<StatusCode, ApiErr> {
+    let removed = state
+        .db
+        .0
+        .call(move |c| Ok(delete(c, &name)?))
+        .await
+        .map_err(internal)?;
+    if !removed {
+        return Err(http_err(KindError::Missing));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Router to be nested under `/emotions/kinds` (see `emotions::router`).
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_http).post(create_http))
+        .route("/resolve", get(resolve_http))
+        .route(
+            "/:name",
+            get(get_http).patch(update_http).delete(delete_http),
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conn() -> rusqlite::Connection {
+        let mut c = rusqlite::Connection::open_in_memory().unwrap();
+        crate::db::ensure_schema(&mut c).unwrap();
+        c
+    }
+
+    #[test]
+    fn fold_lowercases_strips_diacritics_and_joins_words() {
+        assert_eq!(fold("  Anxiety "), "anxiety");
+        assert_eq!(fold("Atac de Panică"), "atac_de_panica");
+        assert_eq!(fold("over-analysis!"), "over_analysis");
+        assert_eq!(fold("rușine"), "rusine");
+        assert_eq!(fold(" -- "), "");
+    }
+
+    #[test]
+    fn spellings_resolve_to_one_canonical_kind() {
+        let c = conn();
+        for raw in ["anxiety", "Anxiety ", "anxious", "neliniște", "NELINISTE"] {
+            let n = normalize(&c, raw).unwrap();
+            assert_eq!(n.kind, "anxiety", "{raw}");
+            assert_eq!(n.known.unwrap().band, Band::Survival);
+        }
+        let n = normalize(&c, "Impulsiveness").unwrap();
+        assert_eq!((n.kind.as_str(), n.known), ("impulsiveness", None));
+        assert_eq!(
+            resolve(&c, "vinovăție").unwrap().unwrap().bridge_kind(),
+            "shame"
+        );
+        assert_eq!(resolve(&c, "joy").unwrap().unwrap().bridge_kind(), "joy");
+    }
+
+    #[test]
+    fn seed_band_agrees_with_the_registry() {
+        let c = conn();
+        for kind in list(&c).unwrap() {
+            assert_eq!(seed_band(&kind.name), Some(kind.band), "{}", kind.name);
+            for syn in &kind.synonyms {
+                assert_eq!(seed_band(syn), Some(kind.band), "{syn}");
+            }
+        }
+        assert_eq!(seed_band("unknown"), None);
+    }
+
+    #[test]
+    fn crud_guards_names_and_synonyms() {
+        let mut c = conn();
+        let now = "2025-09-01T00:00:00+00:00";
+        let k = create(
+            &mut c,
+            &KindIn {
+                name: "Overwhelm".into(),
+                band: "survival".into(),
+                bridge: Some("anxiety".into()),
+                color: Some("#ff0000".into()),
+                synonyms: vec!["Copleșire".into(), "overwhelmed".into(), "overwhelm".into()],
+            },
+            now,
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(k.name, "overwhelm");
+        assert_eq!(k.synonyms, ["coplesire", "overwhelmed"]);
+
+        let dup = KindIn {
+            name: "worried".into(),
+            band: "survival".into(),
+            bridge: None,
+            color: None,
+            synonyms: vec![],
+        };
+        assert!(matches!(
+            create(&mut c, &dup, now).unwrap(),
+            Err(KindError::Conflict(_))
+        ));
+        let taken = KindPatch {
+            synonyms: Some(vec!["scared".into()]),
+            ..Default::default()
+        };
+        assert!(matches!(
+            update(&mut c, "overwhelm", &taken, now).unwrap(),
+            Err(KindError::Conflict(_))
+        ));
+
+        let k = update(
+            &mut c,
+            "coplesire",
+            &KindPatch {
+                band: Some("integrity".into()),
+                color: Some(String::new()),
+                ..Default::default()
+            },
+            now,
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!((k.band, k.color), (Band::Integrity, None));
+        assert_eq!(k.synonyms.len(), 2, "synonyms kept when absent");
+
+        assert!(delete(&mut c, "overwhelmed").unwrap());
+        assert!(resolve(&c, "coplesire").unwrap().is_none());
+        assert!(!delete(&mut c, "overwhelm").unwrap());
+    }
+
+    #[test]
+    fn seeding_renames_existing_rows_and_keeps_the_spelling() {
+        let mut c = rusqlite::Connection::open_in_memory().unwrap();
+        crate::db::ensure_schema(&mut c).unwrap();
+        for kind in ["Anxiety ", "anxious", "Impulsiveness"] {
+            c.execute(
+                "INSERT INTO emotions(ts, who, kind, intensity) VALUES('2025-09-01T00:00:00+00:00', 'Raz', ?1, 0.5)",
+                [kind],
+            )
+            .unwrap();
+        }
+        seed_registry(&c).unwrap();
+        let kinds: Vec<(String, Option<String>)> = c
+            .prepare("SELECT kind, kind_raw FROM emotions ORDER BY id")
+            .unwrap()
+            .query_map([], |r| Ok((r.get(0)?, r.get(1)?)))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(
+            kinds,
+            [
+                ("anxiety".into(), Some("Anxiety ".into())),
+                ("anxiety".into(), Some("anxious".into())),
+                ("impulsiveness".into(), Some("Impulsiveness".into())),
+            ]
+        );
+    }
+
+    #[test]
+    fn a_row_with_an_unknown_band_is_an_error() {
+        let c = conn();
+        c.execute(
+            "UPDATE emotion_kinds SET band = 'cosmic' WHERE name = 'joy'",
+            [],
+        )
+        .unwrap();
+        assert!(matches!(
+            resolve(&c, "joy"),
+            Err(rusqlite::Error::FromSqlConversionFailure(1, _, _))
+        ));
+    }
+}
@@ -0,0 +1,544 @@
+//! Team roster + pillar definitions
+//! --------------------------------
+//! Whisper: "every household names its own." 🏡
+//!
+//! The dashboard used to hardcode Raz, Sawsan and Nico and five pillars. Both now
+//! live in the DB (seeded once with those defaults) and can be edited per household:
+//!
+//!   • `team_members(id, name, avatar, default_energy 0..100, position)`
+//!   • `pillars(key, label, allowed JSON array, default_value, position)`
+//!
+//! `/state/set` validates members and pillar values against these definitions, and
+//! `/state/get` shapes the stored state to the roster (order, avatars, defaults).
+//!
+//! Endpoints (mounted under `/team`)
+//!   GET    /team                    → { members, pillars }
+//!   POST   /team/members            → { name, avatar?, default_energy? }
+//!   PATCH  /team/members/:id        → { name?, avatar?, default_energy?, position? }  (rename carries energy)
+//!   DELETE /team/members/:id
+//!   POST   /team/pillars            → { key, label?, allowed:[..], default_value? }
+//!   PATCH  /team/pillars/:key       → { label?, allowed?, default_value?, position? }
+//!   DELETE /team/pillars/:key
+
+use crate::{
+    models::{MemberEnergy, PillarStatus, SetStateRequest},
+    AppState,
+};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{get, patch, post},
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct Member {
+    pub id: i64,
+    pub name: String,
+    pub avatar: Option<String>,
+    pub default_energy: i32,
+    pub position: i64,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct Pillar {
+    pub key: String,
+    pub label: String,
+    pub allowed: Vec<String>,
+    pub default_value: String,
+    pub position: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Definitions {
+    pub members: Vec<Member>,
+    pub pillars: Vec<Pillar>,
+}
+
+pub fn load(c: &rusqlite::Connection) -> rusqlite::Result<Definitions> {
+    let mut stmt = c.prepare(
+        "SELECT id, name, avatar, default_energy, position FROM team_members ORDER BY position, id",
+    )?;
+    let members = stmt
+        .query_map([], |r| {
+            Ok(Member {
+                id: r.get(0)?,
+                name: r.get(1)?,
+                avatar: r.get(2)?,
+                default_energy: r.get(3)?,
+                position: r.get(4)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut stmt = c.prepare(
+        "SELECT key, label, allowed, default_value, position FROM pillars ORDER BY position, key",
+    )?;
+    let pillars = stmt
+        .query_map([], |r| {
+            let allowed: String = r.get(2)?;
+            Ok(Pillar {
+                key: r.get(0)?,
+                label: r.get(1)?,
+                allowed: serde_json::from_str(&allowed).unwrap_or_default(),
+                default_value: r.get(3)?,
+                position: r.get(4)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(Definitions { members, pillars })
+}
+
+/// Check a partial state update against the definitions; all problems at once.
+pub fn validate(defs: &Definitions, req: &SetStateRequest) -> Result<(), Vec<String>> {
+    let mut problems = Vec::new();
+    if let Some(members) = &req.members {
+        for m in members {
+            if !defs.members.iter().any(|d| d.name == m.name) {
+                problems.push(format!("unknown member '{}'", m.name));
+            }
+            if !(0..=100).contains(&m.energy) {
+                problems.push(format!("energy for '{}' must be 0..100", m.name));
+            }
+        }
+    }
+    if let Some(pillars) = &req.pillars {
+        for (k, v) in pillars {
+            match defs.pillars.iter().find(|p| &p.key == k) {
+                None => problems.push(format!("unknown pillar '{k}'")),
+                Some(p) if !p.allowed.contains(v) => problems.push(format!(
+                    "pillar '{k}' must be one of {}",
+                    p.allowed.join(", ")
+                )),
+                Some(_) => {}
+            }
+        }
+    }
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(problems)
+    }
+}
+
+/// Shape a stored state to the roster: roster order + avatars, saved energy by name
+/// (else default_energy), defined pillars only (saved value if still allowed, else default).
+pub fn shape(defs: &Definitions, state: &mut Value) {
+    let saved_members: Vec<MemberEnergy> = state
+        .get("members")
+        .cloned()
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default();
+    let members: Vec<MemberEnergy> = defs
+        .members
+        .iter()
+        .map(|d| MemberEnergy {
+            name: d.name.clone(),
+            energy: saved_members
+                .iter()
+                .find(|m| m.name == d.name)
+                .map(|m| m.energy)
+                .unwrap_or(d.default_energy),
+            avatar: d.avatar.clone(),
+        })
+        .collect();
+
+    let saved_pillars = state.get("pillars").cloned().unwrap_or(Value::Null);
+    let pillars: PillarStatus = defs
+        .pillars
+        .iter()
+        .map(|p| {
+            let v = saved_pillars
+                .get(&p.key)
+                .and_then(|v| v.as_str())
+                .filter(|v| p.allowed.iter().any(|a| a == v))
+                .unwrap_or(&p.default_value);
+            (p.key.clone(), v.to_string())
+        })
+        .collect();
+
+    state["members"] = serde_json::to_value(members).unwrap_or(Value::Null);
+    state["pillars"] = serde_json::to_value(pillars).unwrap_or(Value::Null);
+}
+
+// ---- HTTP -------------------------------------------------------------------
+
+type ApiErr = (StatusCode, String);
+
+fn unprocessable(msg: impl Into<String>) -> ApiErr {
+    (StatusCode::UNPROCESSABLE_ENTITY, msg.into())
+}
+
+fn internal(e: impl std::fmt::Display) -> ApiErr {
+    (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+}
+
+/// Map a UNIQUE violation to 409, anything else to 500.
+fn write_err(e: tokio_rusqlite::Error) -> ApiErr {
+    match &e {
+        tokio_rusqlite::Error::Rusqlite(rusqlite::Error::SqliteFailure(f, _))
+            if f.code == rusqlite::ErrorCode::ConstraintViolation =>
+        {
+            (StatusCode::CONFLICT, "already exists".into())
+        }
+        _ => internal(e),
+    }
+}
+
+async fn definitions(state: &AppState) -> Result<Definitions, ApiErr> {
+    state.db.0.call(|c| Ok(load(c)?)).await.map_err(internal)
+}
+
+/// GET /team
+async fn get_team(State(state): State<AppState>) -> Result<Json<Definitions>, ApiErr> {
+    definitions(&state).await.map(Json)
+}
+
+#[derive(Debug, Deserialize)]
+struct MemberIn {
+    name: String,
+    #[serde(default)]
+    avatar: Option<String>,
+    #[serde(default)]
+    default_energy: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MemberPatch {
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    avatar: Option<String>,
+    #[serde(default)]
+    default_energy: Option<i32>,
+    #[serde(default)]
+    position: Option<i64>,
+}
+
+fn check_energy(e: i32) -> Result<i32, ApiErr> {
+    if (0..=100).contains(&e) {
+        Ok(e)
+    } else {
+        Err(unprocessable("default_energy must be 0..100"))
+    }
+}
+
+/// POST /team/members
+async fn add_member(
+    State(state): State<AppState>,
+    Json(input): Json<MemberIn>,
+) -> Result<Json<Definitions>, ApiErr> {
+    let name = input.name.trim().to_string();
+    if name.is_empty() {
+        return Err(unprocessable("name required"));
+    }
+    let energy = check_energy(input.default_energy.unwrap_or(60))?;
+    state
+        .db
+        .0
+        .call(move |c| {
+            c.execute(
+                "INSERT INTO team_members(name, avatar, default_energy, position)
+                 VALUES(?1, ?2, ?3, (SELECT COALESCE(MAX(position), -1) + 1 FROM team_members))",
+                rusqlite::params![name, input.avatar, energy],
+            )?;
+            Ok(())
+        })
+        .await
+        .map_err(write_err)?;
+    definitions(&state).await.map(Json)
+}
+
+/// PATCH /team/members/:id — a rename also renames the member in the current
+/// dashboard state so their energy carries over.
+async fn update_member(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+    Json(input): Json<MemberPatch>,
+) -> Result<Json<Definitions>, ApiErr> {
+    let rename = match input.name.as_deref().map(str::trim) {
+        Some("") => return Err(unprocessable("name cannot be empty")),
+        other => other.map(str::to_string),
+    };
+    if let Some(e) = input.default_energy {
+        check_energy(e)?;
+    }
+    let found = state
+        .db
+        .0
+        .call(move |c| {
+            let tx = c.transaction()?;
+            let old: Option<String> = tx
+                .query_row("SELECT name FROM team_members WHERE id = ?1", [id], |r| {
+                    r.get(0)
+                })
+                .ok();
+            let Some(old) = old else {
+                return Ok(false);
+            };
+            tx.execute(
+                "UPDATE team_members SET
+                   name = COALESCE(?1, name),
+                   avatar = COALESCE(?2, avatar),
+                   default_energy = COALESCE(?3, default_energy),
+                   position = COALESCE(?4, position)
+                 WHERE id = ?5",
+                rusqlite::params![
+                    rename,
+                    input.avatar,
+                    input.default_energy,
+                    input.position,
+                    id
+                ],
+            )?;
+            if let Some(new) = rename.as_deref().filter(|n| *n != old) {
+                crate::dashboard::rename_member(&tx, &old, new)?;
+            }
+            tx.commit()?;
+            Ok(true)
+        })
+        .await
+        .map_err(write_err)?;
+    if !found {
+        return Err((StatusCode::NOT_FOUND, "member not found".into()));
+    }
+    definitions(&state).await.map(Json)
+}
+
+/// DELETE /team/members/:id
+async fn remove_member(
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+) -> Result<Json<Definitions>, ApiErr> {
+    let n = state
+        .db
+        .0
+        .call(move |c| Ok(c.execute("DELETE FROM team_members WHERE id = ?1", [id])?))
+        .await
+        .map_err(internal)?;
+    if n == 0 {
+        return Err((StatusCode::NOT_FOUND, "member not found".into()));
+    }
+    definitions(&state).await.map(Json)
+}
+
+#[derive(Debug, Deserialize)]
+struct PillarIn {
+    key: String,
+    #[serde(default)]
+    label: Option<String>,
+    allowed: Vec<String>,
+    #[serde(default)]
+    default_value: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PillarPatch {
+    #[serde(default)]
+    label: Option<String>,
+    #[serde(default)]
+    allowed: Option<Vec<String>>,
+    #[serde(default)]
+    default_value: Option<String>,
+    #[serde(default)]
+    position: Option<i64>,
+}
+
+fn clean_allowed(allowed: Vec<String>) -> Result<Vec<String>, ApiErr> {
+    let mut out: Vec<String> = Vec::new();
+    for v in allowed.into_iter().map(|v| v.trim().to_string()) {
+        if !v.is_empty() && !out.contains(&v) {
+            out.push(v);
+        }
+    }
+    if out.is_empty() {
+        return Err(unprocessable("allowed needs at least one value"));
+    }
+    Ok(out)
+}
+
+/// POST /team/pillars
+async fn add_pillar(
+    State(state): State<AppState>,
+    Json(input): Json<PillarIn>,
+) -> Result<Json<Definitions>, ApiErr> {
+    let key = input.key.trim().to_lowercase().replace([' ', '-'], "_");
+    if key.is_empty() {
+        return Err(unprocessable("key required"));
+    }
+    let allowed = clean_allowed(input.allowed)?;
+    let default_value = input.default_value.unwrap_or_else(|| allowed[0].clone());
+    if !allowed.contains(&default_value) {
+        return Err(unprocessable("default_value must be one of allowed"));
+    }
+    let label = input.label.unwrap_or_else(|| key.clone());
+    let allowed = serde_json::to_string(&allowed).map_err(internal)?;
+    state
+        .db
+        .0
+        .call(move |c| {
+            c.execute(
+                "INSERT INTO pillars(key, label, allowed, default_value, position)
+                 VALUES(?1, ?2, ?3, ?4, (SELECT COALESCE(MAX(position), -1) + 1 FROM pillars))",
+                rusqlite::params![key, label, allowed, default_value],
+            )?;
+            Ok(())
+        })
+        .await
+        .map_err(write_err)?;
+    definitions(&state).await.map(Json)
+}
+
+/// PATCH /team/pillars/:key
+async fn update_pillar(
+    State(state): State<AppState>,
+    Path(key): Path<String>,
+    Json(input): Json<PillarPatch>,
+) -> Result<Json<Definitions>, ApiErr> {
+    let defs = definitions(&state).await?;
+    let Some(cur) = defs.pillars.iter().find(|p| p.key == key) else {
+        return Err((StatusCode::NOT_FOUND, "pillar not found".into()));
+    };
+    let allowed = match input.allowed {
+        Some(a) => clean_allowed(a)?,
+        None => cur.allowed.clone(),
+    };
+    let default_value = input
+        .default_value
+        .unwrap_or_else(|| cur.default_value.clone());
+    if !allowed.contains(&default_value) {
+        return Err(unprocessable("default_value must be one of allowed"));
+    }
+    let label = input.label.unwrap_or_else(|| cur.label.clone());
+    let position = input.position.unwrap_or(cur.position);
+    let allowed = serde_json::to_string(&allowed).map_err(internal)?;
+    state
+        .db
+        .0
+        .call(move |c| {
+            c.execute(
+                "UPDATE pillars SET label=?1, allowed=?2, default_value=?3, position=?4 WHERE key=?5",
+                rusqlite::params![label, allowed, default_value, position, key],
+            )?;
+            Ok(())
+        })
+        .await
+        .map_err(internal)?;
+    definitions(&state).await.map(Json)
+}
+
+/// DELETE /team/pillars/:key
+async fn remove_pillar(
+    State(state): State<AppState>,
+    Path(key): Path<String>,
+) -> Result<Json<Definitions>, ApiErr> {
+    let n = state
+        .db
+        .0
+        .call(move |c| Ok(c.execute("DELETE FROM pillars WHERE key = ?1", [key])?))
+        .await
+        .map_err(internal)?;
+    if n == 0 {
+        return Err((StatusCode::NOT_FOUND, "pillar not found".into()));
+    }
+    definitions(&state).await.map(Json)
+}
+
+/// Router to be mounted under `/team`.
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/", get(get_team))
+        .route("/members", post(add_member))
+        .route("/members/:id", patch(update_member).delete(remove_member))
+        .route("/pillars", post(add_pillar))
+        .route("/pillars/:key", patch(update_pillar).delete(remove_pillar))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seeded() -> (rusqlite::Connection, Definitions) {
+        let mut c = rusqlite::Connection::open_in_memory().unwrap();
+        crate::db::ensure_schema(&mut c).unwrap();
+        let defs = load(&c).unwrap();
+        (c, defs)
+    }
+
+    fn member(name: &str, energy: i32) -> MemberEnergy {
+        MemberEnergy {
+            name: name.into(),
+            energy,
+            avatar: None,
+        }
+    }
+
+    #[test]
+    fn seed_matches_previous_defaults() {
+        let (_c, defs) = seeded();
+        let names: Vec<&str> = defs.members.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(names, vec!["Raz", "Sawsan", "Nico"]);
+        assert_eq!(defs.members[0].default_energy, 70);
+        let keys: Vec<&str> = defs.pillars.iter().map(|p| p.key.as_str()).collect();
+        assert_eq!(keys, vec!["crown", "void", "play", "dragon", "life_force"]);
+        assert_eq!(defs.pillars[2].default_value, "good");
+        assert_eq!(defs.pillars[0].allowed, vec!["good", "watch", "rest"]);
+    }
+
+    #[test]
+    fn seed_runs_once() {
+        let (mut c, _) = seeded();
+        c.execute("DELETE FROM team_members WHERE name='Nico'", [])
+            .unwrap();
+        crate::db::ensure_schema(&mut c).unwrap();
+        assert_eq!(load(&c).unwrap().members.len(), 2);
+    }
+
+    #[test]
+    fn validate_rejects_unknown_names_values_and_ranges() {
+        let (_c, defs) = seeded();
+        let ok = SetStateRequest {
+            members: Some(vec![member("Raz", 80)]),
+            pillars: Some([("play".to_string(), "rest".to_string())].into()),
+            note: None,
+        };
+        assert!(validate(&defs, &ok).is_ok());
+
+        let bad = SetStateRequest {
+            members: Some(vec![member("Zed", 50), member("Raz", 101)]),
+            pillars: Some(
+                [
+                    ("play".to_string(), "great".to_string()),
+                    ("moon".to_string(), "good".to_string()),
+                ]
+                .into(),
+            ),
+            note: None,
+        };
+        let problems = validate(&defs, &bad).unwrap_err();
+        assert_eq!(problems.len(), 4, "{problems:?}");
+    }
+
+    #[test]
+    fn shape_follows_roster_and_pillar_definitions() {
+        let (_c, mut defs) = seeded();
+        defs.members[1].avatar = Some("🌙".into());
+        defs.pillars.retain(|p| p.key != "void");
+        let mut state = serde_json::json!({
+            "members": [{"name":"Nico","energy":10},{"name":"Gone","energy":99}],
+            "pillars": {"crown":"rest","void":"good","play":"bogus"},
+            "note": "keep"
+        });
+        shape(&defs, &mut state);
+        assert_eq!(state["members"][0]["name"], "Raz");
+        assert_eq!(state["members"][0]["energy"], 70);
+        assert_eq!(state["members"][1]["avatar"], "🌙");
+        assert_eq!(state["members"][2]["energy"], 10);
+        assert_eq!(state["members"].as_array().unwrap().len(), 3);
+        assert_eq!(state["pillars"]["crown"], "rest");
+        assert_eq!(state["pillars"]["play"], "good");
+        assert!(state["pillars"].get("void").is_none());
+        assert_eq!(state["note"], "keep");
+    }
+}
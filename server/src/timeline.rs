@@ -97,7 +97,7 @@ async fn recent(
         .call(move |c| -> tokio_rusqlite::Result<_> {
             let mut out = Vec::new();
             let mut st = c.prepare(
-                "SELECT id, ts, who, subject, details, kind, note_id
+                "SELECT id, ts, who, subject, details, kind, note_id, sealed
              FROM gratitude
              ORDER BY ts DESC
              LIMIT ?1",
@@ -110,6 +110,12 @@ async fn recent(
                 let details: Option<String> = r.get(4)?;
                 let gkind: Option<String> = r.get(5)?;
                 let note_id: Option<i64> = r.get(6)?;
+                let sealed: bool = r.get(7)?;
+                let (subject, details) = if sealed {
+                    (crate::gratitude::SEALED.to_string(), None)
+                } else {
+                    (subject, details)
+                };
                 Ok(TimelineItem {
                     id: format!("thanks:{id}"),
                     ts,
@@ -124,7 +130,8 @@ async fn recent(
                         "who": who,
                         "details": details,
                         "kind": gkind,
-                        "note_id": note_id
+                        "note_id": note_id,
+                        "sealed": sealed
                     }),
                 })
             })?;
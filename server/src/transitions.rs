@@ -0,0 +1,130 @@
+//! Energy transitions — broadcast band crossings
+//! ---------------------------------------------
+//! Whisper: "say the band, keep the number." 🔔
+//!
+//! `/energy/mark` records a row in `energy_transitions` when a person moves to another
+//! band (calendar.rs). A background sweep picks up rows not broadcast yet and, once per row:
+//!   • publishes `energy:<json>` on the bus → named `energy` SSE event on /status/stream
+//!   • emits a consent-gated `energy.transition` relational event (webhooks)
+//!
+//! Only the bands travel: no level, no note. Going down carries the exit ritual.
+//!
+//! Notes
+//!   • A sweep with nothing new is one read (`calendar::has_unpublished`); no
+//!     transaction is opened.
+//!   • Relational emits run in their own task, as in rhythm_clock.rs.
+//!   • Shared with the lib: `spawn_background` (lib.rs) starts it for embedders/tests.
+
+use crate::{calendar, relational, AppState};
+use serde_json::json;
+
+/// Bus prefix for band crossings.
+pub const TOPIC: &str = "energy:";
+
+const SWEEP_SECS: u64 = 5;
+
+pub fn payload(t: &calendar::Transition) -> serde_json::Value {
+    let mut body = json!({
+        "id": t.id,
+        "who": t.who,
+        "ts": t.ts,
+        "from": t.from,
+        "to": t.to,
+        "direction": t.direction,
+    });
+    if let Some(exit) = &t.exit {
+        body["exit"] = json!(exit);
+    }
+    body
+}
+
+/// Broadcast every new crossing once. Returns how many were sent.
+pub async fn sweep(state: &AppState) -> usize {
+    let now = chrono::Utc::now().to_rfc3339();
+    let due = match state
+        .db
+        .0
+        .call(move |c| {
+            if !calendar::has_unpublished(c)? {
+                return Ok(Vec::new());
+            }
+            Ok(calendar::take_unpublished(c, &now)?)
+        })
+        .await
+    {
+        Ok(due) => due,
+        Err(e) => {
+            tracing::warn!(error = ?e, "energy.transition: sweep failed");
+            return 0;
+        }
+    };
+    if due.is_empty() {
+        return 0;
+    }
+    let mut announced = Vec::with_capacity(due.len());
+    for t in &due {
+        let body = payload(t);
+        state.bus.publish(&format!("{TOPIC}{body}"));
+        announced.push((t.who.clone(), body));
+    }
+    let emitter = state.clone();
+    tokio::spawn(async move {
+        for (who, body) in announced {
+            relational::emit(
+                &emitter,
+                "energy.transition",
+                &relational::Actor::from_display(&who),
+                relational::Intent::state_change(),
+                json!({ "transition": body }),
+            )
+            .await;
+        }
+    });
+    due.len()
+}
+
+/// Background loop.
+pub fn spawn(state: AppState) {
+    tokio::spawn(async move {
+        let mut tick = tokio::time::interval(std::time::Duration::from_secs(SWEEP_SECS));
+        loop {
+            tick.tick().await;
+            sweep(&state).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[tokio::test]
+    async fn crossings_are_broadcast_once_without_the_level() {
+        let state = crate::test_state().await;
+        state
+            .db
+            .0
+            .call(|c| {
+                for (ts, level) in [("08", 0.8), ("09", 0.35)] {
+                    let before = calendar::band_now(c, "Raz")?;
+                    c.execute(
+                        "INSERT INTO energy_marks(ts, who, kind, level) VALUES(?1, 'Raz', 'focus', ?2)",
+                        rusqlite::params![format!("2025-05-01T{ts}:00:00+00:00"), level],
+                    )?;
+                    calendar::after_mark(c, "Raz", before, c.last_insert_rowid())?;
+                }
+                Ok(())
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(sweep(&state).await, 1);
+        let published = state.bus.drain();
+        let sent: Vec<_> = published.iter().filter(|m| m.starts_with(TOPIC)).collect();
+        assert_eq!(sent.len(), 1);
+        assert!(sent[0].contains("\"from\":\"E3\"") && sent[0].contains("\"to\":\"E1\""));
+        assert!(sent[0].contains("\"exit\""));
+        assert!(!sent[0].contains("0.35"), "the level stays private");
+
+        assert_eq!(sweep(&state).await, 0, "crossings are sent once");
+    }
+}
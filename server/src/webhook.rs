@@ -1,9 +1,290 @@
 use hmac::{Hmac, Mac};
 use sha2::Sha256;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 type HmacSha256 = Hmac<Sha256>;
 
+/// Limits per delivery so one slow receiver can't hold the others up.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Why an inbound signature was rejected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SigError {
+    Malformed,
+    Stale,
+    Mismatch,
+}
+
+impl SigError {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SigError::Malformed => "malformed signature",
+            SigError::Stale => "timestamp outside window",
+            SigError::Mismatch => "signature mismatch",
+        }
+    }
+}
+
+fn mac_for(secret: &str, ts: &str, body: &[u8]) -> anyhow::Result<HmacSha256> {
+    let mut mac = HmacSha256::new_from_slice(secret.as_bytes())?;
+    mac.update(ts.as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    Ok(mac)
+}
+
+/// Header value for `X-M3-Signature`: `m3=t=<ts>,v1=<hex(hmac(ts + "." + body))>`.
+pub fn sign(secret: &str, ts: &str, body: &[u8]) -> anyhow::Result<String> {
+    let bytes = mac_for(secret, ts, body)?.finalize().into_bytes();
+    Ok(format!("m3=t={},v1={}", ts, hex::encode(bytes)))
+}
+
+/// Verify an `X-M3-Signature` header over the raw body.
+/// Returns the signed unix timestamp when valid and within `window_sec` of `now`.
+pub fn verify(
+    secret: &str,
+    header: &str,
+    body: &[u8],
+    now: i64,
+    window_sec: i64,
+) -> Result<i64, SigError> {
+    let rest = header
+        .trim()
+        .strip_prefix("m3=")
+        .ok_or(SigError::Malformed)?;
+    let (mut ts, mut v1) = (None, None);
+    for part in rest.split(',') {
+        match part.split_once('=') {
+            Some(("t", v)) => ts = Some(v),
+            Some(("v1", v)) => v1 = Some(v),
+            _ => {}
+        }
+    }
+    let (ts, v1) = (
+        ts.ok_or(SigError::Malformed)?,
+        v1.ok_or(SigError::Malformed)?,
+    );
+    let t: i64 = ts.parse().map_err(|_| SigError::Malformed)?;
+    // `t` is caller-controlled: compare without overflowing
+    if now.abs_diff(t) > window_sec.unsigned_abs() {
+        return Err(SigError::Stale);
+    }
+    let expected = hex::decode(v1).map_err(|_| SigError::Malformed)?;
+    let mac = mac_for(secret, ts, body).map_err(|_| SigError::Malformed)?;
+    // constant-time comparison
+    mac.verify_slice(&expected)
+        .map_err(|_| SigError::Mismatch)?;
+    Ok(t)
+}
+
+/// Payload adapter for one delivery target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// M3's own JSON (signed envelope, default).
+    M3,
+    /// Slack-compatible incoming webhook: `{ "text": … }`.
+    Slack,
+    /// Matrix hookshot generic webhook: `{ "text": …, "username": "M3" }`.
+    Matrix,
+    /// ntfy topic URL: plain-text body + `Title` / `Tags` headers.
+    Ntfy,
+    /// `application/x-www-form-urlencoded` with flat fields.
+    Form,
+    /// User template with `{{path.to.field}}` placeholders.
+    Custom,
+}
+
+impl Format {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "m3" | "json" => Some(Format::M3),
+            "slack" => Some(Format::Slack),
+            "matrix" | "hookshot" => Some(Format::Matrix),
+            "ntfy" => Some(Format::Ntfy),
+            "form" => Some(Format::Form),
+            "custom" => Some(Format::Custom),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Format::M3 => "m3",
+            Format::Slack => "slack",
+            Format::Matrix => "matrix",
+            Format::Ntfy => "ntfy",
+            Format::Form => "form",
+            Format::Custom => "custom",
+        }
+    }
+}
+
+/// A body ready to POST.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rendered {
+    pub content_type: String,
+    pub body: String,
+    pub headers: Vec<(String, String)>,
+}
+
+fn str_at<'a>(json: &'a serde_json::Value, pointer: &str) -> Option<&'a str> {
+    json.pointer(pointer)
+        .and_then(|v| v.as_str())
+        .filter(|s| !s.trim().is_empty())
+}
+
+/// One human line for chat receivers, e.g.
+/// `🌬️ Raz: I'm here. Not urgent. I'd like company. — “This is Empire’s choke, not my truth.”`
+pub fn summary_text(event: &str, json: &serde_json::Value) -> String {
+    let who = str_at(json, "/actor/display").unwrap_or("Someone");
+    if event.starts_with("panic.") {
+        let mut line = format!(
+            "🌬️ {who}: {}",
+            str_at(json, "/intent/whisper").unwrap_or("signal of care")
+        );
+        if let Some(w) = str_at(json, "/context/redirect/whisper") {
+            line.push_str(&format!(" — “{w}”"));
+        }
+        return line;
+    }
+    if event == "status.set" {
+        let color = str_at(json, "/context/status").unwrap_or("?");
+        let dot = match color {
+            "green" => "🟢",
+            "yellow" => "🟡",
+            "red" => "🔴",
+            _ => "⚪",
+        };
+        return match str_at(json, "/context/note") {
+            Some(note) => format!("{dot} {who} → {color}: {note}"),
+            None => format!("{dot} {who} → {color}"),
+        };
+    }
+    match str_at(json, "/intent/whisper") {
+        Some(w) => format!("{event} · {who}: {w}"),
+        None => format!("{event} · {who}"),
+    }
+}
+
+fn ntfy_tags(event: &str) -> &'static str {
+    if event.starts_with("panic.") {
+        "wind_face"
+    } else if event.starts_with("status.") {
+        "traffic_light"
+    } else {
+        "bell"
+    }
+}
+
+/// Fill `{{path.to.field}}` placeholders from the payload. `{{event}}` and `{{text}}`
+/// are always available; missing fields render empty. When `json_escape` is set the
+/// substituted values are escaped so templates can embed them inside JSON strings.
+pub fn fill_template(
+    template: &str,
+    event: &str,
+    json: &serde_json::Value,
+    json_escape: bool,
+) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(open) = rest.find("{{") {
+        out.push_str(&rest[..open]);
+        let after = &rest[open + 2..];
+        let Some(close) = after.find("}}") else {
+            out.push_str(&rest[open..]);
+            return out;
+        };
+        let key = after[..close].trim();
+        let value = match key {
+            "event" => event.to_string(),
+            "text" => summary_text(event, json),
+            _ => {
+                let pointer = format!("/{}", key.replace('.', "/"));
+                match json.pointer(&pointer) {
+                    Some(serde_json::Value::String(s)) => s.clone(),
+                    Some(serde_json::Value::Null) | None => String::new(),
+                    Some(v) => v.to_string(),
+                }
+            }
+        };
+        if json_escape {
+            let quoted = serde_json::Value::String(value).to_string();
+            out.push_str(&quoted[1..quoted.len() - 1]);
+        } else {
+            out.push_str(&value);
+        }
+        rest = &after[close + 2..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Shape `json` for a receiver. `template` / `content_type` only apply to `Format::Custom`.
+pub fn render(
+    format: Format,
+    event: &str,
+    json: &serde_json::Value,
+    template: Option<&str>,
+    content_type: Option<&str>,
+) -> anyhow::Result<Rendered> {
+    let text = summary_text(event, json);
+    let rendered = match format {
+        Format::M3 => Rendered {
+            content_type: "application/json".into(),
+            body: json.to_string(),
+            headers: Vec::new(),
+        },
+        Format::Slack => Rendered {
+            content_type: "application/json".into(),
+            body: serde_json::json!({ "text": text }).to_string(),
+            headers: Vec::new(),
+        },
+        Format::Matrix => Rendered {
+            content_type: "application/json".into(),
+            body: serde_json::json!({ "text": text, "username": "M3" }).to_string(),
+            headers: Vec::new(),
+        },
+        Format::Ntfy => Rendered {
+            content_type: "text/plain; charset=utf-8".into(),
+            body: text,
+            headers: vec![
+                ("Title".into(), format!("M3 · {event}")),
+                ("Tags".into(), ntfy_tags(event).into()),
+            ],
+        },
+        Format::Form => {
+            let pairs = [
+                ("event", event.to_string()),
+                ("text", text),
+                ("id", str_at(json, "/id").unwrap_or_default().to_string()),
+                ("ts", str_at(json, "/ts").unwrap_or_default().to_string()),
+                (
+                    "actor",
+                    str_at(json, "/actor/id").unwrap_or_default().to_string(),
+                ),
+            ];
+            Rendered {
+                content_type: "application/x-www-form-urlencoded".into(),
+                body: serde_urlencoded::to_string(pairs)?,
+                headers: Vec::new(),
+            }
+        }
+        Format::Custom => {
+            let Some(template) = template else {
+                anyhow::bail!("custom format needs a template");
+            };
+            let content_type = content_type.unwrap_or("application/json");
+            Rendered {
+                content_type: content_type.to_string(),
+                body: fill_template(template, event, json, content_type.contains("json")),
+                headers: Vec::new(),
+            }
+        }
+    };
+    Ok(rendered)
+}
+
 #[derive(Clone)]
 pub struct Webhook {
     pub url: Option<String>,
@@ -16,42 +297,53 @@ impl Webhook {
         Self {
             url,
             secret,
-            client: reqwest::Client::new(),
+            client: reqwest::Client::builder()
+                .connect_timeout(CONNECT_TIMEOUT)
+                .timeout(REQUEST_TIMEOUT)
+                .build()
+                .unwrap_or_default(),
         }
     }
 
+    /// Send M3's own JSON to the env-configured URL (no-op when unset).
     pub async fn send(&self, event: &str, json: &serde_json::Value) -> anyhow::Result<()> {
         let Some(url) = &self.url else {
             return Ok(());
         };
+        let rendered = render(Format::M3, event, json, None, None)?;
+        self.post(url, self.secret.as_deref(), event, rendered)
+            .await
+    }
 
+    /// POST a rendered body to `url`, signing the exact bytes when a secret is set.
+    pub async fn post(
+        &self,
+        url: &str,
+        secret: Option<&str>,
+        event: &str,
+        rendered: Rendered,
+    ) -> anyhow::Result<()> {
         let ts = SystemTime::now()
             .duration_since(UNIX_EPOCH)?
             .as_secs()
             .to_string();
-        let body = json.to_string();
 
         // signature: m3=t=<ts>,v1=<hex(hmac(ts + "." + body))>
-        let sig = if let Some(secret) = &self.secret {
-            let mut mac = HmacSha256::new_from_slice(secret.as_bytes())?;
-            mac.update(ts.as_bytes());
-            mac.update(b".");
-            mac.update(body.as_bytes());
-            let bytes = mac.finalize().into_bytes();
-            format!("m3=t={},v1={}", ts, hex::encode(bytes))
-        } else {
-            "m3=t=0,v1=nosig".to_string()
+        let sig = match secret {
+            Some(secret) => sign(secret, &ts, rendered.body.as_bytes())?,
+            None => "m3=t=0,v1=nosig".to_string(),
         };
 
-        let res = self
+        let mut req = self
             .client
             .post(url)
-            .header("Content-Type", "application/json")
+            .header("Content-Type", rendered.content_type)
             .header("X-M3-Event", event)
-            .header("X-M3-Signature", sig)
-            .body(body)
-            .send()
-            .await?;
+            .header("X-M3-Signature", sig);
+        for (k, v) in rendered.headers {
+            req = req.header(k, v);
+        }
+        let res = req.body(rendered.body).send().await?;
 
         if !res.status().is_success() {
             anyhow::bail!(
@@ -63,3 +355,181 @@ impl Webhook {
         Ok(())
     }
 }
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    #[test]
+    fn sign_then_verify_roundtrip() {
+        let body = br#"{"type":"care.ack"}"#;
+        let sig = sign("s3cret", "1700000000", body).unwrap();
+        assert_eq!(
+            verify("s3cret", &sig, body, 1_700_000_100, 300),
+            Ok(1_700_000_000)
+        );
+    }
+
+    #[test]
+    fn verify_rejects_stale_tampered_and_malformed() {
+        let body = br#"{"type":"care.ack"}"#;
+        let sig = sign("s3cret", "1700000000", body).unwrap();
+        assert_eq!(
+            verify("s3cret", &sig, body, 1_700_000_301, 300),
+            Err(SigError::Stale)
+        );
+        assert_eq!(
+            verify(
+                "s3cret",
+                &sig,
+                br#"{"type":"care.done"}"#,
+                1_700_000_000,
+                300
+            ),
+            Err(SigError::Mismatch)
+        );
+        assert_eq!(
+            verify("other", &sig, body, 1_700_000_000, 300),
+            Err(SigError::Mismatch)
+        );
+        assert_eq!(
+            verify("s3cret", "m3=t=0,v1=nosig", body, 0, 300),
+            Err(SigError::Malformed)
+        );
+        assert_eq!(
+            verify("s3cret", "garbage", body, 0, 300),
+            Err(SigError::Malformed)
+        );
+    }
+
+    #[test]
+    fn verify_rejects_extreme_timestamps() {
+        let body = br#"{"type":"care.ack"}"#;
+        for t in [i64::MIN, i64::MAX] {
+            let sig = sign("s3cret", &t.to_string(), body).unwrap();
+            assert_eq!(
+                verify("s3cret", &sig, body, 1_700_000_000, 300),
+                Err(SigError::Stale)
+            );
+        }
+    }
+
+    fn panic_envelope() -> serde_json::Value {
+        serde_json::json!({
+            "id": "evt_1",
+            "type": "panic.ui",
+            "ts": "2026-01-07T10:12:00Z",
+            "actor": { "id": "raz", "display": "Raz" },
+            "intent": { "whisper": "I'm here. Not urgent." },
+            "context": { "redirect": { "whisper": "Flow > Empire." } }
+        })
+    }
+
+    #[test]
+    fn chat_adapters_carry_the_whisper() {
+        let env = panic_envelope();
+        let slack = render(Format::Slack, "panic.ui", &env, None, None).unwrap();
+        let v: serde_json::Value = serde_json::from_str(&slack.body).unwrap();
+        assert_eq!(
+            v["text"],
+            "🌬️ Raz: I'm here. Not urgent. — “Flow > Empire.”"
+        );
+
+        let matrix = render(Format::Matrix, "panic.ui", &env, None, None).unwrap();
+        let v: serde_json::Value = serde_json::from_str(&matrix.body).unwrap();
+        assert_eq!(v["username"], "M3");
+        assert!(v["text"].as_str().unwrap().contains("Flow > Empire."));
+
+        let ntfy = render(Format::Ntfy, "panic.ui", &env, None, None).unwrap();
+        assert!(ntfy.content_type.starts_with("text/plain"));
+        assert!(ntfy
+            .headers
+            .contains(&("Title".to_string(), "M3 · panic.ui".to_string())));
+    }
+
+    #[test]
+    fn form_adapter_urlencodes_flat_fields() {
+        let form = render(Format::Form, "panic.ui", &panic_envelope(), None, None).unwrap();
+        assert_eq!(form.content_type, "application/x-www-form-urlencoded");
+        assert!(form.body.starts_with("event=panic.ui&text="));
+        assert!(form
+            .body
+            .contains("&id=evt_1&ts=2026-01-07T10%3A12%3A00Z&actor=raz"));
+    }
+
+    #[test]
+    fn custom_template_fills_fields_and_escapes_json() {
+        let env = panic_envelope();
+        let r = render(
+            Format::Custom,
+            "panic.ui",
+            &env,
+            Some(r#"{"who":"{{actor.display}}","say":"{{intent.whisper}}","missing":"{{nope.x}}","e":"{{event}}"}"#),
+            None,
+        )
+        .unwrap();
+        let v: serde_json::Value = serde_json::from_str(&r.body).unwrap();
+        assert_eq!(v["who"], "Raz");
+        assert_eq!(v["say"], "I'm here. Not urgent.");
+        assert_eq!(v["missing"], "");
+        assert_eq!(v["e"], "panic.ui");
+
+        let quoted = serde_json::json!({ "actor": { "display": "R\"az" } });
+        let r = render(
+            Format::Custom,
+            "x",
+            &quoted,
+            Some(r#"{"a":"{{actor.display}}"}"#),
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&r.body).unwrap()["a"],
+            "R\"az"
+        );
+
+        assert!(render(Format::Custom, "x", &quoted, None, None).is_err());
+    }
+
+    /// Local HTTP stand-in that records (headers, body) of every POST.
+    pub(crate) async fn stand_in() -> (
+        String,
+        std::sync::Arc<parking_lot::Mutex<Vec<(axum::http::HeaderMap, String)>>>,
+    ) {
+        use axum::{routing::post, Router};
+        let seen = std::sync::Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let sink = seen.clone();
+        let app = Router::new().route(
+            "/",
+            post(move |headers: axum::http::HeaderMap, body: String| {
+                let sink = sink.clone();
+                async move {
+                    sink.lock().push((headers, body));
+                    "ok"
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (format!("http://{addr}/"), seen)
+    }
+
+    #[tokio::test]
+    async fn post_signs_the_rendered_body() {
+        let (url, seen) = stand_in().await;
+        let hook = Webhook::new(None, None);
+        let rendered = render(Format::Ntfy, "panic.ui", &panic_envelope(), None, None).unwrap();
+        hook.post(&url, Some("s3cret"), "panic.ui", rendered)
+            .await
+            .unwrap();
+
+        let seen = seen.lock();
+        let (headers, body) = &seen[0];
+        assert_eq!(headers["x-m3-event"], "panic.ui");
+        assert_eq!(headers["tags"], "wind_face");
+        let sig = headers["x-m3-signature"].to_str().unwrap();
+        let t: i64 = sig[5..sig.find(',').unwrap()].parse().unwrap();
+        assert_eq!(verify("s3cret", sig, body.as_bytes(), t, 300), Ok(t));
+    }
+}
@@ -0,0 +1,113 @@
+use axum::http::StatusCode;
+use tower::ServiceExt; // for `oneshot`
+
+mod common;
+use common::{body_json, get, send, test_app};
+
+// A fear event opened as an arc, bridged, and closed through /emotions/resolve.
+#[tokio::test]
+async fn arc_from_fear_to_gratitude() {
+    let t = test_app("arcs").await;
+    let app = t.app.clone();
+
+    let fear = body_json(
+        app.clone()
+            .oneshot(send(
+                "POST",
+                "/emotions/add",
+                r#"{"who":"Sawsan","kind":"fear","intensity":0.4,"sealed":false,"privacy":"private"}"#,
+            ))
+            .await
+            .unwrap(),
+    )
+    .await;
+    let emotion_id = fear["id"].as_i64().unwrap();
+
+    let res = app
+        .clone()
+        .oneshot(send(
+            "POST",
+            "/arcs",
+            &format!(r#"{{"emotion_id":{emotion_id}}}"#),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::CREATED);
+    let arc = body_json(res).await;
+    assert_eq!(arc["state"], "opened");
+    let id = arc["id"].as_i64().unwrap();
+
+    let res = app
+        .clone()
+        .oneshot(send("POST", &format!("/arcs/{id}/bridge"), "{}"))
+        .await
+        .unwrap();
+    let arc = body_json(res).await;
+    assert_eq!(arc["state"], "bridged");
+    assert_eq!(arc["bridge"]["breath"], "double_exhale × 6");
+
+    let res = app
+        .clone()
+        .oneshot(send(
+            "POST",
+            "/emotions/resolve",
+            &format!(
+                r#"{{"who":"Sawsan","details":"breathing helped","note_id":11,"arc_id":{id}}}"#
+            ),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let thanks = body_json(res).await;
+    assert_eq!(thanks["kind"], "gratitude");
+    assert_eq!(thanks["note"], format!("arc #{id} resolved"));
+    assert_eq!(thanks["note_id"], 11);
+
+    let arc = body_json(
+        app.clone()
+            .oneshot(get(&format!("/arcs/{id}")))
+            .await
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(arc["state"], "resolved");
+    assert_eq!(arc["resolution_emotion_id"], thanks["id"]);
+    assert_eq!(arc["tell_ids"].as_array().unwrap().len(), 3);
+    assert!(arc["durations"]["to_close"].as_i64().is_some());
+
+    let res = app
+        .clone()
+        .oneshot(send("POST", &format!("/arcs/{id}/abandon"), "{}"))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::CONFLICT);
+    let res = app
+        .clone()
+        .oneshot(send("POST", "/arcs", r#"{"emotion_id":999999}"#))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+    let res = app
+        .clone()
+        .oneshot(get("/arcs?state=closed"))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let page = body_json(
+        app.clone()
+            .oneshot(get("/arcs?who=Sawsan&state=resolved"))
+            .await
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(page["items"][0]["id"], id);
+    let stats = body_json(
+        app.clone()
+            .oneshot(get("/arcs/stats?who=Sawsan"))
+            .await
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(stats["states"]["resolved"], 1);
+}
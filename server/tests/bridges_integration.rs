@@ -0,0 +1,137 @@
+use axum::http::StatusCode;
+use tower::ServiceExt; // for `oneshot`
+
+mod common;
+use common::{body_json, get, send, test_app};
+
+// Both bridge endpoints answer from the same library; an edited tier and a profile
+// override show up in each, and the last global default cannot be removed.
+#[tokio::test]
+async fn bridge_library_serves_both_endpoints() {
+    let t = test_app("bridges").await;
+    let app = t.app.clone();
+
+    let feel = r#"{"kind":"fear","intensity":0.8}"#;
+    let a = body_json(
+        app.clone()
+            .oneshot(send("POST", "/emotions/bridge", feel))
+            .await
+            .unwrap(),
+    )
+    .await;
+    let b = body_json(
+        app.clone()
+            .oneshot(get("/patterns/bridge_suggest?kind=fear&intensity=0.8"))
+            .await
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(a, b);
+    assert_eq!(a["kind"], "anxiety");
+    assert_eq!(a["breath"], "box: in4-hold4-out6 × 4");
+    assert_eq!(a["pattern"], "stabilize-first");
+
+    // edit the high anxiety tier
+    let tiers = body_json(
+        app.clone()
+            .oneshot(get("/bridges?kind=anxiety&profile="))
+            .await
+            .unwrap(),
+    )
+    .await;
+    let high = tiers
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|t| t["min_intensity"] == 0.6)
+        .unwrap()["id"]
+        .as_i64()
+        .unwrap();
+    let res = app
+        .clone()
+        .oneshot(send(
+            "PATCH",
+            &format!("/bridges/{high}"),
+            r#"{"breath":"in4-hold7-out8 × 4"}"#,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+
+    // a per-person override on top of it
+    let res = app
+        .clone()
+        .oneshot(send(
+            "POST",
+            "/bridges",
+            r#"{"kind":"anxiety","profile":"Raz","anchor":"Hand on the dog."}"#,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::CREATED);
+    let res = app
+        .clone()
+        .oneshot(send(
+            "POST",
+            "/bridges",
+            r#"{"kind":"anxiety","profile":"Raz","anchor":"again"}"#,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::CONFLICT);
+
+    let raz = body_json(
+        app.clone()
+            .oneshot(send(
+                "POST",
+                "/emotions/bridge",
+                r#"{"kind":"panic","intensity":0.9,"who":"Raz"}"#,
+            ))
+            .await
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(raz["breath"], "in4-hold7-out8 × 4");
+    assert_eq!(raz["anchor"], "Hand on the dog.");
+    assert_eq!(raz["profile"], "Raz");
+    let nico = body_json(
+        app.clone()
+            .oneshot(get(
+                "/patterns/bridge_suggest?kind=panic&intensity=0.9&who=Nico",
+            ))
+            .await
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(nico["breath"], "in4-hold7-out8 × 4");
+    assert_eq!(nico["anchor"], "Name 3 objects you see.");
+    assert!(nico.get("profile").is_none());
+
+    // incomplete global tier, unknown row, last default
+    let res = app
+        .clone()
+        .oneshot(send("POST", "/bridges", r#"{"kind":"calm","breath":"x"}"#))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let res = app
+        .clone()
+        .oneshot(send("DELETE", "/bridges/99999", ""))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+    let defaults = body_json(
+        app.clone()
+            .oneshot(get("/bridges?kind=default"))
+            .await
+            .unwrap(),
+    )
+    .await;
+    let id = defaults[0]["id"].as_i64().unwrap();
+    let res = app
+        .clone()
+        .oneshot(send("DELETE", &format!("/bridges/{id}"), ""))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
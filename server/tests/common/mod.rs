@@ -1,5 +1,9 @@
+use axum::{body::Body, http::Request, Router};
+use m3_memory_server::{app_router, init_state, AppState};
 use reqwest::Client;
 use std::env;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::time::Duration;
 
 /// Returns the base URL for the server, from the `M3_BASE` environment variable or default.
@@ -27,7 +31,7 @@ pub async fn server_up(base: &str) -> bool {
         .unwrap_or(false)
 }
 
-/// Finds a recent emotion with a specific marker.
+/// Finds a recent emotion whose `details` or `note` contains `marker`.
 #[allow(dead_code)]
 pub async fn find_recent_with_marker(
     base: &str,
@@ -42,7 +46,11 @@ pub async fn find_recent_with_marker(
     let arr: serde_json::Value = resp.json().await.ok()?;
     arr.as_array()?
         .iter()
-        .find(|v| v.get("marker").and_then(|m| m.as_str()) == Some(marker))
+        .find(|v| {
+            ["details", "note"]
+                .iter()
+                .any(|f| v[*f].as_str().is_some_and(|t| t.contains(marker)))
+        })
         .cloned()
 }
 
@@ -76,3 +84,89 @@ pub async fn wait_for_new_after(
     }
     None
 }
+
+// ---- In-process (hermetic) helpers -------------------------------------------
+
+/// `init_state` reads `M3_DB_PATH` from the environment; tests in one binary run in
+/// parallel, so setting it and opening the DB happen under one lock.
+static DB_ENV: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+static NEXT_DB: AtomicUsize = AtomicUsize::new(0);
+
+/// The lib router on a fresh temp database. The file is removed on drop.
+#[allow(dead_code)]
+pub struct TestApp {
+    pub state: AppState,
+    pub app: Router,
+    /// `<millis>_<pid>_<n>`; unique per app, handy for other temp paths.
+    pub tag: String,
+    pub db_file: PathBuf,
+}
+
+#[allow(dead_code)]
+impl TestApp {
+    /// A second state + router on the same file, as after a server restart.
+    pub async fn restart(&self) -> (AppState, Router) {
+        open(&self.db_file).await
+    }
+}
+
+impl Drop for TestApp {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.db_file);
+    }
+}
+
+async fn open(db_file: &std::path::Path) -> (AppState, Router) {
+    let state = {
+        let _env = DB_ENV.lock().await;
+        env::set_var("M3_DB_PATH", db_file);
+        init_state().await.expect("init_state")
+    };
+    let app = app_router(state.clone()).with_state(state.clone());
+    (state, app)
+}
+
+/// Lib state + router on `$TMP/m3_<name>_<tag>.db`, created only through `init_state()`.
+#[allow(dead_code)]
+pub async fn test_app(name: &str) -> TestApp {
+    let millis = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_millis();
+    let n = NEXT_DB.fetch_add(1, Ordering::Relaxed);
+    let tag = format!("{}_{}_{}", millis, std::process::id(), n);
+    let db_file = env::temp_dir().join(format!("m3_{name}_{tag}.db"));
+    let (state, app) = open(&db_file).await;
+    TestApp {
+        state,
+        app,
+        tag,
+        db_file,
+    }
+}
+
+/// Response body as JSON.
+#[allow(dead_code)]
+pub async fn body_json(res: axum::response::Response) -> serde_json::Value {
+    let bytes = axum::body::to_bytes(res.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    serde_json::from_slice(&bytes).unwrap()
+}
+
+/// JSON request (`content-type: application/json`).
+#[allow(dead_code)]
+pub fn send(method: &str, uri: &str, body: &str) -> Request<Body> {
+    Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("content-type", "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap()
+}
+
+/// Plain GET.
+#[allow(dead_code)]
+pub fn get(uri: &str) -> Request<Body> {
+    Request::get(uri).body(Body::empty()).unwrap()
+}
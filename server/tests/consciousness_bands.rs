@@ -0,0 +1,131 @@
+use axum::http::StatusCode;
+use tower::ServiceExt; // for `oneshot`
+
+mod common;
+use common::{body_json, get, send, test_app};
+
+fn emotion(kind: &str, intensity: f32) -> String {
+    serde_json::json!({
+        "who": "Raz", "kind": kind, "intensity": intensity, "sealed": false, "privacy": "private"
+    })
+    .to_string()
+}
+
+// Classification and advice in both band models, `band_model` on emotion responses and
+// a per-person band series.
+#[tokio::test]
+async fn bands_classify_and_series_in_both_models() {
+    let t = test_app("bands").await;
+    let app = t.app.clone();
+
+    let five = body_json(
+        app.clone()
+            .oneshot(get("/consciousness/bands?model=five"))
+            .await
+            .unwrap(),
+    )
+    .await;
+    let names: Vec<&str> = five
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|b| b["band"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, ["ground", "flow", "mind", "field", "source"]);
+    assert!(five[0]["approach"].is_string());
+
+    let cls = body_json(
+        app.clone()
+            .oneshot(get(
+                "/consciousness/classify?kind=furious&intensity=0.8&model=five",
+            ))
+            .await
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(
+        (&cls["kind"], &cls["band"]),
+        (&"anger".into(), &"flow".into())
+    );
+    let cls = body_json(
+        app.clone()
+            .oneshot(get("/consciousness/classify?kind=furious&intensity=0.8"))
+            .await
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(cls["band"], "survival");
+    assert_eq!(cls["advice"]["approach"], "body-first");
+    for bad in [
+        "/consciousness/classify?kind=fear&model=seven",
+        "/consciousness/classify?kind=fear&intensity=2",
+        "/consciousness/classify",
+        "/consciousness/series",
+    ] {
+        let res = app.clone().oneshot(get(bad)).await.unwrap();
+        assert_eq!(res.status(), StatusCode::UNPROCESSABLE_ENTITY, "{bad}");
+    }
+
+    // emotion responses: three by default, five on request, none drops the band
+    let out = body_json(
+        app.clone()
+            .oneshot(send(
+                "POST",
+                "/emotions/add?band_model=five",
+                &emotion("fear", 0.7),
+            ))
+            .await
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(out["band"], "ground");
+    for (kind, i) in [("joy", 0.8), ("gratitude", 1.0)] {
+        app.clone()
+            .oneshot(send("POST", "/emotions/add", &emotion(kind, i)))
+            .await
+            .unwrap();
+    }
+    let page = body_json(
+        app.clone()
+            .oneshot(get("/emotions?who=Raz&band_model=five"))
+            .await
+            .unwrap(),
+    )
+    .await;
+    let bands: Vec<&str> = page["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|e| e["band"].as_str().unwrap())
+        .collect();
+    assert_eq!(bands, ["source", "source", "ground"]);
+    let recent = body_json(
+        app.clone()
+            .oneshot(get("/emotions/recent?band_model=none"))
+            .await
+            .unwrap(),
+    )
+    .await;
+    assert!(recent[0].get("band").is_none());
+    let res = app
+        .clone()
+        .oneshot(get("/emotions?band_model=seven"))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let series = body_json(
+        app.clone()
+            .oneshot(get("/consciousness/series?who=Raz&model=five&bucket=week"))
+            .await
+            .unwrap(),
+    )
+    .await;
+    let point = &series["points"][0];
+    assert_eq!(point["count"], 3);
+    assert_eq!(point["dominant"], "source");
+    assert_eq!(
+        point["bands"][0],
+        serde_json::json!({ "band": "ground", "count": 1 })
+    );
+}
@@ -0,0 +1,98 @@
+use axum::{
+    body::Body,
+    http::{header, Request, StatusCode},
+};
+use tower::ServiceExt; // for `oneshot`
+
+mod common;
+use common::{body_json, test_app};
+
+fn set(body: &str, if_match: Option<&str>) -> Request<Body> {
+    let mut req = Request::builder()
+        .method("POST")
+        .uri("/state/set")
+        .header("content-type", "application/json");
+    if let Some(tag) = if_match {
+        req = req.header(header::IF_MATCH, tag);
+    }
+    req.body(Body::from(body.to_string())).unwrap()
+}
+
+// Two writers read rev N; the first save wins, the second gets 409 + the current state,
+// and a write without If-Match gets 428. History keeps every revision and restore copies an old one forward.
+#[tokio::test]
+async fn dashboard_state_revisions_and_conflicts() {
+    let t = test_app("state").await;
+    let app = t.app.clone();
+
+    let res = app
+        .clone()
+        .oneshot(Request::get("/state/get").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(res.headers()[header::ETAG], "\"0\"");
+    assert_eq!(body_json(res).await["rev"], 0);
+
+    let first = app
+        .clone()
+        .oneshot(set(r#"{"note":"tablet A"}"#, Some("\"0\"")))
+        .await
+        .unwrap();
+    assert_eq!(first.status(), StatusCode::OK);
+    assert_eq!(first.headers()[header::ETAG], "\"1\"");
+
+    let second = app
+        .clone()
+        .oneshot(set(r#"{"note":"tablet B"}"#, Some("\"0\"")))
+        .await
+        .unwrap();
+    assert_eq!(second.status(), StatusCode::CONFLICT);
+    let v = body_json(second).await;
+    assert_eq!(v["current"]["note"], "tablet A");
+    assert_eq!(v["current"]["rev"], 1);
+
+    // no If-Match ⇒ refused; `*` is the explicit overwrite
+    let blind = app
+        .clone()
+        .oneshot(set(r#"{"note":"tablet B"}"#, None))
+        .await
+        .unwrap();
+    assert_eq!(blind.status(), StatusCode::PRECONDITION_REQUIRED);
+    let forced = app
+        .clone()
+        .oneshot(set(r#"{"note":"tablet B"}"#, Some("*")))
+        .await
+        .unwrap();
+    assert_eq!(forced.status(), StatusCode::OK);
+
+    let res = app
+        .clone()
+        .oneshot(Request::get("/state/history").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let hist = body_json(res).await;
+    let revs: Vec<i64> = hist
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| r["rev"].as_i64().unwrap())
+        .collect();
+    assert_eq!(revs, vec![2, 1]);
+
+    let res = app
+        .clone()
+        .oneshot(
+            Request::post("/state/restore")
+                .header("content-type", "application/json")
+                .header(header::IF_MATCH, "\"2\"")
+                .body(Body::from(r#"{"rev":1}"#))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let v = body_json(res).await;
+    assert_eq!(v["rev"], 3);
+    assert_eq!(v["note"], "tablet A");
+}
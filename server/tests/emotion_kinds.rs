@@ -0,0 +1,158 @@
+use std::env;
+
+use axum::http::StatusCode;
+use tower::ServiceExt; // for `oneshot`
+
+mod common;
+use common::{body_json, get, send, test_app};
+
+fn emotion(kind: &str) -> String {
+    serde_json::json!({
+        "who": "Nico", "kind": kind, "intensity": 0.5, "sealed": false, "privacy": "private"
+    })
+    .to_string()
+}
+
+// Spellings collapse onto one canonical kind; unknown kinds are flagged, then rejected
+// once strict mode is on; new kinds and synonyms are editable over HTTP.
+#[tokio::test]
+async fn kinds_normalize_on_add_and_are_editable() {
+    env::remove_var("M3_EMOTION_KINDS_STRICT");
+    let t = test_app("kinds").await;
+    let app = t.app.clone();
+
+    for raw in ["anxiety", "Anxiety ", "anxious", "Neliniște"] {
+        let out = body_json(
+            app.clone()
+                .oneshot(send("POST", "/emotions/add", &emotion(raw)))
+                .await
+                .unwrap(),
+        )
+        .await;
+        assert_eq!(out["kind"], "anxiety", "{raw}");
+        assert!(out.get("unknown_kind").is_none());
+    }
+    let raws: Vec<Option<String>> = t
+        .state
+        .db
+        .0
+        .call(|c| {
+            let mut stmt = c.prepare("SELECT kind_raw FROM emotions ORDER BY id")?;
+            let rows = stmt.query_map([], |r| r.get(0))?;
+            Ok(rows.collect::<rusqlite::Result<_>>()?)
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        raws,
+        [
+            None,
+            Some("Anxiety".into()),
+            Some("anxious".into()),
+            Some("Neliniște".into())
+        ]
+    );
+    let out = body_json(
+        app.clone()
+            .oneshot(send("POST", "/emotions/add", &emotion("Over Whelm")))
+            .await
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(
+        (&out["kind"], &out["unknown_kind"]),
+        (&"over_whelm".into(), &true.into())
+    );
+
+    let agg = body_json(
+        app.clone()
+            .oneshot(get("/emotions/aggregate?kind=worried"))
+            .await
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(agg["kinds"][0]["kind"], "anxiety");
+    assert_eq!(agg["kinds"][0]["count"], 4);
+
+    let res = app
+        .clone()
+        .oneshot(send(
+            "POST",
+            "/emotions/kinds",
+            r##"{"name":"overwhelm","band":"survival","bridge":"anxiety","color":"#ff0000","synonyms":["over whelm","copleșire"]}"##,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::CREATED);
+    let resolved = body_json(
+        app.clone()
+            .oneshot(get("/emotions/kinds/resolve?kind=Copleșire"))
+            .await
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(resolved["kind"], "overwhelm");
+    assert_eq!(resolved["bridge"], "anxiety");
+    let res = app
+        .clone()
+        .oneshot(send(
+            "POST",
+            "/emotions/kinds",
+            r#"{"name":"scared","band":"survival"}"#,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::CONFLICT);
+    let res = app
+        .clone()
+        .oneshot(send(
+            "PATCH",
+            "/emotions/kinds/afraid",
+            r#"{"band":"nope"}"#,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    env::set_var("M3_EMOTION_KINDS_STRICT", "1");
+    let res = app
+        .clone()
+        .oneshot(send("POST", "/emotions/add", &emotion("blorp")))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let res = app
+        .clone()
+        .oneshot(send("POST", "/emotions/add", &emotion("copleșire")))
+        .await
+        .unwrap();
+    assert_eq!(body_json(res).await["kind"], "overwhelm");
+    env::remove_var("M3_EMOTION_KINDS_STRICT");
+}
+
+// Resolving without an arc lands a gratitude row in the band the registry gives it now.
+#[tokio::test]
+async fn resolve_follows_the_registry_band() {
+    let t = test_app("kinds_resolve").await;
+    let app = t.app.clone();
+    let res = app
+        .clone()
+        .oneshot(send(
+            "PATCH",
+            "/emotions/kinds/gratitude",
+            r#"{"band":"integrity"}"#,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let out = body_json(
+        app.clone()
+            .oneshot(send("POST", "/emotions/resolve", r#"{"who":"Nico"}"#))
+            .await
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(out["kind"], "gratitude");
+    assert_eq!(out["band"], "integrity");
+}
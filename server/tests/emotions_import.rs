@@ -0,0 +1,121 @@
+use std::env;
+
+use axum::http::StatusCode;
+use tower::ServiceExt; // for `oneshot`
+
+mod common;
+use common::{body_json, get, send, test_app};
+
+/// Start an import and wait for its report.
+async fn import(app: &axum::Router, body: serde_json::Value) -> serde_json::Value {
+    let res = app
+        .clone()
+        .oneshot(send("POST", "/emotions/import", &body.to_string()))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::ACCEPTED);
+    let id = body_json(res).await["id"].as_i64().unwrap();
+    for _ in 0..200 {
+        let job = body_json(
+            app.clone()
+                .oneshot(get(&format!("/emotions/import/{id}")))
+                .await
+                .unwrap(),
+        )
+        .await;
+        if job["state"] == "done" || job["state"] == "failed" {
+            return job;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    }
+    panic!("import {id} did not finish");
+}
+
+// A Daylio export lands at its own timestamps with taxonomy kinds; importing it again
+// only finds duplicates; a generic mapping brings its own people and scale.
+#[tokio::test]
+async fn mood_history_imports_with_source_timestamps() {
+    env::remove_var("M3_EMOTION_KINDS_STRICT");
+    let t = test_app("import").await;
+    let app = t.app.clone();
+
+    let daylio = "full_date,date,weekday,time,mood,activities,note_title,note\n\
+        2024-01-15,January 15,Monday,8:30 pm,rad,friends,Dinner,\"long, good talk\"\n\
+        2024-01-16,January 16,Tuesday,07:05,awful,,,\n\
+        2024-01-16,January 16,Tuesday,07:05,awful,,,\n\
+        2024-01-17,January 17,Wednesday,09:00,sleepy,,,\n\
+        someday,,,09:00,good,,,\n";
+    let body = serde_json::json!({ "csv": daylio, "who": "Raz", "utc_offset": "+02:00" });
+    let job = import(&app, body.clone()).await;
+    assert_eq!(job["state"], "done", "{job}");
+    assert_eq!(job["layout"], "daylio");
+    assert_eq!(job["total"], 5);
+    assert_eq!(job["imported"], 3);
+    assert_eq!(job["duplicates"], 1);
+    assert_eq!(job["skipped"], 1);
+    assert_eq!(job["errors"][0]["row"], 5);
+    assert_eq!(job["unknown_kinds"]["sleepy"], 1);
+
+    let page = body_json(
+        app.clone()
+            .oneshot(get("/emotions?who=Raz&limit=10"))
+            .await
+            .unwrap(),
+    )
+    .await;
+    let items = page["items"].as_array().unwrap();
+    assert_eq!(items.len(), 3);
+    let rad = items.iter().find(|e| e["kind"] == "joy").unwrap();
+    assert_eq!(rad["ts"], "2024-01-15T18:30:00+00:00");
+    assert_eq!(rad["intensity"], 0.9);
+    assert_eq!(rad["details"], "Dinner\nlong, good talk");
+    assert!(rad["note"].as_str().unwrap().starts_with("import daylio #"));
+    assert!(items.iter().any(|e| e["kind"] == "grief"));
+
+    let again = import(&app, body).await;
+    assert_eq!(
+        (again["imported"].clone(), again["duplicates"].clone()),
+        (0.into(), 4.into())
+    );
+
+    let generic = serde_json::json!({
+        "csv": "Person,When,Feeling,Score\nNico,2024-03-01T09:00:00Z,anxious,8\nSawsan,2024-03-01 10:00,calm,3\n",
+        "mapping": { "who": "Person", "ts": "When", "kind": "Feeling", "intensity": "Score", "scale": 10 },
+        "privacy": "anonymized"
+    });
+    let job = import(&app, generic).await;
+    assert_eq!(job["layout"], "generic");
+    assert_eq!(job["imported"], 2);
+    let nico = body_json(
+        app.clone()
+            .oneshot(get("/emotions?who=Nico"))
+            .await
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(nico["items"][0]["kind"], "anxiety");
+    assert_eq!(nico["items"][0]["privacy"], "anonymized");
+
+    let jobs = body_json(app.clone().oneshot(get("/emotions/import")).await.unwrap()).await;
+    assert_eq!(jobs.as_array().unwrap().len(), 3);
+
+    for bad in [
+        serde_json::json!({ "csv": daylio }),
+        serde_json::json!({ "csv": "a,b\n1,2\n", "who": "Raz" }),
+        serde_json::json!({ "csv": daylio, "who": "Raz", "utc_offset": "later" }),
+        serde_json::json!({ "csv": "When,Feeling\n", "who": "Raz", "mapping": { "ts": "When", "kind": "Mood" } }),
+    ] {
+        let res = app
+            .clone()
+            .oneshot(send("POST", "/emotions/import", &bad.to_string()))
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::UNPROCESSABLE_ENTITY, "{bad}");
+    }
+    let res = app
+        .clone()
+        .oneshot(get("/emotions/import/99999"))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+}
@@ -107,11 +107,12 @@ async fn http_panic_then_resolve_arc_persists_emotion_and_tell() {
 
     let baseline_id = max_recent_id(&base).await.unwrap_or(0);
 
-    // 1) POST /panic (UI flow)
+    // 1) POST /panic (UI flow); the whisper lands in the emotion note (`panic ui: …`)
     let panic_payload = serde_json::json!({
         "who": "Raz",
         "details": format!("panic spike — {}", marker),
         "note": format!("panic spike — {}", marker),
+        "whisper": format!("panic spike — {}", marker),
         // allow server defaults for sealed/archetype/privacy
     });
 
@@ -133,7 +134,7 @@ async fn http_panic_then_resolve_arc_persists_emotion_and_tell() {
     // Be tolerant: if the UI flow doesn't insert an emotion, skip instead of failing CI.
     let item_opt = match wait_for_new_after(&base, &marker, Some(baseline_id), 40, 150).await {
         Some(v) => Some(v),
-        None => find_recent_with_marker(&marker, &base, Some(baseline_id)).await,
+        None => find_recent_with_marker(&base, &marker, Some(baseline_id)).await,
     };
 
     let item = match item_opt {
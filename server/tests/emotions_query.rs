@@ -0,0 +1,75 @@
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use tower::ServiceExt; // for `oneshot`
+
+mod common;
+use common::{body_json, get, test_app};
+
+// GET /emotions (filters + cursor) and /emotions/aggregate through the library router.
+#[tokio::test]
+async fn emotions_query_and_aggregate() {
+    let t = test_app("emotions").await;
+    let app = t.app.clone();
+
+    for (kind, intensity) in [("fear", 0.8), ("joy", 0.7), ("fear", 0.3)] {
+        let body = serde_json::json!({
+            "who": "Ilinca", "kind": kind, "intensity": intensity,
+            "sealed": false, "privacy": "private"
+        });
+        let res = app
+            .clone()
+            .oneshot(
+                Request::post("/emotions/add")
+                    .header("content-type", "application/json")
+                    .body(Body::from(body.to_string()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+
+    let page = body_json(
+        app.clone()
+            .oneshot(get("/emotions?who=Ilinca&band=survival&limit=1"))
+            .await
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(page["items"][0]["intensity"], 0.3);
+    let before = page["next_before"].as_i64().unwrap();
+    let next = body_json(
+        app.clone()
+            .oneshot(get(&format!(
+                "/emotions?who=Ilinca&band=survival&limit=1&before={before}"
+            )))
+            .await
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(next["items"][0]["intensity"], 0.8);
+
+    let agg = body_json(
+        app.clone()
+            .oneshot(get("/emotions/aggregate?who=Ilinca&bucket=week"))
+            .await
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(agg["total"], 3);
+    assert_eq!(agg["kinds"][0]["kind"], "fear");
+    assert_eq!(agg["kinds"][0]["mean_intensity"], 0.55);
+    assert_eq!(agg["bands"]["coherence"], 1);
+
+    for bad in [
+        "/emotions?band=ecstatic",
+        "/emotions?min_intensity=2",
+        "/emotions?since=yesterday",
+        "/emotions/aggregate?bucket=month",
+    ] {
+        let res = app.clone().oneshot(get(bad)).await.unwrap();
+        assert_eq!(res.status(), StatusCode::UNPROCESSABLE_ENTITY, "{bad}");
+    }
+}
@@ -0,0 +1,116 @@
+use axum::http::StatusCode;
+use tower::ServiceExt; // for `oneshot`
+
+mod common;
+use common::{body_json, get, send, test_app};
+
+// Crossing a band shows up on the mark and in /transitions, /energy/now offers the tasks
+// the band can carry, and a band set without a floor is refused.
+#[tokio::test]
+async fn marks_move_people_between_bands() {
+    let t = test_app("calendar").await;
+    let app = t.app.clone();
+
+    let res = app
+        .clone()
+        .oneshot(get("/energy/now?who=Raz"))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+
+    for (title, band) in [
+        ("name the service", "E4"),
+        ("review PR", "E2"),
+        ("inbox", "E1"),
+    ] {
+        let body = serde_json::json!({ "title": title, "band": band });
+        let res = app
+            .clone()
+            .oneshot(send("POST", "/energy/calendar/tasks", &body.to_string()))
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::CREATED);
+    }
+
+    let mut crossings = Vec::new();
+    for (level, hour) in [(0.75, 8), (0.72, 9), (0.55, 10)] {
+        let body = serde_json::json!({
+            "who": "Raz",
+            "kind": "dragon",
+            "level": level,
+            "ts": format!("2025-05-01T{hour:02}:00:00Z"),
+        });
+        let res = app
+            .clone()
+            .oneshot(send("POST", "/energy/mark", &body.to_string()))
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        crossings.push(body_json(res).await["transition"].clone());
+    }
+    assert!(crossings[0].is_null() && crossings[1].is_null());
+    assert_eq!(crossings[2]["from"], "E3");
+    assert_eq!(crossings[2]["to"], "E2");
+    assert_eq!(crossings[2]["direction"], "down");
+
+    let list = body_json(
+        app.clone()
+            .oneshot(get("/energy/calendar/transitions?who=Raz"))
+            .await
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(list.as_array().unwrap().len(), 1);
+
+    let now = body_json(
+        app.clone()
+            .oneshot(get("/energy/now?who=Raz"))
+            .await
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(now["band"]["code"], "E2");
+    assert_eq!(now["band"]["max_level"], 0.7);
+    let titles = |v: &serde_json::Value| {
+        v.as_array()
+            .unwrap()
+            .iter()
+            .map(|t| t["title"].as_str().unwrap().to_string())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(titles(&now["tasks"]), ["review PR", "inbox"]);
+    assert_eq!(titles(&now["later"]), ["name the service"]);
+
+    let no_floor = serde_json::json!([{ "code": "HI", "name": "High", "min_level": 0.5 }]);
+    let res = app
+        .clone()
+        .oneshot(send("PUT", "/energy/calendar/bands", &no_floor.to_string()))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+// A mark right on a threshold lands in the band that starts there.
+#[tokio::test]
+async fn a_mark_on_a_threshold_lands_in_the_band_it_starts() {
+    let t = test_app("calendar_edge").await;
+    for (level, band) in [(0.7, "E3"), (0.9, "E4")] {
+        let body = serde_json::json!({ "who": "Ana", "kind": "dragon", "level": level });
+        let res = t
+            .app
+            .clone()
+            .oneshot(send("POST", "/energy/mark", &body.to_string()))
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        let now = body_json(
+            t.app
+                .clone()
+                .oneshot(get("/energy/now?who=Ana"))
+                .await
+                .unwrap(),
+        )
+        .await;
+        assert_eq!(now["band"]["code"], band, "level {level}");
+    }
+}
@@ -0,0 +1,84 @@
+use axum::http::StatusCode;
+use tower::ServiceExt; // for `oneshot`
+
+mod common;
+use common::{body_json, get, send, test_app};
+
+// A week of strong mornings and flat afternoons becomes a forecast with a deep-work
+// window in the morning and a rest window after lunch, also offered by
+// /patterns/productivity.
+#[tokio::test]
+async fn forecast_learns_the_daily_curve() {
+    let t = test_app("forecast").await;
+    let app = t.app.clone();
+
+    for day in 1..=7 {
+        for (hour, level) in [(8, 0.8), (9, 0.9), (14, 0.2)] {
+            let body = serde_json::json!({
+                "who": "Raz",
+                "kind": "focus",
+                "level": level,
+                "ts": format!("2025-06-{day:02}T{hour:02}:30:00Z"),
+            });
+            let res = app
+                .clone()
+                .oneshot(send("POST", "/energy/mark", &body.to_string()))
+                .await
+                .unwrap();
+            assert_eq!(res.status(), StatusCode::OK);
+        }
+    }
+
+    let query = "who=Raz&from=2025-06-08T00:00:00Z";
+    let f = body_json(
+        app.clone()
+            .oneshot(get(&format!("/energy/forecast?{query}")))
+            .await
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(f["samples"], 21);
+    assert_eq!(f["points"].as_array().unwrap().len(), 24);
+    assert_eq!(
+        f["windows"]["deep"][0]["start"],
+        "2025-06-08T08:00:00+00:00"
+    );
+    assert_eq!(f["windows"]["deep"][0]["end"], "2025-06-08T10:00:00+00:00");
+    assert_eq!(
+        f["windows"]["rest"][0]["start"],
+        "2025-06-08T14:00:00+00:00"
+    );
+
+    let profile = body_json(
+        app.clone()
+            .oneshot(get(&format!("/energy/profile?{query}")))
+            .await
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(profile["kinds"][0]["kind"], "focus");
+    assert_eq!(profile["kinds"][0]["hours"].as_array().unwrap().len(), 3);
+
+    let map = body_json(
+        app.clone()
+            .oneshot(get(&format!("/patterns/productivity?{query}")))
+            .await
+            .unwrap(),
+    )
+    .await;
+    assert!(map["burnout"].is_object());
+    assert_eq!(map["energy_windows"], f["windows"]);
+
+    let res = app
+        .clone()
+        .oneshot(get("/energy/forecast?who=Nico"))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+    let res = app
+        .clone()
+        .oneshot(get("/energy/forecast?who=Raz&hours=0"))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
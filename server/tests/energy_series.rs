@@ -0,0 +1,95 @@
+use axum::http::StatusCode;
+use tower::ServiceExt; // for `oneshot`
+
+mod common;
+use common::{body_json, get, send, test_app};
+
+// Backdated marks land in their own hour, state is per person, and a bad level is a 422
+// instead of a CHECK failure.
+#[tokio::test]
+async fn energy_history_is_bucketed_per_person() {
+    let t = test_app("energy").await;
+    let app = t.app.clone();
+
+    for (who, level, ts) in [
+        ("Raz", 0.2, Some("2025-03-01T09:10:00+00:00")),
+        ("Raz", 0.6, Some("2025-03-01T11:50:00+02:00")),
+        ("Raz", 0.9, Some("2025-03-01T10:15:00Z")),
+        ("Raz", 0.7, None),
+        ("Nico", 0.3, None),
+    ] {
+        let body = serde_json::json!({ "who": who, "kind": "dragon", "level": level, "ts": ts });
+        let res = app
+            .clone()
+            .oneshot(send("POST", "/energy/mark", &body.to_string()))
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        if let Some(ts) = ts {
+            let mark = body_json(res).await;
+            let stored =
+                chrono::DateTime::parse_from_rfc3339(mark["ts"].as_str().unwrap()).unwrap();
+            assert_eq!(stored, chrono::DateTime::parse_from_rfc3339(ts).unwrap());
+        }
+    }
+
+    let series = body_json(
+        app.clone()
+            .oneshot(get(
+                "/energy/series?who=Raz&kind=dragon&from=2025-03-01&to=2025-03-02&bucket=1h",
+            ))
+            .await
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(series["bucket"], "1h");
+    let points = series["series"][0]["points"].as_array().unwrap();
+    assert_eq!(points.len(), 2, "{series}");
+    assert_eq!(points[0]["start"], "2025-03-01T09:00:00+00:00");
+    assert_eq!(
+        (points[0]["count"].clone(), points[0]["min"].clone()),
+        (2.into(), 0.2.into())
+    );
+    assert_eq!(points[0]["max"], 0.6);
+    assert_eq!(points[1]["start"], "2025-03-01T10:00:00+00:00");
+    assert_eq!(points[1]["mean"], 0.9);
+
+    let raz = body_json(
+        app.clone()
+            .oneshot(get("/energy/state?who=Raz"))
+            .await
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(raz["dragon"], 0.7);
+    let nico = body_json(
+        app.clone()
+            .oneshot(get("/energy/state?who=Nico&band_model=three"))
+            .await
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(nico["dragon"]["level"], 0.3);
+
+    let future = (chrono::Utc::now() + chrono::Duration::days(1)).to_rfc3339();
+    for bad in [
+        serde_json::json!({ "who": "Raz", "kind": "dragon", "level": 1.5 }),
+        serde_json::json!({ "who": " ", "kind": "dragon", "level": 0.5 }),
+        serde_json::json!({ "who": "Raz", "kind": "dragon", "level": 0.5, "ts": "last tuesday" }),
+        serde_json::json!({ "who": "Raz", "kind": "dragon", "level": 0.5, "ts": future }),
+    ] {
+        let res = app
+            .clone()
+            .oneshot(send("POST", "/energy/mark", &bad.to_string()))
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::UNPROCESSABLE_ENTITY, "{bad}");
+    }
+    for bad in [
+        "/energy/series?bucket=1y",
+        "/energy/series?from=2025-03-02&to=2025-03-01",
+    ] {
+        let res = app.clone().oneshot(get(bad)).await.unwrap();
+        assert_eq!(res.status(), StatusCode::UNPROCESSABLE_ENTITY, "{bad}");
+    }
+}
@@ -0,0 +1,157 @@
+use std::env;
+
+use axum::http::StatusCode;
+use tower::ServiceExt; // for `oneshot`
+
+mod common;
+use common::{body_json, get, send, test_app};
+
+// /thanks is served by the library router on a DB created only through `init_state()`:
+// create, filter + page, edit (incl. clearing note_id), sealed redaction (API + thanks log), stats, delete.
+#[tokio::test]
+async fn thanks_lifecycle_through_the_library_router() {
+    let t = test_app("thanks").await;
+    let exports = env::temp_dir().join(format!("m3_thanks_exports_{}", t.tag));
+    env::set_var("M3_EXPORTS_DIR", &exports);
+    let app = t.app.clone();
+
+    let mut ids = Vec::new();
+    for body in [
+        r#"{"subject":"Grandma Ana","kind":"ancestor","who":"Raz"}"#,
+        r#"{"subject":"the kettle","kind":"tool","who":"Raz"}"#,
+        r#"{"subject":"the sea","kind":"place","who":"Sawsan"}"#,
+        r#"{"subject":"what she said","details":"private words","who":"Raz","sealed":true}"#,
+    ] {
+        let res = app
+            .clone()
+            .oneshot(send("POST", "/thanks", body))
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        ids.push(body_json(res).await["id"].as_i64().unwrap());
+    }
+
+    let empty = app
+        .clone()
+        .oneshot(send("POST", "/thanks", r#"{"subject":"  "}"#))
+        .await
+        .unwrap();
+    assert_eq!(empty.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    // who filter + cursor
+    let page = body_json(
+        app.clone()
+            .oneshot(get("/thanks?who=Raz&limit=2"))
+            .await
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(page["items"].as_array().unwrap().len(), 2);
+    assert_eq!(page["items"][0]["subject"], "(sealed)");
+    assert!(page["items"][0]["details"].is_null());
+    let before = page["next_before"].as_i64().unwrap();
+    let rest = body_json(
+        app.clone()
+            .oneshot(get(&format!("/thanks?who=Raz&limit=2&before={before}")))
+            .await
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(rest["items"].as_array().unwrap().len(), 1);
+    assert_eq!(rest["items"][0]["subject"], "Grandma Ana");
+    assert!(rest["next_before"].is_null());
+
+    // text search never matches sealed rows
+    let found = body_json(app.clone().oneshot(get("/thanks?q=SAID")).await.unwrap()).await;
+    assert_eq!(found["items"].as_array().unwrap().len(), 0);
+    let bad = app
+        .clone()
+        .oneshot(get("/thanks?since=yesterday"))
+        .await
+        .unwrap();
+    assert_eq!(bad.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    // edit
+    let edited = app
+        .clone()
+        .oneshot(send(
+            "PATCH",
+            &format!("/thanks/{}", ids[1]),
+            r#"{"subject":"the blue kettle","kind":""}"#,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(edited.status(), StatusCode::OK);
+    let v = body_json(edited).await;
+    assert_eq!(v["subject"], "the blue kettle");
+    assert!(v["kind"].is_null());
+    // note_id: kept when absent, cleared by null, set by a number
+    let path = format!("/thanks/{}", ids[1]);
+    for (body, note_id) in [
+        (r#"{"note_id":7}"#, serde_json::json!(7)),
+        (r#"{"details":"on the stove"}"#, serde_json::json!(7)),
+        (r#"{"note_id":null}"#, serde_json::Value::Null),
+    ] {
+        let res = app
+            .clone()
+            .oneshot(send("PATCH", &path, body))
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(body_json(res).await["note_id"], note_id, "{body}");
+    }
+    let missing = app
+        .clone()
+        .oneshot(send("PATCH", "/thanks/99999", r#"{"subject":"x"}"#))
+        .await
+        .unwrap();
+    assert_eq!(missing.status(), StatusCode::NOT_FOUND);
+
+    // stats: Raz thanked three times today; the sealed subject stays hidden
+    let stats = body_json(
+        app.clone()
+            .oneshot(get("/thanks/stats?who=Raz"))
+            .await
+            .unwrap(),
+    )
+    .await;
+    let raz = &stats["people"][0];
+    assert_eq!(raz["who"], "Raz");
+    assert_eq!(raz["total"], 3);
+    assert_eq!(raz["current_streak"], 1);
+    assert!(raz["top_subjects"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .all(|s| s["key"] != "what she said"));
+
+    // the thanks log only carries the redacted line
+    let month = std::fs::read_dir(exports.join("thanks"))
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap()
+        .path();
+    let log = std::fs::read_dir(&month)
+        .unwrap()
+        .map(|f| std::fs::read_to_string(f.unwrap().path()).unwrap())
+        .collect::<String>();
+    assert!(log.contains("Grandma Ana"));
+    assert!(!log.contains("private words") && !log.contains("what she said"));
+
+    // delete
+    let gone = app
+        .clone()
+        .oneshot(send("DELETE", &format!("/thanks/{}", ids[2]), ""))
+        .await
+        .unwrap();
+    assert_eq!(gone.status(), StatusCode::NO_CONTENT);
+    let again = app
+        .clone()
+        .oneshot(send("DELETE", &format!("/thanks/{}", ids[2]), ""))
+        .await
+        .unwrap();
+    assert_eq!(again.status(), StatusCode::NOT_FOUND);
+
+    let _ = std::fs::remove_dir_all(&exports);
+}
@@ -0,0 +1,148 @@
+use std::env;
+
+use axum::{body::Body, http::Request};
+use m3_memory_server::panic;
+use tower::ServiceExt; // for `oneshot`
+
+mod common;
+use common::{body_json, test_app};
+
+// The panic service runs in-process through the lib: one call, every artifact id,
+// and the run shows up in /panic/history for the requested actor.
+#[tokio::test]
+async fn panic_run_records_every_artifact_for_the_actor() {
+    let t = test_app("panic").await;
+    let state = t.state.clone();
+    let req = panic::RunRequest {
+        who: "Ilinca".into(),
+        source: "run".into(),
+        mode: Some("fear-visible".into()),
+        rotation: None,
+        steps: panic::Steps::default(),
+    };
+    let run = state
+        .db
+        .0
+        .call(move |c| Ok(panic::run(c, &req, chrono::Utc::now())?))
+        .await
+        .unwrap()
+        .unwrap();
+    let a = &run.artifacts;
+    assert_eq!(run.event.mode, "fearVisible");
+    assert_eq!(run.event.emotion_kind, "fear");
+    assert!(run.event.suggested_bridge.is_some());
+    assert!(a.tell_id > 0 && a.emotion_id > 0 && a.gratitude_id > 0 && a.status_event_id > 0);
+
+    let app = t.app.clone();
+    let res = app
+        .oneshot(
+            Request::get("/panic/history?who=Ilinca")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let bytes = axum::body::to_bytes(res.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let v: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+    assert_eq!(v["items"][0]["id"], a.event_id);
+    assert_eq!(v["items"][0]["gratitude_id"], a.gratitude_id);
+}
+
+// POST /panic and POST /panic/run are mounted by the lib: blank steps come from the
+// preset, readiness goes out on the bus, and /panic/last reads the run back.
+#[tokio::test]
+async fn panic_http_runs_fill_from_the_preset_and_announce_readiness() {
+    let t = test_app("panic_http").await;
+    let exports = env::temp_dir().join(format!("m3_panic_exports_{}", t.tag));
+    env::set_var("M3_EXPORTS_DIR", &exports);
+    let (state, app) = (t.state.clone(), t.app.clone());
+
+    let res = app
+        .clone()
+        .oneshot(
+            Request::post("/panic")
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    r#"{"who":"Ilinca","doorway":"cold_splash","whisper":"  "}"#,
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(res.status(), 200);
+    let ui = body_json(res).await;
+    let default = state
+        .db
+        .0
+        .call(|c| Ok(panic::find(c, panic::DEFAULT_MODE)?))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(ui["who"], "Ilinca");
+    assert_eq!(ui["doorway"], "cold_splash");
+    assert!(default.whispers.iter().any(|w| ui["whisper"] == w.as_str()));
+    assert!(ui["artifacts"]["status_event_id"].as_i64().unwrap() > 0);
+    assert_eq!(ui["logged"], true);
+    let log_path = ui["artifacts"]["log_path"].as_str().unwrap();
+    assert!(std::path::Path::new(log_path).starts_with(&exports));
+    assert!(state.bus.drain().contains(&"status:main:green".to_string()));
+
+    let res = app
+        .clone()
+        .oneshot(
+            Request::post("/panic/run")
+                .header(panic::ACTOR_HEADER, "Sawsan")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(res.status(), 200);
+    let oracle = body_json(res).await;
+    assert_eq!(oracle["who"], "Sawsan");
+
+    let res = app
+        .oneshot(Request::get("/panic/last").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(res.status(), 200);
+    let last = body_json(res).await;
+    assert_eq!(last["id"], oracle["event_id"]);
+    assert_eq!(last["mode"], panic::RUN_MODE);
+
+    let _ = std::fs::remove_dir_all(&exports);
+}
+
+// A rotation override the server does not know is refused, like on a preset.
+#[tokio::test]
+async fn unknown_rotation_overrides_are_refused() {
+    let t = test_app("panic_rotation").await;
+    let app = t.app.clone();
+
+    let res = app
+        .clone()
+        .oneshot(
+            Request::post("/panic")
+                .header("content-type", "application/json")
+                .body(Body::from(r#"{"who":"Ilinca","rotation":"sideways"}"#))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(res.status(), 422);
+
+    for (query, status) in [("rotation=sideways", 422), ("rotation=lru", 200)] {
+        let res = app
+            .clone()
+            .oneshot(
+                Request::post(format!("/panic/run?who=Sawsan&{query}"))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(res.status(), status, "{query}");
+    }
+}
@@ -0,0 +1,96 @@
+use std::env;
+
+use axum::http::StatusCode;
+use tower::ServiceExt; // for `oneshot`
+
+mod common;
+use common::{get, send, test_app};
+
+async fn body_bytes(res: axum::response::Response) -> Vec<u8> {
+    axum::body::to_bytes(res.into_body(), usize::MAX)
+        .await
+        .unwrap()
+        .to_vec()
+}
+
+fn emotion(who: &str, kind: &str, sealed: bool, privacy: &str) -> String {
+    serde_json::json!({
+        "who": who, "kind": kind, "intensity": 0.64, "details": "told my brother",
+        "sealed": sealed, "privacy": privacy
+    })
+    .to_string()
+}
+
+// Names, details and sealed rows stay home; small groups are suppressed; CSV and JSON
+// carry the same rows under the same pseudonyms.
+#[tokio::test]
+async fn anonymized_export_shares_only_the_refined() {
+    env::remove_var("M3_PSEUDONYM_SALT");
+    let t = test_app("privacy").await;
+    let app = t.app.clone();
+
+    for body in [
+        emotion("Raz", "fear", false, "anonymized"),
+        emotion("Nico", "afraid", false, "public"),
+        emotion("Ana", "fear", false, "anonymized"),
+        emotion("Raz", "shame", false, "anonymized"),
+        emotion("Mara", "fear", true, "anonymized"),
+        emotion("Mara", "fear", false, "private"),
+    ] {
+        let res = app
+            .clone()
+            .oneshot(send("POST", "/emotions/add", &body))
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+
+    let res = app
+        .clone()
+        .oneshot(get("/privacy/export?source=emotions&k=3"))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let raw = body_bytes(res).await;
+    let text = String::from_utf8_lossy(&raw);
+    for leak in ["Raz", "Nico", "Ana", "Mara", "brother"] {
+        assert!(!text.contains(leak), "{leak} leaked");
+    }
+    let out: serde_json::Value = serde_json::from_slice(&raw).unwrap();
+    assert_eq!(out["granularity"], "week");
+    assert_eq!(out["rows"].as_array().unwrap().len(), 3);
+    assert_eq!(out["suppressed"], 1, "Raz's shame is a group of one");
+    assert_eq!(out["dropped_sealed"], 1);
+    assert!(out["rows"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .all(|r| r["kind"] == "fear" && r["value"] == 0.6));
+    let first = out["rows"][0]["who"].as_str().unwrap().to_string();
+
+    let res = app
+        .clone()
+        .oneshot(get("/privacy/export?source=emotions&k=3&format=csv"))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    assert!(res.headers()["content-type"]
+        .to_str()
+        .unwrap()
+        .starts_with("text/csv"));
+    assert_eq!(res.headers()["x-m3-suppressed"], "1");
+    let csv = String::from_utf8(body_bytes(res).await).unwrap();
+    assert_eq!(csv.lines().count(), 4);
+    assert!(csv.contains(&first), "pseudonyms are stable across exports");
+
+    for bad in [
+        "/privacy/export?k=1",
+        "/privacy/export?format=xml",
+        "/privacy/export?granularity=month",
+        "/privacy/export?source=notes",
+        "/privacy/export?since=yesterday",
+    ] {
+        let res = app.clone().oneshot(get(bad)).await.unwrap();
+        assert_eq!(res.status(), StatusCode::UNPROCESSABLE_ENTITY, "{bad}");
+    }
+}
@@ -0,0 +1,96 @@
+use axum::http::StatusCode;
+use m3_memory_server::rhythm_clock;
+use tower::ServiceExt; // for `oneshot`
+
+mod common;
+use common::{body_json, get, send, test_app};
+
+// Each person keeps their own phase and config, and both come back after a restart
+// (a second state on the same database file).
+#[tokio::test]
+async fn rhythm_survives_a_restart_per_person() {
+    let t = test_app("rhythm").await;
+    let app = t.app.clone();
+
+    let res = app
+        .clone()
+        .oneshot(send(
+            "POST",
+            "/rhythm/config?who=Raz",
+            r#"{"work_minutes":25,"rest_minutes":5}"#,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let res = app
+        .clone()
+        .oneshot(send(
+            "POST",
+            "/rhythm/config?who=Raz",
+            r#"{"work_minutes":0,"rest_minutes":5}"#,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let mark = body_json(
+        app.clone()
+            .oneshot(send("POST", "/rhythm/mark?who=Raz", ""))
+            .await
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(mark["phase"], "rest");
+    let team = body_json(app.clone().oneshot(get("/rhythm/next")).await.unwrap()).await;
+    assert_eq!(team["who"], "team");
+    assert_eq!(team["phase"], "work");
+    assert_eq!(team["config"]["work_minutes"], 50);
+
+    let (_, app) = t.restart().await;
+    let raz = body_json(
+        app.clone()
+            .oneshot(get("/rhythm/next?who=Raz"))
+            .await
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(raz["phase"], "rest");
+    assert_eq!(raz["session_id"], mark["session_id"]);
+    assert_eq!(raz["config"]["rest_minutes"], 5);
+    assert_eq!(raz["ends_at"], mark["at"].as_i64().unwrap() + 5 * 60);
+
+    let history = body_json(
+        app.clone()
+            .oneshot(get("/rhythm/history?who=Raz&limit=1"))
+            .await
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(history["marks"][0]["id"], mark["id"]);
+    assert!(history["next_before"].is_i64());
+}
+
+// The clock ships with the lib: a manual mark stays pending until a sweep announces
+// it on the bus, once.
+#[tokio::test]
+async fn lib_clock_announces_pending_marks() {
+    let t = test_app("rhythm_clock").await;
+    let (state, app) = (t.state.clone(), t.app.clone());
+    assert_eq!(rhythm_clock::sweep(&state).await, 0);
+
+    let res = app
+        .oneshot(send("POST", "/rhythm/mark?who=Ana", ""))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(rhythm_clock::sweep(&state).await, 1);
+    let announced: Vec<String> = state
+        .bus
+        .drain()
+        .into_iter()
+        .filter(|m| m.starts_with(rhythm_clock::TOPIC))
+        .collect();
+    assert_eq!(announced.len(), 1);
+    assert!(announced[0].contains(r#""who":"Ana""#));
+    assert_eq!(rhythm_clock::sweep(&state).await, 0, "announced once");
+}
@@ -0,0 +1,119 @@
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use tower::ServiceExt; // for `oneshot`
+
+mod common;
+use common::{body_json, send, test_app};
+
+/// `/state/set` overwriting whatever revision is current.
+fn set_state(body: &str) -> Request<Body> {
+    Request::post("/state/set")
+        .header("content-type", "application/json")
+        .header("if-match", "*")
+        .body(Body::from(body.to_string()))
+        .unwrap()
+}
+
+// Roster edits shape /state/get; /state/set is validated against members + pillars;
+// a rename carries the member's saved energy.
+#[tokio::test]
+async fn roster_and_pillars_drive_dashboard_state() {
+    let t = test_app("team").await;
+    let app = t.app.clone();
+
+    let added = app
+        .clone()
+        .oneshot(send(
+            "POST",
+            "/team/members",
+            r#"{"name":"Ilinca","avatar":"🦊","default_energy":55}"#,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(added.status(), StatusCode::OK);
+    let team = body_json(added).await;
+    let ilinca = team["members"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|m| m["name"] == "Ilinca")
+        .unwrap()
+        .clone();
+
+    let dup = app
+        .clone()
+        .oneshot(send("POST", "/team/members", r#"{"name":"Raz"}"#))
+        .await
+        .unwrap();
+    assert_eq!(dup.status(), StatusCode::CONFLICT);
+
+    let pillar = app
+        .clone()
+        .oneshot(send(
+            "POST",
+            "/team/pillars",
+            r#"{"key":"sleep","allowed":["rested","tired"],"default_value":"tired"}"#,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(pillar.status(), StatusCode::OK);
+
+    let got = app
+        .clone()
+        .oneshot(Request::get("/state/get").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let v = body_json(got).await;
+    assert_eq!(v["members"][3]["name"], "Ilinca");
+    assert_eq!(v["members"][3]["energy"], 55);
+    assert_eq!(v["members"][3]["avatar"], "🦊");
+    assert_eq!(v["pillars"]["sleep"], "tired");
+
+    let bad = app
+        .clone()
+        .oneshot(set_state(
+            r#"{"members":[{"name":"Ghost","energy":40}],"pillars":{"sleep":"great"}}"#,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(bad.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(
+        body_json(bad).await["problems"].as_array().unwrap().len(),
+        2
+    );
+
+    let ok = app
+        .clone()
+        .oneshot(set_state(
+            r#"{"members":[{"name":"Ilinca","energy":20}],"pillars":{"sleep":"rested"}}"#,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(ok.status(), StatusCode::OK);
+    let v = body_json(ok).await;
+    assert_eq!(v["members"][0]["energy"], 70, "other members untouched");
+    assert_eq!(v["pillars"]["crown"], "watch", "other pillars untouched");
+
+    let renamed = app
+        .clone()
+        .oneshot(send(
+            "PATCH",
+            &format!("/team/members/{}", ilinca["id"]),
+            r#"{"name":"Ili"}"#,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(renamed.status(), StatusCode::OK);
+
+    let got = app
+        .clone()
+        .oneshot(Request::get("/state/get").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let v = body_json(got).await;
+    assert_eq!(v["members"][3]["name"], "Ili");
+    assert_eq!(v["members"][3]["energy"], 20);
+    assert_eq!(v["pillars"]["sleep"], "rested");
+}
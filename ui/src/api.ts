@@ -69,6 +69,7 @@ export interface ThanksIn {
   kind?: string; // "ancestor" | "tool" | "place" | …
   note_id?: number;
   who?: string; // current profile if you keep one
+  sealed?: boolean; // subject/details stay in the DB; reads show "(sealed)"
 }
 
 export interface ThanksOut {
@@ -79,6 +80,7 @@ export interface ThanksOut {
   kind?: string | null;
   note_id?: number | null;
   who?: string | null;
+  sealed?: boolean;
 }
 
 // Server row shape for /retrieve results
@@ -100,21 +102,22 @@ export type ReplyOut = {
   actions?: string[]; // <= two quick doors
 };
 
-export type MemberEnergy = { name: string; energy: number };
+export type MemberEnergy = { name: string; energy: number; avatar?: string };
 
-export type PillarStatus = {
-  crown: 'good' | 'watch' | 'rest';
-  void: 'good' | 'watch' | 'rest';
-  play: 'good' | 'watch' | 'rest';
-  dragon: 'good' | 'watch' | 'rest';
-  life_force: 'good' | 'watch' | 'rest';
-};
+// Pillar keys + allowed values come from the server roster (GET /team);
+// the seed set is crown/void/play/dragon/life_force with good | watch | rest.
+export type PillarStatus = Record<string, string>;
+
+export type TeamMember = { id: number; name: string; avatar?: string | null; default_energy: number; position: number };
+export type PillarDef = { key: string; label: string; allowed: string[]; default_value: string; position: number };
+export type TeamDefinitions = { members: TeamMember[]; pillars: PillarDef[] };
 
 export type TeamState = {
   members: MemberEnergy[];
   pillars: PillarStatus;
   note?: string | null;
   ts: string;
+  rev?: number;
 };
 
 // --- Emotions / Timeline ---
@@ -126,11 +129,341 @@ export interface EmotionOut {
   intensity: number;
   note_id?: number | null;
   details?: string | null;
+  note?: string | null;
   sealed: boolean;
   archetype?: string | null;
+  band?: Band | FiveBand; // per ?band_model= (three by default; absent with none)
   privacy: string;
+  unknown_kind?: boolean; // only on /emotions/add, when the kind is not in the taxonomy
+}
+
+export type Band = 'survival' | 'integrity' | 'coherence';
+export type FiveBand = 'ground' | 'flow' | 'mind' | 'field' | 'source';
+export type BandModel = 'three' | 'five';
+
+export type BandAdvice = { approach: string; hints: string[]; ui_color_hint: string };
+export type BandInfo<B = Band | FiveBand> = BandAdvice & { band: B };
+export type BandClassification = {
+  kind: string;
+  known: boolean;
+  intensity: number;
+  model: BandModel;
+  band: Band | FiveBand;
+  advice: BandAdvice;
+};
+export type BandSeries = {
+  who: string;
+  model: BandModel;
+  bucket: 'day' | 'week';
+  points: {
+    start: string;
+    count: number;
+    mean_intensity: number;
+    bands: { band: Band | FiveBand; count: number }[];
+    dominant: Band | FiveBand;
+  }[];
+};
+
+export async function getBands(model: BandModel = 'three'): Promise<BandInfo[]> {
+  return request(`/consciousness/bands?${queryString({ model })}`, { method: 'GET' });
+}
+
+export async function classifyBand(kind: string, intensity?: number, model: BandModel = 'three'): Promise<BandClassification> {
+  return request(`/consciousness/classify?${queryString({ kind, intensity, model })}`, { method: 'GET' });
+}
+
+export async function getBandSeries(
+  who: string,
+  opts: { model?: BandModel; bucket?: 'day' | 'week'; since?: string; until?: string } = {},
+): Promise<BandSeries> {
+  return request(`/consciousness/series?${queryString({ who, ...opts })}`, { method: 'GET' });
+}
+
+export type EnergyTransition = {
+  id: number;
+  ts: string;
+  who: string;
+  from: string;
+  to: string;
+  direction: 'up' | 'down';
+  level: number;
+  mark_id: number;
+  exit?: string;
+};
+export type EnergyMark = {
+  id: number;
+  ts: string;
+  who: string;
+  kind: string;
+  level: number;
+  note?: string | null;
+  band?: Band | FiveBand;
+  transition?: EnergyTransition;
+};
+export type EnergySeries = {
+  who: string | null;
+  kind: string | null;
+  bucket: string;
+  from: string | null;
+  to: string | null;
+  series: { kind: string; points: { start: string; count: number; min: number; mean: number; max: number }[] }[];
+};
+
+export async function markEnergy(body: { who: string; kind: string; level: number; note?: string; ts?: string }): Promise<EnergyMark> {
+  return request('/energy/mark', { method: 'POST', body: JSON.stringify(body) });
+}
+
+export async function getEnergyState(who?: string): Promise<Record<string, number>> {
+  return request(`/energy/state?${queryString({ who })}`, { method: 'GET' });
 }
 
+export async function getEnergySeries(opts: { who?: string; kind?: string; from?: string; to?: string; bucket?: string } = {}): Promise<EnergySeries> {
+  return request(`/energy/series?${queryString({ ...opts })}`, { method: 'GET' });
+}
+
+export type EnergyBand = {
+  code: string;
+  name: string;
+  min_level: number;
+  max_level?: number;
+  felt?: string | null;
+  good_for: string[];
+  avoid: string[];
+};
+export type EnergyTask = {
+  id: number;
+  title: string;
+  band: string;
+  minutes: number | null;
+  who: string | null;
+  done_at: string | null;
+  created_at: string;
+};
+export type EnergyNow = { who: string; level: number; kind: string; ts: string; band: EnergyBand; tasks: EnergyTask[]; later: EnergyTask[] };
+
+export async function getEnergyNow(who: string): Promise<EnergyNow> {
+  return request(`/energy/now?${queryString({ who })}`, { method: 'GET' });
+}
+
+export async function getEnergyBands(): Promise<EnergyBand[]> {
+  return request('/energy/calendar/bands', { method: 'GET' });
+}
+
+export async function putEnergyBands(bands: Omit<EnergyBand, 'max_level'>[]): Promise<EnergyBand[]> {
+  return request('/energy/calendar/bands', { method: 'PUT', body: JSON.stringify(bands) });
+}
+
+export async function getEnergyTasks(opts: { who?: string; all?: boolean } = {}): Promise<EnergyTask[]> {
+  return request(`/energy/calendar/tasks?${queryString({ ...opts })}`, { method: 'GET' });
+}
+
+export async function createEnergyTask(body: { title: string; band: string; minutes?: number; who?: string }): Promise<EnergyTask> {
+  return request('/energy/calendar/tasks', { method: 'POST', body: JSON.stringify(body) });
+}
+
+export async function updateEnergyTask(
+  id: number,
+  patch: { title?: string; band?: string; minutes?: number; who?: string; done?: boolean },
+): Promise<EnergyTask> {
+  return request(`/energy/calendar/tasks/${id}`, { method: 'PATCH', body: JSON.stringify(patch) });
+}
+
+export async function deleteEnergyTask(id: number): Promise<void> {
+  const res = await fetch(`${BASE}/energy/calendar/tasks/${id}`, { method: 'DELETE', headers: cleanHeaders({ Authorization: BEARER ? `Bearer ${BEARER}` : undefined }) });
+  if (!res.ok) throw new Error(await res.text());
+}
+
+export async function getEnergyTransitions(opts: { who?: string; limit?: number } = {}): Promise<EnergyTransition[]> {
+  return request(`/energy/calendar/transitions?${queryString({ ...opts })}`, { method: 'GET' });
+}
+
+export type EnergySlot = { weekday?: string; hour: number; samples: number; mean: number; low: number; high: number };
+export type EnergyProfile = {
+  who: string;
+  days: number;
+  utc_offset: string;
+  kinds: { kind: string; samples: number; mean: number; hours: EnergySlot[]; weekdays: EnergySlot[] }[];
+};
+export type EnergyWindow = { start: string; end: string; hours: number; mean: number };
+export type EnergyForecast = {
+  who: string;
+  kind: string | null;
+  days: number;
+  utc_offset: string;
+  samples: number;
+  points: { start: string; weekday: string; hour: number; level: number; low: number; high: number; confidence: number; band: string | null }[];
+  windows: { deep: EnergyWindow[]; rest: EnergyWindow[] };
+};
+export type ForecastOptions = { kind?: string; days?: number; hours?: number; from?: string; utc_offset?: string; deep?: string; rest?: string };
+
+export async function getEnergyProfile(who: string, opts: Omit<ForecastOptions, 'hours' | 'from' | 'deep' | 'rest'> = {}): Promise<EnergyProfile> {
+  return request(`/energy/profile?${queryString({ who, ...opts })}`, { method: 'GET' });
+}
+
+export async function getEnergyForecast(who: string, opts: ForecastOptions = {}): Promise<EnergyForecast> {
+  return request(`/energy/forecast?${queryString({ who, ...opts })}`, { method: 'GET' });
+}
+
+export type RhythmPhase = 'work' | 'rest';
+export type RhythmConfig = {
+  work_minutes: number;
+  rest_minutes: number;
+  auto_advance?: boolean;
+  grace_secs?: number;
+  doorway_mode?: string | null;
+};
+export type RhythmSuggestion = { mode: string; whisper: string; breath: string; doorway: string; anchor: string };
+export type RhythmPulse = {
+  who: string;
+  session_id: number;
+  phase: RhythmPhase;
+  started_at: number;
+  ends_at: number;
+  remaining_secs: number;
+  advances_at?: number;
+  snoozed_secs: number;
+  suggestion?: RhythmSuggestion;
+  config: RhythmConfig;
+};
+export type RhythmMark = {
+  id: number;
+  who: string;
+  session_id: number;
+  at: number;
+  ts: string;
+  phase: RhythmPhase;
+  source: 'start' | 'manual' | 'auto';
+  snoozed_secs: number;
+  suggestion?: RhythmSuggestion;
+};
+/** Payload of the named `rhythm` event on /status/stream. */
+export type RhythmPhaseEvent = RhythmMark & { ends_at: number };
+export type RhythmSession = { id: number; who: string; started_at: string; ended_at: string | null; marks: number };
+
+export async function getRhythm(who?: string): Promise<RhythmPulse> {
+  return request(`/rhythm/next?${queryString({ who })}`, { method: 'GET' });
+}
+
+export async function markRhythm(who?: string): Promise<RhythmMark> {
+  return request(`/rhythm/mark?${queryString({ who })}`, { method: 'POST' });
+}
+
+export async function resetRhythm(who?: string): Promise<RhythmPulse> {
+  return request(`/rhythm/reset?${queryString({ who })}`, { method: 'POST' });
+}
+
+export async function snoozeRhythm(minutes?: number, who?: string): Promise<RhythmPulse> {
+  return request(`/rhythm/snooze?${queryString({ who })}`, { method: 'POST', body: JSON.stringify({ minutes }) });
+}
+
+export async function getRhythmConfig(who?: string): Promise<RhythmConfig> {
+  return request(`/rhythm/config?${queryString({ who })}`, { method: 'GET' });
+}
+
+export async function setRhythmConfig(config: RhythmConfig, who?: string): Promise<RhythmConfig> {
+  return request(`/rhythm/config?${queryString({ who })}`, { method: 'POST', body: JSON.stringify(config) });
+}
+
+export async function getRhythmHistory(
+  opts: { who?: string; from?: string; to?: string; session?: number; before?: number; limit?: number } = {},
+): Promise<{ marks: RhythmMark[]; next_before: number | null }> {
+  return request(`/rhythm/history?${queryString({ ...opts })}`, { method: 'GET' });
+}
+
+export async function getRhythmSessions(
+  opts: { who?: string; before?: number; limit?: number } = {},
+): Promise<{ items: RhythmSession[]; next_before: number | null }> {
+  return request(`/rhythm/sessions?${queryString({ ...opts })}`, { method: 'GET' });
+}
+
+export type EmotionKind = {
+  name: string;
+  band: Band;
+  bridge?: string | null;
+  color?: string | null;
+  synonyms: string[];
+  updated_at: string;
+};
+
+export async function getEmotionKinds(): Promise<EmotionKind[]> {
+  return request('/emotions/kinds', { method: 'GET' });
+}
+
+export async function resolveEmotionKind(kind: string): Promise<{ input: string; kind: string; known: boolean; band?: Band | null; bridge?: string | null; color?: string | null }> {
+  return request(`/emotions/kinds/resolve?${queryString({ kind })}`, { method: 'GET' });
+}
+
+export type ImportMapping = {
+  ts: string;
+  kind: string;
+  time?: string;
+  who?: string;
+  intensity?: string;
+  note?: string;
+  ts_format?: string;
+  scale?: number;
+};
+export type ImportIn = {
+  csv: string;
+  layout?: 'daylio' | 'generic';
+  mapping?: ImportMapping;
+  who?: string;
+  privacy?: 'private' | 'sealed' | 'anonymized' | 'public';
+  utc_offset?: string;
+};
+export type ImportJob = {
+  id: number;
+  layout: 'daylio' | 'generic';
+  state: 'queued' | 'running' | 'done' | 'failed';
+  total: number;
+  processed: number;
+  imported: number;
+  duplicates: number;
+  skipped: number;
+  unknown_kinds: Record<string, number>;
+  errors: { row: number; reason: string }[];
+  error?: string;
+  created_at: string;
+  finished_at: string | null;
+};
+
+export async function importEmotions(body: ImportIn): Promise<ImportJob> {
+  return request('/emotions/import', { method: 'POST', body: JSON.stringify(body) });
+}
+
+export async function getImportJob(id: number): Promise<ImportJob> {
+  return request(`/emotions/import/${id}`, { method: 'GET' });
+}
+
+export async function getImportJobs(): Promise<ImportJob[]> {
+  return request('/emotions/import', { method: 'GET' });
+}
+
+export type EmotionFilter = {
+  who?: string;
+  kind?: string;
+  band?: Band;
+  archetype?: string;
+  privacy?: string;
+  min_intensity?: number;
+  max_intensity?: number;
+  since?: string; // RFC3339
+  until?: string; // RFC3339
+};
+
+export type EmotionPage = { items: EmotionOut[]; next_before?: number | null };
+
+export type BandCounts = Record<Band, number>;
+
+export type EmotionAggregate = {
+  bucket: 'day' | 'week';
+  total: number;
+  mean_intensity?: number | null;
+  buckets: { start: string; count: number; mean_intensity: number; bands: BandCounts }[];
+  kinds: { kind: string; count: number; mean_intensity: number }[];
+  bands: BandCounts;
+};
+
 // New unified TimelineItem type matching server’s timeline shape
 export type TimelineItem = {
   id: number;
@@ -198,8 +531,15 @@ export async function getTimeline(limit = 20): Promise<TimelineItem[]> {
 }
 
 export async function emotionsRecent(limit = 20): Promise<EmotionOut[]> {
-  // server ignores limit for now; we keep the param for forward-compat
-  return request<EmotionOut[]>('/emotions/recent', { method: 'GET' });
+  return (await getEmotions({}, undefined, limit)).items;
+}
+
+export async function getEmotions(filter: EmotionFilter = {}, before?: number, limit = 50): Promise<EmotionPage> {
+  return request(`/emotions?${queryString({ ...filter, before, limit })}`, { method: 'GET' });
+}
+
+export async function getEmotionsAggregate(filter: EmotionFilter = {}, bucket: 'day' | 'week' = 'day'): Promise<EmotionAggregate> {
+  return request(`/emotions/aggregate?${queryString({ ...filter, bucket })}`, { method: 'GET' });
 }
 
 // ----- Reply API -----
@@ -222,10 +562,27 @@ export type PanicOut = {
   breath: string;
   doorway: string;
   anchor: string;
+  suggested_bridge?: string | null;
   logged?: boolean;
+  event_id?: number | null; // row in panic_events
+  who?: string;
+  artifacts?: PanicArtifacts;
+};
+
+// Every row one panic run wrote (one transaction server-side).
+export type PanicArtifacts = {
+  event_id: number;
+  tell_id: number;
+  emotion_id: number;
+  gratitude_id: number;
+  status_event_id: number;
+  relational_event_id?: string;
+  log_path?: string;
 };
 
-export type PanicMode = 'default' | 'fearVisible';
+// Modes are server presets (GET /panic/presets); these two are always seeded.
+export type PanicMode = 'default' | 'fearVisible' | (string & {});
+export type PanicRotation = 'tick' | 'random' | 'lru' | 'effective';
 
 export type PanicRequest = {
   whisper?: string;
@@ -233,6 +590,22 @@ export type PanicRequest = {
   doorway?: string;
   anchor?: string;
   mode?: PanicMode; // when present, server fills defaults
+  rotation?: PanicRotation;
+  who?: string; // actor; server falls back to x-m3-actor, then Raz
+};
+
+export type PanicPreset = {
+  mode: string;
+  label: string;
+  whispers: string[];
+  breaths: string[];
+  doorways: string[];
+  anchors: string[];
+  emotion_kind: string;
+  intensity: number;
+  bridge_kind: string;
+  rotation: PanicRotation;
+  updated_at: string;
 };
 
 // ---- API surface ----
@@ -256,8 +629,84 @@ export async function createThanks(body: ThanksIn): Promise<ThanksOut> {
   });
 }
 
+export type ThanksFilter = {
+  who?: string;
+  kind?: string;
+  since?: string; // RFC3339
+  until?: string; // RFC3339
+  q?: string; // subject text
+};
+
+export type ThanksPage = { items: ThanksOut[]; next_before?: number | null };
+
+export type ThanksCount = { key: string; n: number };
+
+export type ThanksPersonStats = {
+  who: string | null;
+  total: number;
+  days_active: number;
+  current_streak: number;
+  longest_streak: number;
+  first_day?: string | null;
+  last_day?: string | null;
+  per_day: ThanksCount[];
+  top_subjects: ThanksCount[];
+  kinds: ThanksCount[];
+};
+
+function queryString(params: Record<string, string | number | boolean | undefined>): string {
+  const qs = new URLSearchParams();
+  for (const [k, v] of Object.entries(params)) if (v !== undefined && v !== '') qs.set(k, String(v));
+  return qs.toString();
+}
+
+export async function getThanksPage(filter: ThanksFilter = {}, before?: number, limit = 20): Promise<ThanksPage> {
+  return request(`/thanks?${queryString({ ...filter, before, limit })}`, { method: 'GET' });
+}
+
 export async function getThanks(limit = 10): Promise<ThanksOut[]> {
-  return request(`/thanks?limit=${limit}`, { method: 'GET' });
+  return (await getThanksPage({}, undefined, limit)).items;
+}
+
+export async function patchThanks(id: number, body: Partial<ThanksIn>): Promise<ThanksOut> {
+  return request(`/thanks/${id}`, { method: 'PATCH', body: JSON.stringify(body) });
+}
+
+export async function deleteThanks(id: number): Promise<void> {
+  const res = await fetch(`${BASE}/thanks/${id}`, { method: 'DELETE', headers: cleanHeaders({ Authorization: BEARER ? `Bearer ${BEARER}` : undefined }) });
+  if (!res.ok) throw new Error(await res.text());
+}
+
+export async function getThanksStats(filter: ThanksFilter = {}, top = 5): Promise<{ today: string; people: ThanksPersonStats[] }> {
+  return request(`/thanks/stats?${queryString({ ...filter, top })}`, { method: 'GET' });
+}
+
+export type AnonExportOptions = {
+  source?: 'emotions' | 'energy' | 'all';
+  granularity?: 'day' | 'week';
+  k?: number;
+  since?: string;
+  until?: string;
+  include_private?: boolean;
+};
+export type AnonRow = { source: 'emotions' | 'energy'; who: string; period: string; kind: string; value: number };
+export type AnonExport = {
+  source: 'emotions' | 'energy' | 'all';
+  granularity: 'day' | 'week';
+  k: number;
+  rows: AnonRow[];
+  suppressed: number;
+  dropped_sealed: number;
+};
+
+export async function getAnonymizedExport(opts: AnonExportOptions = {}): Promise<AnonExport> {
+  return request(`/privacy/export?${queryString({ ...opts })}`, { method: 'GET' });
+}
+
+export async function getAnonymizedCsv(opts: AnonExportOptions = {}): Promise<string> {
+  const res = await fetch(`${BASE}/privacy/export?${queryString({ ...opts, format: 'csv' })}`, { headers: cleanHeaders({ Authorization: BEARER ? `Bearer ${BEARER}` : undefined }) });
+  if (!res.ok) throw new Error(await res.text());
+  return res.text();
 }
 
 async function postJSON<T>(path: string, body: Record<string, unknown>, extraHeaders: HeadersMap = {}): Promise<T> {
@@ -444,12 +893,93 @@ export function streamStatus(onUpdate: (updates: { name: string; status: LightSt
   return () => es.close();
 }
 
+export type CareEvent = {
+  type: 'care.ack' | 'care.done';
+  event: string;
+  by: { id: string | null; display: string | null };
+  action: string | null;
+  outcome: string | null;
+  ts: string;
+  message: string; // e.g. "Ana is with you"
+};
+
+/**
+ * Subscribe to care replies (care.ack / care.done) for relational events.
+ * Arrives as named `care` events on the same SSE stream as status updates.
+ */
+export function streamCare(onCare: (evt: CareEvent) => void) {
+  const es = new EventSource(`${BASE}/status/stream`);
+  es.addEventListener('care', (e) => {
+    try {
+      onCare(JSON.parse((e as MessageEvent).data));
+    } catch {
+      // Ignore malformed care events
+    }
+  });
+  return () => es.close();
+}
+
+/** Panic follow-up prompt, published N minutes after a run (named `checkin` SSE event). */
+export type PanicCheckin = {
+  event_id: number;
+  who: string;
+  ts: string;
+  question: string;
+  doorway: string;
+  breath: string;
+  anchor: string;
+  before: number;
+  outcome_url: string;
+};
+
+export function streamCheckins(onCheckin: (evt: PanicCheckin) => void) {
+  const es = new EventSource(`${BASE}/status/stream`);
+  es.addEventListener('checkin', (e) => {
+    try {
+      onCheckin(JSON.parse((e as MessageEvent).data));
+    } catch {
+      // Ignore malformed check-in events
+    }
+  });
+  return () => es.close();
+}
+
+// Last seen dashboard revision (ETag); sent back as If-Match so concurrent saves don't clobber.
+let stateEtag: string | null = null;
+
+/** Thrown by setState when another device saved first; carries the server's current state. */
+export class StateConflictError extends Error {
+  constructor(public current: TeamState) {
+    super('Dashboard state changed on another device');
+  }
+}
+
 export async function getState(): Promise<TeamState> {
   const r = await fetch(`${BASE}/state/get`);
+  stateEtag = r.headers.get('ETag');
+  return r.json();
+}
+export async function getTeam(): Promise<TeamDefinitions> {
+  const r = await fetch(`${BASE}/team`);
+  if (!r.ok) throw new Error(`getTeam failed: ${r.status}`);
   return r.json();
 }
 export async function setState(payload: Partial<TeamState>): Promise<TeamState> {
-  const r = await fetch(`${BASE}/state/set`, { method: 'POST', headers: { 'Content-Type': 'application/json' }, body: JSON.stringify(payload) });
+  // the server refuses writes without If-Match (428): read first if we never have
+  if (stateEtag === null) await getState();
+  const headers = cleanHeaders({ 'Content-Type': 'application/json', 'If-Match': stateEtag ?? undefined });
+  const r = await fetch(`${BASE}/state/set`, { method: 'POST', headers, body: JSON.stringify(payload) });
+  stateEtag = r.headers.get('ETag') ?? stateEtag;
+  if (r.status === 409) {
+    const body = await r.json();
+    throw new StateConflictError(body.current);
+  }
+  return r.json();
+}
+
+export async function getPanicPresets(): Promise<PanicPreset[]> {
+  const r = await fetch(`${BASE}/panic/presets`);
+  if (!r.ok) throw new Error(`getPanicPresets failed: ${r.status}`);
   return r.json();
 }
 
@@ -474,6 +1004,78 @@ export interface PanicLast {
   doorway: string;
   anchor: string;
   path: string;
+  id?: number;
+  who?: string;
+  mode?: string;
+  suggested_bridge?: string | null;
+}
+
+export type PanicEvent = {
+  id: number;
+  ts: string;
+  who: string;
+  mode: string;
+  source: 'ui' | 'run';
+  whisper: string;
+  breath: string;
+  doorway: string;
+  anchor: string;
+  emotion_kind: string;
+  intensity: number;
+  suggested_bridge?: string | null;
+  tell_id?: number | null;
+  emotion_id?: number | null;
+  gratitude_id?: number | null;
+  relational_event_id?: string | null;
+  log_path?: string | null;
+  checkin_due_at?: string | null;
+  checkin_sent_at?: string | null;
+  before_intensity?: number | null;
+  after_intensity?: number | null;
+  outcome_at?: string | null;
+  outcome_note?: string | null;
+  outcome_emotion_id?: number | null;
+};
+
+type Count = { value: string; count: number };
+export type PanicHistory = {
+  items: PanicEvent[];
+  next_before?: number | null;
+  stats: {
+    total: number;
+    first_ts?: string | null;
+    last_ts?: string | null;
+    by_mode: Count[];
+    by_who: Count[];
+    by_source: Count[];
+    doorways: Count[];
+    per_day: Count[];
+  };
+};
+
+export async function getPanicHistory(params: Record<string, string | number | undefined> = {}): Promise<PanicHistory> {
+  const qs = new URLSearchParams();
+  for (const [k, v] of Object.entries(params)) if (v !== undefined && v !== '') qs.set(k, String(v));
+  return request<PanicHistory>(`/panic/history${qs.toString() ? `?${qs}` : ''}`, { method: 'GET' });
+}
+
+export async function postPanicOutcome(id: number, body: { after: number; before?: number; note?: string }): Promise<{ event: PanicEvent; drop: number }> {
+  return request(`/panic/${id}/outcome`, { method: 'POST', body: JSON.stringify(body) });
+}
+
+type PanicScore = { value: string; samples: number; avg_before: number; avg_after: number; avg_drop: number };
+export type PanicEffectiveness = {
+  who: string;
+  outcomes: number;
+  breaths: PanicScore[];
+  doorways: PanicScore[];
+  anchors: PanicScore[];
+  whispers: PanicScore[];
+  bridges: PanicScore[];
+};
+
+export async function getPanicEffectiveness(who?: string): Promise<PanicEffectiveness[]> {
+  return request<PanicEffectiveness[]>(`/panic/effectiveness${who ? `?who=${encodeURIComponent(who)}` : ''}`, { method: 'GET' });
 }
 
 export async function getPanicLast(): Promise<PanicLast | null> {
@@ -484,7 +1086,7 @@ export async function getPanicLast(): Promise<PanicLast | null> {
   }
 }
 
-export async function resolveEmotion(who: string, details?: string) {
+export async function resolveEmotion(who: string, details?: string, arcId?: number) {
   const headers = cleanHeaders({
     'Content-Type': 'application/json',
     Authorization: BEARER ? `Bearer ${BEARER}` : undefined,
@@ -492,20 +1094,99 @@ export async function resolveEmotion(who: string, details?: string) {
   const res = await fetch(`${BASE}/emotions/resolve`, {
     method: 'POST',
     headers,
-    body: JSON.stringify({ who, details }),
+    body: JSON.stringify({ who, details, arc_id: arcId }),
   });
   if (!res.ok) throw new Error(await res.text());
   return res.json();
 }
 
+// --- Healing arcs -----------------------------------------------------------
+export type ArcState = 'opened' | 'bridged' | 'resolved' | 'abandoned';
+
+export type HealingArc = {
+  id: number;
+  who: string;
+  state: ArcState;
+  trigger_emotion_id: number;
+  kind: string;
+  intensity: number;
+  bridge?: { pattern: string; breath: string; doorway: string; anchor: string } | null;
+  resolution_emotion_id?: number | null;
+  tell_ids: number[];
+  note?: string | null;
+  opened_at: string;
+  bridged_at?: string | null;
+  closed_at?: string | null;
+  durations: { to_bridge?: number | null; to_close?: number | null; elapsed: number }; // seconds
+};
+
+export type ArcFilter = { who?: string; state?: ArcState; kind?: string };
+
+export type ArcStats = {
+  total: number;
+  states: Record<ArcState, number>;
+  mean_to_bridge?: number | null;
+  mean_to_resolve?: number | null;
+};
+
+export async function openArc(emotionId: number): Promise<HealingArc> {
+  return request('/arcs', { method: 'POST', body: JSON.stringify({ emotion_id: emotionId }) });
+}
+
+export async function getArcs(filter: ArcFilter = {}, before?: number, limit = 20): Promise<{ items: HealingArc[]; next_before?: number | null }> {
+  return request(`/arcs?${queryString({ ...filter, before, limit })}`, { method: 'GET' });
+}
+
+export async function getArcStats(filter: ArcFilter = {}): Promise<ArcStats> {
+  return request(`/arcs/stats?${queryString(filter)}`, { method: 'GET' });
+}
+
+export async function bridgeArc(id: number, steps: { breath?: string; doorway?: string; anchor?: string } = {}): Promise<HealingArc> {
+  return request(`/arcs/${id}/bridge`, { method: 'POST', body: JSON.stringify(steps) });
+}
+
+export async function abandonArc(id: number, reason?: string): Promise<HealingArc> {
+  return request(`/arcs/${id}/abandon`, { method: 'POST', body: JSON.stringify({ reason }) });
+}
+
 // --- Patterns API -----------------------------------------------------------
-export async function fetchBridge(kind: BridgeKindAlias, intensity: number = 0.6): Promise<BridgeSuggestion> {
+export async function fetchBridge(kind: BridgeKindAlias, intensity: number = 0.6, who?: string): Promise<BridgeSuggestion> {
   const search = new URLSearchParams();
   search.set('kind', normalizeBridgeKind(kind));
   if (Number.isFinite(intensity)) search.set('intensity', intensity.toString());
+  if (who) search.set('who', who);
   return request<BridgeSuggestion>(`/patterns/bridge_suggest?${search.toString()}`, { method: 'GET' });
 }
 
+// --- Bridge library API -----------------------------------------------------
+export type BridgeTier = {
+  id: number;
+  kind: string;
+  profile?: string | null; // null = global
+  min_intensity: number;
+  pattern?: string | null;
+  hint?: string | null;
+  breath?: string | null;
+  doorway?: string | null;
+  anchor?: string | null;
+  updated_at: string;
+};
+export type BridgeTierIn = Partial<Omit<BridgeTier, 'id' | 'updated_at'>> & { kind: string };
+
+export async function getBridges(filter: { kind?: string; profile?: string } = {}): Promise<BridgeTier[]> {
+  return request(`/bridges?${queryString(filter)}`);
+}
+export async function createBridge(tier: BridgeTierIn): Promise<BridgeTier> {
+  return request('/bridges', { method: 'POST', body: JSON.stringify(tier) });
+}
+export async function updateBridge(id: number, patch: Partial<Omit<BridgeTierIn, 'kind' | 'profile'>>): Promise<BridgeTier> {
+  return request(`/bridges/${id}`, { method: 'PATCH', body: JSON.stringify(patch) });
+}
+export async function deleteBridge(id: number): Promise<void> {
+  const res = await fetch(`${BASE}/bridges/${id}`, { method: 'DELETE', headers: cleanHeaders({ Authorization: BEARER ? `Bearer ${BEARER}` : undefined }) });
+  if (!res.ok) throw new Error(await res.text());
+}
+
 export type PatternCategory = 'mirror' | 'repeat' | 'sequence' | 'none';
 export type SignalStrength = 'low' | 'medium' | 'high';
 
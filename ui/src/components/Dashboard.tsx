@@ -1,16 +1,23 @@
 import React, { useEffect, useMemo, useState } from 'react';
 import { Button, Pill } from '@gratiaos/ui';
 import { notifyJoy } from '@/utils/joy';
-import { getState, setState, TeamState, PillarStatus, getPanicLast, type PanicLast, getTells, type Tell, resolveEmotion } from '@/api';
+import { getState, setState, StateConflictError, TeamState, getTeam, type PillarDef, getPanicLast, type PanicLast, getTells, type Tell, resolveEmotion } from '@/api';
 import { Heading, Subheading, Divider, Text, Strong, Input, Textarea } from '@/ui/catalyst';
 import FamJamPad from '@/flows/communication/FamJamPad';
 import { useProfile } from '@/state/profile';
 
 const isDev = import.meta.env.DEV;
 
-const PILLAR_KEYS: (keyof PillarStatus)[] = ['crown', 'void', 'play', 'dragon', 'life_force'];
+// Used until GET /team answers (and for icons of the seed pillars).
+const SEED_PILLARS: PillarDef[] = ['crown', 'void', 'play', 'dragon', 'life_force'].map((key, position) => ({
+  key,
+  label: key.replace('_', ' '),
+  allowed: ['good', 'watch', 'rest'],
+  default_value: key === 'play' ? 'good' : 'watch',
+  position,
+}));
 
-const PILLAR_ICON: Record<keyof PillarStatus, string> = {
+const PILLAR_ICON: Record<string, string> = {
   crown: '👑',
   void: '🕳️',
   play: '🏀',
@@ -26,6 +33,7 @@ const DECISION_ICON: Record<'GO' | 'WAIT' | 'REST', string> = {
 
 export default function Dashboard() {
   const [state, set] = useState<TeamState | null>(null);
+  const [pillarDefs, setPillarDefs] = useState<PillarDef[]>(SEED_PILLARS);
   const [saving, setSaving] = useState(false);
   const [lastRedirect, setLastRedirect] = useState<PanicLast | null>(null);
   const [tells, setTells] = React.useState<Tell[]>([]);
@@ -50,6 +58,13 @@ export default function Dashboard() {
         setError('Failed to load team state');
       }
 
+      try {
+        const team = await getTeam();
+        setPillarDefs(team.pillars);
+      } catch (e) {
+        if (isDev) console.warn('[Dashboard] getTeam() failed', e);
+      }
+
       try {
         const last = await getPanicLast();
         if (isDev) console.debug('[Dashboard] getPanicLast() ->', last);
@@ -134,13 +149,13 @@ export default function Dashboard() {
 
   const pillarScore = useMemo(() => {
     if (!state) return { good: 0, watch: 0, rest: 0 };
-    const vals = PILLAR_KEYS.map((k) => state.pillars[k]);
+    const vals = pillarDefs.map((p) => state.pillars[p.key]);
     return {
       good: vals.filter((v) => v === 'good').length,
       watch: vals.filter((v) => v === 'watch').length,
       rest: vals.filter((v) => v === 'rest').length,
     };
-  }, [state]);
+  }, [state, pillarDefs]);
 
   const isDirty = useMemo(() => {
     if (!state) return false;
@@ -162,7 +177,7 @@ export default function Dashboard() {
     members[i] = { ...members[i], energy };
     set({ ...state, members });
   }
-  function mutatePillar(k: keyof PillarStatus, v: PillarStatus[keyof PillarStatus]) {
+  function mutatePillar(k: string, v: string) {
     if (!state) return;
     set({ ...state, pillars: { ...state.pillars, [k]: v } });
   }
@@ -177,6 +192,12 @@ export default function Dashboard() {
       notifyJoy({ level: 'success', title: 'State saved', body: 'Saved just now', icon: '💾' });
       setError(null);
     } catch (e) {
+      if (e instanceof StateConflictError) {
+        // someone else saved first: show theirs, let the user re-apply
+        set(e.current);
+        notifyJoy({ level: 'warning', title: 'Changed elsewhere', body: 'Reloaded the latest state — re-apply your edit.', icon: '🔄' });
+        return;
+      }
       setError('Failed to save state');
       notifyJoy({ level: 'error', title: 'Save failed', body: String(e), icon: '⚠️' });
     } finally {
@@ -311,32 +332,36 @@ export default function Dashboard() {
       </div>
 
       <div className="grid gap-2">
-        {PILLAR_KEYS.map((k) => (
+        {pillarDefs.map(({ key: k, label, allowed }) => (
           <div key={k} className="grid grid-cols-[140px_auto] items-center gap-2">
             <Text className="capitalize">
-              <span className="mr-1" aria-hidden="true">
-                {PILLAR_ICON[k]}
-              </span>
-              {k.replace('_', ' ')}
+              {PILLAR_ICON[k] && (
+                <span className="mr-1" aria-hidden="true">
+                  {PILLAR_ICON[k]}
+                </span>
+              )}
+              {label}
             </Text>
             <fieldset className="m-0 border-0 p-0">
-              <legend className="sr-only">{k.replace('_', ' ')} status</legend>
-              <div className="flex gap-2" role="radiogroup" aria-label={`${k.replace('_', ' ')} status`}>
-                {(['good', 'watch', 'rest'] as const).map((v) => (
+              <legend className="sr-only">{label} status</legend>
+              <div className="flex gap-2" role="radiogroup" aria-label={`${label} status`}>
+                {allowed.map((v) => (
                   <button
                     key={v}
                     onClick={() => mutatePillar(k, v)}
                     role="radio"
                     aria-checked={state.pillars[k] === v}
                     aria-pressed={undefined}
-                    title={`${k.replace('_', ' ')}: ${v}`}
+                    title={`${label}: ${v}`}
                     className={`rounded-full border px-2.5 py-1 transition-colors ${
                       state.pillars[k] === v
                         ? v === 'good'
                           ? 'border-emerald-600 bg-emerald-500 text-white hover:bg-emerald-600'
                           : v === 'watch'
                           ? 'border-amber-600 bg-amber-500 text-white hover:bg-amber-600'
-                          : 'border-rose-600 bg-rose-500 text-white hover:bg-rose-600'
+                          : v === 'rest'
+                          ? 'border-rose-600 bg-rose-500 text-white hover:bg-rose-600'
+                          : 'border-sky-600 bg-sky-500 text-white hover:bg-sky-600'
                         : 'border-zinc-200 bg-white text-zinc-700 hover:bg-zinc-50 dark:border-zinc-800 dark:bg-zinc-950 dark:text-zinc-300 dark:hover:bg-zinc-900'
                     }`}>
                     {v}
@@ -1,5 +1,5 @@
 import React, { useEffect, useRef, useState, useCallback } from 'react';
-import { runPanic, type PanicMode } from '@/api';
+import { runPanic, streamCare, type PanicMode } from '@/api';
 import { useProfile } from '@/state/profile';
 import { notifyJoy } from '@/utils/joy';
 import { Button, Select } from '@/ui/catalyst';
@@ -86,6 +86,21 @@ export function PanicButton() {
     }
   };
 
+  // Someone answered a care invitation → "Ana is with you"
+  useEffect(
+    () =>
+      streamCare((evt) => {
+        notifyJoy({
+          level: 'success',
+          title: evt.type === 'care.ack' ? 'Care' : 'Care · closed',
+          body: evt.message,
+          icon: '🤍',
+          ttl: 6000,
+        });
+      }),
+    []
+  );
+
   useEffect(() => {
     const onKey = (e: KeyboardEvent) => {
       if (e.altKey && (e.key === 'p' || e.key === 'P')) {
@@ -17,6 +17,9 @@ export interface BridgeSuggestion {
   breath?: string;
   doorway?: string;
   anchor?: string;
+  kind?: string;            // bridge kind that answered (after aliases)
+  tier?: number;            // min_intensity of the tier used
+  profile?: string | null;  // set when a person's override applied
 }
 
 export function normalizeBridgeKind(k: BridgeKindAlias): CanonicalBridgeKind {